@@ -21,8 +21,12 @@ fn main() {
                 if let Some((key, value)) = line.split_once('=') {
                     let key = key.trim();
                     let value = value.trim();
-                    // Only set OAuth-related env vars
-                    if key.starts_with("GOOGLE_") || key.starts_with("DROPBOX_") || key.starts_with("KAIZEN_") {
+                    // Only set OAuth credentials and API keys
+                    if key.starts_with("GOOGLE_")
+                        || key.starts_with("DROPBOX_")
+                        || key.starts_with("KAIZEN_")
+                        || key.starts_with("CURSEFORGE_")
+                    {
                         println!("cargo:rustc-env={}={}", key, value);
                     }
                 }
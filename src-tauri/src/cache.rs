@@ -2,12 +2,17 @@
 //!
 //! Caches API responses to disk with a configurable TTL to reduce
 //! unnecessary network requests for rarely-changing data.
+//!
+//! HTTP responses can also be kept for revalidation (ETag / Last-Modified)
+//! and served as stale data when the network is down. The least recently used
+//! entries are evicted once the cache grows past its size limit.
 
 use serde::{de::DeserializeOwned, Serialize};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::fs;
 
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
 
 /// Default cache TTL: 1 hour
@@ -43,9 +48,79 @@ impl<T> CacheEntry<T> {
     }
 }
 
+/// HTTP response kept for revalidation
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct HttpCacheEntry {
+    url: String,
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: u64, // Unix timestamp
+}
+
+/// Data fetched through the cache
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    pub data: T,
+    /// The server could not be reached and the data may be outdated
+    pub stale: bool,
+}
+
+/// Why a fetch failed with nothing cached to fall back on
+#[derive(Debug)]
+pub enum HttpFetchError {
+    Network(String),
+    Status(u16),
+    Parse(String),
+}
+
+impl std::fmt::Display for HttpFetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Network(msg) => write!(f, "{}", msg),
+            Self::Status(status) => write!(f, "API returned status {}", status),
+            Self::Parse(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Server errors and rate limiting are worth falling back to stale data for,
+/// other client errors (e.g. 404) are real answers
+fn is_transient_status(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+/// Files to delete, least recently used first, to get under `max_bytes`
+fn eviction_order(mut files: Vec<(PathBuf, u64, SystemTime)>, max_bytes: u64) -> Vec<PathBuf> {
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= max_bytes {
+        return Vec::new();
+    }
+
+    files.sort_by_key(|(_, _, accessed)| *accessed);
+    let mut evicted = Vec::new();
+    for (path, size, _) in files {
+        if total <= max_bytes {
+            break;
+        }
+        total = total.saturating_sub(size);
+        evicted.push(path);
+    }
+    evicted
+}
+
 /// API response cache
 pub struct ApiCache {
     cache_dir: PathBuf,
+    /// Size limit, least recently used entries are evicted past it
+    max_bytes: u64,
 }
 
 impl ApiCache {
@@ -53,6 +128,15 @@ impl ApiCache {
     pub fn new(data_dir: &Path) -> Self {
         Self {
             cache_dir: data_dir.join("cache").join("api"),
+            max_bytes: u64::MAX,
+        }
+    }
+
+    /// Create a cache in its own directory with a size limit
+    pub fn with_limit(cache_dir: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            cache_dir,
+            max_bytes,
         }
     }
 
@@ -115,7 +199,10 @@ impl ApiCache {
         let path = self.cache_path(key);
         fs::write(&path, content)
             .await
-            .map_err(|e| AppError::Io(format!("Failed to write cache file: {}", e)))
+            .map_err(|e| AppError::Io(format!("Failed to write cache file: {}", e)))?;
+
+        self.evict().await;
+        Ok(())
     }
 
     /// Get a cached value or fetch it using the provided async function
@@ -157,6 +244,218 @@ impl ApiCache {
         Ok(data)
     }
 
+    /// Fetch JSON over HTTP through the cache.
+    /// Entries younger than `ttl` are served without a request, older ones are
+    /// revalidated with their ETag / Last-Modified. When the server cannot be
+    /// reached the cached data is returned marked as stale
+    pub async fn fetch_json<T: DeserializeOwned>(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        ttl: Duration,
+    ) -> Result<Cached<T>, HttpFetchError> {
+        self.fetch_http(url, url, ttl, true, |url| client.get(url))
+            .await
+    }
+
+    /// POST a JSON body through the cache, keyed by the URL and the body.
+    /// Fresh entries and the stale fallback work like [`Self::fetch_json`],
+    /// but expired entries are fetched again since POST responses cannot be
+    /// revalidated
+    pub async fn post_json<B: Serialize, T: DeserializeOwned>(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        body: &B,
+        ttl: Duration,
+    ) -> Result<Cached<T>, HttpFetchError> {
+        let body_json =
+            serde_json::to_string(body).map_err(|e| HttpFetchError::Parse(e.to_string()))?;
+        let key = format!("POST {}\n{}", url, body_json);
+        self.fetch_http(&key, url, ttl, false, |url| client.post(url).json(body))
+            .await
+    }
+
+    /// Send the request built by `build` through the cache entry of `key`,
+    /// with conditional headers when `revalidate` is set
+    async fn fetch_http<T, F>(
+        &self,
+        key: &str,
+        url: &str,
+        ttl: Duration,
+        revalidate: bool,
+        build: F,
+    ) -> Result<Cached<T>, HttpFetchError>
+    where
+        T: DeserializeOwned,
+        F: Fn(&str) -> reqwest::RequestBuilder,
+    {
+        let path = self.http_cache_path(key);
+        let cached = match self.read_http_entry::<T>(&path, key).await {
+            Some((entry, data)) if now_secs() < entry.fetched_at.saturating_add(ttl.as_secs()) => {
+                self.touch(&path).await;
+                return Ok(Cached { data, stale: false });
+            }
+            other => other,
+        };
+
+        let revalidated = cached.as_ref().filter(|_| revalidate);
+        let request = |url: &str| {
+            let mut request = build(url);
+            if let Some((entry, _)) = revalidated {
+                if let Some(etag) = &entry.etag {
+                    request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+            request
+        };
+
+        let response = match endpoints::send(url, request).await {
+            Ok(response) => response,
+            Err(e) => {
+                return match cached {
+                    Some((_, data)) => {
+                        tracing::debug!("Serving stale {} ({})", url, e);
+                        Ok(Cached { data, stale: true })
+                    }
+                    None => Err(HttpFetchError::Network(e.to_string())),
+                };
+            }
+        };
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some((mut entry, data)) = cached {
+                entry.fetched_at = now_secs();
+                self.write_http_entry(&path, &entry).await;
+                return Ok(Cached { data, stale: false });
+            }
+        }
+
+        if !status.is_success() {
+            return match cached {
+                Some((_, data)) if is_transient_status(status.as_u16()) => {
+                    tracing::debug!("Serving stale {} (status {})", url, status);
+                    Ok(Cached { data, stale: true })
+                }
+                _ => Err(HttpFetchError::Status(status.as_u16())),
+            };
+        }
+
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(e) => {
+                return match cached {
+                    Some((_, data)) => Ok(Cached { data, stale: true }),
+                    None => Err(HttpFetchError::Network(e.to_string())),
+                };
+            }
+        };
+        let data: T =
+            serde_json::from_str(&body).map_err(|e| HttpFetchError::Parse(e.to_string()))?;
+
+        let entry = HttpCacheEntry {
+            url: key.to_string(),
+            body,
+            etag,
+            last_modified,
+            fetched_at: now_secs(),
+        };
+        self.write_http_entry(&path, &entry).await;
+        self.evict().await;
+
+        Ok(Cached { data, stale: false })
+    }
+
+    /// Cache file of an HTTP response, named after a hash of its key
+    /// (the URL, plus the body for POST requests)
+    fn http_cache_path(&self, key: &str) -> PathBuf {
+        use sha2::Digest;
+        let hash = hex::encode(sha2::Sha256::digest(key.as_bytes()));
+        self.cache_dir.join(format!("http_{}.json", hash))
+    }
+
+    /// A cached response and its parsed body, if it is still readable
+    async fn read_http_entry<T: DeserializeOwned>(
+        &self,
+        path: &Path,
+        key: &str,
+    ) -> Option<(HttpCacheEntry, T)> {
+        let content = fs::read_to_string(path).await.ok()?;
+        let entry: HttpCacheEntry = serde_json::from_str(&content).ok()?;
+        if entry.url != key {
+            return None;
+        }
+        let data = serde_json::from_str(&entry.body).ok()?;
+        Some((entry, data))
+    }
+
+    async fn write_http_entry(&self, path: &Path, entry: &HttpCacheEntry) {
+        if self.ensure_dir().await.is_err() {
+            return;
+        }
+        match serde_json::to_string(entry) {
+            Ok(content) => {
+                if let Err(e) = fs::write(path, content).await {
+                    tracing::warn!("Failed to write cache file: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize cache entry: {}", e),
+        }
+    }
+
+    /// Mark an entry as used, for the LRU eviction
+    async fn touch(&self, path: &Path) {
+        let path = path.to_path_buf();
+        let _ = tokio::task::spawn_blocking(move || {
+            std::fs::OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()))
+        })
+        .await;
+    }
+
+    /// Delete the least recently used entries once the cache is over its size limit
+    async fn evict(&self) {
+        if self.max_bytes == u64::MAX {
+            return;
+        }
+
+        let mut files = Vec::new();
+        if let Ok(mut entries) = fs::read_dir(&self.cache_dir).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                if let Ok(meta) = entry.metadata().await {
+                    if meta.is_file() {
+                        let accessed = meta.modified().unwrap_or(UNIX_EPOCH);
+                        files.push((entry.path(), meta.len(), accessed));
+                    }
+                }
+            }
+        }
+
+        let evicted = eviction_order(files, self.max_bytes);
+        if !evicted.is_empty() {
+            tracing::debug!("Evicting {} cache entries", evicted.len());
+        }
+        for path in evicted {
+            let _ = fs::remove_file(path).await;
+        }
+    }
+
     /// Clear all cached data
     pub async fn clear(&self) -> AppResult<()> {
         if self.cache_dir.exists() {
@@ -250,6 +549,117 @@ mod tests {
         assert_eq!(result2, "fetched_value");
     }
 
+    /// Nothing listens on the discard port, requests fail right away
+    const UNREACHABLE_URL: &str = "http://127.0.0.1:9/v2/project/sodium";
+
+    async fn cache_response(cache: &ApiCache, url: &str, body: &str, fetched_at: u64) {
+        let entry = HttpCacheEntry {
+            url: url.to_string(),
+            body: body.to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            fetched_at,
+        };
+        cache
+            .write_http_entry(&cache.http_cache_path(url), &entry)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_fresh_entry() {
+        let temp = tempdir().unwrap();
+        let cache = ApiCache::with_limit(temp.path().to_path_buf(), 1024 * 1024);
+        let client = reqwest::Client::new();
+
+        cache_response(&cache, UNREACHABLE_URL, r#"{"id":"AANobbMI"}"#, now_secs()).await;
+
+        let cached: Cached<serde_json::Value> = cache
+            .fetch_json(&client, UNREACHABLE_URL, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(cached.data["id"], "AANobbMI");
+        assert!(!cached.stale);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_stale_when_offline() {
+        let temp = tempdir().unwrap();
+        let cache = ApiCache::with_limit(temp.path().to_path_buf(), 1024 * 1024);
+        let client = reqwest::Client::new();
+
+        cache_response(&cache, UNREACHABLE_URL, r#"{"id":"AANobbMI"}"#, 0).await;
+
+        let cached: Cached<serde_json::Value> = cache
+            .fetch_json(&client, UNREACHABLE_URL, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(cached.data["id"], "AANobbMI");
+        assert!(cached.stale);
+
+        // Nothing cached: the network error is returned
+        let missing: Result<Cached<serde_json::Value>, _> = cache
+            .fetch_json(
+                &client,
+                "http://127.0.0.1:9/v2/project/lithium",
+                Duration::from_secs(60),
+            )
+            .await;
+        assert!(matches!(missing, Err(HttpFetchError::Network(_))));
+    }
+
+    #[tokio::test]
+    async fn test_post_json_keyed_by_body() {
+        let temp = tempdir().unwrap();
+        let cache = ApiCache::with_limit(temp.path().to_path_buf(), 1024 * 1024);
+        let client = reqwest::Client::new();
+        let url = "http://127.0.0.1:9/v2/version_files";
+        let body = serde_json::json!({"hashes": ["abc"], "algorithm": "sha1"});
+
+        let key = format!("POST {}\n{}", url, body);
+        cache_response(&cache, &key, r#"{"abc":{"id":"v1"}}"#, 0).await;
+
+        let cached: Cached<serde_json::Value> = cache
+            .post_json(&client, url, &body, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(cached.data["abc"]["id"], "v1");
+        assert!(cached.stale);
+
+        // Another body is another entry
+        let other = serde_json::json!({"hashes": ["def"], "algorithm": "sha1"});
+        let missing: Result<Cached<serde_json::Value>, _> = cache
+            .post_json(&client, url, &other, Duration::from_secs(60))
+            .await;
+        assert!(matches!(missing, Err(HttpFetchError::Network(_))));
+    }
+
+    #[test]
+    fn test_eviction_order() {
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        let files = vec![
+            (PathBuf::from("new"), 40, at(300)),
+            (PathBuf::from("old"), 40, at(100)),
+            (PathBuf::from("mid"), 40, at(200)),
+        ];
+
+        assert!(eviction_order(files.clone(), 120).is_empty());
+        assert_eq!(
+            eviction_order(files.clone(), 100),
+            vec![PathBuf::from("old")]
+        );
+        assert_eq!(
+            eviction_order(files, 40),
+            vec![PathBuf::from("old"), PathBuf::from("mid")]
+        );
+    }
+
+    #[test]
+    fn test_transient_status() {
+        assert!(is_transient_status(503));
+        assert!(is_transient_status(429));
+        assert!(!is_transient_status(404));
+    }
+
     #[tokio::test]
     async fn test_cache_invalidate() {
         let temp = tempdir().unwrap();
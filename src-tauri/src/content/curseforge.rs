@@ -0,0 +1,221 @@
+// CurseForge as a content provider
+
+use super::{
+    ContentDependency, ContentFile, ContentProject, ContentProvider, ContentQuery,
+    ContentSearchHit, ContentSearchResponse, ContentSource, ContentVersion,
+};
+use crate::curseforge::{self, CurseForgeClient, File, ModSearch};
+use crate::error::{AppError, AppResult};
+
+/// Page size used when the caller does not ask for one (same as Modrinth)
+const DEFAULT_PAGE_SIZE: u32 = 10;
+
+/// Map a Modrinth sort index to a CurseForge `sortField`
+fn sort_field(sort_by: Option<&str>) -> u32 {
+    match sort_by {
+        Some("downloads") => 6,
+        Some("newest") => 11,
+        Some("updated") => 3,
+        // CurseForge has no relevance sort, popularity is what its website uses
+        _ => 2,
+    }
+}
+
+impl From<File> for ContentVersion {
+    fn from(file: File) -> Self {
+        Self {
+            source: ContentSource::CurseForge,
+            id: file.id.to_string(),
+            project_id: file.mod_id.to_string(),
+            version_type: file.release_channel().to_string(),
+            game_versions: file.minecraft_versions(),
+            loaders: file.loaders(),
+            files: vec![ContentFile {
+                url: file.download_url.clone(),
+                sha1: file.sha1().map(str::to_string),
+                filename: file.file_name,
+                primary: true,
+                size: file.file_length,
+                sha512: None,
+            }],
+            dependencies: file
+                .dependencies
+                .iter()
+                .map(|d| ContentDependency {
+                    project_id: Some(d.mod_id.to_string()),
+                    version_id: None,
+                    dependency_type: d.dependency_type().to_string(),
+                })
+                .collect(),
+            name: file.display_name.clone(),
+            version_number: file.display_name,
+            downloads: file.download_count,
+            date_published: file.file_date,
+            changelog: None,
+            stale: false,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ContentProvider for CurseForgeClient<'_> {
+    fn source(&self) -> ContentSource {
+        ContentSource::CurseForge
+    }
+
+    async fn search_projects(&self, query: &ContentQuery) -> AppResult<ContentSearchResponse> {
+        let class_id = curseforge::class_for_project_type(&query.project_type);
+        // Only mods are filtered by loader
+        let mod_loader_type = match query.project_type.as_str() {
+            "mod" => query
+                .loader
+                .as_deref()
+                .and_then(curseforge::mod_loader_type),
+            _ => None,
+        };
+
+        let response = self
+            .search(&ModSearch {
+                query: &query.query,
+                class_id,
+                game_version: query.game_version.as_deref(),
+                mod_loader_type,
+                sort_field: sort_field(query.sort_by.as_deref()),
+                offset: query.offset.unwrap_or(0),
+                limit: query.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+            })
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(ContentSearchResponse {
+            hits: response
+                .data
+                .into_iter()
+                .map(|project| {
+                    let mut game_versions: Vec<String> = Vec::new();
+                    for index in &project.latest_files_indexes {
+                        if !game_versions.contains(&index.game_version) {
+                            game_versions.push(index.game_version.clone());
+                        }
+                    }
+
+                    ContentSearchHit {
+                        project_id: project.id.to_string(),
+                        slug: project.slug,
+                        title: project.name,
+                        description: project.summary,
+                        author: project
+                            .authors
+                            .first()
+                            .map(|a| a.name.clone())
+                            .unwrap_or_default(),
+                        downloads: project.download_count as u64,
+                        icon_url: project.logo.and_then(|l| l.thumbnail_url.or(l.url)),
+                        categories: project.categories.into_iter().map(|c| c.slug).collect(),
+                        game_versions,
+                    }
+                })
+                .collect(),
+            total_hits: response.pagination.total_count,
+            offset: response.pagination.index,
+            limit: response.pagination.page_size,
+            stale: false,
+        })
+    }
+
+    async fn fetch_project(&self, project_id: &str) -> AppResult<ContentProject> {
+        let project = self
+            .get_mod(project_id)
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(ContentProject {
+            source: ContentSource::CurseForge,
+            allows_distribution: project.allows_distribution(),
+            id: project.id.to_string(),
+            slug: project.slug,
+            title: project.name,
+            description: project.summary,
+            icon_url: project.logo.and_then(|l| l.thumbnail_url.or(l.url)),
+            client_side: None,
+            server_side: None,
+            website_url: project.links.website_url,
+        })
+    }
+
+    async fn fetch_versions(
+        &self,
+        project_id: &str,
+        loader: Option<&str>,
+        game_version: Option<&str>,
+    ) -> AppResult<Vec<ContentVersion>> {
+        let files = self
+            .get_mod_files(
+                project_id,
+                game_version,
+                loader.and_then(curseforge::mod_loader_type),
+            )
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(files.into_iter().map(ContentVersion::from).collect())
+    }
+
+    async fn fetch_version(&self, project_id: &str, version_id: &str) -> AppResult<ContentVersion> {
+        self.get_mod_file(project_id, version_id)
+            .await
+            .map(ContentVersion::from)
+            .map_err(|e| AppError::Network(e.to_string()))
+    }
+
+    async fn identify_file(&self, bytes: &[u8]) -> AppResult<Option<ContentVersion>> {
+        let fingerprint = curseforge::fingerprint(bytes);
+
+        let matches = self
+            .get_fingerprint_matches(&[fingerprint])
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(matches
+            .exact_matches
+            .into_iter()
+            .next()
+            .map(|m| ContentVersion::from(m.file)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_to_version() {
+        let file: File = serde_json::from_str(
+            r#"{
+                "id": 5000, "modId": 300, "displayName": "Mod 2.0", "fileName": "mod-2.0.jar",
+                "releaseType": 2, "hashes": [{"value": "aa", "algo": 1}],
+                "fileDate": "2024-01-01T00:00:00Z", "fileLength": 10,
+                "downloadUrl": "https://edge.forgecdn.net/files/5/0/mod-2.0.jar",
+                "gameVersions": ["Fabric", "1.20.1", "1.20"],
+                "dependencies": [{"modId": 306612, "relationType": 3}]
+            }"#,
+        )
+        .unwrap();
+
+        let version = ContentVersion::from(file);
+        assert_eq!(version.id, "5000");
+        assert_eq!(version.project_id, "300");
+        assert_eq!(version.version_type, "beta");
+        assert_eq!(version.game_versions, vec!["1.20.1", "1.20"]);
+        assert_eq!(version.loaders, vec!["fabric"]);
+
+        let primary = version.primary_file().unwrap();
+        assert_eq!(primary.filename, "mod-2.0.jar");
+        assert_eq!(primary.sha1.as_deref(), Some("aa"));
+        assert_eq!(
+            version.dependencies[0].project_id.as_deref(),
+            Some("306612")
+        );
+        assert_eq!(version.dependencies[0].dependency_type, "required");
+    }
+}
@@ -0,0 +1,572 @@
+// Moving an instance to another Minecraft version or loader: a dry run over the
+// installed content, then an apply that updates what it can and disables the
+// rest after an automatic backup, recorded so the migration can be reverted
+
+use super::updates::{self, UpdateChannel, UpdatePreferences};
+use super::{ContentSource, ContentVersion};
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::instance::commands::{change_version, ChangeVersionRequest};
+use crate::instance::instance_backup;
+use crate::instance::resource_packs::{self, PackCompatibility};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Record of the last applied migration, stored in the instance folder
+const MIGRATION_FILE: &str = ".version-migration.json";
+
+/// A Minecraft version with its loader
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionTarget {
+    pub mc_version: String,
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+}
+
+impl VersionTarget {
+    fn of(instance: &Instance) -> Self {
+        Self {
+            mc_version: instance.mc_version.clone(),
+            loader: instance.loader.clone(),
+            loader_version: instance.loader_version.clone(),
+        }
+    }
+}
+
+/// What a migration does with one installed file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum MigrationStatus {
+    /// The installed version supports the target
+    Compatible,
+    /// Another version of the project supports the target
+    Update {
+        version_id: String,
+        version_number: String,
+    },
+    /// No version supports the target, the file is disabled
+    Incompatible,
+    /// Not from a content provider, or the provider could not be reached.
+    /// The file is left alone
+    Unknown,
+}
+
+/// An installed file and what the migration does with it
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationItem {
+    pub source: Option<ContentSource>,
+    pub project_id: Option<String>,
+    pub name: String,
+    pub folder: String,
+    pub filename: String,
+    pub current_version: Option<String>,
+    #[serde(flatten)]
+    pub status: MigrationStatus,
+    #[serde(skip)]
+    target: Option<ContentVersion>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationPlan {
+    pub from: VersionTarget,
+    pub to: VersionTarget,
+    pub items: Vec<MigrationItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Updated,
+    Disabled,
+}
+
+/// A file the migration replaced or disabled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationChange {
+    pub name: String,
+    pub folder: String,
+    pub filename: String,
+    pub new_filename: String,
+    pub kind: ChangeKind,
+    /// Why the update failed, when the file was disabled instead
+    pub error: Option<String>,
+}
+
+/// An applied migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub created_at: String,
+    pub from: VersionTarget,
+    pub to: VersionTarget,
+    /// Backup of the instance taken before the migration
+    pub backup_filename: String,
+    pub changes: Vec<MigrationChange>,
+    /// Set until the migration finishes, stays set when it failed part way.
+    /// Reverting restores the backup either way
+    #[serde(default)]
+    pub incomplete: bool,
+}
+
+impl MigrationRecord {
+    pub async fn load(instance_dir: &Path) -> AppResult<Option<Self>> {
+        match tokio::fs::read_to_string(instance_dir.join(MIGRATION_FILE)).await {
+            Ok(contents) => Ok(Some(serde_json::from_str(&contents)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AppError::Io(format!(
+                "Failed to read version migration: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn save(&self, instance_dir: &Path) -> AppResult<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(instance_dir.join(MIGRATION_FILE), json)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to save version migration: {}", e)))
+    }
+}
+
+/// What to do with a project given its versions for the target, newest first
+pub fn status_for<'a>(
+    versions: &'a [ContentVersion],
+    current_version_id: Option<&str>,
+    channel: UpdateChannel,
+) -> (MigrationStatus, Option<&'a ContentVersion>) {
+    if current_version_id.is_some_and(|id| versions.iter().any(|v| v.id == id)) {
+        return (MigrationStatus::Compatible, None);
+    }
+    let target = versions
+        .iter()
+        .filter(|v| v.primary_file().and_then(|f| f.url.as_ref()).is_some())
+        .find(|v| channel.allows(&v.version_type));
+    match target {
+        Some(v) => (
+            MigrationStatus::Update {
+                version_id: v.id.clone(),
+                version_number: v.version_number.clone(),
+            },
+            Some(v),
+        ),
+        None => (MigrationStatus::Incompatible, None),
+    }
+}
+
+/// Installed files without provider metadata, by folder
+async fn untracked_files(instance_dir: &Path, folder: &str, tracked: &[String]) -> Vec<String> {
+    let mut files = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(instance_dir.join(folder)).await else {
+        return files;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        let base = filename.trim_end_matches(".disabled");
+        if !(base.ends_with(".jar") || base.ends_with(".zip")) || tracked.contains(&filename) {
+            continue;
+        }
+        files.push(filename);
+    }
+    files.sort_by_key(|f| f.to_lowercase());
+    files
+}
+
+/// Check every mod, resource pack and shader of an instance against a target version
+pub async fn plan(
+    state: &AppState,
+    instance: &Instance,
+    instance_dir: &Path,
+    target: &VersionTarget,
+) -> AppResult<MigrationPlan> {
+    let from = VersionTarget::of(instance);
+    if from.mc_version == target.mc_version && from.loader == target.loader {
+        return Err(AppError::Instance(
+            "The instance already uses this version".to_string(),
+        ));
+    }
+
+    let preferences = UpdatePreferences::load(instance_dir).await?;
+    let curseforge_key = crate::curseforge::api_key(&state.db).await;
+    let folders = crate::modpacks::lockfile::content_folders(instance);
+
+    let mut items = Vec::new();
+    let installed = updates::installed_content(instance_dir, &folders).await;
+    for (folder, filename, meta, source) in &installed {
+        let mods_folder = *folder == folders[0];
+        let mut item = MigrationItem {
+            source: Some(*source),
+            project_id: Some(meta.project_id.clone()),
+            name: meta.name.clone(),
+            folder: folder.to_string(),
+            filename: filename.clone(),
+            current_version: Some(meta.version.clone()),
+            status: MigrationStatus::Unknown,
+            target: None,
+        };
+
+        // Mods and plugins need a loader, and are the only content filtered by it
+        if mods_folder && target.loader.is_none() {
+            item.status = MigrationStatus::Incompatible;
+            items.push(item);
+            continue;
+        }
+        let loader = if mods_folder {
+            target.loader.as_deref()
+        } else {
+            None
+        };
+
+        let provider =
+            match super::provider_for(*source, &state.http_client, curseforge_key.as_deref()) {
+                Ok(provider) => provider,
+                Err(e) => {
+                    log::warn!("Cannot check {} for migration: {}", meta.project_id, e);
+                    items.push(item);
+                    continue;
+                }
+            };
+        match provider
+            .fetch_versions(&meta.project_id, loader, Some(&target.mc_version))
+            .await
+        {
+            Ok(versions) => {
+                let (status, version) =
+                    status_for(&versions, meta.version_id.as_deref(), preferences.channel);
+                item.status = status;
+                item.target = version.cloned();
+            }
+            Err(e) => log::warn!("Failed to check {} for migration: {}", meta.project_id, e),
+        }
+        items.push(item);
+    }
+
+    // Files installed by hand: resource packs can still be checked through pack.mcmeta
+    for folder in &folders {
+        let tracked: Vec<String> = installed
+            .iter()
+            .filter(|(f, ..)| f == folder)
+            .map(|(_, filename, ..)| filename.clone())
+            .collect();
+        for filename in untracked_files(instance_dir, folder, &tracked).await {
+            let status = if *folder == folders[0] && target.loader.is_none() {
+                MigrationStatus::Incompatible
+            } else if *folder == resource_packs::RESOURCE_PACKS_FOLDER {
+                let path = instance_dir.join(folder).join(&filename);
+                let mc_version = target.mc_version.clone();
+                let compatibility = tokio::task::spawn_blocking(move || {
+                    resource_packs::pack_compatibility(&path, &mc_version)
+                })
+                .await
+                .unwrap_or(PackCompatibility::Unknown);
+                match compatibility {
+                    PackCompatibility::Compatible => MigrationStatus::Compatible,
+                    PackCompatibility::TooOld | PackCompatibility::TooNew => {
+                        MigrationStatus::Incompatible
+                    }
+                    PackCompatibility::Unknown => MigrationStatus::Unknown,
+                }
+            } else {
+                MigrationStatus::Unknown
+            };
+            items.push(MigrationItem {
+                source: None,
+                project_id: None,
+                name: filename.trim_end_matches(".disabled").to_string(),
+                folder: folder.to_string(),
+                filename,
+                current_version: None,
+                status,
+                target: None,
+            });
+        }
+    }
+
+    Ok(MigrationPlan {
+        from,
+        to: target.clone(),
+        items,
+    })
+}
+
+/// Disable a file by adding `.disabled`, returns the new name
+async fn disable_file(content_dir: &Path, filename: &str) -> AppResult<String> {
+    if filename.ends_with(".disabled") {
+        return Ok(filename.to_string());
+    }
+    let new_filename = format!("{}.disabled", filename);
+    tokio::fs::rename(content_dir.join(filename), content_dir.join(&new_filename))
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to disable {}: {}", filename, e)))?;
+    Ok(new_filename)
+}
+
+/// Back up the instance, update the content that has a version for the target,
+/// disable the content that has none and switch the instance to the target
+pub async fn apply(
+    state: &AppState,
+    app: &AppHandle,
+    instance: &Instance,
+    target: &VersionTarget,
+) -> AppResult<MigrationRecord> {
+    if state
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot change version while the instance is running".to_string(),
+        ));
+    }
+
+    let instances_dir = state.get_instances_dir().await;
+    let instance_dir = instances_dir.join(&instance.game_dir);
+    let plan = plan(state, instance, &instance_dir, target).await?;
+
+    let backup = instance_backup::create_instance_backup(
+        &instances_dir,
+        &state.data_dir,
+        instance,
+        Some(app),
+    )
+    .await?;
+
+    let curseforge_key = crate::curseforge::api_key(&state.db).await;
+    let pending: Vec<MigrationItem> = plan
+        .items
+        .into_iter()
+        .filter(|item| {
+            matches!(
+                item.status,
+                MigrationStatus::Update { .. } | MigrationStatus::Incompatible
+            )
+        })
+        .collect();
+
+    // Saved before anything changes, so a failure part way can still be reverted
+    let mut record = MigrationRecord {
+        created_at: chrono::Utc::now().to_rfc3339(),
+        from: plan.from,
+        to: plan.to,
+        backup_filename: backup.filename,
+        changes: Vec::new(),
+        incomplete: true,
+    };
+    record.save(&instance_dir).await?;
+
+    let total = pending.len();
+    for (i, item) in pending.into_iter().enumerate() {
+        updates::emit_progress(app, &instance.id, i + 1, total, &item.name);
+
+        let content_dir = instance_dir.join(&item.folder);
+        let mut error = None;
+        if let (Some(source), Some(project_id), Some(version)) =
+            (item.source, &item.project_id, &item.target)
+        {
+            match updates::replace_with_version(
+                state,
+                curseforge_key.as_deref(),
+                &instance.id,
+                &instance_dir,
+                &content_dir,
+                source,
+                project_id,
+                &item.filename,
+                version,
+            )
+            .await
+            {
+                Ok(added) => {
+                    record.changes.push(MigrationChange {
+                        name: item.name,
+                        folder: item.folder,
+                        filename: item.filename,
+                        new_filename: added[0].clone(),
+                        kind: ChangeKind::Updated,
+                        error: None,
+                    });
+                    record.save(&instance_dir).await?;
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!("Failed to update {} for migration: {}", item.name, e);
+                    error = Some(e.to_string());
+                }
+            }
+        }
+
+        if item.filename.ends_with(".disabled") {
+            continue;
+        }
+        let new_filename = disable_file(&content_dir, &item.filename).await?;
+        record.changes.push(MigrationChange {
+            name: item.name,
+            folder: item.folder,
+            filename: item.filename,
+            new_filename,
+            kind: ChangeKind::Disabled,
+            error,
+        });
+        record.save(&instance_dir).await?;
+    }
+
+    change_version(
+        state,
+        app,
+        &ChangeVersionRequest {
+            instance_id: instance.id.clone(),
+            new_mc_version: target.mc_version.clone(),
+            new_loader: target.loader.clone(),
+            new_loader_version: target.loader_version.clone(),
+            mods_to_update: Vec::new(),
+        },
+    )
+    .await?;
+
+    record.incomplete = false;
+    record.save(&instance_dir).await?;
+
+    log::info!(
+        "Migrated {} from {} to {} ({} files changed, backup {})",
+        instance.name,
+        record.from.mc_version,
+        record.to.mc_version,
+        record.changes.len(),
+        record.backup_filename
+    );
+    Ok(record)
+}
+
+/// Restore the backup taken before the last migration and switch the instance back
+pub async fn revert(
+    state: &AppState,
+    app: &AppHandle,
+    instance: &Instance,
+) -> AppResult<MigrationRecord> {
+    if state
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot change version while the instance is running".to_string(),
+        ));
+    }
+
+    let instances_dir = state.get_instances_dir().await;
+    let instance_dir = instances_dir.join(&instance.game_dir);
+    let record = MigrationRecord::load(&instance_dir)
+        .await?
+        .ok_or_else(|| AppError::Instance("No version migration to revert".to_string()))?;
+
+    instance_backup::restore_instance_backup_replace(
+        &instances_dir,
+        &state.data_dir,
+        instance,
+        &record.backup_filename,
+        Some(app),
+    )
+    .await?;
+
+    // Clean the installation files of the target and store the old version
+    change_version(
+        state,
+        app,
+        &ChangeVersionRequest {
+            instance_id: instance.id.clone(),
+            new_mc_version: record.from.mc_version.clone(),
+            new_loader: record.from.loader.clone(),
+            new_loader_version: record.from.loader_version.clone(),
+            mods_to_update: Vec::new(),
+        },
+    )
+    .await?;
+
+    let _ = tokio::fs::remove_file(instance_dir.join(MIGRATION_FILE)).await;
+    log::info!(
+        "Reverted {} to {} from backup {}",
+        instance.name,
+        record.from.mc_version,
+        record.backup_filename
+    );
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::ContentFile;
+
+    fn version(id: &str, version_type: &str, downloadable: bool) -> ContentVersion {
+        ContentVersion {
+            source: ContentSource::Modrinth,
+            id: id.to_string(),
+            project_id: "p".to_string(),
+            name: id.to_string(),
+            version_number: format!("v{}", id),
+            version_type: version_type.to_string(),
+            game_versions: vec!["1.21.1".to_string()],
+            loaders: vec!["fabric".to_string()],
+            downloads: 0,
+            date_published: String::new(),
+            files: vec![ContentFile {
+                filename: format!("{}.jar", id),
+                url: downloadable.then(|| format!("https://example.com/{}.jar", id)),
+                size: 0,
+                sha1: None,
+                sha512: None,
+                primary: true,
+            }],
+            dependencies: Vec::new(),
+            changelog: None,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn test_record_without_incomplete_flag_is_complete() {
+        let json = r#"{
+            "created_at": "2026-10-18T12:00:00Z",
+            "from": {"mc_version": "1.20.1", "loader": "fabric", "loader_version": "0.15.11"},
+            "to": {"mc_version": "1.21.1", "loader": "fabric", "loader_version": "0.16.5"},
+            "backup_filename": "backup.zip",
+            "changes": []
+        }"#;
+        let record: MigrationRecord = serde_json::from_str(json).unwrap();
+        assert!(!record.incomplete);
+    }
+
+    #[test]
+    fn test_status_for() {
+        let versions = vec![
+            version("3", "beta", true),
+            version("2", "release", false),
+            version("1", "release", true),
+        ];
+
+        let (status, target) = status_for(&versions, Some("2"), UpdateChannel::Release);
+        assert_eq!(status, MigrationStatus::Compatible);
+        assert!(target.is_none());
+
+        // Newest downloadable version of the channel
+        let (status, target) = status_for(&versions, Some("0"), UpdateChannel::Release);
+        assert_eq!(
+            status,
+            MigrationStatus::Update {
+                version_id: "1".to_string(),
+                version_number: "v1".to_string()
+            }
+        );
+        assert_eq!(target.unwrap().id, "1");
+
+        let (_, target) = status_for(&versions, None, UpdateChannel::Beta);
+        assert_eq!(target.unwrap().id, "3");
+
+        let (status, _) = status_for(&versions[..2], None, UpdateChannel::Release);
+        assert_eq!(status, MigrationStatus::Incompatible);
+        let (status, _) = status_for(&[], Some("1"), UpdateChannel::Alpha);
+        assert_eq!(status, MigrationStatus::Incompatible);
+    }
+}
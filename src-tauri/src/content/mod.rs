@@ -0,0 +1,421 @@
+// Content providers (Modrinth, CurseForge) behind a common interface for
+// searching, installing, identifying and updating mods and other content
+
+pub mod curseforge;
+pub mod migration;
+pub mod modrinth;
+pub mod shader_loader;
+pub mod updates;
+
+use crate::error::{AppError, AppResult};
+use crate::instance::dedup;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Where content is downloaded from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentSource {
+    #[default]
+    Modrinth,
+    CurseForge,
+    /// Server plugins only
+    Hangar,
+}
+
+impl ContentSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Modrinth => "modrinth",
+            Self::CurseForge => "curseforge",
+            Self::Hangar => "hangar",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Modrinth => "Modrinth",
+            Self::CurseForge => "CurseForge",
+            Self::Hangar => "Hangar",
+        }
+    }
+
+    /// Source recorded in a `.meta.json` file (`None` means Modrinth)
+    pub fn from_meta(source: Option<&str>) -> Option<Self> {
+        match source {
+            None | Some("modrinth") => Some(Self::Modrinth),
+            Some("curseforge") => Some(Self::CurseForge),
+            Some("hangar") => Some(Self::Hangar),
+            _ => None,
+        }
+    }
+
+    /// Value written to the `source` field of `.meta.json`
+    pub fn meta_value(&self) -> Option<String> {
+        match self {
+            Self::Modrinth => None,
+            _ => Some(self.as_str().to_string()),
+        }
+    }
+
+    /// Whether [`provider_for`] supports this source
+    pub fn has_content_provider(&self) -> bool {
+        matches!(self, Self::Modrinth | Self::CurseForge)
+    }
+
+    /// Whether [`crate::plugins::provider_for`] supports this source
+    pub fn has_plugin_provider(&self) -> bool {
+        matches!(self, Self::Modrinth | Self::Hangar)
+    }
+}
+
+/// Search parameters shared by all providers
+#[derive(Debug, Clone, Default)]
+pub struct ContentQuery {
+    pub query: String,
+    /// Modrinth project type: mod, plugin, resourcepack, shader, datapack, modpack
+    pub project_type: String,
+    pub game_version: Option<String>,
+    pub loader: Option<String>,
+    /// Modrinth category slugs (ignored by providers without matching categories)
+    pub categories: Vec<String>,
+    /// relevance, downloads, follows, newest, updated
+    pub sort_by: Option<String>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+/// A project returned by a search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentSearchHit {
+    pub project_id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub downloads: u64,
+    pub icon_url: Option<String>,
+    pub categories: Vec<String>,
+    pub game_versions: Vec<String>,
+}
+
+/// Search response with pagination info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentSearchResponse {
+    pub hits: Vec<ContentSearchHit>,
+    pub total_hits: u32,
+    pub offset: u32,
+    pub limit: u32,
+    /// Served from a cache because the provider could not be reached
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// Project details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentProject {
+    pub source: ContentSource,
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+    /// "required", "optional" or "unsupported" when the provider reports it
+    pub client_side: Option<String>,
+    pub server_side: Option<String>,
+    pub website_url: Option<String>,
+    /// `false` when files must be downloaded by hand from the provider's website
+    pub allows_distribution: bool,
+}
+
+/// A downloadable file of a version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentFile {
+    /// `None` when the author disallows downloads from third-party launchers
+    pub url: Option<String>,
+    pub filename: String,
+    pub primary: bool,
+    pub size: u64,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
+}
+
+/// Dependency of a version on another project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentDependency {
+    pub project_id: Option<String>,
+    pub version_id: Option<String>,
+    /// "required", "optional", "incompatible" or "embedded"
+    pub dependency_type: String,
+}
+
+/// A version (Modrinth) or file (CurseForge) of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentVersion {
+    pub source: ContentSource,
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub version_number: String,
+    /// "release", "beta" or "alpha"
+    pub version_type: String,
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<String>,
+    pub downloads: u64,
+    pub date_published: String,
+    pub files: Vec<ContentFile>,
+    pub dependencies: Vec<ContentDependency>,
+    /// Release notes (Modrinth version body), not returned by CurseForge file listings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<String>,
+    /// Served from a cache because the provider could not be reached
+    #[serde(default)]
+    pub stale: bool,
+}
+
+impl ContentVersion {
+    /// The file to install: the primary one, or the first
+    pub fn primary_file(&self) -> Option<&ContentFile> {
+        self.files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| self.files.first())
+    }
+}
+
+/// Newest release, or newest version when there is no release.
+/// `versions` are sorted newest first, as providers return them
+pub fn pick_version<T>(versions: Vec<T>, version_type: impl Fn(&T) -> &str) -> Option<T> {
+    let release = versions.iter().position(|v| version_type(v) == "release");
+    versions.into_iter().nth(release.unwrap_or(0))
+}
+
+/// A source of mods, resource packs, shaders and datapacks
+#[async_trait::async_trait]
+pub trait ContentProvider: Send + Sync {
+    fn source(&self) -> ContentSource;
+
+    /// Search projects
+    async fn search_projects(&self, query: &ContentQuery) -> AppResult<ContentSearchResponse>;
+
+    /// Get project details by id (or slug on Modrinth)
+    async fn fetch_project(&self, project_id: &str) -> AppResult<ContentProject>;
+
+    /// List versions matching the loader and game version, newest first
+    async fn fetch_versions(
+        &self,
+        project_id: &str,
+        loader: Option<&str>,
+        game_version: Option<&str>,
+    ) -> AppResult<Vec<ContentVersion>>;
+
+    /// Get a single version of a project
+    async fn fetch_version(&self, project_id: &str, version_id: &str) -> AppResult<ContentVersion>;
+
+    /// Identify a file from its contents (SHA-512 on Modrinth, murmur2 fingerprint on CurseForge)
+    async fn identify_file(&self, bytes: &[u8]) -> AppResult<Option<ContentVersion>>;
+}
+
+/// Create the provider for a content source.
+/// CurseForge needs an API key, see [`crate::curseforge::api_key`]
+pub fn provider_for<'a>(
+    source: ContentSource,
+    http_client: &'a reqwest::Client,
+    curseforge_api_key: Option<&str>,
+) -> AppResult<Box<dyn ContentProvider + 'a>> {
+    match source {
+        ContentSource::Modrinth => Ok(Box::new(crate::modrinth::ModrinthClient::new(http_client))),
+        ContentSource::CurseForge => {
+            let api_key = curseforge_api_key.ok_or_else(|| {
+                AppError::Network(
+                    "CurseForge is not configured: add an API key in the settings".to_string(),
+                )
+            })?;
+            Ok(Box::new(crate::curseforge::CurseForgeClient::new(
+                http_client,
+                api_key.to_string(),
+            )))
+        }
+        ContentSource::Hangar => Err(AppError::Network(
+            "Hangar only provides server plugins".to_string(),
+        )),
+    }
+}
+
+/// Create the provider for a content source, reading the CurseForge API key from the settings
+pub async fn provider_for_state(
+    state: &AppState,
+    source: ContentSource,
+) -> AppResult<Box<dyn ContentProvider + '_>> {
+    let api_key = match source {
+        ContentSource::CurseForge => crate::curseforge::api_key(&state.db).await,
+        ContentSource::Modrinth | ContentSource::Hangar => None,
+    };
+    provider_for(source, &state.http_client, api_key.as_deref())
+}
+
+/// Error shown when a file cannot be downloaded through the API
+pub fn manual_download_error(project: &ContentProject, version_id: &str, folder: &str) -> AppError {
+    let page = project
+        .website_url
+        .as_ref()
+        .map(|url| format!("{}/files/{}", url.trim_end_matches('/'), version_id))
+        .unwrap_or_else(|| format!("the {} website", project.source.display_name()));
+
+    AppError::Download(format!(
+        "{} does not allow downloads from third-party launchers. Download it from {} and add it to the {} folder, or select the downloaded file to install it",
+        project.title, page, folder
+    ))
+}
+
+/// Checksums a provider published for a file
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileHashes<'a> {
+    pub sha512: Option<&'a str>,
+    pub sha256: Option<&'a str>,
+    pub sha1: Option<&'a str>,
+}
+
+impl FileHashes<'_> {
+    pub fn is_empty(&self) -> bool {
+        self.sha512.is_none() && self.sha256.is_none() && self.sha1.is_none()
+    }
+}
+
+impl ContentFile {
+    pub fn hashes(&self) -> FileHashes<'_> {
+        FileHashes {
+            sha512: self.sha512.as_deref(),
+            sha256: None,
+            sha1: self.sha1.as_deref(),
+        }
+    }
+}
+
+/// Download a content file, checking the download domain and the strongest available hash
+pub async fn download_content_file(
+    http_client: &reqwest::Client,
+    file: &ContentFile,
+    dest_path: &Path,
+) -> AppResult<()> {
+    let url = file
+        .url
+        .as_deref()
+        .ok_or_else(|| AppError::Download(format!("{} has no download URL", file.filename)))?;
+    download_verified_file(http_client, url, &file.filename, file.hashes(), dest_path).await
+}
+
+/// Download a file from an allowed domain and write it only if it matches its hash
+pub async fn download_verified_file(
+    http_client: &reqwest::Client,
+    url: &str,
+    filename: &str,
+    hashes: FileHashes<'_>,
+    dest_path: &Path,
+) -> AppResult<()> {
+    crate::modrinth::validate_download_url(url).map_err(|e| AppError::Network(e.to_string()))?;
+
+    let response = http_client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::Download(format!(
+            "Download returned status {}",
+            response.status()
+        )));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    verify_file_hash(filename, hashes, &bytes)?;
+
+    dedup::unlink_shared(dest_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", filename, e)))?;
+    tokio::fs::write(dest_path, &bytes)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", filename, e)))
+}
+
+/// Check bytes against the strongest hash the provider gave.
+/// Files without any hash are rejected, they cannot be verified
+pub fn verify_file_hash(filename: &str, hashes: FileHashes<'_>, bytes: &[u8]) -> AppResult<()> {
+    use sha2::Digest;
+    let (expected, actual) = if let Some(expected) = hashes.sha512 {
+        (expected, hex::encode(sha2::Sha512::digest(bytes)))
+    } else if let Some(expected) = hashes.sha256 {
+        (expected, hex::encode(sha2::Sha256::digest(bytes)))
+    } else if let Some(expected) = hashes.sha1 {
+        (expected, hex::encode(sha1::Sha1::digest(bytes)))
+    } else {
+        return Err(AppError::Download(format!(
+            "No checksum available for {}",
+            filename
+        )));
+    };
+
+    if !expected.eq_ignore_ascii_case(&actual) {
+        return Err(AppError::Download(format!(
+            "Hash mismatch for {}: expected {}, got {}",
+            filename, expected, actual
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_meta_round_trip() {
+        assert_eq!(
+            ContentSource::from_meta(None),
+            Some(ContentSource::Modrinth)
+        );
+        assert_eq!(
+            ContentSource::from_meta(Some("curseforge")),
+            Some(ContentSource::CurseForge)
+        );
+        assert_eq!(
+            ContentSource::from_meta(Some("hangar")),
+            Some(ContentSource::Hangar)
+        );
+        assert_eq!(ContentSource::from_meta(Some("spiget")), None);
+        assert_eq!(ContentSource::Modrinth.meta_value(), None);
+        assert_eq!(
+            ContentSource::CurseForge.meta_value().as_deref(),
+            Some("curseforge")
+        );
+        assert_eq!(
+            ContentSource::Hangar.meta_value().as_deref(),
+            Some("hangar")
+        );
+    }
+
+    #[test]
+    fn test_verify_file_hash() {
+        let mut file = ContentFile {
+            url: None,
+            filename: "a.jar".to_string(),
+            primary: true,
+            size: 3,
+            sha1: Some("A9993E364706816ABA3E25717850C26C9CD0D89D".to_string()),
+            sha512: None,
+        };
+        assert!(verify_file_hash(&file.filename, file.hashes(), b"abc").is_ok());
+        assert!(verify_file_hash(&file.filename, file.hashes(), b"abd").is_err());
+
+        // Nothing to check against
+        file.sha1 = None;
+        assert!(verify_file_hash(&file.filename, file.hashes(), b"abc").is_err());
+    }
+}
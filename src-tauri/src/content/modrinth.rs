@@ -0,0 +1,173 @@
+// Modrinth as a content provider
+
+use super::{
+    ContentDependency, ContentFile, ContentProject, ContentProvider, ContentQuery,
+    ContentSearchHit, ContentSearchResponse, ContentSource, ContentVersion,
+};
+use crate::error::{AppError, AppResult};
+use crate::modrinth::{build_facets, ModrinthClient, SearchQuery, Version};
+
+impl From<Version> for ContentVersion {
+    fn from(v: Version) -> Self {
+        Self {
+            source: ContentSource::Modrinth,
+            id: v.id,
+            project_id: v.project_id,
+            name: v.name,
+            version_number: v.version_number,
+            version_type: v.version_type,
+            changelog: v.changelog,
+            stale: v.stale,
+            game_versions: v.game_versions,
+            loaders: v.loaders,
+            downloads: v.downloads,
+            date_published: v.date_published,
+            files: v
+                .files
+                .into_iter()
+                .map(|f| ContentFile {
+                    url: Some(f.url),
+                    filename: f.filename,
+                    primary: f.primary,
+                    size: f.size,
+                    sha1: Some(f.hashes.sha1),
+                    sha512: Some(f.hashes.sha512),
+                })
+                .collect(),
+            dependencies: v
+                .dependencies
+                .into_iter()
+                .map(|d| ContentDependency {
+                    project_id: d.project_id,
+                    version_id: d.version_id,
+                    dependency_type: d.dependency_type,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ContentProvider for ModrinthClient<'_> {
+    fn source(&self) -> ContentSource {
+        ContentSource::Modrinth
+    }
+
+    async fn search_projects(&self, query: &ContentQuery) -> AppResult<ContentSearchResponse> {
+        let game_versions = query.game_version.as_deref().map(|v| vec![v]);
+        let categories: Vec<&str> = query.categories.iter().map(|c| c.as_str()).collect();
+
+        // Only mods and plugins are filtered by loader
+        let loaders = match query.project_type.as_str() {
+            "mod" | "plugin" => query.loader.as_deref().map(|l| vec![l]),
+            _ => None,
+        };
+
+        let facets = build_facets(
+            Some(&query.project_type),
+            (!categories.is_empty()).then_some(categories.as_slice()),
+            game_versions.as_deref(),
+            loaders.as_deref(),
+        );
+
+        let mut search_query = SearchQuery::new(&query.query)
+            .with_facets(&facets)
+            .with_index(query.sort_by.as_deref().unwrap_or("relevance"));
+        if let Some(offset) = query.offset {
+            search_query = search_query.with_offset(offset);
+        }
+        if let Some(limit) = query.limit {
+            search_query = search_query.with_limit(limit);
+        }
+
+        let response = self
+            .search(&search_query)
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(ContentSearchResponse {
+            hits: response
+                .hits
+                .into_iter()
+                .map(|hit| ContentSearchHit {
+                    project_id: hit.project_id,
+                    slug: hit.slug,
+                    title: hit.title,
+                    description: hit.description,
+                    author: hit.author,
+                    downloads: hit.downloads,
+                    icon_url: hit.icon_url,
+                    categories: hit.categories,
+                    game_versions: hit.versions,
+                })
+                .collect(),
+            total_hits: response.total_hits,
+            offset: response.offset,
+            limit: response.limit,
+            stale: response.stale,
+        })
+    }
+
+    async fn fetch_project(&self, project_id: &str) -> AppResult<ContentProject> {
+        let project = self
+            .get_project(project_id)
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(ContentProject {
+            source: ContentSource::Modrinth,
+            website_url: Some(format!(
+                "https://modrinth.com/{}/{}",
+                project.project_type, project.slug
+            )),
+            id: project.id,
+            slug: project.slug,
+            title: project.title,
+            description: project.description,
+            icon_url: project.icon_url,
+            client_side: Some(project.client_side),
+            server_side: Some(project.server_side),
+            allows_distribution: true,
+        })
+    }
+
+    async fn fetch_versions(
+        &self,
+        project_id: &str,
+        loader: Option<&str>,
+        game_version: Option<&str>,
+    ) -> AppResult<Vec<ContentVersion>> {
+        let loader = loader.map(|l| l.to_lowercase());
+        let loaders = loader.as_deref().map(|l| vec![l]);
+        let game_versions = game_version.map(|v| vec![v]);
+
+        let versions = self
+            .get_project_versions(project_id, loaders.as_deref(), game_versions.as_deref())
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(versions.into_iter().map(ContentVersion::from).collect())
+    }
+
+    async fn fetch_version(
+        &self,
+        _project_id: &str,
+        version_id: &str,
+    ) -> AppResult<ContentVersion> {
+        self.get_version(version_id)
+            .await
+            .map(ContentVersion::from)
+            .map_err(|e| AppError::Network(e.to_string()))
+    }
+
+    async fn identify_file(&self, bytes: &[u8]) -> AppResult<Option<ContentVersion>> {
+        use sha2::Digest;
+        let sha512 = hex::encode(sha2::Sha512::digest(bytes));
+
+        match self.get_version_by_hash(&sha512).await {
+            Ok(version) => Ok(Some(version.into())),
+            Err(crate::modrinth::ModrinthError::Api(_)) => Ok(None),
+            Err(e) => Err(AppError::Network(e.to_string())),
+        }
+    }
+}
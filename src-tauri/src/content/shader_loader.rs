@@ -0,0 +1,348 @@
+// Shader packs only work with a shader loader mod: Iris on Fabric/Quilt (and
+// NeoForge on recent versions), Oculus on Forge/NeoForge. Detects the one
+// installed in an instance and resolves a compatible one from Modrinth
+
+use super::{pick_version, ContentProvider};
+use crate::error::AppResult;
+use crate::instance::mod_profiles;
+use crate::modrinth::commands::ModMetadata;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A mod that loads shader packs
+pub struct ShaderLoader {
+    pub name: &'static str,
+    /// Modrinth project id
+    pub project_id: &'static str,
+    /// Mod loaders the project publishes builds for
+    pub loaders: &'static [&'static str],
+    /// Start of the jar names, to recognize jars installed without metadata
+    filename_prefix: &'static str,
+}
+
+/// Known shader loaders, preferred first
+pub const SHADER_LOADERS: &[ShaderLoader] = &[
+    ShaderLoader {
+        name: "Iris",
+        project_id: "YL57xq9U",
+        loaders: &["fabric", "quilt", "neoforge"],
+        filename_prefix: "iris-",
+    },
+    ShaderLoader {
+        name: "Oculus",
+        project_id: "GchcoXML",
+        loaders: &["forge", "neoforge"],
+        filename_prefix: "oculus-",
+    },
+];
+
+/// Shader loaders that can run on a mod loader, preferred first
+pub fn candidates(loader: &str) -> Vec<&'static ShaderLoader> {
+    let loader = loader.to_lowercase();
+    SHADER_LOADERS
+        .iter()
+        .filter(|s| s.loaders.contains(&loader.as_str()))
+        .collect()
+}
+
+/// The shader loader a jar is, from its Modrinth project id or its name
+fn identify(filename: &str, project_id: Option<&str>) -> Option<&'static ShaderLoader> {
+    let filename = filename.to_lowercase();
+    SHADER_LOADERS
+        .iter()
+        .find(|s| project_id == Some(s.project_id) || filename.starts_with(s.filename_prefix))
+}
+
+/// Whether shader packs can work in an instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShaderLoaderState {
+    /// A shader loader is installed and enabled
+    Enabled,
+    /// A shader loader is installed but disabled, shader packs do nothing
+    Disabled,
+    /// No shader loader is installed
+    Missing,
+    /// No shader pack is installed, so a missing or disabled shader loader does not matter
+    NoShaderPacks,
+    /// Servers, vanilla and loaders without a shader loader
+    Unsupported,
+}
+
+/// A shader loader jar found in the mods folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledShaderLoader {
+    pub name: String,
+    /// Jar name, without `.disabled`
+    pub filename: String,
+    pub enabled: bool,
+}
+
+/// What the mods folder contains, as far as shader loaders are concerned
+#[derive(Debug, Clone, Default)]
+pub struct ModsScan {
+    /// Project ids from the `.meta.json` files
+    pub project_ids: HashSet<String>,
+    /// The installed shader loader, an enabled one if there are several
+    pub shader_loader: Option<InstalledShaderLoader>,
+}
+
+impl ModsScan {
+    pub fn state(&self) -> ShaderLoaderState {
+        match &self.shader_loader {
+            Some(installed) if installed.enabled => ShaderLoaderState::Enabled,
+            Some(_) => ShaderLoaderState::Disabled,
+            None => ShaderLoaderState::Missing,
+        }
+    }
+}
+
+/// Whether a shaderpacks folder holds a pack, zipped or unpacked.
+/// Iris and Oculus keep pack settings next to them as `<pack>.txt`
+pub async fn has_shader_packs(shaderpacks_dir: &Path) -> bool {
+    let Ok(mut entries) = tokio::fs::read_dir(shaderpacks_dir).await else {
+        return false;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") || entry.path().is_dir() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Look for a shader loader in a mods folder
+pub async fn scan(mods_dir: &Path) -> AppResult<ModsScan> {
+    let mut result = ModsScan::default();
+
+    for (filename, enabled) in mod_profiles::scan_mods(mods_dir).await? {
+        let meta_path = mods_dir.join(format!("{}.meta.json", filename.trim_end_matches(".jar")));
+        let project_id = tokio::fs::read_to_string(&meta_path)
+            .await
+            .ok()
+            .and_then(|json| serde_json::from_str::<ModMetadata>(&json).ok())
+            .map(|meta| meta.project_id);
+
+        if let Some(loader) = identify(&filename, project_id.as_deref()) {
+            let keep_previous = result
+                .shader_loader
+                .as_ref()
+                .is_some_and(|previous| previous.enabled);
+            if !keep_previous {
+                result.shader_loader = Some(InstalledShaderLoader {
+                    name: loader.name.to_string(),
+                    filename: filename.clone(),
+                    enabled,
+                });
+            }
+        }
+        result.project_ids.extend(project_id);
+    }
+
+    Ok(result)
+}
+
+/// A version of a project to install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfferedVersion {
+    pub project_id: String,
+    pub title: String,
+    pub version_id: String,
+    pub version_number: String,
+}
+
+/// A shader loader version compatible with an instance, with its required dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShaderLoaderOffer {
+    pub shader_loader: OfferedVersion,
+    pub dependencies: Vec<OfferedVersion>,
+}
+
+impl ShaderLoaderOffer {
+    /// (project id, version id) pairs to install, leaving out installed projects
+    pub fn to_install(&self, installed: &HashSet<String>) -> Vec<(String, String)> {
+        std::iter::once(&self.shader_loader)
+            .chain(&self.dependencies)
+            .filter(|v| !installed.contains(&v.project_id))
+            .map(|v| (v.project_id.clone(), v.version_id.clone()))
+            .collect()
+    }
+}
+
+/// Latest compatible version of a project
+async fn resolve_project(
+    provider: &dyn ContentProvider,
+    project_id: &str,
+    mc_version: &str,
+    loader: &str,
+) -> AppResult<Option<OfferedVersion>> {
+    let versions = provider
+        .fetch_versions(project_id, Some(loader), Some(mc_version))
+        .await?;
+    let Some(version) = pick_version(versions, |v| &v.version_type) else {
+        return Ok(None);
+    };
+    let project = provider.fetch_project(project_id).await?;
+
+    Ok(Some(OfferedVersion {
+        project_id: project.id,
+        title: project.title,
+        version_id: version.id,
+        version_number: version.version_number,
+    }))
+}
+
+/// Find the shader loader to offer for a Minecraft version and mod loader.
+/// Candidates without a build for the version, or with a required
+/// dependency that has none, are skipped
+pub async fn resolve(
+    provider: &dyn ContentProvider,
+    mc_version: &str,
+    loader: &str,
+) -> AppResult<Option<ShaderLoaderOffer>> {
+    let loader = loader.to_lowercase();
+
+    'candidates: for candidate in candidates(&loader) {
+        let versions = provider
+            .fetch_versions(candidate.project_id, Some(&loader), Some(mc_version))
+            .await?;
+        let Some(version) = pick_version(versions, |v| &v.version_type) else {
+            continue;
+        };
+
+        let mut dependencies = Vec::new();
+        for dep in version
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == "required")
+        {
+            let resolved = match (&dep.project_id, &dep.version_id) {
+                (Some(project_id), Some(version_id)) => {
+                    let project = provider.fetch_project(project_id).await?;
+                    let pinned = provider.fetch_version(project_id, version_id).await?;
+                    Some(OfferedVersion {
+                        project_id: project.id,
+                        title: project.title,
+                        version_id: pinned.id,
+                        version_number: pinned.version_number,
+                    })
+                }
+                (Some(project_id), None) => {
+                    resolve_project(provider, project_id, mc_version, &loader).await?
+                }
+                _ => None,
+            };
+            match resolved {
+                Some(resolved) => dependencies.push(resolved),
+                None => continue 'candidates,
+            }
+        }
+
+        let project = provider.fetch_project(candidate.project_id).await?;
+        return Ok(Some(ShaderLoaderOffer {
+            shader_loader: OfferedVersion {
+                project_id: project.id,
+                title: project.title,
+                version_id: version.id,
+                version_number: version.version_number,
+            },
+            dependencies,
+        }));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates() {
+        let names = |loader| {
+            candidates(loader)
+                .iter()
+                .map(|s| s.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("fabric"), vec!["Iris"]);
+        assert_eq!(names("Quilt"), vec!["Iris"]);
+        assert_eq!(names("forge"), vec!["Oculus"]);
+        assert_eq!(names("neoforge"), vec!["Iris", "Oculus"]);
+        assert!(names("paper").is_empty());
+    }
+
+    #[test]
+    fn test_identify() {
+        assert_eq!(
+            identify("iris-fabric-1.7.2+mc1.21.jar", None).map(|s| s.name),
+            Some("Iris")
+        );
+        assert_eq!(
+            identify("Oculus-mc1.20.1-1.6.9.jar", None).map(|s| s.name),
+            Some("Oculus")
+        );
+        assert_eq!(
+            identify("shaders.jar", Some("YL57xq9U")).map(|s| s.name),
+            Some("Iris")
+        );
+        assert!(identify("sodium-fabric-0.5.8.jar", Some("AANobbMI")).is_none());
+        assert!(identify("irisflower-1.0.jar", None).is_none());
+    }
+
+    #[test]
+    fn test_to_install_skips_installed() {
+        let offered = |project_id: &str| OfferedVersion {
+            project_id: project_id.to_string(),
+            title: project_id.to_string(),
+            version_id: format!("{}-v", project_id),
+            version_number: "1.0".to_string(),
+        };
+        let offer = ShaderLoaderOffer {
+            shader_loader: offered("iris"),
+            dependencies: vec![offered("sodium")],
+        };
+
+        let installed = HashSet::from(["sodium".to_string()]);
+        assert_eq!(
+            offer.to_install(&installed),
+            vec![("iris".to_string(), "iris-v".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_prefers_enabled_loader() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("iris-1.6.jar.disabled"), b"").unwrap();
+        std::fs::write(dir.path().join("iris-1.7.jar"), b"").unwrap();
+        std::fs::write(dir.path().join("sodium-0.5.jar"), b"").unwrap();
+
+        let scan = scan(dir.path()).await.unwrap();
+        assert_eq!(scan.state(), ShaderLoaderState::Enabled);
+        assert_eq!(scan.shader_loader.unwrap().filename, "iris-1.7.jar");
+
+        let empty = tempfile::tempdir().unwrap();
+        assert_eq!(
+            super::scan(empty.path()).await.unwrap().state(),
+            ShaderLoaderState::Missing
+        );
+    }
+
+    #[tokio::test]
+    async fn test_has_shader_packs() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!has_shader_packs(&dir.path().join("shaderpacks")).await);
+
+        std::fs::write(dir.path().join("BSL.zip.txt"), b"").unwrap();
+        assert!(!has_shader_packs(dir.path()).await);
+
+        std::fs::write(dir.path().join("BSL.zip"), b"").unwrap();
+        assert!(has_shader_packs(dir.path()).await);
+
+        let unpacked = tempfile::tempdir().unwrap();
+        std::fs::create_dir(unpacked.path().join("Complementary")).unwrap();
+        assert!(has_shader_packs(unpacked.path()).await);
+    }
+}
@@ -0,0 +1,894 @@
+// Updating all the content of an instance at once: release-channel preferences,
+// per-project pins, changelogs and a snapshot to roll the update back
+
+use super::{ContentSource, ContentVersion};
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::instance::{dedup, malware_scan};
+use crate::modrinth::commands::{find_content_file, ModMetadata};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// Per-instance update preferences, stored in the instance folder
+pub const PREFERENCES_FILE: &str = "update-preferences.json";
+
+/// Folder holding the snapshots taken before bulk updates
+const SNAPSHOTS_FOLDER: &str = ".update-snapshots";
+const SNAPSHOT_MANIFEST: &str = "snapshot.json";
+
+/// Older snapshots are deleted once there are more than this
+const MAX_SNAPSHOTS: usize = 3;
+
+/// Least stable versions an instance accepts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Release,
+    Beta,
+    Alpha,
+}
+
+impl UpdateChannel {
+    /// Whether a version type ("release", "beta", "alpha") is accepted
+    pub fn allows(&self, version_type: &str) -> bool {
+        match version_type {
+            "release" => true,
+            "beta" => matches!(self, Self::Beta | Self::Alpha),
+            _ => *self == Self::Alpha,
+        }
+    }
+}
+
+/// What bulk updates may do with a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UpdatePin {
+    /// Never update
+    Hold,
+    /// Stay on this version, reinstalling it if the file was changed
+    Version { version_id: String },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatePreferences {
+    #[serde(default)]
+    pub channel: UpdateChannel,
+    /// Pins by project id
+    #[serde(default)]
+    pub pins: BTreeMap<String, UpdatePin>,
+}
+
+impl UpdatePreferences {
+    pub async fn load(instance_dir: &Path) -> AppResult<Self> {
+        match tokio::fs::read_to_string(instance_dir.join(PREFERENCES_FILE)).await {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(AppError::Io(format!(
+                "Failed to read update preferences: {}",
+                e
+            ))),
+        }
+    }
+
+    pub async fn save(&self, instance_dir: &Path) -> AppResult<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(instance_dir.join(PREFERENCES_FILE), json)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to save update preferences: {}", e)))
+    }
+}
+
+/// Why a project is left out of a bulk update
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    Held,
+    Pinned,
+    PinnedVersionUnavailable,
+    ManualDownload,
+    Unavailable,
+}
+
+/// A release in the changelog of an update
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogEntry {
+    pub version_number: String,
+    pub version_type: String,
+    pub date_published: String,
+    pub changelog: Option<String>,
+}
+
+/// An update a bulk update will apply (or applied)
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedUpdate {
+    pub source: ContentSource,
+    pub project_id: String,
+    pub name: String,
+    pub folder: String,
+    pub filename: String,
+    pub current_version: String,
+    pub target_version: String,
+    pub target_version_id: String,
+    /// Releases between the installed version and the target, newest first
+    pub changelog: Vec<ChangelogEntry>,
+    #[serde(skip)]
+    target: Option<ContentVersion>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedUpdate {
+    pub project_id: String,
+    pub name: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedUpdate {
+    pub project_id: String,
+    pub name: String,
+    pub error: String,
+}
+
+/// What a bulk update would do
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkUpdatePlan {
+    pub channel: UpdateChannel,
+    pub updates: Vec<PlannedUpdate>,
+    pub skipped: Vec<SkippedUpdate>,
+}
+
+/// What a bulk update did
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkUpdateSummary {
+    /// Snapshot to pass to the rollback, `None` when nothing was updated
+    pub snapshot_id: Option<String>,
+    pub updated: Vec<PlannedUpdate>,
+    pub skipped: Vec<SkippedUpdate>,
+    pub failed: Vec<FailedUpdate>,
+}
+
+/// Files replaced by one update: the originals are kept in the snapshot,
+/// the added ones are deleted on rollback
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SnapshotEntry {
+    folder: String,
+    removed: Vec<String>,
+    added: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotManifest {
+    id: String,
+    created_at: String,
+    entries: Vec<SnapshotEntry>,
+}
+
+/// A snapshot that can be rolled back to
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateSnapshotInfo {
+    pub id: String,
+    pub created_at: String,
+    pub files: usize,
+}
+
+/// The version a bulk update should install, `Ok(None)` when the installed one is current.
+/// `versions` are newest first, as returned by the providers
+pub fn select_update<'a>(
+    versions: &'a [ContentVersion],
+    current_version_id: Option<&str>,
+    channel: UpdateChannel,
+    pin: Option<&UpdatePin>,
+) -> Result<Option<&'a ContentVersion>, SkipReason> {
+    match pin {
+        Some(UpdatePin::Hold) => return Err(SkipReason::Held),
+        Some(UpdatePin::Version { version_id }) => {
+            if current_version_id == Some(version_id.as_str()) {
+                return Err(SkipReason::Pinned);
+            }
+            return versions
+                .iter()
+                .find(|v| v.id == *version_id)
+                .map(Some)
+                .ok_or(SkipReason::PinnedVersionUnavailable);
+        }
+        None => {}
+    }
+
+    let Some((newest_pos, newest)) = versions
+        .iter()
+        .enumerate()
+        .find(|(_, v)| channel.allows(&v.version_type))
+    else {
+        return Ok(None);
+    };
+
+    // Never go back from a newer (e.g. beta) version to an older release
+    let current_pos = current_version_id.and_then(|id| versions.iter().position(|v| v.id == id));
+    match current_pos {
+        Some(pos) if pos <= newest_pos => Ok(None),
+        _ => Ok(Some(newest)),
+    }
+}
+
+/// Releases from the target back to (but not including) the installed version
+pub fn changelog_between(
+    versions: &[ContentVersion],
+    current_version_id: Option<&str>,
+    target: &ContentVersion,
+    channel: UpdateChannel,
+) -> Vec<ChangelogEntry> {
+    let entry = |v: &ContentVersion| ChangelogEntry {
+        version_number: v.version_number.clone(),
+        version_type: v.version_type.clone(),
+        date_published: v.date_published.clone(),
+        changelog: v.changelog.clone(),
+    };
+
+    let target_pos = versions.iter().position(|v| v.id == target.id);
+    let current_pos = current_version_id.and_then(|id| versions.iter().position(|v| v.id == id));
+    match (target_pos, current_pos) {
+        (Some(start), Some(end)) if start < end => versions[start..end]
+            .iter()
+            .filter(|v| v.id == target.id || channel.allows(&v.version_type))
+            .map(entry)
+            .collect(),
+        _ => vec![entry(target)],
+    }
+}
+
+/// Installed content with metadata from a content provider, by folder
+pub(super) async fn installed_content(
+    instance_dir: &Path,
+    folders: &[&'static str],
+) -> Vec<(&'static str, String, ModMetadata, ContentSource)> {
+    let mut installed = Vec::new();
+    for folder in folders {
+        let content_dir = instance_dir.join(folder);
+        let Ok(mut entries) = tokio::fs::read_dir(&content_dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let Some(base_name) = filename.strip_suffix(".meta.json") else {
+                continue;
+            };
+            let Ok(contents) = tokio::fs::read_to_string(entry.path()).await else {
+                continue;
+            };
+            let Ok(meta) = serde_json::from_str::<ModMetadata>(&contents) else {
+                continue;
+            };
+            let Some(source) = ContentSource::from_meta(meta.source.as_deref())
+                .filter(ContentSource::has_content_provider)
+            else {
+                continue;
+            };
+            if let Some(file) = find_content_file(&content_dir, base_name) {
+                installed.push((*folder, file, meta, source));
+            }
+        }
+    }
+    installed.sort_by(|a, b| a.2.name.to_lowercase().cmp(&b.2.name.to_lowercase()));
+    installed
+}
+
+/// Find the update of every installed project that the preferences allow
+pub async fn plan(
+    state: &AppState,
+    instance: &Instance,
+    instance_dir: &Path,
+) -> AppResult<BulkUpdatePlan> {
+    let preferences = UpdatePreferences::load(instance_dir).await?;
+    let curseforge_key = crate::curseforge::api_key(&state.db).await;
+    let folders = crate::modpacks::lockfile::content_folders(instance);
+
+    let mut plan = BulkUpdatePlan {
+        channel: preferences.channel,
+        ..Default::default()
+    };
+
+    for (folder, filename, meta, source) in installed_content(instance_dir, &folders).await {
+        let skip = |reason| SkippedUpdate {
+            project_id: meta.project_id.clone(),
+            name: meta.name.clone(),
+            reason,
+        };
+
+        let pin = preferences.pins.get(&meta.project_id);
+        if pin == Some(&UpdatePin::Hold) {
+            plan.skipped.push(skip(SkipReason::Held));
+            continue;
+        }
+
+        let provider =
+            match super::provider_for(source, &state.http_client, curseforge_key.as_deref()) {
+                Ok(provider) => provider,
+                Err(e) => {
+                    log::warn!("Cannot check updates for {}: {}", meta.project_id, e);
+                    plan.skipped.push(skip(SkipReason::Unavailable));
+                    continue;
+                }
+            };
+
+        // Only mods and plugins are filtered by loader
+        let loader = if folder == folders[0] {
+            instance.loader.as_deref()
+        } else {
+            None
+        };
+        let versions = match provider
+            .fetch_versions(&meta.project_id, loader, Some(&instance.mc_version))
+            .await
+        {
+            Ok(versions) => versions,
+            Err(e) => {
+                log::warn!("Failed to check updates for {}: {}", meta.project_id, e);
+                plan.skipped.push(skip(SkipReason::Unavailable));
+                continue;
+            }
+        };
+
+        let current_version_id = meta.version_id.as_deref();
+        let target = match select_update(&versions, current_version_id, preferences.channel, pin) {
+            Ok(Some(target)) => target,
+            Ok(None) => continue,
+            Err(reason) => {
+                plan.skipped.push(skip(reason));
+                continue;
+            }
+        };
+
+        if target.primary_file().and_then(|f| f.url.as_ref()).is_none() {
+            plan.skipped.push(skip(SkipReason::ManualDownload));
+            continue;
+        }
+
+        plan.updates.push(PlannedUpdate {
+            source,
+            project_id: meta.project_id.clone(),
+            name: meta.name.clone(),
+            folder: folder.to_string(),
+            filename,
+            current_version: meta.version.clone(),
+            target_version: target.version_number.clone(),
+            target_version_id: target.id.clone(),
+            changelog: changelog_between(
+                &versions,
+                current_version_id,
+                target,
+                preferences.channel,
+            ),
+            target: Some(target.clone()),
+        });
+    }
+
+    Ok(plan)
+}
+
+fn meta_filename(filename: &str) -> String {
+    let base = filename
+        .trim_end_matches(".disabled")
+        .trim_end_matches(".jar")
+        .trim_end_matches(".zip");
+    format!("{}.meta.json", base)
+}
+
+fn snapshots_dir(instance_dir: &Path) -> PathBuf {
+    instance_dir.join(SNAPSHOTS_FOLDER)
+}
+
+async fn save_manifest(snapshot_dir: &Path, manifest: &SnapshotManifest) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(manifest)?;
+    tokio::fs::write(snapshot_dir.join(SNAPSHOT_MANIFEST), json)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to save update snapshot: {}", e)))
+}
+
+/// Create the directory of a new snapshot. Ids are timestamps, a counter is
+/// appended when a snapshot with the same timestamp already exists
+async fn create_snapshot_dir(instance_dir: &Path) -> AppResult<(String, PathBuf)> {
+    let create_error =
+        |e: std::io::Error| AppError::Io(format!("Failed to create update snapshot: {}", e));
+    tokio::fs::create_dir_all(snapshots_dir(instance_dir))
+        .await
+        .map_err(create_error)?;
+
+    let base = chrono::Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+    let mut id = base.clone();
+    let mut attempt = 1;
+    loop {
+        let dir = snapshots_dir(instance_dir).join(&id);
+        match tokio::fs::create_dir(&dir).await {
+            Ok(()) => return Ok((id, dir)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                attempt += 1;
+                id = format!("{}-{}", base, attempt);
+            }
+            Err(e) => return Err(create_error(e)),
+        }
+    }
+}
+
+/// Delete all but the newest snapshots
+async fn prune_snapshots(instance_dir: &Path) {
+    let mut ids = list_snapshot_ids(instance_dir).await;
+    while ids.len() > MAX_SNAPSHOTS {
+        let oldest = ids.remove(0);
+        let _ = tokio::fs::remove_dir_all(snapshots_dir(instance_dir).join(oldest)).await;
+    }
+}
+
+/// Snapshot ids, oldest first (ids are timestamps)
+async fn list_snapshot_ids(instance_dir: &Path) -> Vec<String> {
+    let mut ids = Vec::new();
+    if let Ok(mut entries) = tokio::fs::read_dir(snapshots_dir(instance_dir)).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.path().join(SNAPSHOT_MANIFEST).exists() {
+                ids.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    ids.sort();
+    ids
+}
+
+pub(super) fn emit_progress(
+    app: &AppHandle,
+    instance_id: &str,
+    current: usize,
+    total: usize,
+    name: &str,
+) {
+    let _ = app.emit(
+        "content-update-progress",
+        serde_json::json!({
+            "instance_id": instance_id,
+            "current": current,
+            "total": total,
+            "name": name
+        }),
+    );
+}
+
+/// Replace one file with its update, keeping it disabled if it was
+async fn apply_update(
+    state: &AppState,
+    curseforge_key: Option<&str>,
+    instance_id: &str,
+    instance_dir: &Path,
+    update: &PlannedUpdate,
+) -> AppResult<Vec<String>> {
+    let version = update
+        .target
+        .as_ref()
+        .ok_or_else(|| AppError::Instance("Update target is missing".to_string()))?;
+    replace_with_version(
+        state,
+        curseforge_key,
+        instance_id,
+        instance_dir,
+        &instance_dir.join(&update.folder),
+        update.source,
+        &update.project_id,
+        &update.filename,
+        version,
+    )
+    .await
+}
+
+/// Replace an installed file with another version of its project, keeping it
+/// disabled if it was. Returns the names of the new file and its metadata
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn replace_with_version(
+    state: &AppState,
+    curseforge_key: Option<&str>,
+    instance_id: &str,
+    instance_dir: &Path,
+    content_dir: &Path,
+    source: ContentSource,
+    project_id: &str,
+    filename: &str,
+    version: &ContentVersion,
+) -> AppResult<Vec<String>> {
+    let file = version
+        .primary_file()
+        .ok_or_else(|| AppError::Instance("No files found for this version".to_string()))?;
+
+    let provider = super::provider_for(source, &state.http_client, curseforge_key)?;
+    let project = provider.fetch_project(project_id).await?;
+
+    let disabled = filename.ends_with(".disabled");
+    let new_filename = if disabled {
+        format!("{}.disabled", file.filename)
+    } else {
+        file.filename.clone()
+    };
+
+    // Download next to the old file first, the old one stays until this succeeded
+    let temp_path = content_dir.join(format!("{}.part", file.filename));
+    if let Err(e) = super::download_content_file(&state.http_client, file, &temp_path).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e);
+    }
+
+    let old_path = content_dir.join(filename);
+    let _ = tokio::fs::remove_file(&old_path).await;
+    let _ = tokio::fs::remove_file(content_dir.join(meta_filename(filename))).await;
+
+    let new_path = content_dir.join(&new_filename);
+    tokio::fs::rename(&temp_path, &new_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to install {}: {}", file.filename, e)))?;
+    malware_scan::check_added_file(state, instance_id, instance_dir, &new_path).await?;
+
+    ModMetadata::from_content(&project, version)
+        .write_for(content_dir, &file.filename)
+        .await;
+
+    Ok(vec![new_filename, meta_filename(&file.filename)])
+}
+
+/// Apply every update of the plan after copying the files it replaces into a snapshot
+pub async fn apply(
+    state: &AppState,
+    app: &AppHandle,
+    instance: &Instance,
+    instance_dir: &Path,
+) -> AppResult<BulkUpdateSummary> {
+    if state
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot update content while the instance is running".to_string(),
+        ));
+    }
+
+    let plan = plan(state, instance, instance_dir).await?;
+    if plan.updates.is_empty() {
+        return Ok(BulkUpdateSummary {
+            snapshot_id: None,
+            updated: Vec::new(),
+            skipped: plan.skipped,
+            failed: Vec::new(),
+        });
+    }
+
+    // Snapshot the jars and metadata that are about to be replaced
+    let (id, snapshot_dir) = create_snapshot_dir(instance_dir).await?;
+    let mut manifest = SnapshotManifest {
+        id: id.clone(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        entries: Vec::new(),
+    };
+    for update in &plan.updates {
+        let target_dir = snapshot_dir.join(&update.folder);
+        tokio::fs::create_dir_all(&target_dir)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to create update snapshot: {}", e)))?;
+
+        let mut entry = SnapshotEntry {
+            folder: update.folder.clone(),
+            ..Default::default()
+        };
+        let content_dir = instance_dir.join(&update.folder);
+        for name in [update.filename.clone(), meta_filename(&update.filename)] {
+            let source = content_dir.join(&name);
+            if !source.exists() {
+                continue;
+            }
+            tokio::fs::copy(&source, target_dir.join(&name))
+                .await
+                .map_err(|e| AppError::Io(format!("Failed to snapshot {}: {}", name, e)))?;
+            entry.removed.push(name);
+        }
+        manifest.entries.push(entry);
+    }
+    save_manifest(&snapshot_dir, &manifest).await?;
+
+    let curseforge_key = crate::curseforge::api_key(&state.db).await;
+    let total = plan.updates.len();
+    let mut updated = Vec::new();
+    let mut failed = Vec::new();
+    for (i, update) in plan.updates.into_iter().enumerate() {
+        emit_progress(app, &instance.id, i + 1, total, &update.name);
+
+        match apply_update(
+            state,
+            curseforge_key.as_deref(),
+            &instance.id,
+            instance_dir,
+            &update,
+        )
+        .await
+        {
+            Ok(added) => {
+                manifest.entries[i].added = added;
+                updated.push(update);
+            }
+            Err(e) => {
+                log::warn!("Failed to update {}: {}", update.name, e);
+                failed.push(FailedUpdate {
+                    project_id: update.project_id.clone(),
+                    name: update.name.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    save_manifest(&snapshot_dir, &manifest).await?;
+    prune_snapshots(instance_dir).await;
+
+    log::info!(
+        "Bulk update of {}: {} updated, {} skipped, {} failed (snapshot {})",
+        instance.name,
+        updated.len(),
+        plan.skipped.len(),
+        failed.len(),
+        id
+    );
+
+    Ok(BulkUpdateSummary {
+        snapshot_id: Some(id),
+        updated,
+        skipped: plan.skipped,
+        failed,
+    })
+}
+
+/// The newest snapshot of an instance
+pub async fn latest_snapshot(instance_dir: &Path) -> AppResult<Option<UpdateSnapshotInfo>> {
+    let Some(id) = list_snapshot_ids(instance_dir).await.pop() else {
+        return Ok(None);
+    };
+    let manifest = load_manifest(instance_dir, &id).await?;
+    Ok(Some(UpdateSnapshotInfo {
+        id: manifest.id,
+        created_at: manifest.created_at,
+        files: manifest.entries.iter().map(|e| e.removed.len()).sum(),
+    }))
+}
+
+async fn load_manifest(instance_dir: &Path, id: &str) -> AppResult<SnapshotManifest> {
+    // Ids are timestamps, never paths
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err(AppError::Instance("Invalid snapshot id".to_string()));
+    }
+    let path = snapshots_dir(instance_dir).join(id).join(SNAPSHOT_MANIFEST);
+    let contents = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|_| AppError::Instance(format!("Update snapshot {} not found", id)))?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Put back the files a bulk update replaced, then delete the snapshot.
+/// Returns the number of restored files
+pub async fn rollback(
+    state: &AppState,
+    instance: &Instance,
+    instance_dir: &Path,
+    id: &str,
+) -> AppResult<usize> {
+    if state
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot roll back while the instance is running".to_string(),
+        ));
+    }
+
+    let manifest = load_manifest(instance_dir, id).await?;
+    let snapshot_dir = snapshots_dir(instance_dir).join(id);
+
+    let mut restored = 0;
+    for entry in &manifest.entries {
+        let content_dir = instance_dir.join(&entry.folder);
+        for name in &entry.added {
+            if !entry.removed.contains(name) {
+                let _ = tokio::fs::remove_file(content_dir.join(name)).await;
+            }
+        }
+        for name in &entry.removed {
+            let dest = content_dir.join(name);
+            dedup::unlink_shared(&dest)
+                .await
+                .map_err(|e| AppError::Io(format!("Failed to restore {}: {}", name, e)))?;
+            tokio::fs::copy(snapshot_dir.join(&entry.folder).join(name), &dest)
+                .await
+                .map_err(|e| AppError::Io(format!("Failed to restore {}: {}", name, e)))?;
+            restored += 1;
+        }
+    }
+
+    let _ = tokio::fs::remove_dir_all(&snapshot_dir).await;
+    log::info!(
+        "Rolled back bulk update {} of {} ({} files restored)",
+        id,
+        instance.name,
+        restored
+    );
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, version_type: &str) -> ContentVersion {
+        ContentVersion {
+            source: ContentSource::Modrinth,
+            id: id.to_string(),
+            project_id: "p".to_string(),
+            name: id.to_string(),
+            version_number: id.to_string(),
+            version_type: version_type.to_string(),
+            game_versions: vec!["1.20.1".to_string()],
+            loaders: vec!["fabric".to_string()],
+            downloads: 0,
+            date_published: String::new(),
+            files: Vec::new(),
+            dependencies: Vec::new(),
+            changelog: Some(format!("Changes in {}", id)),
+            stale: false,
+        }
+    }
+
+    fn versions() -> Vec<ContentVersion> {
+        vec![
+            version("4", "alpha"),
+            version("3", "beta"),
+            version("2", "release"),
+            version("1", "release"),
+        ]
+    }
+
+    fn selected(
+        current: Option<&str>,
+        channel: UpdateChannel,
+        pin: Option<&UpdatePin>,
+    ) -> Result<Option<String>, SkipReason> {
+        let versions = versions();
+        select_update(&versions, current, channel, pin).map(|v| v.map(|v| v.id.clone()))
+    }
+
+    #[test]
+    fn test_channel_allows() {
+        assert!(UpdateChannel::Release.allows("release"));
+        assert!(!UpdateChannel::Release.allows("beta"));
+        assert!(UpdateChannel::Beta.allows("beta"));
+        assert!(!UpdateChannel::Beta.allows("alpha"));
+        assert!(UpdateChannel::Alpha.allows("alpha"));
+    }
+
+    #[test]
+    fn test_select_update_by_channel() {
+        assert_eq!(
+            selected(Some("1"), UpdateChannel::Release, None),
+            Ok(Some("2".to_string()))
+        );
+        assert_eq!(
+            selected(Some("1"), UpdateChannel::Beta, None),
+            Ok(Some("3".to_string()))
+        );
+        assert_eq!(
+            selected(Some("1"), UpdateChannel::Alpha, None),
+            Ok(Some("4".to_string()))
+        );
+        assert_eq!(selected(Some("2"), UpdateChannel::Release, None), Ok(None));
+        // Installed beta is newer than the latest release, no downgrade
+        assert_eq!(selected(Some("3"), UpdateChannel::Release, None), Ok(None));
+        // Unknown installed version
+        assert_eq!(
+            selected(None, UpdateChannel::Release, None),
+            Ok(Some("2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_select_update_with_pins() {
+        assert_eq!(
+            selected(Some("1"), UpdateChannel::Release, Some(&UpdatePin::Hold)),
+            Err(SkipReason::Held)
+        );
+
+        let pin = UpdatePin::Version {
+            version_id: "1".to_string(),
+        };
+        assert_eq!(
+            selected(Some("1"), UpdateChannel::Release, Some(&pin)),
+            Err(SkipReason::Pinned)
+        );
+        assert_eq!(
+            selected(Some("2"), UpdateChannel::Release, Some(&pin)),
+            Ok(Some("1".to_string()))
+        );
+
+        let missing = UpdatePin::Version {
+            version_id: "0".to_string(),
+        };
+        assert_eq!(
+            selected(Some("1"), UpdateChannel::Release, Some(&missing)),
+            Err(SkipReason::PinnedVersionUnavailable)
+        );
+    }
+
+    #[test]
+    fn test_changelog_between() {
+        let versions = versions();
+        let numbers = |entries: Vec<ChangelogEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.version_number).collect()
+        };
+
+        assert_eq!(
+            numbers(changelog_between(
+                &versions,
+                Some("1"),
+                &versions[0],
+                UpdateChannel::Alpha
+            )),
+            vec!["4", "3", "2"]
+        );
+        assert_eq!(
+            numbers(changelog_between(
+                &versions,
+                Some("1"),
+                &versions[1],
+                UpdateChannel::Beta
+            )),
+            vec!["3", "2"]
+        );
+        // Unknown installed version: only the target
+        assert_eq!(
+            numbers(changelog_between(
+                &versions,
+                None,
+                &versions[2],
+                UpdateChannel::Release
+            )),
+            vec!["2"]
+        );
+    }
+
+    #[test]
+    fn test_preferences_serialization() {
+        let json = r#"{"channel":"beta","pins":{"AANobbMI":{"type":"hold"},"P7dR8mSH":{"type":"version","version_id":"abc"}}}"#;
+        let preferences: UpdatePreferences = serde_json::from_str(json).unwrap();
+        assert_eq!(preferences.channel, UpdateChannel::Beta);
+        assert_eq!(preferences.pins["AANobbMI"], UpdatePin::Hold);
+        assert_eq!(
+            preferences.pins["P7dR8mSH"],
+            UpdatePin::Version {
+                version_id: "abc".to_string()
+            }
+        );
+
+        let empty: UpdatePreferences = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty.channel, UpdateChannel::Release);
+    }
+
+    #[tokio::test]
+    async fn test_create_snapshot_dir_never_reuses_an_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let (first, first_dir) = create_snapshot_dir(dir.path()).await.unwrap();
+        let (second, second_dir) = create_snapshot_dir(dir.path()).await.unwrap();
+        assert_ne!(first, second);
+        assert!(first_dir.is_dir() && second_dir.is_dir());
+        assert!(first < second);
+    }
+
+    #[test]
+    fn test_meta_filename() {
+        assert_eq!(meta_filename("sodium-0.5.jar"), "sodium-0.5.meta.json");
+        assert_eq!(meta_filename("pack.zip.disabled"), "pack.meta.json");
+    }
+}
@@ -0,0 +1,559 @@
+// CurseForge API client for searching and downloading mods
+// API Documentation: https://docs.curseforge.com/rest-api/
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+const CURSEFORGE_API_BASE: &str = "https://api.curseforge.com/v1";
+
+/// Game id of Minecraft on CurseForge
+pub const MINECRAFT_GAME_ID: u32 = 432;
+
+/// Largest page size accepted by the API
+const MAX_PAGE_SIZE: u32 = 50;
+
+/// Settings key of a user-provided API key, overriding the built-in one
+pub const API_KEY_SETTING: &str = "curseforge_api_key";
+
+/// API key embedded at compile time (CI secret or `.env` for local builds)
+pub const CURSEFORGE_API_KEY: Option<&str> = option_env!("CURSEFORGE_API_KEY");
+
+/// Resolve the API key: the user setting first, then the built-in key
+pub async fn api_key(db: &SqlitePool) -> Option<String> {
+    let configured = crate::db::settings::get_setting(db, API_KEY_SETTING)
+        .await
+        .ok()
+        .flatten()
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty());
+
+    configured.or_else(|| {
+        CURSEFORGE_API_KEY
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+    })
+}
+
+/// CurseForge class (project type) ids for Minecraft
+pub mod class_id {
+    pub const BUKKIT_PLUGINS: u32 = 5;
+    pub const MODS: u32 = 6;
+    pub const RESOURCE_PACKS: u32 = 12;
+    pub const MODPACKS: u32 = 4471;
+    pub const SHADERS: u32 = 6552;
+    pub const DATA_PACKS: u32 = 6945;
+}
+
+/// Map a Modrinth-style project type to a CurseForge class id
+pub fn class_for_project_type(project_type: &str) -> Option<u32> {
+    match project_type {
+        "mod" => Some(class_id::MODS),
+        "plugin" => Some(class_id::BUKKIT_PLUGINS),
+        "resourcepack" => Some(class_id::RESOURCE_PACKS),
+        "shader" => Some(class_id::SHADERS),
+        "datapack" => Some(class_id::DATA_PACKS),
+        "modpack" => Some(class_id::MODPACKS),
+        _ => None,
+    }
+}
+
+/// Map a loader name to CurseForge's `modLoaderType`
+pub fn mod_loader_type(loader: &str) -> Option<u32> {
+    match loader.to_lowercase().as_str() {
+        "forge" => Some(1),
+        "fabric" => Some(4),
+        "quilt" => Some(5),
+        "neoforge" => Some(6),
+        _ => None,
+    }
+}
+
+/// Loader names CurseForge lists among a file's game versions
+const LOADER_TAGS: &[&str] = &["forge", "neoforge", "fabric", "quilt"];
+
+/// Paginated response wrapper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PagedResponse<T> {
+    pub data: Vec<T>,
+    pub pagination: Pagination,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    pub index: u32,
+    pub page_size: u32,
+    pub result_count: u32,
+    pub total_count: u32,
+}
+
+/// Single object response wrapper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataResponse<T> {
+    pub data: T,
+}
+
+/// A project ("mod") on CurseForge, regardless of its class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mod {
+    pub id: u32,
+    pub name: String,
+    pub slug: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub links: ModLinks,
+    #[serde(default)]
+    pub download_count: f64,
+    #[serde(default)]
+    pub class_id: Option<u32>,
+    #[serde(default)]
+    pub authors: Vec<ModAuthor>,
+    #[serde(default)]
+    pub logo: Option<ModAsset>,
+    #[serde(default)]
+    pub categories: Vec<Category>,
+    #[serde(default)]
+    pub latest_files_indexes: Vec<FileIndex>,
+    /// `Some(false)` when the author disallows downloads from third-party apps
+    #[serde(default)]
+    pub allow_mod_distribution: Option<bool>,
+    #[serde(default)]
+    pub date_modified: String,
+}
+
+impl Mod {
+    /// Whether files of this project may be downloaded through the API
+    pub fn allows_distribution(&self) -> bool {
+        self.allow_mod_distribution != Some(false)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModLinks {
+    #[serde(default)]
+    pub website_url: Option<String>,
+    #[serde(default)]
+    pub wiki_url: Option<String>,
+    #[serde(default)]
+    pub issues_url: Option<String>,
+    #[serde(default)]
+    pub source_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModAuthor {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModAsset {
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndex {
+    pub game_version: String,
+    pub file_id: u32,
+    #[serde(default)]
+    pub mod_loader: Option<u32>,
+}
+
+/// A downloadable file of a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct File {
+    pub id: u32,
+    pub mod_id: u32,
+    pub display_name: String,
+    pub file_name: String,
+    /// 1 = release, 2 = beta, 3 = alpha
+    pub release_type: u8,
+    #[serde(default)]
+    pub hashes: Vec<FileHash>,
+    pub file_date: String,
+    pub file_length: u64,
+    #[serde(default)]
+    pub download_count: u64,
+    /// `None` when the project disallows third-party distribution
+    #[serde(default)]
+    pub download_url: Option<String>,
+    /// Minecraft versions mixed with loader and environment tags
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub dependencies: Vec<FileDependency>,
+    #[serde(default)]
+    pub file_fingerprint: u32,
+}
+
+impl File {
+    pub fn sha1(&self) -> Option<&str> {
+        self.hashes
+            .iter()
+            .find(|h| h.algo == 1)
+            .map(|h| h.value.as_str())
+    }
+
+    pub fn release_channel(&self) -> &'static str {
+        match self.release_type {
+            2 => "beta",
+            3 => "alpha",
+            _ => "release",
+        }
+    }
+
+    /// Minecraft versions the file is tagged with
+    pub fn minecraft_versions(&self) -> Vec<String> {
+        self.game_versions
+            .iter()
+            .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+            .cloned()
+            .collect()
+    }
+
+    /// Lowercase loader names the file is tagged with
+    pub fn loaders(&self) -> Vec<String> {
+        self.game_versions
+            .iter()
+            .filter_map(|v| {
+                let tag = v.to_lowercase();
+                LOADER_TAGS.contains(&tag.as_str()).then_some(tag)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHash {
+    pub value: String,
+    /// 1 = SHA-1, 2 = MD5
+    pub algo: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDependency {
+    pub mod_id: u32,
+    /// 1 = embedded library, 2 = optional, 3 = required, 4 = tool,
+    /// 5 = incompatible, 6 = include
+    pub relation_type: u8,
+}
+
+impl FileDependency {
+    /// Dependency type using Modrinth's vocabulary
+    pub fn dependency_type(&self) -> &'static str {
+        match self.relation_type {
+            3 => "required",
+            5 => "incompatible",
+            1 | 6 => "embedded",
+            _ => "optional",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintMatches {
+    #[serde(default)]
+    pub exact_matches: Vec<FingerprintMatch>,
+    #[serde(default)]
+    pub unmatched_fingerprints: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintMatch {
+    pub id: u32,
+    pub file: File,
+}
+
+/// Search parameters
+#[derive(Debug, Clone, Default)]
+pub struct ModSearch<'q> {
+    pub query: &'q str,
+    pub class_id: Option<u32>,
+    pub game_version: Option<&'q str>,
+    pub mod_loader_type: Option<u32>,
+    /// 1 = featured, 2 = popularity, 3 = last updated, 6 = downloads, 11 = released date
+    pub sort_field: u32,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// Client for interacting with the CurseForge API
+pub struct CurseForgeClient<'a> {
+    http_client: &'a reqwest::Client,
+    api_key: String,
+}
+
+impl<'a> CurseForgeClient<'a> {
+    pub fn new(http_client: &'a reqwest::Client, api_key: String) -> Self {
+        Self {
+            http_client,
+            api_key,
+        }
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, CurseForgeError> {
+        let response = self
+            .http_client
+            .get(url)
+            .header("x-api-key", &self.api_key)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|e| CurseForgeError::Network(e.to_string()))?;
+
+        Self::parse_response(response).await
+    }
+
+    async fn parse_response<T: serde::de::DeserializeOwned>(
+        response: reqwest::Response,
+    ) -> Result<T, CurseForgeError> {
+        let status = response.status();
+        if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(CurseForgeError::Api(
+                "CurseForge rejected the API key".to_string(),
+            ));
+        }
+        if !status.is_success() {
+            return Err(CurseForgeError::Api(format!(
+                "API returned status {}",
+                status
+            )));
+        }
+
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| CurseForgeError::Parse(e.to_string()))
+    }
+
+    /// Search for Minecraft projects
+    pub async fn search(
+        &self,
+        search: &ModSearch<'_>,
+    ) -> Result<PagedResponse<Mod>, CurseForgeError> {
+        let limit = search.limit.clamp(1, MAX_PAGE_SIZE);
+        let mut url = format!(
+            "{}/mods/search?gameId={}&searchFilter={}&sortField={}&sortOrder=desc&index={}&pageSize={}",
+            CURSEFORGE_API_BASE,
+            MINECRAFT_GAME_ID,
+            urlencoding::encode(search.query),
+            search.sort_field,
+            search.offset,
+            limit
+        );
+
+        if let Some(class_id) = search.class_id {
+            url.push_str(&format!("&classId={}", class_id));
+        }
+        if let Some(game_version) = search.game_version {
+            url.push_str(&format!(
+                "&gameVersion={}",
+                urlencoding::encode(game_version)
+            ));
+        }
+        if let Some(loader_type) = search.mod_loader_type {
+            url.push_str(&format!("&modLoaderType={}", loader_type));
+        }
+
+        self.get(&url).await
+    }
+
+    /// Get project details by id
+    pub async fn get_mod(&self, mod_id: &str) -> Result<Mod, CurseForgeError> {
+        let url = format!("{}/mods/{}", CURSEFORGE_API_BASE, mod_id);
+        self.get::<DataResponse<Mod>>(&url).await.map(|r| r.data)
+    }
+
+    /// Get files of a project, newest first
+    pub async fn get_mod_files(
+        &self,
+        mod_id: &str,
+        game_version: Option<&str>,
+        mod_loader_type: Option<u32>,
+    ) -> Result<Vec<File>, CurseForgeError> {
+        let mut url = format!(
+            "{}/mods/{}/files?pageSize={}",
+            CURSEFORGE_API_BASE, mod_id, MAX_PAGE_SIZE
+        );
+        if let Some(game_version) = game_version {
+            url.push_str(&format!(
+                "&gameVersion={}",
+                urlencoding::encode(game_version)
+            ));
+        }
+        if let Some(loader_type) = mod_loader_type {
+            url.push_str(&format!("&modLoaderType={}", loader_type));
+        }
+
+        let mut files = self.get::<PagedResponse<File>>(&url).await?.data;
+        files.sort_by(|a, b| b.file_date.cmp(&a.file_date));
+        Ok(files)
+    }
+
+    /// Get a single file of a project
+    pub async fn get_mod_file(&self, mod_id: &str, file_id: &str) -> Result<File, CurseForgeError> {
+        let url = format!("{}/mods/{}/files/{}", CURSEFORGE_API_BASE, mod_id, file_id);
+        self.get::<DataResponse<File>>(&url).await.map(|r| r.data)
+    }
+
+    /// Look up files by their murmur2 fingerprints
+    pub async fn get_fingerprint_matches(
+        &self,
+        fingerprints: &[u32],
+    ) -> Result<FingerprintMatches, CurseForgeError> {
+        if fingerprints.is_empty() {
+            return Ok(FingerprintMatches {
+                exact_matches: vec![],
+                unmatched_fingerprints: vec![],
+            });
+        }
+
+        let url = format!("{}/fingerprints/{}", CURSEFORGE_API_BASE, MINECRAFT_GAME_ID);
+        let response = self
+            .http_client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("Accept", "application/json")
+            .json(&serde_json::json!({ "fingerprints": fingerprints }))
+            .send()
+            .await
+            .map_err(|e| CurseForgeError::Network(e.to_string()))?;
+
+        Self::parse_response::<DataResponse<FingerprintMatches>>(response)
+            .await
+            .map(|r| r.data)
+    }
+}
+
+/// CurseForge file fingerprint: MurmurHash2 (seed 1) of the file with
+/// whitespace bytes (tab, LF, CR, space) removed
+pub fn fingerprint(data: &[u8]) -> u32 {
+    let filtered: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !matches!(b, 9 | 10 | 13 | 32))
+        .collect();
+    murmur2(&filtered, 1)
+}
+
+fn murmur2(data: &[u8], seed: u32) -> u32 {
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let mut h = seed ^ data.len() as u32;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, byte) in tail.iter().enumerate() {
+            h ^= (*byte as u32) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h
+}
+
+/// Errors that can occur when using the CurseForge API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CurseForgeError {
+    Network(String),
+    Api(String),
+    Parse(String),
+}
+
+impl std::fmt::Display for CurseForgeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Network(msg) => write!(f, "Network error: {}", msg),
+            Self::Api(msg) => write!(f, "API error: {}", msg),
+            Self::Parse(msg) => write!(f, "Parse error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CurseForgeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_known_values() {
+        assert_eq!(murmur2(b"", 1), 0x5bd1_5e36);
+        assert_eq!(murmur2(b"helloworld", 1), 2_824_650_221);
+        assert_eq!(fingerprint(b"hello world"), 2_824_650_221);
+        assert_eq!(fingerprint(b"a\tb\r\nc d"), fingerprint(b"abcd"));
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let json = r#"{
+            "id": 4712345,
+            "modId": 238222,
+            "displayName": "JEI 15.2.0.27",
+            "fileName": "jei-1.20.1-forge-15.2.0.27.jar",
+            "releaseType": 1,
+            "hashes": [{"value": "abc123", "algo": 1}, {"value": "ffee", "algo": 2}],
+            "fileDate": "2023-08-22T10:00:00Z",
+            "fileLength": 1234,
+            "downloadUrl": null,
+            "gameVersions": ["1.20.1", "Forge", "Client", "NeoForge"],
+            "dependencies": [{"modId": 1, "relationType": 3}, {"modId": 2, "relationType": 6}],
+            "fileFingerprint": 123456789
+        }"#;
+
+        let file: File = serde_json::from_str(json).unwrap();
+        assert_eq!(file.sha1(), Some("abc123"));
+        assert_eq!(file.release_channel(), "release");
+        assert_eq!(file.minecraft_versions(), vec!["1.20.1"]);
+        assert_eq!(file.loaders(), vec!["forge", "neoforge"]);
+        assert!(file.download_url.is_none());
+        assert_eq!(file.dependencies[0].dependency_type(), "required");
+        assert_eq!(file.dependencies[1].dependency_type(), "embedded");
+    }
+
+    #[test]
+    fn test_parse_mod_distribution() {
+        let project: Mod = serde_json::from_str(
+            r#"{"id": 1, "name": "Example", "slug": "example",
+                "links": {"websiteUrl": "https://www.curseforge.com/minecraft/mc-mods/example"},
+                "allowModDistribution": false}"#,
+        )
+        .unwrap();
+
+        assert!(!project.allows_distribution());
+        assert_eq!(
+            project.links.website_url.as_deref(),
+            Some("https://www.curseforge.com/minecraft/mc-mods/example")
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Number of distinct commands kept per instance
+pub const MAX_HISTORY_PER_INSTANCE: i64 = 500;
+
+/// A command sent to a server console
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CommandHistoryEntry {
+    pub command: String,
+    pub use_count: i64,
+    pub last_used_at: String,
+}
+
+impl CommandHistoryEntry {
+    /// Record a command, moving it to the top of the history if it was already sent
+    pub async fn record(db: &SqlitePool, instance_id: &str, command: &str) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO server_command_history (instance_id, command, use_count, last_used_at)
+            VALUES (?, ?, 1, strftime('%Y-%m-%d %H:%M:%f', 'now'))
+            ON CONFLICT(instance_id, command) DO UPDATE SET
+                use_count = use_count + 1,
+                last_used_at = excluded.last_used_at
+            "#,
+        )
+        .bind(instance_id)
+        .bind(command)
+        .execute(db)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM server_command_history
+            WHERE instance_id = ? AND command NOT IN (
+                SELECT command FROM server_command_history
+                WHERE instance_id = ?
+                ORDER BY last_used_at DESC
+                LIMIT ?
+            )
+            "#,
+        )
+        .bind(instance_id)
+        .bind(instance_id)
+        .bind(MAX_HISTORY_PER_INSTANCE)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Most recent commands first, optionally filtered by a substring
+    pub async fn search(
+        db: &SqlitePool,
+        instance_id: &str,
+        query: Option<&str>,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        let pattern = format!("%{}%", escape_like(query.unwrap_or("")));
+        Self::fetch_matching(db, instance_id, &pattern, limit).await
+    }
+
+    /// Most recent commands starting with a prefix
+    pub async fn with_prefix(
+        db: &SqlitePool,
+        instance_id: &str,
+        prefix: &str,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        let pattern = format!("{}%", escape_like(prefix));
+        Self::fetch_matching(db, instance_id, &pattern, limit).await
+    }
+
+    async fn fetch_matching(
+        db: &SqlitePool,
+        instance_id: &str,
+        pattern: &str,
+        limit: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as::<_, CommandHistoryEntry>(
+            r#"
+            SELECT command, use_count, last_used_at
+            FROM server_command_history
+            WHERE instance_id = ? AND command LIKE ? ESCAPE '\'
+            ORDER BY last_used_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(pattern)
+        .bind(limit)
+        .fetch_all(db)
+        .await
+    }
+
+    pub async fn clear(db: &SqlitePool, instance_id: &str) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM server_command_history WHERE instance_id = ?")
+            .bind(instance_id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Escape LIKE wildcards so user input is matched literally
+fn escape_like(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
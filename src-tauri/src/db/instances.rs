@@ -235,6 +235,15 @@ impl Instance {
         Ok(())
     }
 
+    pub async fn update_server_port(db: &SqlitePool, id: &str, port: i64) -> sqlx::Result<()> {
+        sqlx::query("UPDATE instances SET server_port = ? WHERE id = ?")
+            .bind(port)
+            .bind(id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
     pub async fn update_version(
         db: &SqlitePool,
         id: &str,
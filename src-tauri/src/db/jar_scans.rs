@@ -0,0 +1,92 @@
+//! Database operations for malware scan results of instance jars
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Latest scan of one jar in an instance
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JarScanRecord {
+    pub instance_id: String,
+    /// Path relative to the instance directory, e.g. `mods/foo.jar`
+    pub path: String,
+    pub sha1: String,
+    pub verdict: String,
+    /// Findings as a JSON list
+    pub findings: String,
+    pub quarantined: bool,
+    pub scanned_at: String,
+}
+
+impl JarScanRecord {
+    pub async fn save(&self, db: &SqlitePool) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO jar_scan_results
+                (instance_id, path, sha1, verdict, findings, quarantined, scanned_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(instance_id, path) DO UPDATE SET
+                sha1 = excluded.sha1,
+                verdict = excluded.verdict,
+                findings = excluded.findings,
+                quarantined = excluded.quarantined,
+                scanned_at = excluded.scanned_at
+            "#,
+        )
+        .bind(&self.instance_id)
+        .bind(&self.path)
+        .bind(&self.sha1)
+        .bind(&self.verdict)
+        .bind(&self.findings)
+        .bind(self.quarantined)
+        .bind(&self.scanned_at)
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get(db: &SqlitePool, instance_id: &str, path: &str) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as::<_, JarScanRecord>(
+            r#"
+            SELECT instance_id, path, sha1, verdict, findings, quarantined, scanned_at
+            FROM jar_scan_results
+            WHERE instance_id = ? AND path = ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(path)
+        .fetch_optional(db)
+        .await
+    }
+
+    pub async fn get_for_instance(db: &SqlitePool, instance_id: &str) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as::<_, JarScanRecord>(
+            r#"
+            SELECT instance_id, path, sha1, verdict, findings, quarantined, scanned_at
+            FROM jar_scan_results
+            WHERE instance_id = ?
+            ORDER BY path
+            "#,
+        )
+        .bind(instance_id)
+        .fetch_all(db)
+        .await
+    }
+
+    pub async fn delete(db: &SqlitePool, instance_id: &str, path: &str) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM jar_scan_results WHERE instance_id = ? AND path = ?")
+            .bind(instance_id)
+            .bind(path)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Forget the jars of an instance that are not quarantined, before a full rescan
+    pub async fn clear_unquarantined(db: &SqlitePool, instance_id: &str) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM jar_scan_results WHERE instance_id = ? AND quarantined = 0")
+            .bind(instance_id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,10 @@
 pub mod accounts;
+pub mod command_history;
 pub mod instances;
+pub mod jar_scans;
 pub mod kaizen_accounts;
+pub mod release_feed;
+pub mod server_eula;
 pub mod settings;
+pub mod shared_files;
 pub mod shares;
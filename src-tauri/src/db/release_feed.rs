@@ -0,0 +1,133 @@
+//! Database operations for the feed of new releases of installed Modrinth projects
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// A release newer than the version installed in an instance
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReleaseFeedEntry {
+    pub id: i64,
+    pub instance_id: String,
+    pub project_id: String,
+    pub project_name: String,
+    pub icon_url: Option<String>,
+    /// Installed file, relative to the instance directory, e.g. `mods/foo.jar`
+    pub path: String,
+    pub installed_version_id: String,
+    pub installed_version_number: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub version_type: String,
+    pub changelog: Option<String>,
+    pub date_published: String,
+    pub discovered_at: String,
+    pub read: bool,
+}
+
+impl ReleaseFeedEntry {
+    /// Store a release, returns false when the instance already has it
+    pub async fn insert(&self, db: &SqlitePool) -> sqlx::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO release_feed
+                (instance_id, project_id, project_name, icon_url, path,
+                 installed_version_id, installed_version_number, version_id,
+                 version_number, version_type, changelog, date_published,
+                 discovered_at, read)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0)
+            "#,
+        )
+        .bind(&self.instance_id)
+        .bind(&self.project_id)
+        .bind(&self.project_name)
+        .bind(&self.icon_url)
+        .bind(&self.path)
+        .bind(&self.installed_version_id)
+        .bind(&self.installed_version_number)
+        .bind(&self.version_id)
+        .bind(&self.version_number)
+        .bind(&self.version_type)
+        .bind(&self.changelog)
+        .bind(&self.date_published)
+        .bind(&self.discovered_at)
+        .execute(db)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Releases by instance name, newest first within an instance
+    pub async fn get_page(
+        db: &SqlitePool,
+        instance_id: Option<&str>,
+        unread_only: bool,
+        limit: i64,
+        offset: i64,
+    ) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as::<_, ReleaseFeedEntry>(
+            r#"
+            SELECT f.id, f.instance_id, f.project_id, f.project_name, f.icon_url, f.path,
+                   f.installed_version_id, f.installed_version_number, f.version_id,
+                   f.version_number, f.version_type, f.changelog, f.date_published,
+                   f.discovered_at, f.read
+            FROM release_feed f
+            JOIN instances i ON i.id = f.instance_id
+            WHERE (? IS NULL OR f.instance_id = ?) AND (? = 0 OR f.read = 0)
+            ORDER BY i.name COLLATE NOCASE, f.instance_id, f.date_published DESC, f.id DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(instance_id)
+        .bind(unread_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(db)
+        .await
+    }
+
+    /// Number of releases, and of unread ones
+    pub async fn count(db: &SqlitePool, instance_id: Option<&str>) -> sqlx::Result<(i64, i64)> {
+        sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT COUNT(*), COALESCE(SUM(CASE WHEN read = 0 THEN 1 ELSE 0 END), 0)
+            FROM release_feed
+            WHERE ? IS NULL OR instance_id = ?
+            "#,
+        )
+        .bind(instance_id)
+        .bind(instance_id)
+        .fetch_one(db)
+        .await
+    }
+
+    pub async fn set_read(db: &SqlitePool, ids: &[i64], read: bool) -> sqlx::Result<()> {
+        let mut tx = db.begin().await?;
+        for id in ids {
+            sqlx::query("UPDATE release_feed SET read = ? WHERE id = ?")
+                .bind(read)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Mark every release as read, or only those of one instance
+    pub async fn mark_all_read(db: &SqlitePool, instance_id: Option<&str>) -> sqlx::Result<()> {
+        sqlx::query("UPDATE release_feed SET read = 1 WHERE ? IS NULL OR instance_id = ?")
+            .bind(instance_id)
+            .bind(instance_id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Forget read releases discovered before `before` (RFC 3339)
+    pub async fn prune_read(db: &SqlitePool, before: &str) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM release_feed WHERE read = 1 AND discovered_at < ?")
+            .bind(before)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+}
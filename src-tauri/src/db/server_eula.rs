@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// Official Minecraft EULA shown to the user before a server can run
+pub const MINECRAFT_EULA_URL: &str = "https://aka.ms/MinecraftEULA";
+
+/// Record of a user accepting the Minecraft EULA for a server instance
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EulaAcceptance {
+    pub instance_id: String,
+    pub accepted_at: String,
+    pub eula_url: String,
+}
+
+impl EulaAcceptance {
+    pub async fn get(db: &SqlitePool, instance_id: &str) -> sqlx::Result<Option<Self>> {
+        sqlx::query_as::<_, EulaAcceptance>(
+            r#"
+            SELECT instance_id, accepted_at, eula_url
+            FROM server_eula_acceptances
+            WHERE instance_id = ?
+            "#,
+        )
+        .bind(instance_id)
+        .fetch_optional(db)
+        .await
+    }
+
+    pub async fn record(db: &SqlitePool, instance_id: &str) -> sqlx::Result<Self> {
+        sqlx::query(
+            r#"
+            INSERT INTO server_eula_acceptances (instance_id, accepted_at, eula_url)
+            VALUES (?, datetime('now'), ?)
+            ON CONFLICT(instance_id) DO UPDATE SET
+                accepted_at = excluded.accepted_at,
+                eula_url = excluded.eula_url
+            "#,
+        )
+        .bind(instance_id)
+        .bind(MINECRAFT_EULA_URL)
+        .execute(db)
+        .await?;
+
+        Self::get(db, instance_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn is_accepted(db: &SqlitePool, instance_id: &str) -> sqlx::Result<bool> {
+        Ok(Self::get(db, instance_id).await?.is_some())
+    }
+}
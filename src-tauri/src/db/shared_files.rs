@@ -0,0 +1,71 @@
+//! Database operations for instance files deduplicated into the shared pool
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+/// An instance file that shares its data with the pooled copy of its hash
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SharedFileRecord {
+    pub instance_id: String,
+    /// Path relative to the instance directory, e.g. `mods/foo.jar`
+    pub path: String,
+    pub sha1: String,
+    pub size: i64,
+    /// `hardlink` or `reflink`
+    pub method: String,
+    /// Modification time when linked, in nanoseconds since the epoch. A file
+    /// written since then no longer shares its data
+    pub modified_ns: i64,
+}
+
+impl SharedFileRecord {
+    pub async fn save(&self, db: &SqlitePool) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO shared_files
+                (instance_id, path, sha1, size, method, modified_ns)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(instance_id, path) DO UPDATE SET
+                sha1 = excluded.sha1,
+                size = excluded.size,
+                method = excluded.method,
+                modified_ns = excluded.modified_ns
+            "#,
+        )
+        .bind(&self.instance_id)
+        .bind(&self.path)
+        .bind(&self.sha1)
+        .bind(self.size)
+        .bind(&self.method)
+        .bind(self.modified_ns)
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_all(db: &SqlitePool) -> sqlx::Result<Vec<Self>> {
+        sqlx::query_as::<_, SharedFileRecord>(
+            r#"
+            SELECT instance_id, path, sha1, size, method, modified_ns
+            FROM shared_files
+            ORDER BY instance_id, path
+            "#,
+        )
+        .fetch_all(db)
+        .await
+    }
+
+    pub async fn delete(db: &SqlitePool, instance_id: &str, path: &str) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM shared_files WHERE instance_id = ? AND path = ?")
+            .bind(instance_id)
+            .bind(path)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_all(db: &SqlitePool) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM shared_files").execute(db).await?;
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
+use crate::instance::dedup;
 use futures_util::StreamExt;
 use sha1::{Digest, Sha1};
 use sha2::{Sha256, Sha512};
@@ -102,10 +104,8 @@ pub async fn download_file_with_hash(
         }
     }
 
-    // Download the file
-    let response = client
-        .get(url)
-        .send()
+    // Download the file, from a configured mirror when there is one
+    let response = endpoints::get(client, url)
         .await
         .map_err(|e| AppError::Network(format!("Failed to download {}: {}", url, e)))?;
 
@@ -117,6 +117,9 @@ pub async fn download_file_with_hash(
         )));
     }
 
+    dedup::unlink_shared(dest)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to create file {}: {}", dest.display(), e)))?;
     let mut file = File::create(dest)
         .await
         .map_err(|e| AppError::Io(format!("Failed to create file {}: {}", dest.display(), e)))?;
@@ -1,5 +1,8 @@
+use super::endpoints::{self, MirrorConfig, ServiceEndpoints};
 use crate::error::AppResult;
+use crate::state::SharedState;
 use serde::{Deserialize, Serialize};
+use tauri::State;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
@@ -16,3 +19,20 @@ pub struct DownloadProgress {
 pub async fn get_download_queue() -> AppResult<Vec<DownloadProgress>> {
     Ok(vec![])
 }
+
+/// Get the services that can be mirrored, with their configured mirrors
+#[tauri::command]
+pub async fn get_endpoint_mirrors() -> AppResult<Vec<ServiceEndpoints>> {
+    Ok(endpoints::list())
+}
+
+/// Replace the mirrors of all services
+#[tauri::command]
+pub async fn set_endpoint_mirrors(
+    state: State<'_, SharedState>,
+    mirrors: MirrorConfig,
+) -> AppResult<Vec<ServiceEndpoints>> {
+    let state = state.read().await;
+    endpoints::save(&state.db, &mirrors).await?;
+    Ok(endpoints::list())
+}
@@ -0,0 +1,380 @@
+//! Registry of the upstream services the launcher talks to
+//!
+//! Every service has a fixed set of official base URLs. Users can configure an
+//! ordered list of mirrors per service (stored as JSON in the settings table).
+//! Requests to an official base URL are sent to the mirrors in order, moving
+//! on to the next one on connection errors or 5xx responses, and finally to
+//! the official URL itself.
+
+use crate::db::settings::{get_setting, set_setting};
+use crate::error::{AppError, AppResult};
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use tracing::warn;
+
+/// Settings key holding the configured mirrors
+pub const MIRRORS_SETTING_KEY: &str = "endpoint_mirrors";
+
+/// Mirrors per service id, in the order they are tried
+pub type MirrorConfig = BTreeMap<String, Vec<String>>;
+
+/// An upstream service that can be mirrored
+pub struct Service {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Official base URLs, replaced by the mirror base when rewriting
+    pub upstreams: &'static [&'static str],
+}
+
+pub const SERVICES: &[Service] = &[
+    Service {
+        id: "mojang_meta",
+        name: "Mojang version metadata",
+        upstreams: &[
+            "https://launchermeta.mojang.com",
+            "https://piston-meta.mojang.com",
+            "https://piston-data.mojang.com",
+        ],
+    },
+    Service {
+        id: "mojang_resources",
+        name: "Minecraft assets",
+        upstreams: &["https://resources.download.minecraft.net"],
+    },
+    Service {
+        id: "mojang_libraries",
+        name: "Minecraft libraries",
+        upstreams: &["https://libraries.minecraft.net"],
+    },
+    Service {
+        id: "modrinth_api",
+        name: "Modrinth API",
+        upstreams: &["https://api.modrinth.com"],
+    },
+    Service {
+        id: "modrinth_cdn",
+        name: "Modrinth CDN",
+        upstreams: &["https://cdn.modrinth.com"],
+    },
+    Service {
+        id: "fabric_meta",
+        name: "Fabric metadata",
+        upstreams: &["https://meta.fabricmc.net"],
+    },
+    Service {
+        id: "fabric_maven",
+        name: "Fabric maven",
+        upstreams: &["https://maven.fabricmc.net"],
+    },
+    Service {
+        id: "quilt_meta",
+        name: "Quilt metadata",
+        upstreams: &["https://meta.quiltmc.org"],
+    },
+    Service {
+        id: "quilt_maven",
+        name: "Quilt maven",
+        upstreams: &["https://maven.quiltmc.org"],
+    },
+    Service {
+        id: "forge_maven",
+        name: "Forge maven",
+        upstreams: &[
+            "https://maven.minecraftforge.net",
+            "https://files.minecraftforge.net",
+        ],
+    },
+    Service {
+        id: "neoforge_maven",
+        name: "NeoForge maven",
+        upstreams: &["https://maven.neoforged.net"],
+    },
+];
+
+/// Mirrors in use, loaded from settings at startup
+static MIRRORS: Lazy<RwLock<MirrorConfig>> = Lazy::new(Default::default);
+
+/// A service with its configured mirrors, as shown in the settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceEndpoints {
+    pub id: String,
+    pub name: String,
+    pub upstreams: Vec<String>,
+    pub mirrors: Vec<String>,
+}
+
+/// Check and normalize a mirror configuration
+///
+/// Unknown services and invalid URLs are rejected, trailing slashes are
+/// removed and services without mirrors are dropped.
+pub fn validate(config: &MirrorConfig) -> AppResult<MirrorConfig> {
+    let mut normalized = MirrorConfig::new();
+
+    for (service, mirrors) in config {
+        if !SERVICES.iter().any(|s| s.id == service) {
+            return Err(AppError::Custom(format!("Unknown service '{}'", service)));
+        }
+
+        let mut list = Vec::new();
+        for mirror in mirrors {
+            let mirror = mirror.trim().trim_end_matches('/');
+            if mirror.is_empty() {
+                continue;
+            }
+            let url = Url::parse(mirror)
+                .map_err(|e| AppError::Custom(format!("Invalid mirror URL '{}': {}", mirror, e)))?;
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                return Err(AppError::Custom(format!(
+                    "Invalid mirror URL '{}': expected an http(s) URL",
+                    mirror
+                )));
+            }
+            if url.query().is_some() || url.fragment().is_some() {
+                return Err(AppError::Custom(format!(
+                    "Invalid mirror URL '{}': query strings are not supported",
+                    mirror
+                )));
+            }
+            if !list.iter().any(|m| m == mirror) {
+                list.push(mirror.to_string());
+            }
+        }
+
+        if !list.is_empty() {
+            normalized.insert(service.clone(), list);
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Load the configured mirrors from settings
+///
+/// An invalid setting is logged and ignored, so a bad mirror never keeps
+/// the launcher from reaching the official services.
+pub async fn load(db: &SqlitePool) {
+    let config = match get_setting(db, MIRRORS_SETTING_KEY).await {
+        Ok(Some(json)) => serde_json::from_str::<MirrorConfig>(&json)
+            .map_err(AppError::from)
+            .and_then(|config| validate(&config)),
+        Ok(None) => return,
+        Err(e) => Err(AppError::from(e)),
+    };
+
+    match config {
+        Ok(config) => set_current(config),
+        Err(e) => warn!("Ignoring invalid endpoint mirrors setting: {}", e),
+    }
+}
+
+/// Validate, persist and apply a new mirror configuration
+pub async fn save(db: &SqlitePool, config: &MirrorConfig) -> AppResult<MirrorConfig> {
+    let config = validate(config)?;
+    let json = serde_json::to_string(&config)?;
+    set_setting(db, MIRRORS_SETTING_KEY, &json).await?;
+    set_current(config.clone());
+    Ok(config)
+}
+
+fn set_current(config: MirrorConfig) {
+    if let Ok(mut mirrors) = MIRRORS.write() {
+        *mirrors = config;
+    }
+}
+
+fn current() -> MirrorConfig {
+    MIRRORS.read().map(|m| m.clone()).unwrap_or_default()
+}
+
+/// All services with the mirrors currently in use
+pub fn list() -> Vec<ServiceEndpoints> {
+    let config = current();
+    SERVICES
+        .iter()
+        .map(|service| ServiceEndpoints {
+            id: service.id.to_string(),
+            name: service.name.to_string(),
+            upstreams: service.upstreams.iter().map(|u| u.to_string()).collect(),
+            mirrors: config.get(service.id).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Path and query of `url` below `base`, if `url` lives under it
+fn strip_base<'a>(url: &'a str, base: &str) -> Option<&'a str> {
+    let rest = url.strip_prefix(base)?;
+    (rest.is_empty() || rest.starts_with('/') || rest.starts_with('?')).then_some(rest)
+}
+
+/// URLs to try for a request, mirrors first and the original URL last
+fn candidates_with(config: &MirrorConfig, url: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+
+    for service in SERVICES {
+        let Some(mirrors) = config.get(service.id) else {
+            continue;
+        };
+        if let Some(rest) = service
+            .upstreams
+            .iter()
+            .find_map(|base| strip_base(url, base))
+        {
+            urls.extend(mirrors.iter().map(|mirror| format!("{}{}", mirror, rest)));
+            break;
+        }
+    }
+
+    urls.push(url.to_string());
+    urls
+}
+
+/// URLs to try for a request to `url`, in order
+pub fn candidates(url: &str) -> Vec<String> {
+    candidates_with(&current(), url)
+}
+
+fn is_mirror_host_with(config: &MirrorConfig, host: &str) -> bool {
+    config
+        .values()
+        .flatten()
+        .filter_map(|mirror| Url::parse(mirror).ok())
+        .any(|url| url.host_str() == Some(host))
+}
+
+/// Whether `host` belongs to one of the configured mirrors
+pub fn is_mirror_host(host: &str) -> bool {
+    is_mirror_host_with(&current(), host)
+}
+
+/// Send a request to `url` or its mirrors, trying the next candidate when
+/// one cannot be reached or answers with a server error
+///
+/// `build` creates the request for a candidate URL, so the same headers and
+/// body can be sent to each of them.
+pub async fn send<F>(url: &str, build: F) -> reqwest::Result<reqwest::Response>
+where
+    F: Fn(&str) -> reqwest::RequestBuilder,
+{
+    let urls = candidates(url);
+    let last = urls.len() - 1;
+
+    for (i, candidate) in urls.iter().enumerate() {
+        let result = build(candidate).send().await;
+        if i == last {
+            return result;
+        }
+        match &result {
+            Err(e) if e.is_connect() || e.is_timeout() => {
+                warn!("Mirror {} unreachable ({}), trying next", candidate, e);
+            }
+            Ok(response) if response.status().is_server_error() => {
+                warn!(
+                    "Mirror {} returned {}, trying next",
+                    candidate,
+                    response.status()
+                );
+            }
+            _ => return result,
+        }
+    }
+
+    unreachable!("candidates always contain the original URL")
+}
+
+/// GET `url` or its mirrors
+pub async fn get(client: &reqwest::Client, url: &str) -> reqwest::Result<reqwest::Response> {
+    send(url, |candidate| client.get(candidate)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(service: &str, mirrors: &[&str]) -> MirrorConfig {
+        MirrorConfig::from([(
+            service.to_string(),
+            mirrors.iter().map(|m| m.to_string()).collect(),
+        )])
+    }
+
+    #[test]
+    fn test_candidates_rewrite_known_service() {
+        let config = config(
+            "mojang_libraries",
+            &["https://mirror.example/maven", "http://127.0.0.1:8080"],
+        );
+        let url = "https://libraries.minecraft.net/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3.jar";
+
+        assert_eq!(
+            candidates_with(&config, url),
+            vec![
+                "https://mirror.example/maven/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3.jar",
+                "http://127.0.0.1:8080/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3.jar",
+                url,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_candidates_leave_other_urls_alone() {
+        let config = config("mojang_libraries", &["https://mirror.example"]);
+
+        assert_eq!(
+            candidates_with(&config, "https://cdn.modrinth.com/data/x.jar"),
+            vec!["https://cdn.modrinth.com/data/x.jar"]
+        );
+        // Only whole host names match
+        assert_eq!(
+            candidates_with(&config, "https://libraries.minecraft.net.evil.com/x.jar"),
+            vec!["https://libraries.minecraft.net.evil.com/x.jar"]
+        );
+    }
+
+    #[test]
+    fn test_candidates_any_upstream_of_service() {
+        let config = config("mojang_meta", &["https://meta.mirror.example"]);
+
+        assert_eq!(
+            candidates_with(
+                &config,
+                "https://piston-meta.mojang.com/v1/packages/abc/1.21.json"
+            )[0],
+            "https://meta.mirror.example/v1/packages/abc/1.21.json"
+        );
+    }
+
+    #[test]
+    fn test_validate_normalizes() {
+        let mut input = config(
+            "modrinth_api",
+            &["https://mirror.example/", " ", "https://mirror.example"],
+        );
+        input.insert("fabric_maven".to_string(), vec![]);
+
+        let normalized = validate(&input).unwrap();
+        assert_eq!(
+            normalized,
+            config("modrinth_api", &["https://mirror.example"])
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_input() {
+        assert!(validate(&config("unknown", &["https://mirror.example"])).is_err());
+        assert!(validate(&config("modrinth_api", &["not a url"])).is_err());
+        assert!(validate(&config("modrinth_api", &["ftp://mirror.example"])).is_err());
+        assert!(validate(&config("modrinth_api", &["https://mirror.example?a=b"])).is_err());
+    }
+
+    #[test]
+    fn test_is_mirror_host() {
+        let config = config("modrinth_cdn", &["https://cdn.mirror.example/modrinth"]);
+
+        assert!(is_mirror_host_with(&config, "cdn.mirror.example"));
+        assert!(!is_mirror_host_with(&config, "mirror.example"));
+    }
+}
@@ -62,6 +62,10 @@ pub enum AppError {
     #[error("External import error: {0}")]
     ExternalImport(String),
 
+    /// Stable code the frontend matches on to prompt for the Minecraft EULA
+    #[error("EULA_REQUIRED")]
+    EulaRequired,
+
     #[error("{0}")]
     Custom(String),
 }
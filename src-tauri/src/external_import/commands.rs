@@ -12,6 +12,7 @@ use crate::external_import::{
     ImportOptions, ImportableContent, LauncherParser, LauncherType, ModFile, ParsedLauncher,
     WorldInfo,
 };
+use crate::instance::malware_scan;
 use crate::state::SharedState;
 
 /// Detect all installed external launchers and their instances
@@ -232,7 +233,7 @@ pub async fn import_external_instance(
     let instances_dir = crate::utils::paths::get_instances_dir()
         .map_err(|e| AppError::ExternalImport(format!("Failed to get instances dir: {}", e)))?;
 
-    importer::import_external_instance(
+    let instance = importer::import_external_instance(
         &app,
         &state_guard.db,
         &state_guard.http_client,
@@ -240,7 +241,11 @@ pub async fn import_external_instance(
         &detected,
         &options,
     )
-    .await
+    .await?;
+
+    malware_scan::scan_imported_instance(&state_guard, &instance).await;
+
+    Ok(instance)
 }
 
 /// Helper: Count files and total size in a directory
@@ -1,7 +1,7 @@
 //! Parser for CurseForge App and exported modpacks
 //! Reads manifest.json from CurseForge exports
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::io::Read;
 use std::path::PathBuf;
 use tracing::{debug, warn};
@@ -16,48 +16,49 @@ use crate::external_import::{
 /// Parser for CurseForge App and exported modpacks
 pub struct CurseForgeParser;
 
-/// Structure of manifest.json in CurseForge exports
-#[derive(Debug, Deserialize)]
+/// Structure of manifest.json in CurseForge exports.
+/// Also written by the modpack exporter
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct CurseForgeManifest {
-    minecraft: CurseForgeMinecraft,
-    #[serde(default)]
-    manifest_type: Option<String>,
-    #[serde(default)]
-    manifest_version: Option<u32>,
-    name: String,
-    #[serde(default)]
-    version: Option<String>,
-    #[serde(default)]
-    author: Option<String>,
-    files: Vec<CurseForgeFile>,
-    #[serde(default)]
-    overrides: Option<String>,
+pub(crate) struct CurseForgeManifest {
+    pub(crate) minecraft: CurseForgeMinecraft,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) manifest_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) manifest_version: Option<u32>,
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) author: Option<String>,
+    pub(crate) files: Vec<CurseForgeFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) overrides: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct CurseForgeMinecraft {
-    version: String,
-    mod_loaders: Vec<CurseForgeModLoader>,
+pub(crate) struct CurseForgeMinecraft {
+    pub(crate) version: String,
+    pub(crate) mod_loaders: Vec<CurseForgeModLoader>,
 }
 
-#[derive(Debug, Deserialize)]
-struct CurseForgeModLoader {
-    id: String,
-    #[serde(default)]
-    primary: Option<bool>,
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CurseForgeModLoader {
+    pub(crate) id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) primary: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct CurseForgeFile {
+pub(crate) struct CurseForgeFile {
     #[serde(rename = "projectID")]
-    project_id: u64,
+    pub(crate) project_id: u64,
     #[serde(rename = "fileID")]
-    file_id: u64,
-    #[serde(default)]
-    required: Option<bool>,
+    pub(crate) file_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) required: Option<bool>,
 }
 
 /// Structure for CurseForge App's minecraftinstance.json
@@ -1,8 +1,18 @@
 use crate::db::instances::{CreateInstance, Instance};
+use crate::db::jar_scans::JarScanRecord;
+use crate::db::server_eula::EulaAcceptance;
 use crate::error::{AppError, AppResult};
+use crate::instance::datapacks;
+use crate::instance::dedup;
+use crate::instance::game_options;
 use crate::instance::instance_backup::{
     self, GlobalInstanceBackupInfo, InstanceBackupInfo, InstanceBackupManifest, InstanceBackupStats,
 };
+use crate::instance::jar_metadata::{self, JarDependency, JarMetadataFormat, ModSide};
+use crate::instance::malware_scan;
+use crate::instance::mod_analyzer;
+use crate::instance::mod_profiles::{self, ModProfiles};
+use crate::instance::resource_packs::{self, ResourcePackInfo};
 use crate::instance::worlds::{self, BackupInfo, BackupStats, GlobalBackupInfo, WorldInfo};
 use crate::minecraft::versions;
 use crate::state::SharedState;
@@ -77,6 +87,15 @@ pub struct ModInfo {
     pub enabled: bool,
     pub icon_url: Option<String>,
     pub project_id: Option<String>,
+    /// Mod/plugin id declared inside the jar
+    pub mod_id: Option<String>,
+    pub authors: Vec<String>,
+    pub description: Option<String>,
+    pub dependencies: Vec<JarDependency>,
+    pub side: Option<ModSide>,
+    /// Descriptor the jar metadata was read from
+    pub metadata_format: Option<JarMetadataFormat>,
+    pub sha1: Option<String>,
 }
 
 /// Stored dependency info for mods
@@ -103,13 +122,16 @@ pub struct ModMetadata {
     /// Dependencies (project_id and type)
     #[serde(default)]
     pub dependencies: Vec<StoredDependency>,
+    /// Where the file was installed from ("curseforge", "hangar"), `None` for Modrinth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 /// Determine the content folder name based on loader type
 /// - "mods" for Fabric, Forge, NeoForge, Quilt, Sponge (client and server)
 /// - "plugins" for Paper, Purpur, Folia, Pufferfish, Spigot, Velocity, BungeeCord, Waterfall
 /// - "mods" as default for clients
-fn get_content_folder(loader: Option<&str>, is_server: bool) -> &'static str {
+pub(crate) fn get_content_folder(loader: Option<&str>, is_server: bool) -> &'static str {
     match loader.map(|l| l.to_lowercase()).as_deref() {
         // Mod loaders - use "mods" folder
         Some("fabric") | Some("forge") | Some("neoforge") | Some("quilt") => "mods",
@@ -175,6 +197,7 @@ pub async fn create_instance(
     is_server: Option<bool>,
     is_proxy: Option<bool>,
     server_port: Option<i64>,
+    eula_accepted: Option<bool>,
 ) -> AppResult<Instance> {
     let state_guard = state.read().await;
 
@@ -337,6 +360,13 @@ pub async fn create_instance(
         .await
         .map_err(AppError::from)?;
 
+    // Record the EULA acceptance shown in the server creation dialog
+    if is_server && !is_proxy && eula_accepted.unwrap_or(false) {
+        EulaAcceptance::record(&state_guard.db, &instance.id)
+            .await
+            .map_err(AppError::from)?;
+    }
+
     Ok(instance)
 }
 
@@ -426,7 +456,7 @@ pub async fn get_instance_mods(
         return Ok(vec![]);
     }
 
-    let mut mods = Vec::new();
+    let mut jar_files = Vec::new();
     let mut entries = fs::read_dir(&mods_dir)
         .await
         .map_err(|e| AppError::Io(format!("Failed to read {} directory: {}", folder_name, e)))?;
@@ -447,7 +477,30 @@ pub async fn get_instance_mods(
             continue;
         };
 
-        // Try to extract mod info from filename
+        jar_files.push((filename, is_enabled, base_filename));
+    }
+
+    // Inspect all jars concurrently, unchanged files are served from the cache
+    let data_dir = &state_guard.data_dir;
+    let inspections = future::join_all(jar_files.iter().map(|(filename, _, _)| {
+        let path = mods_dir.join(filename);
+        async move { jar_metadata::inspect_jar(data_dir, &path).await }
+    }))
+    .await;
+
+    let mut mods = Vec::new();
+    for ((filename, is_enabled, base_filename), inspection) in
+        jar_files.into_iter().zip(inspections)
+    {
+        let (sha1, jar_meta) = match inspection {
+            Ok(inspected) => (Some(inspected.sha1), inspected.metadata),
+            Err(e) => {
+                log::warn!("Failed to inspect {}: {}", filename, e);
+                (None, None)
+            }
+        };
+
+        // Last resort: guess mod info from the filename
         let name = base_filename
             .trim_end_matches(".jar")
             .split('-')
@@ -482,17 +535,45 @@ pub async fn get_instance_mods(
             (None, None, None, None)
         };
 
-        mods.push(ModInfo {
-            name: meta_name.unwrap_or(name),
-            version: meta_version.unwrap_or(if version.is_empty() {
+        let name = meta_name
+            .or_else(|| jar_meta.as_ref().and_then(|m| m.name.clone()))
+            .unwrap_or(name);
+        let version = meta_version
+            .or_else(|| jar_meta.as_ref().and_then(|m| m.version.clone()))
+            .unwrap_or(if version.is_empty() {
                 "Unknown".to_string()
             } else {
                 version
-            }),
+            });
+
+        let (mod_id, authors, description, dependencies, side, metadata_format, jar_icon) =
+            match jar_meta {
+                Some(m) => (
+                    Some(m.id),
+                    m.authors,
+                    m.description,
+                    m.dependencies,
+                    m.side,
+                    Some(m.format),
+                    m.icon,
+                ),
+                None => (None, Vec::new(), None, Vec::new(), None, None, None),
+            };
+
+        mods.push(ModInfo {
+            name,
+            version,
             filename,
             enabled: is_enabled,
-            icon_url,
+            icon_url: icon_url.or(jar_icon),
             project_id,
+            mod_id,
+            authors,
+            description,
+            dependencies,
+            side,
+            metadata_format,
+            sha1,
         });
     }
 
@@ -511,29 +592,6 @@ pub struct ContentInfo {
     pub project_id: Option<String>,
 }
 
-/// Helper function to find the first world folder in saves/
-async fn find_world_folder(instance_dir: &std::path::Path) -> Option<String> {
-    let saves_dir = instance_dir.join("saves");
-    if !saves_dir.exists() {
-        return None;
-    }
-
-    let mut entries = match fs::read_dir(&saves_dir).await {
-        Ok(e) => e,
-        Err(_) => return None,
-    };
-
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        if let Ok(file_type) = entry.file_type().await {
-            if file_type.is_dir() {
-                return Some(entry.file_name().to_string_lossy().to_string());
-            }
-        }
-    }
-
-    None
-}
-
 /// Get installed resource packs for an instance
 #[tauri::command]
 pub async fn get_instance_resourcepacks(
@@ -570,14 +628,8 @@ pub async fn get_instance_datapacks(
         .join("instances")
         .join(&instance.game_dir);
 
-    // Find world folder for datapacks
-    let world_name = find_world_folder(&instance_dir)
-        .await
-        .unwrap_or_else(|| "world".to_string());
-    let datapacks_dir = instance_dir
-        .join("saves")
-        .join(&world_name)
-        .join("datapacks");
+    // Datapacks of the server world, or of the first world of a client
+    let datapacks_dir = datapacks::default_datapacks_dir(&instance_dir, instance.is_server).await;
 
     if !datapacks_dir.exists() {
         return Ok(vec![]);
@@ -1702,6 +1754,10 @@ pub struct StorageInfo {
     pub cache_size_bytes: u64,
     pub other_size_bytes: u64,
     pub instance_count: u32,
+    /// Bytes of the single copies kept for deduplicated files
+    pub shared_size_bytes: u64,
+    /// Bytes saved by deduplicated files sharing those copies
+    pub dedup_saved_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1709,6 +1765,8 @@ pub struct InstanceStorageInfo {
     pub id: String,
     pub name: String,
     pub size_bytes: u64,
+    /// Part of `size_bytes` shared with other instances through deduplication
+    pub shared_bytes: u64,
     pub mc_version: String,
     pub loader: Option<String>,
     pub last_played: Option<String>,
@@ -1762,7 +1820,16 @@ pub async fn get_storage_info(state: State<'_, SharedState>) -> AppResult<Storag
         0
     };
 
-    let total_size = get_dir_size(data_dir).await;
+    // Deduplicated files are walked once in the pool and once per instance
+    // sharing them, but take the space of the pooled copy only
+    let dedup = dedup::report(&state_guard).await?;
+    let counted_twice = dedup.pooled_bytes + dedup.saved_bytes;
+    let instances_size = instances_size.saturating_sub(counted_twice);
+
+    let mut total_size = get_dir_size(data_dir).await;
+    if instances_dir.starts_with(data_dir) {
+        total_size = total_size.saturating_sub(counted_twice);
+    }
     let other_size = total_size.saturating_sub(instances_size + java_size + cache_size);
 
     let instances = Instance::get_all(&state_guard.db)
@@ -1777,6 +1844,8 @@ pub async fn get_storage_info(state: State<'_, SharedState>) -> AppResult<Storag
         cache_size_bytes: cache_size,
         other_size_bytes: other_size,
         instance_count: instances.len() as u32,
+        shared_size_bytes: dedup.pooled_bytes,
+        dedup_saved_bytes: dedup.saved_bytes,
     })
 }
 
@@ -1792,6 +1861,12 @@ pub async fn get_instances_storage(
         .map_err(AppError::from)?;
 
     let instances_base_dir = state_guard.get_instances_dir().await;
+    let shared: HashMap<String, u64> = dedup::report(&state_guard)
+        .await?
+        .instances
+        .into_iter()
+        .map(|share| (share.instance_id, share.shared_bytes))
+        .collect();
 
     // Drop the lock before parallel operations
     drop(state_guard);
@@ -1801,6 +1876,7 @@ pub async fn get_instances_storage(
 
     for instance in instances {
         let instance_dir = instances_base_dir.join(&instance.game_dir);
+        let shared_bytes = shared.get(&instance.id).copied().unwrap_or(0);
 
         tasks.push(async move {
             let size = if instance_dir.exists() {
@@ -1813,6 +1889,7 @@ pub async fn get_instances_storage(
                 id: instance.id,
                 name: instance.name,
                 size_bytes: size,
+                shared_bytes,
                 mc_version: instance.mc_version,
                 loader: instance.loader,
                 last_played: instance.last_played,
@@ -2558,6 +2635,9 @@ pub struct CreateServerFromClientOptions {
     /// Server port (default 25565)
     #[serde(default = "default_server_port")]
     pub server_port: i64,
+    /// Whether the user accepted the Minecraft EULA in the creation dialog
+    #[serde(default)]
+    pub eula_accepted: bool,
 }
 
 fn default_server_port() -> i64 {
@@ -2625,6 +2705,12 @@ pub async fn create_server_from_client(
         .await
         .map_err(AppError::from)?;
 
+    if options.eula_accepted {
+        EulaAcceptance::record(&state_guard.db, &instance.id)
+            .await
+            .map_err(AppError::from)?;
+    }
+
     // Now use the instance's game_dir to create the actual directory
     let instances_dir = state_guard.data_dir.join("instances");
     let server_dir = instances_dir.join(&instance.game_dir);
@@ -3023,8 +3109,445 @@ pub async fn analyze_instance_logs(
     Ok(all_issues)
 }
 
+/// Check installed mods for missing dependencies, conflicts and version mismatches
+/// before launching, using the metadata declared inside the jars
+#[tauri::command]
+pub async fn analyze_instance_mods(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<crate::instance::log_parser::DetectedIssue>> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let content_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir)
+        .join(get_content_folder(
+            instance.loader.as_deref(),
+            instance.is_server,
+        ));
+
+    let issues =
+        mod_analyzer::analyze_instance(&state_guard.data_dir, &content_dir, &instance).await?;
+
+    log::info!(
+        "analyze_instance_mods: Found {} issues for instance {}",
+        issues.len(),
+        instance_id
+    );
+
+    Ok(issues)
+}
+
+// ============= Datapacks =============
+
+/// Get the datapacks of a world, with whether `level.dat` enables them
+#[tauri::command]
+pub async fn get_world_datapacks(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    world_name: String,
+) -> AppResult<datapacks::WorldDatapacks> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+
+    datapacks::sync_global_datapacks(&instance_dir, instance.is_server).await?;
+    datapacks::world_datapacks(&instance_dir, instance.is_server, &world_name).await
+}
+
+/// Get the global datapacks of an instance
+#[tauri::command]
+pub async fn get_global_datapacks(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<datapacks::DatapackInfo>> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+
+    Ok(datapacks::global_datapacks(&instance_dir).await)
+}
+
+/// Remove a global datapack, worlds that already have it keep their copy
+#[tauri::command]
+pub async fn remove_global_datapack(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    filename: String,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+
+    datapacks::remove_global_datapack(&instance_dir, &filename).await
+}
+
+// ============= Resource Packs =============
+
+/// Game directory of a client instance, for its resource packs. Changes are
+/// refused while the instance is running, the game rewrites `options.txt` on exit
+async fn resource_pack_instance(
+    state_guard: &crate::state::AppState,
+    instance_id: &str,
+    editing: bool,
+) -> AppResult<(Instance, std::path::PathBuf)> {
+    let instance = Instance::get_by_id(&state_guard.db, instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    if instance.is_server {
+        return Err(AppError::Instance(
+            "Server instances have no resource packs".to_string(),
+        ));
+    }
+    if editing
+        && state_guard
+            .running_instances
+            .read()
+            .await
+            .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot change resource packs while the instance is running".to_string(),
+        ));
+    }
+
+    let game_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+    Ok((instance, game_dir))
+}
+
+/// Get the resource packs of an instance, enabled ones first in the order the
+/// game applies them, with their metadata and whether they match the version
+#[tauri::command]
+pub async fn get_resource_packs(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<ResourcePackInfo>> {
+    let state_guard = state.read().await;
+    let (instance, game_dir) = resource_pack_instance(&state_guard, &instance_id, false).await?;
+    resource_packs::list(&game_dir, &instance.mc_version).await
+}
+
+/// Set the enabled resource packs of an instance, lowest priority first
+#[tauri::command]
+pub async fn set_enabled_resource_packs(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    packs: Vec<String>,
+) -> AppResult<Vec<ResourcePackInfo>> {
+    let state_guard = state.read().await;
+    let (instance, game_dir) = resource_pack_instance(&state_guard, &instance_id, true).await?;
+    resource_packs::set_enabled(&game_dir, &instance.mc_version, &packs).await
+}
+
+/// Enable a resource pack on top of the others, or disable it
+#[tauri::command]
+pub async fn set_resource_pack_enabled(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    pack_id: String,
+    enabled: bool,
+) -> AppResult<Vec<ResourcePackInfo>> {
+    let state_guard = state.read().await;
+    let (instance, game_dir) = resource_pack_instance(&state_guard, &instance_id, true).await?;
+    resource_packs::set_pack_enabled(&game_dir, &instance.mc_version, &pack_id, enabled).await
+}
+
+// ============= Malware Scanning =============
+
+/// Get the latest scan results of the jars of an instance, quarantined ones included
+#[tauri::command]
+pub async fn get_jar_scan_results(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<malware_scan::JarScanResult>> {
+    let state_guard = state.read().await;
+    let records = JarScanRecord::get_for_instance(&state_guard.db, &instance_id).await?;
+    Ok(records.into_iter().map(Into::into).collect())
+}
+
+/// Scan the jars of an instance now
+#[tauri::command]
+pub async fn scan_instance_jars(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<malware_scan::JarScanResult>> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    malware_scan::scan_instance(&state_guard, &instance).await
+}
+
+/// Rescan the jars of every instance and report what was found
+#[tauri::command]
+pub async fn scan_all_instance_jars(
+    state: State<'_, SharedState>,
+) -> AppResult<malware_scan::ScanReport> {
+    let state_guard = state.read().await;
+    malware_scan::scan_all(&state_guard).await
+}
+
+/// Move a quarantined jar back into its instance
+#[tauri::command]
+pub async fn restore_quarantined_jar(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    path: String,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    malware_scan::restore(&state_guard, &instance, &path).await
+}
+
+/// Delete a quarantined jar
+#[tauri::command]
+pub async fn delete_quarantined_jar(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    path: String,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    malware_scan::delete_quarantined(&state_guard, &instance, &path).await
+}
+
+// ============= Storage Deduplication =============
+
+/// Get the space used and saved by files shared across instances
+#[tauri::command]
+pub async fn get_dedup_report(state: State<'_, SharedState>) -> AppResult<dedup::DedupReport> {
+    let state_guard = state.read().await;
+    dedup::report(&state_guard).await
+}
+
+/// Replace identical mods, resource packs and shader packs of all instances
+/// with links to a single copy
+#[tauri::command]
+pub async fn deduplicate_instance_files(
+    state: State<'_, SharedState>,
+) -> AppResult<dedup::DedupReport> {
+    let state_guard = state.read().await;
+    dedup::deduplicate(&state_guard).await
+}
+
+/// Give every shared file its own copy again
+#[tauri::command]
+pub async fn undo_instance_deduplication(state: State<'_, SharedState>) -> AppResult<usize> {
+    let state_guard = state.read().await;
+    dedup::restore(&state_guard).await
+}
+
+// ============= Mod Profiles =============
+
+/// Game and content directories of an instance, for mod profiles
+fn mod_profile_dirs(
+    state_guard: &crate::state::AppState,
+    instance: &Instance,
+) -> (std::path::PathBuf, std::path::PathBuf) {
+    let game_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+    let content_dir = game_dir.join(get_content_folder(
+        instance.loader.as_deref(),
+        instance.is_server,
+    ));
+    (game_dir, content_dir)
+}
+
+/// Apply a mod profile and mark it active. Refused while the instance is running
+pub(crate) async fn switch_mod_profile_for(
+    state_guard: &crate::state::AppState,
+    instance: &Instance,
+    profile_name: &str,
+) -> AppResult<mod_profiles::ProfileSwitchResult> {
+    if state_guard
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot switch mod profile while the instance is running".to_string(),
+        ));
+    }
+
+    let (game_dir, content_dir) = mod_profile_dirs(state_guard, instance);
+    let mut profiles = ModProfiles::load(&game_dir).await?;
+    let profile = profiles
+        .get(profile_name)
+        .cloned()
+        .ok_or_else(|| AppError::Instance(format!("Mod profile '{}' not found", profile_name)))?;
+
+    let result =
+        mod_profiles::apply(&game_dir, &content_dir, &profile, !instance.is_server).await?;
+
+    profiles.active = Some(profile.name);
+    profiles.save(&game_dir).await?;
+
+    Ok(result)
+}
+
+/// List the mod profiles of an instance and whether the active one is still applied
+#[tauri::command]
+pub async fn get_mod_profiles(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<mod_profiles::ModProfilesInfo> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let (game_dir, content_dir) = mod_profile_dirs(&state_guard, &instance);
+    let profiles = ModProfiles::load(&game_dir).await?;
+
+    let active_in_sync = match profiles
+        .active
+        .as_deref()
+        .and_then(|name| profiles.get(name))
+    {
+        Some(profile) => {
+            let current = mod_profiles::scan_mods(&content_dir).await?;
+            let packs = if instance.is_server {
+                None
+            } else {
+                Some(game_options::read_resource_packs(&game_dir).await?)
+            };
+            mod_profiles::is_in_sync(profile, &current, packs.as_deref())
+        }
+        None => false,
+    };
+
+    Ok(mod_profiles::ModProfilesInfo {
+        active: profiles.active,
+        active_in_sync,
+        profiles: profiles.profiles,
+    })
+}
+
+/// Save the current enabled mods (and resource pack order) as a profile,
+/// replacing the profile with the same name
+#[tauri::command]
+pub async fn save_mod_profile(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    name: String,
+) -> AppResult<mod_profiles::ModProfile> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let (game_dir, content_dir) = mod_profile_dirs(&state_guard, &instance);
+    let profile =
+        mod_profiles::capture(&name, &game_dir, &content_dir, !instance.is_server).await?;
+
+    let mut profiles = ModProfiles::load(&game_dir).await?;
+    profiles.active = Some(profile.name.clone());
+    profiles.upsert(profile.clone());
+    profiles.save(&game_dir).await?;
+
+    Ok(profile)
+}
+
+/// Delete a mod profile. The mods themselves are left as they are
+#[tauri::command]
+pub async fn delete_mod_profile(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    name: String,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let (game_dir, _) = mod_profile_dirs(&state_guard, &instance);
+    let mut profiles = ModProfiles::load(&game_dir).await?;
+    if !profiles.remove(&name) {
+        return Err(AppError::Instance(format!(
+            "Mod profile '{}' not found",
+            name
+        )));
+    }
+    profiles.save(&game_dir).await
+}
+
+/// Enable and disable mods to match a profile
+#[tauri::command]
+pub async fn switch_mod_profile(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    name: String,
+) -> AppResult<mod_profiles::ProfileSwitchResult> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    switch_mod_profile_for(&state_guard, &instance, &name).await
+}
+
 // ============= Version Change Feature =============
 
+use crate::content::migration::{self, MigrationPlan, MigrationRecord, VersionTarget};
 use tauri::Emitter;
 
 /// Request to change an instance's Minecraft version
@@ -3056,7 +3579,86 @@ pub async fn change_instance_version(
     request: ChangeVersionRequest,
 ) -> AppResult<()> {
     let state_guard = state.read().await;
+    change_version(&state_guard, &app, &request).await
+}
+
+/// Check which mods, resource packs and shaders work with another version,
+/// without changing anything
+#[tauri::command]
+pub async fn preview_version_migration(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    target: VersionTarget,
+) -> AppResult<MigrationPlan> {
+    let state_guard = state.read().await;
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+    let instance_dir = state_guard
+        .get_instances_dir()
+        .await
+        .join(&instance.game_dir);
+    migration::plan(&state_guard, &instance, &instance_dir, &target).await
+}
+
+/// Change the version of an instance, updating the content that supports it
+/// and disabling the rest. An automatic backup allows reverting
+#[tauri::command]
+pub async fn migrate_instance_version(
+    state: State<'_, SharedState>,
+    app: AppHandle,
+    instance_id: String,
+    target: VersionTarget,
+) -> AppResult<MigrationRecord> {
+    let state_guard = state.read().await;
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+    migration::apply(&state_guard, &app, &instance, &target).await
+}
 
+/// The last version migration of an instance, if it can still be reverted
+#[tauri::command]
+pub async fn get_version_migration(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Option<MigrationRecord>> {
+    let state_guard = state.read().await;
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+    let instance_dir = state_guard
+        .get_instances_dir()
+        .await
+        .join(&instance.game_dir);
+    MigrationRecord::load(&instance_dir).await
+}
+
+/// Undo the last version migration from its backup
+#[tauri::command]
+pub async fn revert_version_migration(
+    state: State<'_, SharedState>,
+    app: AppHandle,
+    instance_id: String,
+) -> AppResult<MigrationRecord> {
+    let state_guard = state.read().await;
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+    migration::revert(&state_guard, &app, &instance).await
+}
+
+/// Clean the installation files, update the requested mods and store the new
+/// versions. The game is reinstalled on the next launch
+pub(crate) async fn change_version(
+    state_guard: &crate::state::AppState,
+    app: &AppHandle,
+    request: &ChangeVersionRequest,
+) -> AppResult<()> {
     // Get the instance
     let instance = Instance::get_by_id(&state_guard.db, &request.instance_id)
         .await
@@ -3265,6 +3867,7 @@ pub async fn change_instance_version(
                 server_side: Some(project.server_side.clone()),
                 client_side: Some(project.client_side.clone()),
                 dependencies,
+                source: None,
             };
 
             if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
@@ -0,0 +1,619 @@
+//! Datapacks across the worlds of an instance
+//!
+//! Datapacks live in the `datapacks` folder of each world (`saves/<world>` on
+//! clients, `world` on servers). Global datapacks are kept in the instance's
+//! `global-datapacks` folder and copied into every world created after they
+//! were set up. Whether the game enabled a pack is read from `level.dat`.
+
+use crate::error::{AppError, AppResult};
+use crate::instance::worlds;
+use crate::modrinth::commands::ModMetadata;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Instance folder holding the global datapacks
+pub const GLOBAL_DATAPACKS_FOLDER: &str = "global-datapacks";
+
+/// Worlds that existed when global datapacks were last synced, kept in the global folder
+const KNOWN_WORLDS_FILE: &str = ".known-worlds.json";
+
+/// The world of server instances
+const SERVER_WORLD: &str = "world";
+
+const DISABLED_SUFFIX: &str = ".disabled";
+
+/// Where to install a datapack
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DatapackTarget {
+    /// The given worlds (`world` on servers)
+    Worlds { worlds: Vec<String> },
+    /// The instance's global datapacks, copied into new worlds
+    Global,
+}
+
+/// A datapack of a world or of the global datapacks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatapackInfo {
+    /// File or folder name in `datapacks`, without `.disabled`
+    pub filename: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub project_id: Option<String>,
+    pub icon_url: Option<String>,
+    /// Whether the world enables the pack according to `level.dat`,
+    /// `None` when the game has not loaded it yet
+    pub enabled: Option<bool>,
+    /// Also one of the instance's global datapacks
+    pub global: bool,
+}
+
+/// Datapacks of a world
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldDatapacks {
+    pub world: String,
+    pub datapacks: Vec<DatapackInfo>,
+    /// Enabled packs that are not files of the world (vanilla, features, mods)
+    pub builtin_enabled: Vec<String>,
+}
+
+/// `Data.DataPacks` of `level.dat`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelDataPacks {
+    #[serde(rename = "Enabled", default)]
+    pub enabled: Vec<String>,
+    #[serde(rename = "Disabled", default)]
+    pub disabled: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct LevelDat {
+    #[serde(rename = "Data")]
+    data: LevelData,
+}
+
+#[derive(Deserialize)]
+struct LevelData {
+    #[serde(rename = "DataPacks", default)]
+    data_packs: LevelDataPacks,
+}
+
+/// Id the game gives a pack found in the `datapacks` folder
+fn pack_id(filename: &str) -> String {
+    format!("file/{}", filename)
+}
+
+/// Parse the datapack lists of a gzip-compressed `level.dat`
+pub fn parse_level_datapacks(bytes: &[u8]) -> AppResult<LevelDataPacks> {
+    let mut data = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut data)
+        .map_err(|e| AppError::Io(format!("Failed to decompress level.dat: {}", e)))?;
+
+    let level: LevelDat = fastnbt::from_bytes(&data)
+        .map_err(|e| AppError::Io(format!("Failed to parse level.dat: {}", e)))?;
+    Ok(level.data.data_packs)
+}
+
+/// Datapack lists of a world, `None` when it has no readable `level.dat`
+async fn read_level_datapacks(world_dir: &Path) -> Option<LevelDataPacks> {
+    let bytes = fs::read(world_dir.join("level.dat")).await.ok()?;
+    match parse_level_datapacks(&bytes) {
+        Ok(packs) => Some(packs),
+        Err(e) => {
+            log::warn!("{}: {}", world_dir.display(), e);
+            None
+        }
+    }
+}
+
+/// Reject world names that would leave the saves folder
+fn validate_world_name(world: &str) -> AppResult<()> {
+    if world.is_empty() || world == "." || world == ".." || world.contains(['/', '\\']) {
+        return Err(AppError::Instance(format!(
+            "Invalid world name '{}'",
+            world
+        )));
+    }
+    Ok(())
+}
+
+/// Folder of a world
+pub fn world_dir(instance_dir: &Path, is_server: bool, world: &str) -> AppResult<PathBuf> {
+    validate_world_name(world)?;
+    Ok(if is_server {
+        instance_dir.join(world)
+    } else {
+        instance_dir.join("saves").join(world)
+    })
+}
+
+/// Worlds of an instance that have been created (have a `level.dat`)
+pub async fn list_worlds(instance_dir: &Path, is_server: bool) -> Vec<String> {
+    if is_server {
+        return if instance_dir.join(SERVER_WORLD).join("level.dat").exists() {
+            vec![SERVER_WORLD.to_string()]
+        } else {
+            vec![]
+        };
+    }
+
+    let mut worlds = Vec::new();
+    let Ok(mut entries) = fs::read_dir(instance_dir.join("saves")).await else {
+        return worlds;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let is_dir = fs::symlink_metadata(entry.path())
+            .await
+            .map(|m| m.is_dir())
+            .unwrap_or(false);
+        if is_dir && entry.path().join("level.dat").exists() {
+            worlds.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    worlds.sort();
+    worlds
+}
+
+/// `datapacks` folder used when no world is picked: the server world,
+/// or the first world of a client (`world` when there is none)
+pub async fn default_datapacks_dir(instance_dir: &Path, is_server: bool) -> PathBuf {
+    if is_server {
+        return instance_dir.join(SERVER_WORLD).join("datapacks");
+    }
+
+    let world = list_worlds(instance_dir, false)
+        .await
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| SERVER_WORLD.to_string());
+    instance_dir.join("saves").join(world).join("datapacks")
+}
+
+/// Folders a datapack install goes to
+pub async fn target_dirs(
+    instance_dir: &Path,
+    is_server: bool,
+    target: Option<&DatapackTarget>,
+) -> AppResult<Vec<PathBuf>> {
+    match target {
+        None => Ok(vec![default_datapacks_dir(instance_dir, is_server).await]),
+        Some(DatapackTarget::Global) => Ok(vec![instance_dir.join(GLOBAL_DATAPACKS_FOLDER)]),
+        Some(DatapackTarget::Worlds { worlds }) => {
+            if worlds.is_empty() {
+                return Err(AppError::Instance("No world selected".to_string()));
+            }
+            let mut dirs = Vec::new();
+            for world in worlds {
+                let dir = world_dir(instance_dir, is_server, world)?;
+                // The server creates its world on first start, client worlds must exist
+                if !is_server && !dir.join("level.dat").exists() {
+                    return Err(AppError::Instance(format!("World '{}' not found", world)));
+                }
+                let dir = dir.join("datapacks");
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+            Ok(dirs)
+        }
+    }
+}
+
+/// Datapack file and folder names in a `datapacks` folder, with whether
+/// they are enabled on disk (not renamed to `.disabled`)
+async fn scan_datapacks(dir: &Path) -> Vec<(String, bool)> {
+    let mut packs = Vec::new();
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return packs;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name.ends_with(".meta.json") {
+            continue;
+        }
+        let (name, on) = match name.strip_suffix(DISABLED_SUFFIX) {
+            Some(base) => (base.to_string(), false),
+            None => (name, true),
+        };
+        let is_dir = entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false);
+        if name.ends_with(".zip") || (is_dir && entry.path().join("pack.mcmeta").exists()) {
+            packs.push((name, on));
+        }
+    }
+    packs.sort();
+    packs
+}
+
+fn meta_path(dir: &Path, filename: &str) -> PathBuf {
+    dir.join(format!("{}.meta.json", filename.trim_end_matches(".zip")))
+}
+
+async fn read_meta(dir: &Path, filename: &str) -> Option<ModMetadata> {
+    let json = fs::read_to_string(meta_path(dir, filename)).await.ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Describe the datapacks of a folder
+async fn describe(
+    dir: &Path,
+    level: Option<&LevelDataPacks>,
+    global: &BTreeSet<String>,
+) -> Vec<DatapackInfo> {
+    let mut datapacks = Vec::new();
+    for (filename, on) in scan_datapacks(dir).await {
+        let meta = read_meta(dir, &filename).await;
+        let id = pack_id(&filename);
+        let enabled = if !on {
+            Some(false)
+        } else {
+            level.and_then(|level| {
+                if level.enabled.contains(&id) {
+                    Some(true)
+                } else if level.disabled.contains(&id) {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+        };
+
+        datapacks.push(DatapackInfo {
+            name: meta
+                .as_ref()
+                .map(|m| m.name.clone())
+                .unwrap_or_else(|| filename.trim_end_matches(".zip").replace(['-', '_'], " ")),
+            version: meta.as_ref().map(|m| m.version.clone()),
+            project_id: meta.as_ref().map(|m| m.project_id.clone()),
+            icon_url: meta.and_then(|m| m.icon_url),
+            enabled,
+            global: global.contains(&filename),
+            filename,
+        });
+    }
+    datapacks
+}
+
+async fn global_names(instance_dir: &Path) -> BTreeSet<String> {
+    scan_datapacks(&instance_dir.join(GLOBAL_DATAPACKS_FOLDER))
+        .await
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Datapacks of a world with their state in `level.dat`
+pub async fn world_datapacks(
+    instance_dir: &Path,
+    is_server: bool,
+    world: &str,
+) -> AppResult<WorldDatapacks> {
+    let dir = world_dir(instance_dir, is_server, world)?;
+    let level = read_level_datapacks(&dir).await;
+    let global = global_names(instance_dir).await;
+    let datapacks = describe(&dir.join("datapacks"), level.as_ref(), &global).await;
+
+    let builtin_enabled = level
+        .map(|level| {
+            level
+                .enabled
+                .into_iter()
+                .filter(|id| !id.starts_with("file/"))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(WorldDatapacks {
+        world: world.to_string(),
+        datapacks,
+        builtin_enabled,
+    })
+}
+
+/// The instance's global datapacks
+pub async fn global_datapacks(instance_dir: &Path) -> Vec<DatapackInfo> {
+    let global = global_names(instance_dir).await;
+    describe(&instance_dir.join(GLOBAL_DATAPACKS_FOLDER), None, &global).await
+}
+
+/// Delete a global datapack. Worlds keep their copies
+pub async fn remove_global_datapack(instance_dir: &Path, filename: &str) -> AppResult<()> {
+    let dir = instance_dir.join(GLOBAL_DATAPACKS_FOLDER);
+    let Some((name, on)) = scan_datapacks(&dir)
+        .await
+        .into_iter()
+        .find(|(name, _)| name == filename)
+    else {
+        return Err(AppError::Instance(format!(
+            "Global datapack '{}' not found",
+            filename
+        )));
+    };
+
+    let path = if on {
+        dir.join(&name)
+    } else {
+        dir.join(format!("{}{}", name, DISABLED_SUFFIX))
+    };
+    let result = if path.is_dir() {
+        fs::remove_dir_all(&path).await
+    } else {
+        fs::remove_file(&path).await
+    };
+    result.map_err(|e| AppError::Io(format!("Failed to delete {}: {}", name, e)))?;
+    let _ = fs::remove_file(meta_path(&dir, &name)).await;
+    Ok(())
+}
+
+async fn read_known_worlds(global_dir: &Path) -> Option<BTreeSet<String>> {
+    let json = fs::read_to_string(global_dir.join(KNOWN_WORLDS_FILE))
+        .await
+        .ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+async fn write_known_worlds(global_dir: &Path, worlds: &BTreeSet<String>) -> AppResult<()> {
+    fs::create_dir_all(global_dir)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to create global datapacks folder: {}", e)))?;
+    let json = serde_json::to_string_pretty(worlds)?;
+    fs::write(global_dir.join(KNOWN_WORLDS_FILE), json)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to save known worlds: {}", e)))
+}
+
+/// Record the existing worlds the first time global datapacks are set up,
+/// so only worlds created afterwards receive them
+pub async fn init_global_datapacks(instance_dir: &Path, is_server: bool) -> AppResult<()> {
+    let global_dir = instance_dir.join(GLOBAL_DATAPACKS_FOLDER);
+    if read_known_worlds(&global_dir).await.is_some() {
+        return Ok(());
+    }
+    let worlds = list_worlds(instance_dir, is_server).await;
+    write_known_worlds(&global_dir, &worlds.into_iter().collect()).await
+}
+
+/// Copy a datapack (and its metadata) from one `datapacks` folder to another,
+/// leaving an existing copy alone
+pub async fn copy_datapack(from: &Path, to: &Path, filename: &str) -> AppResult<bool> {
+    let src = from.join(filename);
+    let dst = to.join(filename);
+    if dst.exists() || to.join(format!("{}{}", filename, DISABLED_SUFFIX)).exists() {
+        return Ok(false);
+    }
+
+    fs::create_dir_all(to)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to create datapacks folder: {}", e)))?;
+    if src.is_dir() {
+        worlds::copy_directory(&src, &dst).await?;
+    } else {
+        fs::copy(&src, &dst)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to copy {}: {}", filename, e)))?;
+    }
+
+    let meta = meta_path(from, filename);
+    if meta.exists() {
+        let _ = fs::copy(&meta, meta_path(to, filename)).await;
+    }
+    Ok(true)
+}
+
+/// Copy the enabled global datapacks into worlds created since the last sync.
+/// A server world that does not exist yet gets them before its first start.
+/// Returns the worlds that received them
+pub async fn sync_global_datapacks(instance_dir: &Path, is_server: bool) -> AppResult<Vec<String>> {
+    let global_dir = instance_dir.join(GLOBAL_DATAPACKS_FOLDER);
+    let packs: Vec<String> = scan_datapacks(&global_dir)
+        .await
+        .into_iter()
+        .filter_map(|(name, on)| on.then_some(name))
+        .collect();
+    if packs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let Some(mut known) = read_known_worlds(&global_dir).await else {
+        init_global_datapacks(instance_dir, is_server).await?;
+        return Ok(vec![]);
+    };
+
+    let mut worlds = list_worlds(instance_dir, is_server).await;
+    if is_server && worlds.is_empty() {
+        worlds.push(SERVER_WORLD.to_string());
+    }
+    worlds.retain(|w| !known.contains(w));
+
+    let mut synced = Vec::new();
+    for world in worlds {
+        let datapacks_dir = world_dir(instance_dir, is_server, &world)?.join("datapacks");
+        for pack in &packs {
+            copy_datapack(&global_dir, &datapacks_dir, pack).await?;
+        }
+        log::info!("Copied {} global datapack(s) into {}", packs.len(), world);
+        // The server world only counts as known once the server created it
+        if !is_server || datapacks_dir.with_file_name("level.dat").exists() {
+            known.insert(world.clone());
+        }
+        synced.push(world);
+    }
+
+    if !synced.is_empty() {
+        write_known_worlds(&global_dir, &known).await?;
+    }
+    Ok(synced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    #[derive(Serialize)]
+    struct TestLevel {
+        #[serde(rename = "Data")]
+        data: TestData,
+    }
+
+    #[derive(Serialize)]
+    struct TestData {
+        #[serde(rename = "LevelName")]
+        level_name: String,
+        #[serde(rename = "DataPacks")]
+        data_packs: LevelDataPacks,
+    }
+
+    fn level_dat(enabled: &[&str], disabled: &[&str]) -> Vec<u8> {
+        let level = TestLevel {
+            data: TestData {
+                level_name: "Test".to_string(),
+                data_packs: LevelDataPacks {
+                    enabled: enabled.iter().map(|s| s.to_string()).collect(),
+                    disabled: disabled.iter().map(|s| s.to_string()).collect(),
+                },
+            },
+        };
+        let nbt = fastnbt::to_bytes(&level).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&nbt).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn create_world(instance_dir: &Path, world: &str, enabled: &[&str], disabled: &[&str]) {
+        let dir = instance_dir.join("saves").join(world);
+        std::fs::create_dir_all(dir.join("datapacks")).unwrap();
+        std::fs::write(dir.join("level.dat"), level_dat(enabled, disabled)).unwrap();
+    }
+
+    #[test]
+    fn test_parse_level_datapacks() {
+        let packs =
+            parse_level_datapacks(&level_dat(&["vanilla", "file/a.zip"], &["file/b.zip"])).unwrap();
+        assert_eq!(packs.enabled, vec!["vanilla", "file/a.zip"]);
+        assert_eq!(packs.disabled, vec!["file/b.zip"]);
+        assert!(parse_level_datapacks(b"not gzip").is_err());
+    }
+
+    #[test]
+    fn test_world_dir_rejects_escapes() {
+        let instance = Path::new("/instances/test");
+        assert_eq!(
+            world_dir(instance, false, "My World").unwrap(),
+            instance.join("saves").join("My World")
+        );
+        assert_eq!(
+            world_dir(instance, true, "world").unwrap(),
+            instance.join("world")
+        );
+        assert!(world_dir(instance, false, "..").is_err());
+        assert!(world_dir(instance, false, "a/b").is_err());
+        assert!(world_dir(instance, false, "").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_target_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        create_world(dir.path(), "A", &[], &[]);
+        create_world(dir.path(), "B", &[], &[]);
+
+        let target = DatapackTarget::Worlds {
+            worlds: vec!["A".to_string(), "B".to_string(), "A".to_string()],
+        };
+        let dirs = target_dirs(dir.path(), false, Some(&target)).await.unwrap();
+        assert_eq!(
+            dirs,
+            vec![
+                dir.path().join("saves/A/datapacks"),
+                dir.path().join("saves/B/datapacks")
+            ]
+        );
+
+        let missing = DatapackTarget::Worlds {
+            worlds: vec!["C".to_string()],
+        };
+        assert!(target_dirs(dir.path(), false, Some(&missing))
+            .await
+            .is_err());
+
+        let server = target_dirs(dir.path(), true, None).await.unwrap();
+        assert_eq!(server, vec![dir.path().join("world/datapacks")]);
+    }
+
+    #[tokio::test]
+    async fn test_world_datapacks_state() {
+        let dir = tempfile::tempdir().unwrap();
+        create_world(
+            dir.path(),
+            "A",
+            &["vanilla", "fabric", "file/on.zip"],
+            &["file/off.zip"],
+        );
+        let packs_dir = dir.path().join("saves/A/datapacks");
+        for name in ["on.zip", "off.zip", "new.zip", "gone.zip.disabled"] {
+            std::fs::write(packs_dir.join(name), b"").unwrap();
+        }
+
+        let world = world_datapacks(dir.path(), false, "A").await.unwrap();
+        let states: Vec<_> = world
+            .datapacks
+            .iter()
+            .map(|p| (p.filename.as_str(), p.enabled))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("gone.zip", Some(false)),
+                ("new.zip", None),
+                ("off.zip", Some(false)),
+                ("on.zip", Some(true)),
+            ]
+        );
+        assert_eq!(world.builtin_enabled, vec!["vanilla", "fabric"]);
+    }
+
+    #[tokio::test]
+    async fn test_global_datapacks_only_reach_new_worlds() {
+        let dir = tempfile::tempdir().unwrap();
+        create_world(dir.path(), "Old", &[], &[]);
+
+        let global_dir = dir.path().join(GLOBAL_DATAPACKS_FOLDER);
+        std::fs::create_dir_all(&global_dir).unwrap();
+        std::fs::write(global_dir.join("g.zip"), b"pack").unwrap();
+        init_global_datapacks(dir.path(), false).await.unwrap();
+
+        create_world(dir.path(), "New", &[], &[]);
+        let synced = sync_global_datapacks(dir.path(), false).await.unwrap();
+        assert_eq!(synced, vec!["New"]);
+        assert!(dir.path().join("saves/New/datapacks/g.zip").exists());
+        assert!(!dir.path().join("saves/Old/datapacks/g.zip").exists());
+
+        // Nothing left to do on the next sync, even after the copy was removed
+        std::fs::remove_file(dir.path().join("saves/New/datapacks/g.zip")).unwrap();
+        assert!(sync_global_datapacks(dir.path(), false)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let old = world_datapacks(dir.path(), false, "Old").await.unwrap();
+        assert!(old.datapacks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_global_datapacks_before_server_first_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let global_dir = dir.path().join(GLOBAL_DATAPACKS_FOLDER);
+        std::fs::create_dir_all(&global_dir).unwrap();
+        std::fs::write(global_dir.join("g.zip"), b"pack").unwrap();
+        init_global_datapacks(dir.path(), true).await.unwrap();
+
+        let synced = sync_global_datapacks(dir.path(), true).await.unwrap();
+        assert_eq!(synced, vec!["world"]);
+        assert!(dir.path().join("world/datapacks/g.zip").exists());
+    }
+}
@@ -0,0 +1,658 @@
+//! Deduplication of identical content files across instances
+//!
+//! Jars and zips in `mods/`, `resourcepacks/` and `shaderpacks/` are hashed
+//! across every instance. Files with the same contents are replaced with links
+//! to a single copy in `.shared/` in the instances directory: a reflink where
+//! the filesystem can clone files, a hard link otherwise. Hard links share
+//! their data, so the launcher calls [`unlink_shared`] before it replaces a
+//! content file and [`break_link`] before it changes one in place. Every
+//! linked file is recorded, which tells the storage info which bytes are
+//! counted more than once.
+
+use crate::db::instances::Instance;
+use crate::db::shared_files::SharedFileRecord;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Folder of the instances directory holding one copy of each shared file
+pub const POOL_DIR: &str = ".shared";
+
+/// Folders of an instance whose files are shared
+const SHARED_FOLDERS: &[&str] = &["mods", "resourcepacks", "shaderpacks"];
+
+/// Files smaller than this are left alone
+const MIN_SHARED_SIZE: u64 = 16 * 1024;
+
+/// How a file shares its data with the pooled copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMethod {
+    Hardlink,
+    Reflink,
+}
+
+impl LinkMethod {
+    fn as_str(self) -> &'static str {
+        match self {
+            LinkMethod::Hardlink => "hardlink",
+            LinkMethod::Reflink => "reflink",
+        }
+    }
+}
+
+/// Shared files of one instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceShare {
+    pub instance_id: String,
+    pub instance_name: String,
+    pub shared_files: usize,
+    pub shared_bytes: u64,
+}
+
+/// Space used and saved by deduplication
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DedupReport {
+    /// Instance files sharing their data with the pool
+    pub shared_files: usize,
+    /// Distinct files in the pool
+    pub pooled_files: usize,
+    /// Bytes taken by the pool
+    pub pooled_bytes: u64,
+    /// Bytes the shared files would take on top of the pool as separate copies
+    pub saved_bytes: u64,
+    /// Files linked by this run
+    pub linked: usize,
+    /// Files that could not be linked
+    pub failed: usize,
+    pub instances: Vec<InstanceShare>,
+}
+
+/// A content file that may be linked to the pool
+#[derive(Debug, Clone)]
+struct Candidate {
+    instance_id: String,
+    relative: String,
+    path: PathBuf,
+    size: u64,
+}
+
+fn is_shareable(filename: &str) -> bool {
+    let name = filename.to_lowercase();
+    let name = name.strip_suffix(".disabled").unwrap_or(&name);
+    !name.starts_with('.') && (name.ends_with(".jar") || name.ends_with(".zip"))
+}
+
+fn pool_path(pool_dir: &Path, sha1: &str) -> PathBuf {
+    pool_dir.join(&sha1[..2]).join(sha1)
+}
+
+/// Sibling path a file is prepared at before replacing it
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.dedup", name))
+}
+
+fn modified_ns(metadata: &std::fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or_default()
+}
+
+/// Number of hard links to a file
+#[cfg(unix)]
+fn link_count(path: &Path) -> io::Result<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(std::fs::metadata(path)?.nlink())
+}
+
+/// Number of hard links to a file
+#[cfg(windows)]
+fn link_count(path: &Path) -> io::Result<u64> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    };
+
+    let file = std::fs::File::open(path)?;
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(info.nNumberOfLinks as u64)
+}
+
+/// Clone `src` to the new file `dst`, sharing its data until either is written
+#[cfg(target_os = "linux")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let source = std::fs::File::open(src)?;
+    let dest = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dst)?;
+    if unsafe { libc::ioctl(dest.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) } != 0 {
+        let err = io::Error::last_os_error();
+        drop(dest);
+        let _ = std::fs::remove_file(dst);
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Clone `src` to the new file `dst`, sharing its data until either is written
+#[cfg(target_os = "macos")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(src.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    let dst = CString::new(dst.as_os_str().as_bytes()).map_err(io::Error::other)?;
+    if unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Create `dst` sharing the data of `src`, by reflink when possible
+fn share(src: &Path, dst: &Path) -> io::Result<LinkMethod> {
+    if reflink(src, dst).is_ok() {
+        return Ok(LinkMethod::Reflink);
+    }
+    std::fs::hard_link(src, dst)?;
+    Ok(LinkMethod::Hardlink)
+}
+
+/// Put a file into the pool. The file itself is left in place
+fn add_to_pool(file: &Path, pooled: &Path) -> io::Result<LinkMethod> {
+    if let Some(parent) = pooled.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    share(file, pooled)
+}
+
+/// Replace a file with a link to its pooled copy
+fn link_to_pool(pooled: &Path, file: &Path) -> io::Result<LinkMethod> {
+    let temp = temp_path(file);
+    let _ = std::fs::remove_file(&temp);
+    let method = share(pooled, &temp)?;
+    std::fs::rename(&temp, file).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })?;
+    Ok(method)
+}
+
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Give a hard-linked content file its own copy of its data. Writing through a
+/// hard link changes the file in every instance sharing it, so this is called
+/// before a content file is written in place
+pub async fn break_link(path: &Path) -> io::Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_file() => {}
+            _ => return Ok(()),
+        }
+        if link_count(&path)? <= 1 {
+            return Ok(());
+        }
+
+        let temp = temp_path(&path);
+        std::fs::copy(&path, &temp)?;
+        std::fs::rename(&temp, &path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Remove a hard-linked content file that is about to be replaced, so the new
+/// contents do not end up in every instance sharing it. Unlike [`break_link`]
+/// nothing is copied, the data is overwritten anyway
+pub async fn unlink_shared(path: &Path) -> io::Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_file() => {}
+            _ => return Ok(()),
+        }
+        if link_count(&path)? <= 1 {
+            return Ok(());
+        }
+        std::fs::remove_file(&path)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Content files of an instance large enough to be shared
+fn list_candidates(instance_id: &str, instance_dir: &Path) -> Vec<Candidate> {
+    let mut files = Vec::new();
+    for folder in SHARED_FOLDERS {
+        let Ok(entries) = std::fs::read_dir(instance_dir.join(folder)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() || !is_shareable(&name) || metadata.len() < MIN_SHARED_SIZE {
+                continue;
+            }
+            files.push(Candidate {
+                instance_id: instance_id.to_string(),
+                relative: format!("{}/{}", folder, name),
+                path: entry.path(),
+                size: metadata.len(),
+            });
+        }
+    }
+    files
+}
+
+/// Hash the candidates whose size matches another candidate or a pooled file,
+/// files of a unique size cannot have a duplicate
+fn hash_candidates(
+    candidates: Vec<Candidate>,
+    pooled: &HashMap<String, u64>,
+) -> Vec<(String, Candidate)> {
+    let mut sizes: HashMap<u64, usize> = HashMap::new();
+    for size in candidates
+        .iter()
+        .map(|c| c.size)
+        .chain(pooled.values().copied())
+    {
+        *sizes.entry(size).or_default() += 1;
+    }
+
+    candidates
+        .into_iter()
+        .filter(|c| sizes[&c.size] > 1)
+        .filter_map(|c| match hash_file(&c.path) {
+            Ok(sha1) => Some((sha1, c)),
+            Err(e) => {
+                log::warn!("Failed to hash {}: {}", c.path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Candidates by hash, for the hashes held by several files or already pooled
+fn duplicate_groups(
+    hashed: Vec<(String, Candidate)>,
+    pooled: &HashMap<String, u64>,
+) -> BTreeMap<String, Vec<Candidate>> {
+    let mut groups: BTreeMap<String, Vec<Candidate>> = BTreeMap::new();
+    for (sha1, candidate) in hashed {
+        groups.entry(sha1).or_default().push(candidate);
+    }
+    groups.retain(|sha1, files| files.len() > 1 || pooled.contains_key(sha1));
+    groups
+}
+
+/// Link the files of a group to the pool, adding the first one when the hash
+/// is not pooled yet
+fn link_group(pool_dir: &Path, sha1: &str, files: &[Candidate]) -> (Vec<SharedFileRecord>, usize) {
+    let pooled = pool_path(pool_dir, sha1);
+    let mut records = Vec::new();
+    let mut failed = 0;
+
+    for file in files {
+        let linked = if pooled.exists() {
+            link_to_pool(&pooled, &file.path)
+        } else {
+            add_to_pool(&file.path, &pooled)
+        };
+        let record = linked.and_then(|method| {
+            let metadata = std::fs::metadata(&file.path)?;
+            Ok(SharedFileRecord {
+                instance_id: file.instance_id.clone(),
+                path: file.relative.clone(),
+                sha1: sha1.to_string(),
+                size: metadata.len() as i64,
+                method: method.as_str().to_string(),
+                modified_ns: modified_ns(&metadata),
+            })
+        });
+        match record {
+            Ok(record) => records.push(record),
+            Err(e) => {
+                log::warn!("Failed to share {}: {}", file.path.display(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    (records, failed)
+}
+
+/// Remove pooled files no instance file shares anymore
+fn remove_unused_pool_files(pool_dir: &Path, used: &HashMap<String, u64>) {
+    let Ok(prefixes) = std::fs::read_dir(pool_dir) else {
+        return;
+    };
+    for prefix in prefixes.flatten() {
+        let Ok(files) = std::fs::read_dir(prefix.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let sha1 = file.file_name().to_string_lossy().to_string();
+            if !used.contains_key(&sha1) {
+                let _ = std::fs::remove_file(file.path());
+            }
+        }
+        // Only succeeds once the folder is empty
+        let _ = std::fs::remove_dir(prefix.path());
+    }
+}
+
+/// Whether a recorded file still shares its data: written files get a new
+/// modification time
+async fn is_current(record: &SharedFileRecord, path: &Path) -> bool {
+    match tokio::fs::metadata(path).await {
+        Ok(metadata) => {
+            metadata.is_file()
+                && metadata.len() as i64 == record.size
+                && modified_ns(&metadata) == record.modified_ns
+        }
+        Err(_) => false,
+    }
+}
+
+/// Records of the files that still share their data, optionally forgetting the others
+async fn current_records(
+    state: &AppState,
+    instances_dir: &Path,
+    instances: &[Instance],
+    prune: bool,
+) -> AppResult<Vec<SharedFileRecord>> {
+    let game_dirs: HashMap<&str, &str> = instances
+        .iter()
+        .map(|i| (i.id.as_str(), i.game_dir.as_str()))
+        .collect();
+
+    let mut current = Vec::new();
+    for record in SharedFileRecord::get_all(&state.db).await? {
+        let path = game_dirs
+            .get(record.instance_id.as_str())
+            .map(|dir| instances_dir.join(dir).join(&record.path));
+        match path {
+            Some(path) if is_current(&record, &path).await => current.push(record),
+            _ if prune => {
+                SharedFileRecord::delete(&state.db, &record.instance_id, &record.path).await?
+            }
+            _ => {}
+        }
+    }
+    Ok(current)
+}
+
+fn summarize(records: &[SharedFileRecord], instances: &[Instance]) -> DedupReport {
+    let mut pooled: HashMap<&str, u64> = HashMap::new();
+    let mut per_instance: HashMap<&str, (usize, u64)> = HashMap::new();
+    for record in records {
+        pooled.insert(&record.sha1, record.size as u64);
+        let entry = per_instance.entry(&record.instance_id).or_default();
+        entry.0 += 1;
+        entry.1 += record.size as u64;
+    }
+
+    let shared_bytes: u64 = records.iter().map(|r| r.size as u64).sum();
+    let pooled_bytes: u64 = pooled.values().sum();
+    DedupReport {
+        shared_files: records.len(),
+        pooled_files: pooled.len(),
+        pooled_bytes,
+        saved_bytes: shared_bytes.saturating_sub(pooled_bytes),
+        linked: 0,
+        failed: 0,
+        instances: instances
+            .iter()
+            .filter_map(|instance| {
+                let (files, bytes) = per_instance.get(instance.id.as_str())?;
+                Some(InstanceShare {
+                    instance_id: instance.id.clone(),
+                    instance_name: instance.name.clone(),
+                    shared_files: *files,
+                    shared_bytes: *bytes,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Current space used and saved by shared files, without changing anything
+pub async fn report(state: &AppState) -> AppResult<DedupReport> {
+    let instances_dir = state.get_instances_dir().await;
+    let instances = Instance::get_all(&state.db).await?;
+    let records = current_records(state, &instances_dir, &instances, false).await?;
+    Ok(summarize(&records, &instances))
+}
+
+/// Share identical content files of all instances that are not running
+pub async fn deduplicate(state: &AppState) -> AppResult<DedupReport> {
+    let instances_dir = state.get_instances_dir().await;
+    let pool_dir = instances_dir.join(POOL_DIR);
+    let instances = Instance::get_all(&state.db).await?;
+    let current = current_records(state, &instances_dir, &instances, true).await?;
+
+    let running = state.running_instances.read().await;
+    let dirs: Vec<(String, PathBuf)> = instances
+        .iter()
+        .filter(|i| !running.contains_key(&i.id))
+        .map(|i| (i.id.clone(), instances_dir.join(&i.game_dir)))
+        .collect();
+    drop(running);
+
+    let linked: HashSet<(String, String)> = current
+        .iter()
+        .map(|r| (r.instance_id.clone(), r.path.clone()))
+        .collect();
+    let pooled: HashMap<String, u64> = current
+        .iter()
+        .map(|r| (r.sha1.clone(), r.size as u64))
+        .collect();
+
+    let (records, failed) = tokio::task::spawn_blocking(move || {
+        remove_unused_pool_files(&pool_dir, &pooled);
+
+        let candidates: Vec<Candidate> = dirs
+            .iter()
+            .flat_map(|(id, dir)| list_candidates(id, dir))
+            .filter(|c| !linked.contains(&(c.instance_id.clone(), c.relative.clone())))
+            .collect();
+
+        let mut records = Vec::new();
+        let mut failed = 0;
+        for (sha1, files) in duplicate_groups(hash_candidates(candidates, &pooled), &pooled) {
+            let (group_records, group_failed) = link_group(&pool_dir, &sha1, &files);
+            records.extend(group_records);
+            failed += group_failed;
+        }
+        (records, failed)
+    })
+    .await
+    .map_err(|e| AppError::Io(format!("Deduplication failed: {}", e)))?;
+
+    for record in &records {
+        record.save(&state.db).await?;
+    }
+    log::info!("Shared {} content files, {} failed", records.len(), failed);
+
+    let mut all = current;
+    all.extend(records.iter().cloned());
+    let mut report = summarize(&all, &instances);
+    report.linked = records.len();
+    report.failed = failed;
+    Ok(report)
+}
+
+/// Give every shared file its own copy again and remove the pool.
+/// Returns the number of files that were shared
+pub async fn restore(state: &AppState) -> AppResult<usize> {
+    let instances_dir = state.get_instances_dir().await;
+    let instances = Instance::get_all(&state.db).await?;
+    let records = current_records(state, &instances_dir, &instances, false).await?;
+    let game_dirs: HashMap<&str, &str> = instances
+        .iter()
+        .map(|i| (i.id.as_str(), i.game_dir.as_str()))
+        .collect();
+
+    for record in &records {
+        let Some(dir) = game_dirs.get(record.instance_id.as_str()) else {
+            continue;
+        };
+        let path = instances_dir.join(dir).join(&record.path);
+        break_link(&path)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to unshare {}: {}", record.path, e)))?;
+    }
+
+    SharedFileRecord::delete_all(&state.db).await?;
+    let pool_dir = instances_dir.join(POOL_DIR);
+    if pool_dir.exists() {
+        tokio::fs::remove_dir_all(&pool_dir).await?;
+    }
+
+    Ok(records.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(dir: &Path, instance: &str, name: &str, contents: &[u8]) -> Candidate {
+        let path = dir.join(instance).join("mods").join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, contents).unwrap();
+        Candidate {
+            instance_id: instance.to_string(),
+            relative: format!("mods/{}", name),
+            path,
+            size: contents.len() as u64,
+        }
+    }
+
+    #[test]
+    fn test_is_shareable() {
+        assert!(is_shareable("sodium-0.5.jar"));
+        assert!(is_shareable("Sodium-0.5.JAR.disabled"));
+        assert!(is_shareable("Faithful.zip"));
+        assert!(!is_shareable("sodium-0.5.meta.json"));
+        assert!(!is_shareable(".sodium-0.5.jar.dedup"));
+        assert!(!is_shareable("options.txt"));
+    }
+
+    #[test]
+    fn test_duplicate_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = candidate(dir.path(), "a", "x.jar", b"x");
+        let b = candidate(dir.path(), "b", "x.jar", b"x");
+        let c = candidate(dir.path(), "c", "y.jar", b"y");
+        let d = candidate(dir.path(), "d", "z.jar", b"z");
+        let hashed = vec![
+            ("x".to_string(), a),
+            ("x".to_string(), b),
+            ("y".to_string(), c),
+            ("z".to_string(), d),
+        ];
+        let pooled = HashMap::from([("z".to_string(), 1)]);
+
+        let groups = duplicate_groups(hashed, &pooled);
+        assert_eq!(groups.keys().collect::<Vec<_>>(), vec!["x", "z"]);
+        assert_eq!(groups["x"].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_link_and_break() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool_dir = dir.path().join(POOL_DIR);
+        let contents = vec![7u8; 64];
+        let files = vec![
+            candidate(dir.path(), "a", "lib.jar", &contents),
+            candidate(dir.path(), "b", "lib.jar", &contents),
+        ];
+        let sha1 = hash_file(&files[0].path).unwrap();
+
+        let (records, failed) = link_group(&pool_dir, &sha1, &files);
+        assert_eq!((records.len(), failed), (2, 0));
+        assert!(pool_path(&pool_dir, &sha1).exists());
+        if records[1].method == "hardlink" {
+            assert_eq!(link_count(&files[1].path).unwrap(), 3);
+        }
+
+        // Writing after breaking the link leaves the other instance alone
+        break_link(&files[1].path).await.unwrap();
+        assert_eq!(link_count(&files[1].path).unwrap(), 1);
+        std::fs::write(&files[1].path, b"changed").unwrap();
+        assert_eq!(std::fs::read(&files[0].path).unwrap(), contents);
+        assert_eq!(
+            std::fs::read(pool_path(&pool_dir, &sha1)).unwrap(),
+            contents
+        );
+
+        // Replacing a linked file only drops this link
+        unlink_shared(&files[0].path).await.unwrap();
+        std::fs::write(&files[0].path, b"replaced").unwrap();
+        assert_eq!(
+            std::fs::read(pool_path(&pool_dir, &sha1)).unwrap(),
+            contents
+        );
+
+        // The pooled copy goes once nothing records it
+        remove_unused_pool_files(&pool_dir, &HashMap::new());
+        assert!(!pool_path(&pool_dir, &sha1).exists());
+        assert!(!pool_dir.join(&sha1[..2]).exists());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let record = |instance: &str, sha1: &str, size| SharedFileRecord {
+            instance_id: instance.to_string(),
+            path: format!("mods/{}.jar", sha1),
+            sha1: sha1.to_string(),
+            size,
+            method: "hardlink".to_string(),
+            modified_ns: 0,
+        };
+        let records = vec![
+            record("a", "x", 100),
+            record("b", "x", 100),
+            record("c", "x", 100),
+            record("a", "y", 40),
+            record("b", "y", 40),
+        ];
+
+        let report = summarize(&records, &[]);
+        assert_eq!(report.shared_files, 5);
+        assert_eq!(report.pooled_files, 2);
+        assert_eq!(report.pooled_bytes, 140);
+        assert_eq!(report.saved_bytes, 240);
+    }
+}
@@ -0,0 +1,134 @@
+//! Reading and editing the game's `options.txt`
+//!
+//! The file is a list of `key:value` lines. Only the lines being changed are
+//! rewritten, everything else (including unknown keys) is kept as is.
+
+use crate::error::{AppError, AppResult};
+use std::path::Path;
+
+pub const OPTIONS_FILE: &str = "options.txt";
+
+/// Enabled resource packs, in the order the game applies them (lowest priority first)
+pub const RESOURCE_PACKS_KEY: &str = "resourcePacks";
+
+/// Enabled resource packs the player accepted despite being made for another version
+pub const INCOMPATIBLE_RESOURCE_PACKS_KEY: &str = "incompatibleResourcePacks";
+
+/// Value of a key, if present
+pub fn get_option<'a>(contents: &'a str, key: &str) -> Option<&'a str> {
+    contents.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k == key).then_some(v.trim_end_matches('\r'))
+    })
+}
+
+/// Set a key, replacing its line or appending one
+pub fn set_option(contents: &str, key: &str, value: &str) -> String {
+    let newline = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut found = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|line| match line.split_once(':') {
+            Some((k, _)) if k == key => {
+                found = true;
+                format!("{}:{}", key, value)
+            }
+            _ => line.trim_end_matches('\r').to_string(),
+        })
+        .collect();
+
+    if !found {
+        lines.push(format!("{}:{}", key, value));
+    }
+
+    let mut result = lines.join(newline);
+    result.push_str(newline);
+    result
+}
+
+/// Parse a pack list value such as `["vanilla","file/Faithful.zip"]`
+pub fn parse_pack_list(value: &str) -> Vec<String> {
+    serde_json::from_str(value).unwrap_or_default()
+}
+
+/// Format a pack list the way the game writes it
+pub fn format_pack_list(packs: &[String]) -> String {
+    serde_json::to_string(packs).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Read `options.txt` from a game directory, empty when the game never wrote it
+pub async fn read_options(game_dir: &Path) -> AppResult<String> {
+    match tokio::fs::read_to_string(game_dir.join(OPTIONS_FILE)).await {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(AppError::Io(format!("Failed to read options.txt: {}", e))),
+    }
+}
+
+/// Replace `options.txt` in a game directory through a temporary file,
+/// so the game never sees a half-written file
+pub async fn write_options(game_dir: &Path, contents: &str) -> AppResult<()> {
+    let path = game_dir.join(OPTIONS_FILE);
+    let temp = game_dir.join(format!("{}.tmp", OPTIONS_FILE));
+
+    tokio::fs::write(&temp, contents)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write options.txt: {}", e)))?;
+    if let Err(e) = tokio::fs::rename(&temp, &path).await {
+        let _ = tokio::fs::remove_file(&temp).await;
+        return Err(AppError::Io(format!("Failed to write options.txt: {}", e)));
+    }
+    Ok(())
+}
+
+/// Enabled resource packs of a game directory
+pub async fn read_resource_packs(game_dir: &Path) -> AppResult<Vec<String>> {
+    let contents = read_options(game_dir).await?;
+    Ok(get_option(&contents, RESOURCE_PACKS_KEY)
+        .map(parse_pack_list)
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: &str =
+        "version:3465\nresourcePacks:[\"vanilla\",\"file/Faithful.zip\"]\nlang:en_us\n";
+
+    #[test]
+    fn test_get_option() {
+        assert_eq!(get_option(OPTIONS, "lang"), Some("en_us"));
+        assert_eq!(get_option(OPTIONS, "fov"), None);
+        assert_eq!(get_option("lang:fr_fr\r\n", "lang"), Some("fr_fr"));
+    }
+
+    #[test]
+    fn test_set_option() {
+        let updated = set_option(OPTIONS, "lang", "fr_fr");
+        assert_eq!(
+            updated,
+            "version:3465\nresourcePacks:[\"vanilla\",\"file/Faithful.zip\"]\nlang:fr_fr\n"
+        );
+
+        let appended = set_option("version:3465\r\n", "fov", "0.5");
+        assert_eq!(appended, "version:3465\r\nfov:0.5\r\n");
+
+        assert_eq!(set_option("", "fov", "0.5"), "fov:0.5\n");
+    }
+
+    #[test]
+    fn test_pack_list_round_trip() {
+        let packs = parse_pack_list(get_option(OPTIONS, RESOURCE_PACKS_KEY).unwrap());
+        assert_eq!(packs, vec!["vanilla", "file/Faithful.zip"]);
+        assert_eq!(
+            format_pack_list(&packs),
+            "[\"vanilla\",\"file/Faithful.zip\"]"
+        );
+        assert!(parse_pack_list("not json").is_empty());
+    }
+}
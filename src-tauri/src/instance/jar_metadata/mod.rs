@@ -0,0 +1,1229 @@
+//! Jar metadata inspector
+//!
+//! Reads the descriptor shipped inside mod and plugin jars (Fabric, Quilt,
+//! Forge, NeoForge, legacy Forge, Bukkit/Paper and Velocity) to get a file's
+//! real identity instead of guessing it from the filename. Results are cached
+//! on disk by file hash, so a jar is only opened once.
+
+use crate::error::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use zip::ZipArchive;
+
+/// Bump when the parsing logic changes so cached results are re-read
+const CACHE_FORMAT_VERSION: u32 = 3;
+
+/// Icons larger than this are not embedded
+const MAX_ICON_SIZE: u64 = 256 * 1024;
+
+/// Nested jars larger than this are not inspected
+const MAX_NESTED_JAR_SIZE: u64 = 64 * 1024 * 1024;
+
+/// How deep jar-in-jar bundles are followed
+const MAX_NESTING_DEPTH: usize = 4;
+
+/// Descriptor files, in the order they are looked up
+const DESCRIPTORS: [(&str, JarMetadataFormat); 8] = [
+    ("fabric.mod.json", JarMetadataFormat::Fabric),
+    ("quilt.mod.json", JarMetadataFormat::Quilt),
+    ("META-INF/neoforge.mods.toml", JarMetadataFormat::NeoForge),
+    ("META-INF/mods.toml", JarMetadataFormat::Forge),
+    ("mcmod.info", JarMetadataFormat::LegacyForge),
+    ("velocity-plugin.json", JarMetadataFormat::Velocity),
+    ("paper-plugin.yml", JarMetadataFormat::Paper),
+    ("plugin.yml", JarMetadataFormat::Bukkit),
+];
+
+/// Descriptor a jar's metadata was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JarMetadataFormat {
+    /// fabric.mod.json
+    Fabric,
+    /// quilt.mod.json
+    Quilt,
+    /// META-INF/mods.toml
+    Forge,
+    /// META-INF/neoforge.mods.toml
+    NeoForge,
+    /// mcmod.info
+    LegacyForge,
+    /// plugin.yml
+    Bukkit,
+    /// paper-plugin.yml
+    Paper,
+    /// velocity-plugin.json
+    Velocity,
+}
+
+/// Physical side a mod runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModSide {
+    Client,
+    Server,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    Required,
+    Optional,
+    Incompatible,
+    /// Known to cause problems, but the loader only warns about it
+    Discouraged,
+}
+
+/// A dependency declared in a jar descriptor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JarDependency {
+    pub id: String,
+    /// Version requirement in the descriptor's own syntax (Fabric semver or Maven range)
+    pub version_range: Option<String>,
+    pub kind: DependencyKind,
+}
+
+/// A mod shipped inside another jar (jar-in-jar)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledMod {
+    pub id: String,
+    pub version: Option<String>,
+}
+
+/// Identity of a mod or plugin jar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JarMetadata {
+    pub format: JarMetadataFormat,
+    pub id: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    pub dependencies: Vec<JarDependency>,
+    pub side: Option<ModSide>,
+    /// Embedded icon as a data URL
+    pub icon: Option<String>,
+    /// Additional ids this jar satisfies (Fabric `provides`, extra mods in a mods.toml)
+    #[serde(default)]
+    pub provides: Vec<String>,
+    #[serde(default)]
+    pub bundled: Vec<BundledMod>,
+    /// Descriptors for other loaders shipped in the same jar (multi-loader jars)
+    #[serde(default)]
+    pub other_formats: Vec<JarMetadataFormat>,
+}
+
+/// Result of inspecting a jar on disk
+#[derive(Debug, Clone)]
+pub struct InspectedJar {
+    pub sha1: String,
+    pub metadata: Option<JarMetadata>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedJarMetadata {
+    format_version: u32,
+    metadata: Option<JarMetadata>,
+}
+
+/// Size, modification time and SHA-1 of a jar
+type HashIndexEntry = (u64, SystemTime, String);
+
+/// Jar path -> last known hash, avoids re-hashing unchanged files
+static HASH_INDEX: Lazy<Mutex<HashMap<PathBuf, HashIndexEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cache_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("cache").join("jar_metadata")
+}
+
+/// Inspect a jar, using the on-disk cache when the same file was seen before
+pub async fn inspect_jar(data_dir: &Path, jar_path: &Path) -> AppResult<InspectedJar> {
+    let sha1 = jar_sha1(jar_path).await?;
+    let cache_path = cache_dir(data_dir).join(format!("{}.json", sha1));
+
+    if let Ok(content) = tokio::fs::read_to_string(&cache_path).await {
+        if let Ok(cached) = serde_json::from_str::<CachedJarMetadata>(&content) {
+            if cached.format_version == CACHE_FORMAT_VERSION {
+                return Ok(InspectedJar {
+                    sha1,
+                    metadata: cached.metadata,
+                });
+            }
+        }
+    }
+
+    let path = jar_path.to_path_buf();
+    let metadata = tokio::task::spawn_blocking(move || read_jar_metadata(&path))
+        .await
+        .map_err(|e| AppError::Io(format!("Jar inspection task failed: {}", e)))??;
+
+    let cached = CachedJarMetadata {
+        format_version: CACHE_FORMAT_VERSION,
+        metadata,
+    };
+    let write_result = async {
+        tokio::fs::create_dir_all(cache_dir(data_dir)).await?;
+        tokio::fs::write(&cache_path, serde_json::to_vec(&cached)?).await
+    }
+    .await;
+    if let Err(e) = write_result {
+        tracing::warn!("Failed to cache jar metadata for {:?}: {}", jar_path, e);
+    }
+
+    Ok(InspectedJar {
+        sha1,
+        metadata: cached.metadata,
+    })
+}
+
+/// SHA-1 of a jar, reusing the previous hash while size and mtime are unchanged
+pub(crate) async fn jar_sha1(jar_path: &Path) -> AppResult<String> {
+    let file_meta = tokio::fs::metadata(jar_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to read {:?}: {}", jar_path, e)))?;
+    let size = file_meta.len();
+    let modified = file_meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+
+    if let Ok(index) = HASH_INDEX.lock() {
+        if let Some((s, m, hash)) = index.get(jar_path) {
+            if *s == size && *m == modified {
+                return Ok(hash.clone());
+            }
+        }
+    }
+
+    let path = jar_path.to_path_buf();
+    let hash = tokio::task::spawn_blocking(move || -> std::io::Result<String> {
+        let mut file = std::fs::File::open(&path)?;
+        let mut hasher = Sha1::new();
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(hex::encode(hasher.finalize()))
+    })
+    .await
+    .map_err(|e| AppError::Io(format!("Hashing task failed: {}", e)))?
+    .map_err(|e| AppError::Io(format!("Failed to hash {:?}: {}", jar_path, e)))?;
+
+    if let Ok(mut index) = HASH_INDEX.lock() {
+        index.insert(jar_path.to_path_buf(), (size, modified, hash.clone()));
+    }
+
+    Ok(hash)
+}
+
+/// Read the metadata of a jar without caching
+pub fn read_jar_metadata(jar_path: &Path) -> AppResult<Option<JarMetadata>> {
+    let file = std::fs::File::open(jar_path)
+        .map_err(|e| AppError::Io(format!("Failed to open {:?}: {}", jar_path, e)))?;
+    let Ok(mut archive) = ZipArchive::new(file) else {
+        // Not a valid zip, nothing to read
+        return Ok(None);
+    };
+    Ok(inspect_archive(&mut archive, 0))
+}
+
+fn inspect_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    depth: usize,
+) -> Option<JarMetadata> {
+    let mut metadata = None;
+    let mut icon_path = None;
+    let mut nested_jars = Vec::new();
+
+    if let Some(content) = read_entry_string(archive, "fabric.mod.json") {
+        if let Some((meta, icon, jars)) = parse_fabric_mod_json(&content) {
+            metadata = Some(meta);
+            icon_path = icon;
+            nested_jars = jars;
+        }
+    }
+    if metadata.is_none() {
+        if let Some(content) = read_entry_string(archive, "quilt.mod.json") {
+            if let Some((meta, icon, jars)) = parse_quilt_mod_json(&content) {
+                metadata = Some(meta);
+                icon_path = icon;
+                nested_jars = jars;
+            }
+        }
+    }
+    for (entry, format) in [
+        ("META-INF/neoforge.mods.toml", JarMetadataFormat::NeoForge),
+        ("META-INF/mods.toml", JarMetadataFormat::Forge),
+    ] {
+        if metadata.is_some() {
+            break;
+        }
+        if let Some(content) = read_entry_string(archive, entry) {
+            if let Some((mut meta, icon)) = parse_mods_toml(&content, format) {
+                if meta.version.as_deref().is_some_and(|v| v.contains("${")) {
+                    meta.version = read_entry_string(archive, "META-INF/MANIFEST.MF")
+                        .and_then(|m| manifest_attribute(&m, "Implementation-Version"));
+                }
+                metadata = Some(meta);
+                icon_path = icon;
+                nested_jars = read_entry_string(archive, "META-INF/jarjar/metadata.json")
+                    .map(|c| parse_jarjar_metadata(&c))
+                    .unwrap_or_default();
+            }
+        }
+    }
+    if metadata.is_none() {
+        if let Some(content) = read_entry_string(archive, "mcmod.info") {
+            if let Some((meta, icon)) = parse_mcmod_info(&content) {
+                metadata = Some(meta);
+                icon_path = icon;
+            }
+        }
+    }
+    if metadata.is_none() {
+        metadata = read_entry_string(archive, "velocity-plugin.json")
+            .and_then(|c| parse_velocity_plugin_json(&c))
+            .or_else(|| {
+                read_entry_string(archive, "paper-plugin.yml")
+                    .and_then(|c| parse_plugin_yml(&c, JarMetadataFormat::Paper))
+            })
+            .or_else(|| {
+                read_entry_string(archive, "plugin.yml")
+                    .and_then(|c| parse_plugin_yml(&c, JarMetadataFormat::Bukkit))
+            });
+    }
+
+    let mut metadata = metadata?;
+
+    for (entry, format) in DESCRIPTORS {
+        if format != metadata.format
+            && archive.index_for_name(entry).is_some()
+            && !metadata.other_formats.contains(&format)
+        {
+            metadata.other_formats.push(format);
+        }
+    }
+
+    // Icons of nested jars are never shown, skip reading them
+    if depth == 0 {
+        metadata.icon = icon_path.and_then(|p| read_icon(archive, &p));
+    }
+
+    for_each_nested_jar(archive, depth, nested_jars, |_, nested, depth| {
+        let Some(inner) = inspect_archive(nested, depth) else {
+            return;
+        };
+        metadata.bundled.push(BundledMod {
+            id: inner.id,
+            version: inner.version.clone(),
+        });
+        for provided in inner.provides {
+            metadata.bundled.push(BundledMod {
+                id: provided,
+                version: inner.version.clone(),
+            });
+        }
+        metadata.bundled.extend(inner.bundled);
+    });
+
+    Some(metadata)
+}
+
+/// Open the given jars nested in an archive at `depth` and pass each one to
+/// `visit` with its entry name and depth. Jars that are too large, not zips,
+/// or nested too deep are skipped
+pub(crate) fn for_each_nested_jar<R, I, F>(
+    archive: &mut ZipArchive<R>,
+    depth: usize,
+    names: I,
+    mut visit: F,
+) where
+    R: Read + Seek,
+    I: IntoIterator<Item = String>,
+    F: FnMut(&str, &mut ZipArchive<Cursor<Vec<u8>>>, usize),
+{
+    if depth >= MAX_NESTING_DEPTH {
+        return;
+    }
+    for name in names {
+        let Some(bytes) = read_entry_bytes(archive, &name, MAX_NESTED_JAR_SIZE) else {
+            continue;
+        };
+        let Ok(mut nested) = ZipArchive::new(Cursor::new(bytes)) else {
+            continue;
+        };
+        visit(&name, &mut nested, depth + 1);
+    }
+}
+
+fn read_entry_bytes<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+    max_size: u64,
+) -> Option<Vec<u8>> {
+    let name = name.trim_start_matches('/');
+    let mut entry = archive.by_name(name).ok()?;
+    if entry.size() > max_size {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+fn read_entry_string<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<String> {
+    let bytes = read_entry_bytes(archive, name, MAX_NESTED_JAR_SIZE)?;
+    let content = String::from_utf8_lossy(&bytes);
+    // Some descriptors are saved with a BOM
+    Some(content.trim_start_matches('\u{feff}').to_string())
+}
+
+fn read_icon<R: Read + Seek>(archive: &mut ZipArchive<R>, path: &str) -> Option<String> {
+    let lower = path.to_lowercase();
+    let mime = if lower.ends_with(".png") {
+        "image/png"
+    } else if lower.ends_with(".jpg") || lower.ends_with(".jpeg") {
+        "image/jpeg"
+    } else if lower.ends_with(".gif") {
+        "image/gif"
+    } else if lower.ends_with(".webp") {
+        "image/webp"
+    } else {
+        return None;
+    };
+    let bytes = read_entry_bytes(archive, path, MAX_ICON_SIZE)?;
+    Some(format!("data:{};base64,{}", mime, STANDARD.encode(bytes)))
+}
+
+fn manifest_attribute(manifest: &str, key: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key)
+            .then(|| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+// ============================================================================
+// Descriptor parsers
+// ============================================================================
+
+/// String value of a JSON scalar
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Strings from a value that may be a single string or a list of strings/objects
+fn string_list(value: &Value, object_key: &str) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::Object(map) => map.get(object_key).and_then(scalar_string),
+                other => scalar_string(other),
+            })
+            .collect(),
+        Value::Object(map) => map
+            .get(object_key)
+            .and_then(scalar_string)
+            .into_iter()
+            .collect(),
+        other => scalar_string(other).into_iter().collect(),
+    }
+}
+
+/// Split a comma separated author string ("Alice, Bob and Carol")
+fn split_authors(authors: &str) -> Vec<String> {
+    authors
+        .split([',', '&'])
+        .flat_map(|part| part.split(" and "))
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// Icon path from a string or a size -> path map (largest wins)
+fn icon_path(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(size, path)| Some((size.parse::<u32>().unwrap_or(0), path.as_str()?)))
+            .max_by_key(|(size, _)| *size)
+            .map(|(_, path)| path.to_string()),
+        _ => None,
+    }
+}
+
+fn value_version_range(value: &Value) -> Option<String> {
+    match value {
+        Value::Array(items) => {
+            let ranges: Vec<String> = items.iter().filter_map(scalar_string).collect();
+            (!ranges.is_empty()).then(|| ranges.join(" || "))
+        }
+        other => scalar_string(other),
+    }
+    .filter(|r| r != "*")
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FabricModJson {
+    id: String,
+    version: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    authors: Value,
+    environment: Option<String>,
+    icon: Value,
+    depends: BTreeMap<String, Value>,
+    recommends: BTreeMap<String, Value>,
+    suggests: BTreeMap<String, Value>,
+    breaks: BTreeMap<String, Value>,
+    conflicts: BTreeMap<String, Value>,
+    provides: Vec<String>,
+    jars: Vec<FabricJar>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FabricJar {
+    file: String,
+}
+
+/// Parse fabric.mod.json, returning the metadata, icon path and nested jars
+fn parse_fabric_mod_json(content: &str) -> Option<(JarMetadata, Option<String>, Vec<String>)> {
+    let json: FabricModJson = serde_json::from_str(content).ok()?;
+    if json.id.is_empty() {
+        return None;
+    }
+
+    let mut dependencies = Vec::new();
+    for (map, kind) in [
+        (&json.depends, DependencyKind::Required),
+        (&json.recommends, DependencyKind::Optional),
+        (&json.suggests, DependencyKind::Optional),
+        (&json.breaks, DependencyKind::Incompatible),
+        (&json.conflicts, DependencyKind::Discouraged),
+    ] {
+        dependencies.extend(map.iter().map(|(id, range)| JarDependency {
+            id: id.clone(),
+            version_range: value_version_range(range),
+            kind,
+        }));
+    }
+
+    let side = match json.environment.as_deref() {
+        Some("client") => ModSide::Client,
+        Some("server") => ModSide::Server,
+        _ => ModSide::Both,
+    };
+
+    let metadata = JarMetadata {
+        format: JarMetadataFormat::Fabric,
+        id: json.id,
+        name: json.name,
+        version: json.version,
+        description: json.description,
+        authors: string_list(&json.authors, "name"),
+        dependencies,
+        side: Some(side),
+        icon: None,
+        provides: json.provides,
+        bundled: Vec::new(),
+        other_formats: Vec::new(),
+    };
+    let jars = json.jars.into_iter().map(|j| j.file).collect();
+    Some((metadata, icon_path(&json.icon), jars))
+}
+
+/// Parse quilt.mod.json, returning the metadata, icon path and nested jars
+fn parse_quilt_mod_json(content: &str) -> Option<(JarMetadata, Option<String>, Vec<String>)> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let loader = json.get("quilt_loader")?;
+    let id = loader.get("id").and_then(scalar_string)?;
+    let meta = loader.get("metadata").cloned().unwrap_or(Value::Null);
+
+    let mut dependencies = Vec::new();
+    for (key, default_kind) in [
+        ("depends", DependencyKind::Required),
+        ("breaks", DependencyKind::Incompatible),
+    ] {
+        let Some(Value::Array(entries)) = loader.get(key) else {
+            continue;
+        };
+        for entry in entries {
+            let (dep_id, range, optional) = match entry {
+                Value::String(id) => (Some(id.clone()), None, false),
+                Value::Object(map) => (
+                    map.get("id").and_then(scalar_string),
+                    map.get("versions").and_then(value_version_range),
+                    map.get("optional")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                ),
+                _ => continue,
+            };
+            let Some(dep_id) = dep_id else {
+                continue;
+            };
+            // Quilt ids may be namespaced as "maven_group:id"
+            let dep_id = dep_id.rsplit(':').next().unwrap_or(&dep_id).to_string();
+            let kind = if optional && default_kind == DependencyKind::Required {
+                DependencyKind::Optional
+            } else {
+                default_kind
+            };
+            dependencies.push(JarDependency {
+                id: dep_id,
+                version_range: range,
+                kind,
+            });
+        }
+    }
+
+    let authors = match meta.get("contributors") {
+        Some(Value::Object(map)) => map.keys().cloned().collect(),
+        _ => Vec::new(),
+    };
+
+    let side = match json
+        .get("minecraft")
+        .and_then(|m| m.get("environment"))
+        .and_then(Value::as_str)
+    {
+        Some("client") => ModSide::Client,
+        Some("dedicated_server") => ModSide::Server,
+        _ => ModSide::Both,
+    };
+
+    let provides = loader
+        .get("provides")
+        .map(|p| string_list(p, "id"))
+        .unwrap_or_default();
+    let jars = loader
+        .get("jars")
+        .map(|j| string_list(j, "file"))
+        .unwrap_or_default();
+
+    let metadata = JarMetadata {
+        format: JarMetadataFormat::Quilt,
+        id,
+        name: meta.get("name").and_then(scalar_string),
+        version: loader.get("version").and_then(scalar_string),
+        description: meta.get("description").and_then(scalar_string),
+        authors,
+        dependencies,
+        side: Some(side),
+        icon: None,
+        provides,
+        bundled: Vec::new(),
+        other_formats: Vec::new(),
+    };
+    Some((metadata, meta.get("icon").and_then(icon_path), jars))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ModsToml {
+    #[serde(rename = "logoFile")]
+    logo_file: Option<String>,
+    mods: Vec<ModsTomlMod>,
+    dependencies: BTreeMap<String, Vec<ModsTomlDependency>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ModsTomlMod {
+    #[serde(rename = "modId")]
+    mod_id: String,
+    version: Option<String>,
+    #[serde(rename = "displayName")]
+    display_name: Option<String>,
+    description: Option<String>,
+    authors: Value,
+    #[serde(rename = "logoFile")]
+    logo_file: Option<String>,
+    #[serde(rename = "displayTest")]
+    display_test: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ModsTomlDependency {
+    #[serde(rename = "modId")]
+    mod_id: String,
+    /// Forge
+    mandatory: Option<bool>,
+    /// NeoForge: "required", "optional", "incompatible" or "discouraged"
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(rename = "versionRange")]
+    version_range: Option<String>,
+}
+
+/// Parse (neoforge.)mods.toml, returning the metadata and icon path
+fn parse_mods_toml(
+    content: &str,
+    format: JarMetadataFormat,
+) -> Option<(JarMetadata, Option<String>)> {
+    let toml: ModsToml = toml::from_str(content).ok()?;
+    let mut mods = toml.mods.into_iter().filter(|m| !m.mod_id.is_empty());
+    let primary = mods.next()?;
+    let provides = mods.map(|m| m.mod_id).collect();
+
+    let dependencies = toml
+        .dependencies
+        .get(&primary.mod_id)
+        .map(|deps| {
+            deps.iter()
+                .filter(|d| !d.mod_id.is_empty())
+                .map(|d| {
+                    let kind = match d.kind.as_deref().map(str::to_lowercase).as_deref() {
+                        Some("required") => DependencyKind::Required,
+                        Some("incompatible") => DependencyKind::Incompatible,
+                        Some("discouraged") => DependencyKind::Discouraged,
+                        Some(_) => DependencyKind::Optional,
+                        None if d.mandatory.unwrap_or(true) => DependencyKind::Required,
+                        None => DependencyKind::Optional,
+                    };
+                    JarDependency {
+                        id: d.mod_id.clone(),
+                        version_range: d.version_range.clone().filter(|r| !r.is_empty()),
+                        kind,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Client-only mods ignore version checks entirely, server-only mods ignore the server's
+    let side = match primary.display_test.as_deref() {
+        Some("IGNORE_ALL_VERSION") => Some(ModSide::Client),
+        Some("IGNORE_SERVER_VERSION") => Some(ModSide::Server),
+        _ => None,
+    };
+
+    let authors = match &primary.authors {
+        Value::String(s) => split_authors(s),
+        other => string_list(other, "name"),
+    };
+
+    let icon = primary.logo_file.clone().or(toml.logo_file);
+    let metadata = JarMetadata {
+        format,
+        id: primary.mod_id,
+        name: primary.display_name,
+        version: primary.version,
+        description: primary.description.map(|d| d.trim().to_string()),
+        authors,
+        dependencies,
+        side,
+        icon: None,
+        provides,
+        bundled: Vec::new(),
+        other_formats: Vec::new(),
+    };
+    Some((metadata, icon))
+}
+
+/// Paths of jars listed in META-INF/jarjar/metadata.json
+fn parse_jarjar_metadata(content: &str) -> Vec<String> {
+    serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|v| v.get("jars").map(|jars| string_list(jars, "path")))
+        .unwrap_or_default()
+}
+
+/// Parse a legacy mcmod.info (either a bare list or `modListVersion` 2)
+fn parse_mcmod_info(content: &str) -> Option<(JarMetadata, Option<String>)> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let list = match &json {
+        Value::Array(list) => list.clone(),
+        Value::Object(map) => map.get("modList")?.as_array()?.clone(),
+        _ => return None,
+    };
+    let primary = list.first()?;
+    let id = primary.get("modid").and_then(scalar_string)?;
+
+    let mut authors = primary
+        .get("authorList")
+        .or_else(|| primary.get("authors"))
+        .map(|a| string_list(a, "name"))
+        .unwrap_or_default();
+    if authors.is_empty() {
+        authors = primary
+            .get("author")
+            .and_then(scalar_string)
+            .map(|a| split_authors(&a))
+            .unwrap_or_default();
+    }
+
+    // Entries look like "modid" or "modid@[1.0,)"
+    let dependencies = primary
+        .get("requiredMods")
+        .map(|r| string_list(r, "modid"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| {
+            let (dep_id, range) = match entry.split_once('@') {
+                Some((id, range)) => (id.to_string(), Some(range.to_string())),
+                None => (entry, None),
+            };
+            JarDependency {
+                id: dep_id,
+                version_range: range,
+                kind: DependencyKind::Required,
+            }
+        })
+        .collect();
+
+    let provides = list
+        .iter()
+        .skip(1)
+        .filter_map(|m| m.get("modid").and_then(scalar_string))
+        .collect();
+
+    let metadata = JarMetadata {
+        format: JarMetadataFormat::LegacyForge,
+        id,
+        name: primary.get("name").and_then(scalar_string),
+        version: primary.get("version").and_then(scalar_string),
+        description: primary.get("description").and_then(scalar_string),
+        authors,
+        dependencies,
+        side: None,
+        icon: None,
+        provides,
+        bundled: Vec::new(),
+        other_formats: Vec::new(),
+    };
+    let icon = primary.get("logoFile").and_then(scalar_string);
+    Some((metadata, icon))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PluginYml {
+    name: Option<String>,
+    /// Read as written, `version: 1.20` is not the number 1.2
+    version: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    authors: Value,
+    depend: Value,
+    softdepend: Value,
+    dependencies: Value,
+}
+
+/// Parse plugin.yml or paper-plugin.yml
+fn parse_plugin_yml(content: &str, format: JarMetadataFormat) -> Option<JarMetadata> {
+    let yaml: PluginYml = serde_yaml::from_str(content).ok()?;
+    let name = trimmed(yaml.name)?;
+
+    let mut authors = string_list(&yaml.authors, "name");
+    if let Some(author) = trimmed(yaml.author) {
+        if !authors.contains(&author) {
+            authors.insert(0, author);
+        }
+    }
+
+    let mut dependencies = Vec::new();
+    let mut push_list = |value: &Value, kind: DependencyKind| {
+        for id in string_list(value, "name") {
+            dependencies.push(JarDependency {
+                id,
+                version_range: None,
+                kind,
+            });
+        }
+    };
+    push_list(&yaml.depend, DependencyKind::Required);
+    push_list(&yaml.softdepend, DependencyKind::Optional);
+
+    // paper-plugin.yml: dependencies.server.<name>.required (defaults to true)
+    match &yaml.dependencies {
+        Value::Object(groups) => {
+            if let Some(Value::Object(server)) = groups.get("server") {
+                for (id, spec) in server {
+                    let required = spec
+                        .get("required")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(true);
+                    dependencies.push(JarDependency {
+                        id: id.clone(),
+                        version_range: None,
+                        kind: if required {
+                            DependencyKind::Required
+                        } else {
+                            DependencyKind::Optional
+                        },
+                    });
+                }
+            }
+        }
+        // Early paper-plugin.yml format: a list of { name, required }
+        Value::Array(entries) => {
+            for entry in entries {
+                let Some(id) = entry.get("name").and_then(scalar_string) else {
+                    continue;
+                };
+                let required = entry
+                    .get("required")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true);
+                dependencies.push(JarDependency {
+                    id,
+                    version_range: None,
+                    kind: if required {
+                        DependencyKind::Required
+                    } else {
+                        DependencyKind::Optional
+                    },
+                });
+            }
+        }
+        _ => {}
+    }
+
+    Some(JarMetadata {
+        format,
+        id: name.clone(),
+        name: Some(name),
+        version: trimmed(yaml.version),
+        description: trimmed(yaml.description),
+        authors,
+        dependencies,
+        side: Some(ModSide::Server),
+        icon: None,
+        provides: Vec::new(),
+        bundled: Vec::new(),
+        other_formats: Vec::new(),
+    })
+}
+
+/// Parse velocity-plugin.json
+fn parse_velocity_plugin_json(content: &str) -> Option<JarMetadata> {
+    let json: Value = serde_json::from_str(content).ok()?;
+    let id = json.get("id").and_then(scalar_string)?;
+
+    let dependencies = match json.get("dependencies") {
+        Some(Value::Array(entries)) => entries
+            .iter()
+            .filter_map(|entry| {
+                let dep_id = entry.get("id").and_then(scalar_string)?;
+                let optional = entry
+                    .get("optional")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                Some(JarDependency {
+                    id: dep_id,
+                    version_range: None,
+                    kind: if optional {
+                        DependencyKind::Optional
+                    } else {
+                        DependencyKind::Required
+                    },
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some(JarMetadata {
+        format: JarMetadataFormat::Velocity,
+        name: json.get("name").and_then(scalar_string),
+        id,
+        version: json.get("version").and_then(scalar_string),
+        description: json.get("description").and_then(scalar_string),
+        authors: json
+            .get("authors")
+            .map(|a| string_list(a, "name"))
+            .unwrap_or_default(),
+        dependencies,
+        side: Some(ModSide::Server),
+        icon: None,
+        provides: Vec::new(),
+        bundled: Vec::new(),
+        other_formats: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn build_jar(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_parse_fabric_mod_json() {
+        let content = r#"{
+            "schemaVersion": 1,
+            "id": "sodium",
+            "version": "0.5.8+mc1.20.4",
+            "name": "Sodium",
+            "authors": ["JellySquid", {"name": "IMS"}],
+            "environment": "client",
+            "icon": {"32": "icon32.png", "128": "icon128.png"},
+            "depends": {"fabricloader": ">=0.12.0", "minecraft": ["1.20.3", "1.20.4"], "java": "*"},
+            "breaks": {"optifabric": "*"},
+            "conflicts": {"iris": "<1.6"},
+            "jars": [{"file": "META-INF/jars/api.jar"}]
+        }"#;
+        let (meta, icon, jars) = parse_fabric_mod_json(content).unwrap();
+        assert_eq!(meta.id, "sodium");
+        assert_eq!(meta.name.as_deref(), Some("Sodium"));
+        assert_eq!(meta.authors, vec!["JellySquid", "IMS"]);
+        assert_eq!(meta.side, Some(ModSide::Client));
+        assert_eq!(icon.as_deref(), Some("icon128.png"));
+        assert_eq!(jars, vec!["META-INF/jars/api.jar"]);
+
+        let minecraft = meta
+            .dependencies
+            .iter()
+            .find(|d| d.id == "minecraft")
+            .unwrap();
+        assert_eq!(minecraft.version_range.as_deref(), Some("1.20.3 || 1.20.4"));
+        let java = meta.dependencies.iter().find(|d| d.id == "java").unwrap();
+        assert_eq!(java.version_range, None);
+        let optifabric = meta
+            .dependencies
+            .iter()
+            .find(|d| d.id == "optifabric")
+            .unwrap();
+        assert_eq!(optifabric.kind, DependencyKind::Incompatible);
+        let iris = meta.dependencies.iter().find(|d| d.id == "iris").unwrap();
+        assert_eq!(iris.kind, DependencyKind::Discouraged);
+        assert_eq!(iris.version_range.as_deref(), Some("<1.6"));
+    }
+
+    #[test]
+    fn test_parse_quilt_mod_json() {
+        let content = r#"{
+            "schema_version": 1,
+            "quilt_loader": {
+                "group": "org.example",
+                "id": "example",
+                "version": "1.0.0",
+                "metadata": {"name": "Example", "contributors": {"Alice": "Owner"}, "icon": "icon.png"},
+                "depends": ["quilt_loader", {"id": "org.quiltmc:qsl", "versions": ">=6.0", "optional": true}]
+            },
+            "minecraft": {"environment": "dedicated_server"}
+        }"#;
+        let (meta, icon, _) = parse_quilt_mod_json(content).unwrap();
+        assert_eq!(meta.id, "example");
+        assert_eq!(meta.authors, vec!["Alice"]);
+        assert_eq!(meta.side, Some(ModSide::Server));
+        assert_eq!(icon.as_deref(), Some("icon.png"));
+        assert_eq!(meta.dependencies[1].id, "qsl");
+        assert_eq!(meta.dependencies[1].kind, DependencyKind::Optional);
+    }
+
+    #[test]
+    fn test_parse_mods_toml() {
+        let content = r#"
+modLoader="javafml"
+loaderVersion="[47,)"
+logoFile="logo.png"
+
+[[mods]]
+modId="jei"
+version="${file.jarVersion}"
+displayName="Just Enough Items"
+authors="mezz, Alice and Bob"
+displayTest="IGNORE_ALL_VERSION"
+
+[[dependencies.jei]]
+modId="forge"
+mandatory=true
+versionRange="[47,)"
+
+[[dependencies.jei]]
+modId="create"
+type="optional"
+
+[[dependencies.jei]]
+modId="roughlyenoughitems"
+type="discouraged"
+
+[[dependencies.jei]]
+modId="optifine"
+type="incompatible"
+"#;
+        let (meta, icon) = parse_mods_toml(content, JarMetadataFormat::Forge).unwrap();
+        assert_eq!(meta.id, "jei");
+        assert_eq!(meta.name.as_deref(), Some("Just Enough Items"));
+        assert_eq!(meta.authors, vec!["mezz", "Alice", "Bob"]);
+        assert_eq!(meta.side, Some(ModSide::Client));
+        assert_eq!(icon.as_deref(), Some("logo.png"));
+        assert_eq!(meta.dependencies.len(), 4);
+        assert_eq!(meta.dependencies[0].kind, DependencyKind::Required);
+        assert_eq!(meta.dependencies[0].version_range.as_deref(), Some("[47,)"));
+        assert_eq!(meta.dependencies[1].kind, DependencyKind::Optional);
+        assert_eq!(meta.dependencies[2].kind, DependencyKind::Discouraged);
+        assert_eq!(meta.dependencies[3].kind, DependencyKind::Incompatible);
+    }
+
+    #[test]
+    fn test_parse_mcmod_info() {
+        let content = r#"[{"modid": "examplemod", "name": "Example", "version": "1.2", "authorList": ["Alice"], "requiredMods": ["Forge@[10.13,)"], "logoFile": "/logo.png"}]"#;
+        let (meta, icon) = parse_mcmod_info(content).unwrap();
+        assert_eq!(meta.format, JarMetadataFormat::LegacyForge);
+        assert_eq!(meta.id, "examplemod");
+        assert_eq!(meta.authors, vec!["Alice"]);
+        assert_eq!(meta.dependencies[0].id, "Forge");
+        assert_eq!(
+            meta.dependencies[0].version_range.as_deref(),
+            Some("[10.13,)")
+        );
+        assert_eq!(icon.as_deref(), Some("/logo.png"));
+    }
+
+    #[test]
+    fn test_parse_plugin_yml() {
+        let content = "name: LuckPerms\nversion: 5.4.102\nauthor: Luck\ndepend: [Vault]\nsoftdepend:\n  - PlaceholderAPI\n";
+        let meta = parse_plugin_yml(content, JarMetadataFormat::Bukkit).unwrap();
+        assert_eq!(meta.id, "LuckPerms");
+        assert_eq!(meta.version.as_deref(), Some("5.4.102"));
+        assert_eq!(meta.authors, vec!["Luck"]);
+        assert_eq!(meta.side, Some(ModSide::Server));
+        assert_eq!(meta.dependencies.len(), 2);
+        assert_eq!(meta.dependencies[1].kind, DependencyKind::Optional);
+    }
+
+    #[test]
+    fn test_parse_plugin_yml_keeps_version_as_written() {
+        let content = "name: Example\nversion: 1.20\nauthors: [Alice, 'Bob']\ndescription: |\n  Line one\n  Line #two\ncommands:\n  example:\n    usage: /example\n";
+        let meta = parse_plugin_yml(content, JarMetadataFormat::Bukkit).unwrap();
+        assert_eq!(meta.version.as_deref(), Some("1.20"));
+        assert_eq!(meta.authors, vec!["Alice", "Bob"]);
+        assert_eq!(meta.description.as_deref(), Some("Line one\nLine #two"));
+    }
+
+    #[test]
+    fn test_parse_paper_plugin_yml() {
+        let content = "name: Example\nversion: '1.0'\ndependencies:\n  server:\n    Vault:\n      load: BEFORE\n      required: false\n    LuckPerms:\n      load: BEFORE\n";
+        let meta = parse_plugin_yml(content, JarMetadataFormat::Paper).unwrap();
+        assert_eq!(meta.version.as_deref(), Some("1.0"));
+        let vault = meta.dependencies.iter().find(|d| d.id == "Vault").unwrap();
+        assert_eq!(vault.kind, DependencyKind::Optional);
+        let luckperms = meta
+            .dependencies
+            .iter()
+            .find(|d| d.id == "LuckPerms")
+            .unwrap();
+        assert_eq!(luckperms.kind, DependencyKind::Required);
+    }
+
+    #[test]
+    fn test_parse_velocity_plugin_json() {
+        let content = r#"{"id": "example", "name": "Example", "version": "2.0", "authors": ["Alice"], "dependencies": [{"id": "luckperms", "optional": true}], "main": "a.B"}"#;
+        let meta = parse_velocity_plugin_json(content).unwrap();
+        assert_eq!(meta.id, "example");
+        assert_eq!(meta.dependencies[0].kind, DependencyKind::Optional);
+    }
+
+    #[test]
+    fn test_inspect_archive_with_icon_manifest_and_nested_jar() {
+        let nested = build_jar(&[(
+            "fabric.mod.json",
+            br#"{"id": "nested-api", "version": "2.0.0", "provides": ["nested"]}"#,
+        )]);
+        let outer = build_jar(&[
+            (
+                "fabric.mod.json",
+                br#"{"id": "outer", "version": "1.0.0", "icon": "assets/icon.png", "jars": [{"file": "META-INF/jars/nested.jar"}]}"#,
+            ),
+            ("assets/icon.png", b"\x89PNG"),
+            ("META-INF/jars/nested.jar", &nested),
+        ]);
+
+        let mut archive = ZipArchive::new(Cursor::new(outer)).unwrap();
+        let meta = inspect_archive(&mut archive, 0).unwrap();
+        assert_eq!(meta.id, "outer");
+        assert_eq!(meta.icon.as_deref(), Some("data:image/png;base64,iVBORw=="));
+        assert_eq!(
+            meta.bundled,
+            vec![
+                BundledMod {
+                    id: "nested-api".to_string(),
+                    version: Some("2.0.0".to_string())
+                },
+                BundledMod {
+                    id: "nested".to_string(),
+                    version: Some("2.0.0".to_string())
+                },
+            ]
+        );
+
+        let forge = build_jar(&[
+            (
+                "META-INF/mods.toml",
+                b"[[mods]]\nmodId=\"example\"\nversion=\"${file.jarVersion}\"\n",
+            ),
+            (
+                "META-INF/MANIFEST.MF",
+                b"Manifest-Version: 1.0\r\nImplementation-Version: 3.1.4\r\n",
+            ),
+        ]);
+        let mut archive = ZipArchive::new(Cursor::new(forge)).unwrap();
+        let meta = inspect_archive(&mut archive, 0).unwrap();
+        assert_eq!(meta.format, JarMetadataFormat::Forge);
+        assert_eq!(meta.version.as_deref(), Some("3.1.4"));
+        assert!(meta.other_formats.is_empty());
+    }
+
+    #[test]
+    fn test_inspect_archive_multi_loader() {
+        let jar = build_jar(&[
+            (
+                "fabric.mod.json",
+                br#"{"id": "example", "version": "1.0.0"}"#,
+            ),
+            ("META-INF/mods.toml", b"[[mods]]\nmodId=\"example\"\n"),
+        ]);
+        let mut archive = ZipArchive::new(Cursor::new(jar)).unwrap();
+        let meta = inspect_archive(&mut archive, 0).unwrap();
+        assert_eq!(meta.format, JarMetadataFormat::Fabric);
+        assert_eq!(meta.other_formats, vec![JarMetadataFormat::Forge]);
+    }
+
+    #[test]
+    fn test_inspect_archive_without_descriptor() {
+        let jar = build_jar(&[("com/example/Main.class", b"")]);
+        let mut archive = ZipArchive::new(Cursor::new(jar)).unwrap();
+        assert!(inspect_archive(&mut archive, 0).is_none());
+    }
+}
@@ -120,6 +120,8 @@ pub enum IssueType {
     RecommendedVersionMismatch,
     /// Two mods are incompatible
     ModConflict,
+    /// Two mods are known to cause problems together, but can still load
+    DiscouragedMod,
     /// A mod is not compatible with the current Minecraft version
     MinecraftVersionMismatch,
     /// A mod is not compatible with the current loader version
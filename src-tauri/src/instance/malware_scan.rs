@@ -0,0 +1,898 @@
+//! Static malware scanner for mod and plugin jars
+//!
+//! Every class file, including the ones in nested jars, is checked for
+//! indicators of known malware (the fractureiser stages and their loaders)
+//! and for patterns such malware relies on: running processes, loading
+//! classes from a URL decoded at runtime and writing to autostart locations.
+//! Results are stored per file. Jars judged suspicious or worse are moved to
+//! `quarantine/<instance id>/` in the data directory, unless quarantining is
+//! turned off in the settings.
+
+use crate::db::instances::Instance;
+use crate::db::jar_scans::JarScanRecord;
+use crate::db::settings::get_setting;
+use crate::error::{AppError, AppResult};
+use crate::instance::jar_metadata;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// Settings key, quarantining is on unless set to "false"
+pub const QUARANTINE_SETTING: &str = "malware_quarantine_enabled";
+
+/// Folders of an instance whose jars are scanned
+const SCANNED_FOLDERS: &[&str] = &["mods", "plugins"];
+
+/// Class files larger than this are not inspected
+const MAX_CLASS_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Findings kept per jar, a heavily obfuscated jar can match in every class
+const MAX_FINDINGS: usize = 100;
+
+/// Strings published as indicators of fractureiser and related loaders,
+/// matched against the constant pool of every class
+const KNOWN_INDICATORS: &[(&str, &str)] = &[
+    ("85.217.144.130", "fractureiser stage 0 server"),
+    ("107.189.3.101", "fractureiser stage 1 server"),
+    ("skyrage.de", "fractureiser payload host"),
+    ("dev/neko/nekoclient", "fractureiser stage 3 (Nekoclient)"),
+    ("dev/neko/nekoinjector", "fractureiser jar injector"),
+    ("libWebGL64.jar", "fractureiser stage 1 persistence"),
+    ("systemd-utility", "fractureiser stage 1 persistence"),
+];
+
+/// Paths that start programs at login or boot
+const AUTOSTART_LOCATIONS: &[&str] = &[
+    "start menu\\programs\\startup",
+    "start menu/programs/startup",
+    "currentversion\\run",
+    ".config/autostart",
+    "systemd/user",
+    "/etc/systemd/system",
+    "library/launchagents",
+    "/etc/init.d",
+    "crontab",
+];
+
+/// Methods that create or write files, (owner class, method name)
+const FILE_WRITE_METHODS: &[(&str, &str)] = &[
+    ("java/io/FileOutputStream", "<init>"),
+    ("java/io/FileWriter", "<init>"),
+    ("java/io/RandomAccessFile", "<init>"),
+    ("java/nio/file/Files", "write"),
+    ("java/nio/file/Files", "writeString"),
+    ("java/nio/file/Files", "copy"),
+    ("java/nio/file/Files", "move"),
+    ("java/nio/file/Files", "newOutputStream"),
+    ("java/nio/file/Files", "newBufferedWriter"),
+];
+
+/// How bad a finding is, and the worst finding of a jar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Clean,
+    /// Used by legitimate mods too, shown but not acted on
+    Notice,
+    Suspicious,
+    Malicious,
+}
+
+impl Verdict {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Clean => "clean",
+            Self::Notice => "notice",
+            Self::Suspicious => "suspicious",
+            Self::Malicious => "malicious",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "notice" => Self::Notice,
+            "suspicious" => Self::Suspicious,
+            "malicious" => Self::Malicious,
+            _ => Self::Clean,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    KnownMalware,
+    ProcessExecution,
+    RemoteClassLoading,
+    AutostartWrite,
+}
+
+/// Something worth reporting in a class file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    pub severity: Verdict,
+    /// Class path in the jar, nested jars separated by `!/`
+    pub entry: String,
+    pub detail: String,
+}
+
+/// Worst severity among findings
+pub fn verdict(findings: &[Finding]) -> Verdict {
+    findings
+        .iter()
+        .map(|f| f.severity)
+        .max()
+        .unwrap_or(Verdict::Clean)
+}
+
+// ============================================================================
+// Class file inspection
+// ============================================================================
+
+/// Strings and method references of a class file's constant pool
+#[derive(Debug, Default)]
+struct ConstantPool {
+    strings: Vec<String>,
+    /// (owner class, method name, descriptor)
+    methods: Vec<(String, String, String)>,
+}
+
+impl ConstantPool {
+    fn calls(&self, owner: &str, name: &str) -> bool {
+        self.methods.iter().any(|(o, n, _)| o == owner && n == name)
+    }
+
+    fn calls_with(&self, owner: &str, name: &str, descriptor: &str) -> bool {
+        self.methods
+            .iter()
+            .any(|(o, n, d)| o == owner && n == name && d == descriptor)
+    }
+
+    fn contains(&self, needle: &str) -> bool {
+        self.strings.iter().any(|s| s.contains(needle))
+    }
+}
+
+enum PoolEntry {
+    Utf8(String),
+    Class(u16),
+    NameAndType(u16, u16),
+    MethodRef(u16, u16),
+    Other,
+}
+
+fn read_u16(bytes: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*bytes.get(pos)?, *bytes.get(pos + 1)?]))
+}
+
+/// Parse the constant pool of a class file, `None` if it is not one
+fn parse_constant_pool(bytes: &[u8]) -> Option<ConstantPool> {
+    if bytes.get(..4)? != [0xCA, 0xFE, 0xBA, 0xBE] {
+        return None;
+    }
+    let count = read_u16(bytes, 8)? as usize;
+    let mut entries = Vec::with_capacity(count);
+    entries.push(PoolEntry::Other); // Index 0 is unused
+    let mut pos = 10;
+
+    while entries.len() < count {
+        let tag = *bytes.get(pos)?;
+        pos += 1;
+        let (entry, size, slots) = match tag {
+            1 => {
+                let len = read_u16(bytes, pos)? as usize;
+                let data = bytes.get(pos + 2..pos + 2 + len)?;
+                let text = String::from_utf8_lossy(data).into_owned();
+                (PoolEntry::Utf8(text), 2 + len, 1)
+            }
+            7 => (PoolEntry::Class(read_u16(bytes, pos)?), 2, 1),
+            10 | 11 => (
+                PoolEntry::MethodRef(read_u16(bytes, pos)?, read_u16(bytes, pos + 2)?),
+                4,
+                1,
+            ),
+            12 => (
+                PoolEntry::NameAndType(read_u16(bytes, pos)?, read_u16(bytes, pos + 2)?),
+                4,
+                1,
+            ),
+            8 | 16 | 19 | 20 => (PoolEntry::Other, 2, 1),
+            15 => (PoolEntry::Other, 3, 1),
+            3 | 4 | 9 | 17 | 18 => (PoolEntry::Other, 4, 1),
+            // Longs and doubles take two slots
+            5 | 6 => (PoolEntry::Other, 8, 2),
+            _ => return None,
+        };
+        pos += size;
+        entries.push(entry);
+        if slots == 2 {
+            entries.push(PoolEntry::Other);
+        }
+    }
+
+    let utf8 = |index: u16| match entries.get(index as usize) {
+        Some(PoolEntry::Utf8(s)) => Some(s.as_str()),
+        _ => None,
+    };
+    let class_name = |index: u16| match entries.get(index as usize) {
+        Some(PoolEntry::Class(name)) => utf8(*name),
+        _ => None,
+    };
+
+    let mut pool = ConstantPool::default();
+    for entry in &entries {
+        match entry {
+            PoolEntry::Utf8(s) => pool.strings.push(s.clone()),
+            PoolEntry::MethodRef(class, nat) => {
+                let Some(PoolEntry::NameAndType(name, descriptor)) = entries.get(*nat as usize)
+                else {
+                    continue;
+                };
+                if let (Some(owner), Some(name), Some(descriptor)) =
+                    (class_name(*class), utf8(*name), utf8(*descriptor))
+                {
+                    pool.methods.push((
+                        owner.to_string(),
+                        name.to_string(),
+                        descriptor.to_string(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(pool)
+}
+
+/// Check one class file
+fn scan_class(bytes: &[u8], entry: &str) -> Vec<Finding> {
+    let Some(pool) = parse_constant_pool(bytes) else {
+        return Vec::new();
+    };
+    let mut findings = Vec::new();
+    let mut report = |kind, severity, detail: String| {
+        findings.push(Finding {
+            kind,
+            severity,
+            entry: entry.to_string(),
+            detail,
+        })
+    };
+
+    for (indicator, description) in KNOWN_INDICATORS {
+        if pool.contains(indicator) {
+            report(
+                FindingKind::KnownMalware,
+                Verdict::Malicious,
+                format!("Contains \"{}\" ({})", indicator, description),
+            );
+        }
+    }
+
+    let reflection = pool.calls("java/lang/reflect/Method", "invoke")
+        || pool.calls("java/lang/ClassLoader", "loadClass")
+        || pool.calls("java/net/URLClassLoader", "loadClass")
+        || pool.calls("java/lang/Class", "forName");
+
+    if pool.calls("java/lang/Runtime", "exec") || pool.calls("java/lang/ProcessBuilder", "start") {
+        report(
+            FindingKind::ProcessExecution,
+            Verdict::Notice,
+            "Runs external programs".to_string(),
+        );
+    } else if reflection
+        && pool.strings.iter().any(|s| s == "java.lang.Runtime")
+        && pool.strings.iter().any(|s| s == "exec")
+    {
+        report(
+            FindingKind::ProcessExecution,
+            Verdict::Suspicious,
+            "Runs external programs through reflection".to_string(),
+        );
+    }
+
+    if pool.calls("java/net/URLClassLoader", "<init>")
+        || pool.calls("java/net/URLClassLoader", "newInstance")
+    {
+        let decoded_url = pool.calls_with("java/lang/String", "<init>", "([B)V")
+            || pool.calls_with("java/lang/String", "<init>", "([C)V")
+            || pool.calls("java/util/Base64$Decoder", "decode");
+        if decoded_url && reflection {
+            // Stage 0 loads the `Utility` class from the downloaded jar and runs it
+            let (severity, detail) = if pool.strings.iter().any(|s| s == "Utility") {
+                (
+                    Verdict::Malicious,
+                    "Matches the fractureiser stage 0 loader",
+                )
+            } else {
+                (
+                    Verdict::Suspicious,
+                    "Loads and runs classes from a URL decoded at runtime",
+                )
+            };
+            report(
+                FindingKind::RemoteClassLoading,
+                severity,
+                detail.to_string(),
+            );
+        } else if pool.contains("http://") || pool.contains("https://") {
+            report(
+                FindingKind::RemoteClassLoading,
+                Verdict::Notice,
+                "Loads classes from a URL".to_string(),
+            );
+        }
+    }
+
+    // Schedulers and log messages mention these paths too, only writing to them is suspicious
+    let writes_files = FILE_WRITE_METHODS
+        .iter()
+        .any(|(owner, name)| pool.calls(owner, name));
+    for location in AUTOSTART_LOCATIONS {
+        if pool
+            .strings
+            .iter()
+            .any(|s| s.to_lowercase().contains(location))
+        {
+            let (severity, detail) = if writes_files {
+                (Verdict::Suspicious, "Writes files and references")
+            } else {
+                (Verdict::Notice, "References")
+            };
+            report(
+                FindingKind::AutostartWrite,
+                severity,
+                format!("{} the autostart location \"{}\"", detail, location),
+            );
+        }
+    }
+
+    findings
+}
+
+/// Scan the class files of an archive and of the jars nested in it
+fn scan_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    prefix: &str,
+    depth: usize,
+    findings: &mut Vec<Finding>,
+) {
+    let mut nested_jars = Vec::new();
+    for index in 0..archive.len() {
+        if findings.len() >= MAX_FINDINGS {
+            return;
+        }
+        let Ok(mut entry) = archive.by_index(index) else {
+            continue;
+        };
+        let name = entry.name().to_string();
+        let lower = name.to_lowercase();
+
+        if lower.ends_with(".jar") {
+            nested_jars.push(name);
+            continue;
+        }
+        if !lower.ends_with(".class") || entry.size() > MAX_CLASS_SIZE {
+            continue;
+        }
+
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        if entry.read_to_end(&mut bytes).is_ok() {
+            findings.extend(scan_class(&bytes, &format!("{}{}", prefix, name)));
+        }
+    }
+
+    jar_metadata::for_each_nested_jar(archive, depth, nested_jars, |name, nested, depth| {
+        if findings.len() < MAX_FINDINGS {
+            scan_archive(nested, &format!("{}{}!/", prefix, name), depth, findings);
+        }
+    });
+    findings.truncate(MAX_FINDINGS);
+}
+
+/// SHA-1 and findings of a jar
+pub fn scan_jar(path: &Path) -> AppResult<(String, Vec<Finding>)> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| AppError::Io(format!("Failed to read {:?}: {}", path, e)))?;
+    let sha1 = hex::encode(Sha1::digest(&bytes));
+
+    let mut findings = Vec::new();
+    if let Ok(mut archive) = ZipArchive::new(Cursor::new(bytes)) {
+        scan_archive(&mut archive, "", 0, &mut findings);
+    }
+    Ok((sha1, findings))
+}
+
+// ============================================================================
+// Recording and quarantine
+// ============================================================================
+
+/// Scan result of a jar, as shown to the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JarScanResult {
+    /// Path relative to the instance directory
+    pub path: String,
+    pub sha1: String,
+    pub verdict: Verdict,
+    pub findings: Vec<Finding>,
+    pub quarantined: bool,
+    pub scanned_at: String,
+}
+
+impl From<JarScanRecord> for JarScanResult {
+    fn from(record: JarScanRecord) -> Self {
+        Self {
+            verdict: Verdict::parse(&record.verdict),
+            findings: serde_json::from_str(&record.findings).unwrap_or_default(),
+            path: record.path,
+            sha1: record.sha1,
+            quarantined: record.quarantined,
+            scanned_at: record.scanned_at,
+        }
+    }
+}
+
+pub async fn quarantine_enabled(state: &AppState) -> bool {
+    !matches!(
+        get_setting(&state.db, QUARANTINE_SETTING).await,
+        Ok(Some(value)) if value == "false"
+    )
+}
+
+fn is_jar(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.ends_with(".jar") || name.ends_with(".jar.disabled")
+}
+
+/// Where a jar of an instance is kept while quarantined
+fn quarantine_path(data_dir: &Path, instance_id: &str, relative: &str) -> PathBuf {
+    data_dir.join("quarantine").join(instance_id).join(relative)
+}
+
+/// The `.meta.json` written next to a jar by the content installers
+fn meta_path(jar: &Path) -> PathBuf {
+    let name = jar
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let base = name.trim_end_matches(".disabled").trim_end_matches(".jar");
+    jar.with_file_name(format!("{}.meta.json", base))
+}
+
+/// Move a file, copying when source and destination are on different drives
+async fn move_file(from: &Path, to: &Path) -> AppResult<()> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    if tokio::fs::rename(from, to).await.is_err() {
+        tokio::fs::copy(from, to).await?;
+        tokio::fs::remove_file(from).await?;
+    }
+    Ok(())
+}
+
+/// Move a jar, and its metadata file when there is one
+async fn move_jar(from: &Path, to: &Path) -> AppResult<()> {
+    move_file(from, to).await?;
+    let meta = meta_path(from);
+    if meta.exists() {
+        move_file(&meta, &meta_path(to)).await?;
+    }
+    Ok(())
+}
+
+/// Scan a jar of an instance, record the result and quarantine the jar when
+/// it is suspicious or worse
+pub async fn scan_and_record(
+    state: &AppState,
+    instance_id: &str,
+    instance_dir: &Path,
+    jar: &Path,
+) -> AppResult<JarScanResult> {
+    let relative = jar
+        .strip_prefix(instance_dir)
+        .map_err(|_| AppError::Security(format!("{:?} is not in the instance", jar)))?
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let path = jar.to_path_buf();
+    let (sha1, findings) = tokio::task::spawn_blocking(move || scan_jar(&path))
+        .await
+        .map_err(|e| AppError::Io(format!("Scan task failed: {}", e)))??;
+    let verdict = verdict(&findings);
+
+    let quarantined = verdict >= Verdict::Suspicious && quarantine_enabled(state).await;
+    if quarantined {
+        move_jar(
+            jar,
+            &quarantine_path(&state.data_dir, instance_id, &relative),
+        )
+        .await?;
+        tracing::warn!(
+            "Quarantined {} of instance {}: {:?}",
+            relative,
+            instance_id,
+            findings.first().map(|f| &f.detail)
+        );
+    }
+
+    let record = JarScanRecord {
+        instance_id: instance_id.to_string(),
+        path: relative,
+        sha1,
+        verdict: verdict.as_str().to_string(),
+        findings: serde_json::to_string(&findings)?,
+        quarantined,
+        scanned_at: chrono::Utc::now().to_rfc3339(),
+    };
+    record.save(&state.db).await?;
+    Ok(record.into())
+}
+
+/// Scan a file just added to an instance. Fails when the file was quarantined
+pub async fn check_added_file(
+    state: &AppState,
+    instance_id: &str,
+    instance_dir: &Path,
+    path: &Path,
+) -> AppResult<()> {
+    if !is_jar(path) {
+        return Ok(());
+    }
+    let result = scan_and_record(state, instance_id, instance_dir, path).await?;
+    if result.quarantined {
+        let reason = result
+            .findings
+            .iter()
+            .find(|f| f.severity == result.verdict)
+            .map(|f| f.detail.clone())
+            .unwrap_or_default();
+        return Err(AppError::Security(format!(
+            "{} was quarantined: {}",
+            result.path, reason
+        )));
+    }
+    Ok(())
+}
+
+/// Scan every jar of an instance, replacing the previous results
+pub async fn scan_instance(state: &AppState, instance: &Instance) -> AppResult<Vec<JarScanResult>> {
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+    JarScanRecord::clear_unquarantined(&state.db, &instance.id).await?;
+
+    let mut results = Vec::new();
+    for folder in SCANNED_FOLDERS {
+        let Ok(mut entries) = tokio::fs::read_dir(instance_dir.join(folder)).await else {
+            continue;
+        };
+        let mut jars = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.is_file() && is_jar(&path) {
+                jars.push(path);
+            }
+        }
+        jars.sort();
+
+        for jar in jars {
+            match scan_and_record(state, &instance.id, &instance_dir, &jar).await {
+                Ok(result) => results.push(result),
+                Err(e) => tracing::warn!("Failed to scan {:?}: {}", jar, e),
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Jars of an instance that a full rescan flagged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceScanReport {
+    pub instance_id: String,
+    pub instance_name: String,
+    pub scanned: usize,
+    /// Jars with findings, quarantined or not
+    pub flagged: Vec<JarScanResult>,
+}
+
+/// Outcome of rescanning every instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub scanned: usize,
+    pub quarantined: usize,
+    /// Instances with flagged jars
+    pub instances: Vec<InstanceScanReport>,
+}
+
+/// Rescan the jars of all instances
+pub async fn scan_all(state: &AppState) -> AppResult<ScanReport> {
+    let mut report = ScanReport {
+        scanned: 0,
+        quarantined: 0,
+        instances: Vec::new(),
+    };
+
+    for instance in Instance::get_all(&state.db).await? {
+        let results = scan_instance(state, &instance).await?;
+        let scanned = results.len();
+        report.scanned += scanned;
+        report.quarantined += results.iter().filter(|r| r.quarantined).count();
+
+        let flagged: Vec<_> = results
+            .into_iter()
+            .filter(|r| r.verdict != Verdict::Clean)
+            .collect();
+        if !flagged.is_empty() {
+            report.instances.push(InstanceScanReport {
+                instance_id: instance.id.clone(),
+                instance_name: instance.name.clone(),
+                scanned,
+                flagged,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Scan the jars of an instance created by an import, logging instead of failing
+pub async fn scan_imported_instance(state: &AppState, instance: &Instance) {
+    match scan_instance(state, instance).await {
+        Ok(results) => {
+            let quarantined = results.iter().filter(|r| r.quarantined).count();
+            if quarantined > 0 {
+                tracing::warn!(
+                    "Quarantined {} jar(s) of imported instance {}",
+                    quarantined,
+                    instance.name
+                );
+            }
+        }
+        Err(e) => tracing::warn!("Failed to scan imported instance {}: {}", instance.name, e),
+    }
+}
+
+/// Put a quarantined jar back into its instance
+pub async fn restore(state: &AppState, instance: &Instance, path: &str) -> AppResult<()> {
+    let record = JarScanRecord::get(&state.db, &instance.id, path)
+        .await?
+        .filter(|r| r.quarantined)
+        .ok_or_else(|| AppError::Instance(format!("{} is not quarantined", path)))?;
+
+    let destination = state
+        .get_instances_dir()
+        .await
+        .join(&instance.game_dir)
+        .join(&record.path);
+    if destination.exists() {
+        return Err(AppError::Instance(format!(
+            "{} already exists",
+            record.path
+        )));
+    }
+    move_jar(
+        &quarantine_path(&state.data_dir, &instance.id, &record.path),
+        &destination,
+    )
+    .await?;
+
+    // Keep the findings, the user chose to trust the file
+    JarScanRecord {
+        quarantined: false,
+        ..record
+    }
+    .save(&state.db)
+    .await?;
+    Ok(())
+}
+
+/// Delete a quarantined jar for good
+pub async fn delete_quarantined(
+    state: &AppState,
+    instance: &Instance,
+    path: &str,
+) -> AppResult<()> {
+    let record = JarScanRecord::get(&state.db, &instance.id, path)
+        .await?
+        .filter(|r| r.quarantined)
+        .ok_or_else(|| AppError::Instance(format!("{} is not quarantined", path)))?;
+
+    let jar = quarantine_path(&state.data_dir, &instance.id, &record.path);
+    for file in [meta_path(&jar), jar] {
+        if file.exists() {
+            tokio::fs::remove_file(&file).await?;
+        }
+    }
+    JarScanRecord::delete(&state.db, &instance.id, &record.path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Minimal class file with the given constant pool entries
+    struct ClassBuilder {
+        pool: Vec<u8>,
+        count: u16,
+    }
+
+    impl ClassBuilder {
+        fn new() -> Self {
+            Self {
+                pool: Vec::new(),
+                count: 1,
+            }
+        }
+
+        fn push(&mut self, bytes: &[u8]) -> u16 {
+            self.pool.extend_from_slice(bytes);
+            self.count += 1;
+            self.count - 1
+        }
+
+        fn utf8(&mut self, s: &str) -> u16 {
+            let mut bytes = vec![1];
+            bytes.extend_from_slice(&(s.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(s.as_bytes());
+            self.push(&bytes)
+        }
+
+        fn method(&mut self, owner: &str, name: &str, descriptor: &str) {
+            let owner = self.utf8(owner);
+            let class = self.push(&[&[7u8][..], &owner.to_be_bytes()].concat());
+            let name = self.utf8(name);
+            let descriptor = self.utf8(descriptor);
+            let nat =
+                self.push(&[&[12u8][..], &name.to_be_bytes(), &descriptor.to_be_bytes()].concat());
+            self.push(&[&[10u8][..], &class.to_be_bytes(), &nat.to_be_bytes()].concat());
+        }
+
+        fn build(&self) -> Vec<u8> {
+            let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 61];
+            bytes.extend_from_slice(&self.count.to_be_bytes());
+            bytes.extend_from_slice(&self.pool);
+            bytes
+        }
+    }
+
+    fn jar(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, bytes) in entries {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn kinds(findings: &[Finding]) -> Vec<(FindingKind, Verdict)> {
+        findings.iter().map(|f| (f.kind, f.severity)).collect()
+    }
+
+    #[test]
+    fn test_clean_class() {
+        let mut class = ClassBuilder::new();
+        class.utf8("com/example/MyMod");
+        class.method("java/io/PrintStream", "println", "(Ljava/lang/String;)V");
+        assert!(scan_class(&class.build(), "com/example/MyMod.class").is_empty());
+        assert!(scan_class(b"not a class", "x.class").is_empty());
+    }
+
+    #[test]
+    fn test_known_indicator() {
+        let mut class = ClassBuilder::new();
+        class.utf8("dev/neko/nekoclient/Client");
+        let findings = scan_class(&class.build(), "a.class");
+        assert_eq!(
+            kinds(&findings),
+            vec![(FindingKind::KnownMalware, Verdict::Malicious)]
+        );
+    }
+
+    #[test]
+    fn test_process_execution() {
+        let mut class = ClassBuilder::new();
+        class.method(
+            "java/lang/Runtime",
+            "exec",
+            "(Ljava/lang/String;)Ljava/lang/Process;",
+        );
+        assert_eq!(
+            kinds(&scan_class(&class.build(), "a.class")),
+            vec![(FindingKind::ProcessExecution, Verdict::Notice)]
+        );
+
+        let mut reflective = ClassBuilder::new();
+        reflective.utf8("java.lang.Runtime");
+        reflective.utf8("exec");
+        reflective.method(
+            "java/lang/Class",
+            "forName",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+        );
+        assert_eq!(
+            kinds(&scan_class(&reflective.build(), "a.class")),
+            vec![(FindingKind::ProcessExecution, Verdict::Suspicious)]
+        );
+    }
+
+    #[test]
+    fn test_stage0_loader() {
+        let mut class = ClassBuilder::new();
+        class.method("java/net/URLClassLoader", "<init>", "([Ljava/net/URL;)V");
+        class.method("java/lang/String", "<init>", "([B)V");
+        class.method(
+            "java/lang/reflect/Method",
+            "invoke",
+            "(Ljava/lang/Object;[Ljava/lang/Object;)Ljava/lang/Object;",
+        );
+        let suspicious = class.build();
+        assert_eq!(
+            kinds(&scan_class(&suspicious, "a.class")),
+            vec![(FindingKind::RemoteClassLoading, Verdict::Suspicious)]
+        );
+
+        class.utf8("Utility");
+        assert_eq!(
+            kinds(&scan_class(&class.build(), "a.class")),
+            vec![(FindingKind::RemoteClassLoading, Verdict::Malicious)]
+        );
+    }
+
+    #[test]
+    fn test_autostart_location() {
+        let mut class = ClassBuilder::new();
+        class.utf8("\\AppData\\Roaming\\Microsoft\\Windows\\Start Menu\\Programs\\Startup\\");
+        assert_eq!(
+            kinds(&scan_class(&class.build(), "a.class")),
+            vec![(FindingKind::AutostartWrite, Verdict::Notice)]
+        );
+
+        class.method("java/io/FileOutputStream", "<init>", "(Ljava/io/File;)V");
+        assert_eq!(
+            kinds(&scan_class(&class.build(), "a.class")),
+            vec![(FindingKind::AutostartWrite, Verdict::Suspicious)]
+        );
+    }
+
+    #[test]
+    fn test_scan_nested_jar() {
+        let mut bad = ClassBuilder::new();
+        bad.utf8("http://85.217.144.130:8080/dl");
+        let inner = jar(&[("evil/Stage.class", bad.build())]);
+        let outer = jar(&[
+            ("fabric.mod.json", b"{}".to_vec()),
+            ("META-INF/jars/lib.jar", inner),
+        ]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mod.jar");
+        std::fs::write(&path, outer).unwrap();
+
+        let (sha1, findings) = scan_jar(&path).unwrap();
+        assert_eq!(sha1.len(), 40);
+        assert_eq!(verdict(&findings), Verdict::Malicious);
+        assert_eq!(findings[0].entry, "META-INF/jars/lib.jar!/evil/Stage.class");
+    }
+
+    #[test]
+    fn test_meta_path() {
+        assert_eq!(
+            meta_path(Path::new("mods/sodium-0.5.jar.disabled")),
+            Path::new("mods/sodium-0.5.meta.json")
+        );
+        assert_eq!(
+            meta_path(Path::new("mods/sodium-0.5.jar")),
+            Path::new("mods/sodium-0.5.meta.json")
+        );
+    }
+}
@@ -1,6 +1,14 @@
 pub mod commands;
+pub mod datapacks;
+pub mod dedup;
+pub mod game_options;
 pub mod instance_backup;
+pub mod jar_metadata;
 pub mod log_parser;
+pub mod malware_scan;
+pub mod mod_analyzer;
+pub mod mod_profiles;
+pub mod resource_packs;
 pub mod worlds;
 
 // TODO: Implement these modules in Phase 4-5
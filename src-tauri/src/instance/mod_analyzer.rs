@@ -0,0 +1,953 @@
+//! Pre-launch mod analysis
+//!
+//! Checks the dependencies declared inside installed jars before the game
+//! starts, reporting the same issues `log_parser` would only find after a
+//! crash: missing dependencies, unsatisfied version ranges, incompatible mods,
+//! duplicate mod ids and jars built for another loader or Minecraft version.
+
+use crate::db::instances::Instance;
+use crate::error::AppResult;
+use crate::instance::jar_metadata::{self, DependencyKind, JarMetadata, JarMetadataFormat};
+use crate::instance::log_parser::{DetectedIssue, IssueType, SuggestedAction};
+use sqlx::SqlitePool;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Setting key ("true"/"false") controlling whether launches with fatal issues are refused
+pub const BLOCK_LAUNCH_SETTING: &str = "block_launch_on_mod_issues";
+
+/// Ids provided by the game, loader or runtime rather than by a jar
+const PLATFORM_IDS: &[&str] = &["java", "javafml", "lowcodefml", "mcp", "fml"];
+
+/// An enabled jar and its metadata
+#[derive(Debug, Clone)]
+pub struct InstalledJar {
+    pub filename: String,
+    pub metadata: JarMetadata,
+}
+
+/// The game setup the jars are checked against
+#[derive(Debug, Clone, Copy)]
+pub struct AnalysisTarget<'a> {
+    pub mc_version: &'a str,
+    pub loader: Option<&'a str>,
+    pub loader_version: Option<&'a str>,
+}
+
+/// Whether launches with fatal mod issues should be refused
+pub async fn block_launch_enabled(db: &SqlitePool) -> bool {
+    matches!(
+        crate::db::settings::get_setting(db, BLOCK_LAUNCH_SETTING).await,
+        Ok(Some(value)) if value == "true"
+    )
+}
+
+/// Issues that will make the loader refuse to start
+pub fn is_fatal(issue: &DetectedIssue) -> bool {
+    !matches!(
+        issue.issue_type,
+        IssueType::MissingRecommendation
+            | IssueType::RecommendedVersionMismatch
+            | IssueType::DiscouragedMod
+            | IssueType::Unknown
+    )
+}
+
+/// Inspect the enabled jars of a content folder and analyze them
+pub async fn analyze_instance(
+    data_dir: &Path,
+    content_dir: &Path,
+    instance: &Instance,
+) -> AppResult<Vec<DetectedIssue>> {
+    let mut jars = Vec::new();
+
+    if let Ok(mut entries) = tokio::fs::read_dir(content_dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            // Disabled jars (.jar.disabled) are not loaded
+            if !filename.ends_with(".jar") {
+                continue;
+            }
+            match jar_metadata::inspect_jar(data_dir, &entry.path()).await {
+                Ok(inspected) => {
+                    if let Some(metadata) = inspected.metadata {
+                        jars.push(InstalledJar { filename, metadata });
+                    }
+                }
+                Err(e) => log::warn!("Failed to inspect {}: {}", filename, e),
+            }
+        }
+    }
+
+    jars.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+    let target = AnalysisTarget {
+        mc_version: &instance.mc_version,
+        loader: instance.loader.as_deref(),
+        loader_version: instance.loader_version.as_deref(),
+    };
+    Ok(analyze(&jars, &target))
+}
+
+/// Analyze a set of jars against a game setup
+pub fn analyze(jars: &[InstalledJar], target: &AnalysisTarget) -> Vec<DetectedIssue> {
+    let mut issues = Vec::new();
+    let loader = target.loader.map(str::to_lowercase);
+    let is_plugin_platform = loader.as_deref().is_some_and(is_plugin_loader);
+
+    // Jars built for another loader are not loaded at all, leave them out of the other checks
+    let mut loaded = Vec::new();
+    for jar in jars {
+        if supports_loader(&jar.metadata, loader.as_deref()) {
+            loaded.push(jar);
+            continue;
+        }
+        let meta = &jar.metadata;
+        issues.push(DetectedIssue {
+            issue_type: IssueType::LoaderVersionMismatch,
+            description: format!(
+                "Mod '{}' is made for {} and will not load on {}",
+                display_name(meta),
+                format_loader_name(meta.format),
+                target.loader.unwrap_or("vanilla")
+            ),
+            mod_id: Some(meta.id.clone()),
+            mod_name: meta.name.clone(),
+            required_mod_id: None,
+            required_mod_name: None,
+            required_version: None,
+            current_version: meta.version.clone(),
+            suggested_action: SuggestedAction::RemoveMod,
+            raw_log: jar.filename.clone(),
+        });
+    }
+
+    // Everything that can satisfy a dependency: mod ids, provided aliases and bundled jars
+    let mut available: HashMap<String, Option<String>> = HashMap::new();
+    let mut by_id: HashMap<String, Vec<&InstalledJar>> = HashMap::new();
+    for jar in &loaded {
+        let meta = &jar.metadata;
+        by_id.entry(normalize_id(&meta.id)).or_default().push(jar);
+        available.insert(normalize_id(&meta.id), meta.version.clone());
+        for provided in &meta.provides {
+            available
+                .entry(normalize_id(provided))
+                .or_insert(meta.version.clone());
+        }
+        for bundled in &meta.bundled {
+            available
+                .entry(normalize_id(&bundled.id))
+                .or_insert(bundled.version.clone());
+        }
+    }
+
+    // Duplicate ids across jars
+    let mut duplicate_ids: Vec<_> = by_id.iter().filter(|(_, jars)| jars.len() > 1).collect();
+    duplicate_ids.sort_by(|a, b| a.0.cmp(b.0));
+    for (_, duplicates) in duplicate_ids {
+        let meta = &duplicates[0].metadata;
+        let filenames: Vec<&str> = duplicates.iter().map(|j| j.filename.as_str()).collect();
+        issues.push(DetectedIssue {
+            issue_type: IssueType::DuplicateMod,
+            description: format!(
+                "Mod '{}' is installed {} times ({})",
+                display_name(meta),
+                duplicates.len(),
+                filenames.join(", ")
+            ),
+            mod_id: Some(meta.id.clone()),
+            mod_name: meta.name.clone(),
+            required_mod_id: None,
+            required_mod_name: None,
+            required_version: None,
+            current_version: meta.version.clone(),
+            suggested_action: SuggestedAction::RemoveDuplicate,
+            raw_log: filenames.join("\n"),
+        });
+    }
+
+    for jar in &loaded {
+        let meta = &jar.metadata;
+        let syntax = RangeSyntax::for_format(meta.format);
+
+        for dep in &meta.dependencies {
+            let dep_id = normalize_id(&dep.id);
+            if PLATFORM_IDS.contains(&dep_id.as_str()) {
+                continue;
+            }
+            let raw_log = format!(
+                "{}: {} {}{}",
+                jar.filename,
+                match dep.kind {
+                    DependencyKind::Required => "depends on",
+                    DependencyKind::Optional => "recommends",
+                    DependencyKind::Incompatible => "breaks",
+                    DependencyKind::Discouraged => "conflicts with",
+                },
+                dep.id,
+                dep.version_range
+                    .as_deref()
+                    .map(|r| format!(" {}", r))
+                    .unwrap_or_default()
+            );
+
+            // Minecraft and loader requirements are checked against the instance
+            if dep_id == "minecraft" {
+                if dep.kind == DependencyKind::Required
+                    && !is_plugin_platform
+                    && range_matches(syntax, dep.version_range.as_deref(), target.mc_version)
+                        == Some(false)
+                {
+                    issues.push(DetectedIssue {
+                        issue_type: IssueType::MinecraftVersionMismatch,
+                        description: format!(
+                            "Mod '{}' requires Minecraft {} but the instance uses {}",
+                            display_name(meta),
+                            dep.version_range.as_deref().unwrap_or("?"),
+                            target.mc_version
+                        ),
+                        mod_id: Some(meta.id.clone()),
+                        mod_name: meta.name.clone(),
+                        required_mod_id: Some("minecraft".to_string()),
+                        required_mod_name: Some("Minecraft".to_string()),
+                        required_version: dep.version_range.clone(),
+                        current_version: Some(target.mc_version.to_string()),
+                        suggested_action: SuggestedAction::UpdateMod,
+                        raw_log,
+                    });
+                }
+                continue;
+            }
+            if let Some(loader_id) = loader.as_deref() {
+                if is_loader_id(loader_id, &dep_id) {
+                    let current = target.loader_version.map(strip_mc_prefix);
+                    let mismatch = dep.kind == DependencyKind::Required
+                        && current.is_some_and(|v| {
+                            range_matches(syntax, dep.version_range.as_deref(), v) == Some(false)
+                        });
+                    if mismatch {
+                        issues.push(DetectedIssue {
+                            issue_type: IssueType::LoaderVersionMismatch,
+                            description: format!(
+                                "Mod '{}' requires {} {} but {} is installed",
+                                display_name(meta),
+                                dep.id,
+                                dep.version_range.as_deref().unwrap_or("?"),
+                                current.unwrap_or("?")
+                            ),
+                            mod_id: Some(meta.id.clone()),
+                            mod_name: meta.name.clone(),
+                            required_mod_id: Some(dep.id.clone()),
+                            required_mod_name: None,
+                            required_version: dep.version_range.clone(),
+                            current_version: current.map(str::to_string),
+                            suggested_action: SuggestedAction::ManualFix,
+                            raw_log,
+                        });
+                    }
+                    continue;
+                }
+            }
+            // A loader this instance doesn't run, e.g. "forge" on a Fabric instance
+            if is_any_loader_id(&dep_id) {
+                continue;
+            }
+
+            let installed = available.get(&dep_id);
+            match (dep.kind, installed) {
+                (DependencyKind::Required, None) => issues.push(DetectedIssue {
+                    issue_type: IssueType::MissingDependency,
+                    description: format!(
+                        "Mod '{}' requires '{}' which is not installed",
+                        display_name(meta),
+                        dep.id
+                    ),
+                    mod_id: Some(meta.id.clone()),
+                    mod_name: meta.name.clone(),
+                    required_mod_id: Some(dep.id.clone()),
+                    required_mod_name: None,
+                    required_version: dep.version_range.clone(),
+                    current_version: None,
+                    suggested_action: SuggestedAction::InstallDependency,
+                    raw_log,
+                }),
+                // Missing optional dependencies are normal
+                (DependencyKind::Optional, None) => {}
+                (DependencyKind::Required | DependencyKind::Optional, Some(version)) => {
+                    let Some(version) = version else {
+                        continue;
+                    };
+                    if range_matches(syntax, dep.version_range.as_deref(), version) != Some(false) {
+                        continue;
+                    }
+                    let required = dep.kind == DependencyKind::Required;
+                    issues.push(DetectedIssue {
+                        issue_type: if required {
+                            IssueType::VersionMismatch
+                        } else {
+                            IssueType::RecommendedVersionMismatch
+                        },
+                        description: format!(
+                            "Mod '{}' {} '{}' version {} but {} is installed",
+                            display_name(meta),
+                            if required { "requires" } else { "recommends" },
+                            dep.id,
+                            dep.version_range.as_deref().unwrap_or("?"),
+                            version
+                        ),
+                        mod_id: Some(meta.id.clone()),
+                        mod_name: meta.name.clone(),
+                        required_mod_id: Some(dep.id.clone()),
+                        required_mod_name: required_name(&by_id, &dep_id),
+                        required_version: dep.version_range.clone(),
+                        current_version: Some(version.clone()),
+                        suggested_action: SuggestedAction::UpdateMod,
+                        raw_log,
+                    });
+                }
+                (DependencyKind::Incompatible | DependencyKind::Discouraged, Some(version)) => {
+                    // Only versions inside the range conflict
+                    let conflicts = match (version, &dep.version_range) {
+                        (Some(v), Some(range)) => {
+                            range_matches(syntax, Some(range), v) != Some(false)
+                        }
+                        _ => true,
+                    };
+                    if !conflicts {
+                        continue;
+                    }
+                    let breaks = dep.kind == DependencyKind::Incompatible;
+                    issues.push(DetectedIssue {
+                        issue_type: if breaks {
+                            IssueType::ModConflict
+                        } else {
+                            IssueType::DiscouragedMod
+                        },
+                        description: format!(
+                            "Mod '{}' {} '{}'",
+                            display_name(meta),
+                            if breaks {
+                                "is incompatible with"
+                            } else {
+                                "is known to cause problems with"
+                            },
+                            required_name(&by_id, &dep_id).unwrap_or_else(|| dep.id.clone())
+                        ),
+                        mod_id: Some(meta.id.clone()),
+                        mod_name: meta.name.clone(),
+                        required_mod_id: Some(dep.id.clone()),
+                        required_mod_name: required_name(&by_id, &dep_id),
+                        required_version: dep.version_range.clone(),
+                        current_version: version.clone(),
+                        suggested_action: SuggestedAction::ResolveConflict,
+                        raw_log,
+                    });
+                }
+                (DependencyKind::Incompatible | DependencyKind::Discouraged, None) => {}
+            }
+        }
+    }
+
+    issues
+}
+
+fn display_name(meta: &JarMetadata) -> &str {
+    meta.name.as_deref().unwrap_or(&meta.id)
+}
+
+fn required_name(by_id: &HashMap<String, Vec<&InstalledJar>>, id: &str) -> Option<String> {
+    by_id
+        .get(id)
+        .and_then(|jars| jars.first())
+        .and_then(|jar| jar.metadata.name.clone())
+}
+
+/// Plugin names are case-insensitive in practice, mod ids are lowercase anyway
+fn normalize_id(id: &str) -> String {
+    id.to_lowercase()
+}
+
+fn is_plugin_loader(loader: &str) -> bool {
+    matches!(
+        loader,
+        "paper"
+            | "purpur"
+            | "folia"
+            | "pufferfish"
+            | "spigot"
+            | "bukkit"
+            | "velocity"
+            | "bungeecord"
+            | "waterfall"
+    )
+}
+
+/// Whether a jar can be loaded by the instance's loader (`None` = vanilla)
+fn supports_loader(meta: &JarMetadata, loader: Option<&str>) -> bool {
+    let accepted: &[JarMetadataFormat] = match loader {
+        Some("fabric") => &[JarMetadataFormat::Fabric],
+        Some("quilt") => &[JarMetadataFormat::Quilt, JarMetadataFormat::Fabric],
+        Some("forge") => &[JarMetadataFormat::Forge, JarMetadataFormat::LegacyForge],
+        Some("neoforge") => &[JarMetadataFormat::NeoForge, JarMetadataFormat::Forge],
+        Some("paper") | Some("purpur") | Some("folia") | Some("pufferfish") => {
+            &[JarMetadataFormat::Paper, JarMetadataFormat::Bukkit]
+        }
+        Some("spigot") | Some("bukkit") => &[JarMetadataFormat::Bukkit],
+        Some("velocity") => &[JarMetadataFormat::Velocity],
+        // BungeeCord reads plugin.yml as a fallback for bungee.yml, and
+        // unknown loaders can't be checked
+        _ => return true,
+    };
+    std::iter::once(&meta.format)
+        .chain(meta.other_formats.iter())
+        .any(|f| accepted.contains(f))
+}
+
+fn format_loader_name(format: JarMetadataFormat) -> &'static str {
+    match format {
+        JarMetadataFormat::Fabric => "Fabric",
+        JarMetadataFormat::Quilt => "Quilt",
+        JarMetadataFormat::Forge | JarMetadataFormat::LegacyForge => "Forge",
+        JarMetadataFormat::NeoForge => "NeoForge",
+        JarMetadataFormat::Bukkit => "Bukkit",
+        JarMetadataFormat::Paper => "Paper",
+        JarMetadataFormat::Velocity => "Velocity",
+    }
+}
+
+/// Dependency ids that refer to the given loader itself
+fn is_loader_id(loader: &str, id: &str) -> bool {
+    match loader {
+        "fabric" => id == "fabricloader",
+        "quilt" => id == "quilt_loader",
+        "forge" => id == "forge",
+        "neoforge" => id == "neoforge",
+        _ => false,
+    }
+}
+
+fn is_any_loader_id(id: &str) -> bool {
+    matches!(id, "fabricloader" | "quilt_loader" | "forge" | "neoforge")
+}
+
+/// Loader versions are sometimes stored with the Minecraft version ("1.20.1-47.2.0")
+fn strip_mc_prefix(version: &str) -> &str {
+    match version.split_once('-') {
+        Some((prefix, rest)) if prefix.starts_with("1.") && !rest.is_empty() => rest,
+        _ => version,
+    }
+}
+
+// ============================================================================
+// Version ranges
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeSyntax {
+    /// Fabric/Quilt version predicates (">=1.2 <2", "1.20.x", "~1.2")
+    Fabric,
+    /// Maven version ranges ("[1.0,2.0)")
+    Maven,
+    /// Plugin descriptors don't declare version ranges
+    None,
+}
+
+impl RangeSyntax {
+    fn for_format(format: JarMetadataFormat) -> Self {
+        match format {
+            JarMetadataFormat::Fabric | JarMetadataFormat::Quilt => Self::Fabric,
+            JarMetadataFormat::Forge
+            | JarMetadataFormat::NeoForge
+            | JarMetadataFormat::LegacyForge => Self::Maven,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Whether `version` satisfies `range`; `None` when it can't be decided
+fn range_matches(syntax: RangeSyntax, range: Option<&str>, version: &str) -> Option<bool> {
+    let range = range?.trim();
+    if range.is_empty() || range == "*" {
+        return Some(true);
+    }
+    match syntax {
+        RangeSyntax::Fabric => fabric_range_matches(range, version),
+        RangeSyntax::Maven => maven_range_matches(range, version),
+        RangeSyntax::None => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Number(u64),
+    Text(String),
+}
+
+fn parse_segments(version: &str, separators: &[char]) -> Vec<Segment> {
+    version
+        .split(|c| separators.contains(&c))
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.parse::<u64>() {
+            Ok(n) => Segment::Number(n),
+            Err(_) => Segment::Text(s.to_lowercase()),
+        })
+        .collect()
+}
+
+fn compare_segment(a: &Segment, b: &Segment) -> Ordering {
+    match (a, b) {
+        (Segment::Number(x), Segment::Number(y)) => x.cmp(y),
+        (Segment::Text(x), Segment::Text(y)) => x.cmp(y),
+        // A numeric component is newer than a qualifier ("1.0.1" > "1.0.beta")
+        (Segment::Number(_), Segment::Text(_)) => Ordering::Greater,
+        (Segment::Text(_), Segment::Number(_)) => Ordering::Less,
+    }
+}
+
+/// Compare semantic versions: build metadata is ignored and pre-releases sort first
+fn compare_semver(a: &str, b: &str) -> Ordering {
+    let strip_build = |v: &str| v.split('+').next().unwrap_or(v).to_string();
+    let (a, b) = (strip_build(a), strip_build(b));
+    let (a_core, a_pre) = a.split_once('-').unwrap_or((&a, ""));
+    let (b_core, b_pre) = b.split_once('-').unwrap_or((&b, ""));
+
+    let a_segments = parse_segments(a_core, &['.']);
+    let b_segments = parse_segments(b_core, &['.']);
+    for i in 0..a_segments.len().max(b_segments.len()) {
+        let x = a_segments.get(i).cloned().unwrap_or(Segment::Number(0));
+        let y = b_segments.get(i).cloned().unwrap_or(Segment::Number(0));
+        match compare_segment(&x, &y) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+    }
+
+    match (a_pre.is_empty(), b_pre.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => {
+            let x = parse_segments(a_pre, &['.']);
+            let y = parse_segments(b_pre, &['.']);
+            for (p, q) in x.iter().zip(y.iter()) {
+                match compare_segment(p, q) {
+                    Ordering::Equal => {}
+                    other => return other,
+                }
+            }
+            x.len().cmp(&y.len())
+        }
+    }
+}
+
+fn is_wildcard(segment: &str) -> bool {
+    matches!(segment, "x" | "X" | "*")
+}
+
+/// Evaluate a Fabric version predicate, alternatives separated by `||`
+fn fabric_range_matches(range: &str, version: &str) -> Option<bool> {
+    let mut result = false;
+    for alternative in range.split("||") {
+        let mut all = true;
+        for predicate in alternative.split_whitespace() {
+            all &= fabric_predicate_matches(predicate, version)?;
+        }
+        result |= all;
+    }
+    Some(result)
+}
+
+fn fabric_predicate_matches(predicate: &str, version: &str) -> Option<bool> {
+    if predicate == "*" {
+        return Some(true);
+    }
+    let (op, body) = [">=", "<=", ">", "<", "=", "~", "^"]
+        .iter()
+        .find_map(|op| predicate.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("=", predicate));
+    // A trailing "-" only widens the range to pre-releases
+    let body = body.trim().trim_end_matches('-');
+    if body.is_empty() {
+        return None;
+    }
+
+    // "1.20.x": every non-wildcard component must match
+    let parts: Vec<&str> = body.split('.').collect();
+    if let Some(wildcard_at) = parts.iter().position(|p| is_wildcard(p)) {
+        if op != "=" {
+            return None;
+        }
+        let core = version.split(['-', '+']).next().unwrap_or(version);
+        let version_parts: Vec<&str> = core.split('.').collect();
+        return Some(
+            parts[..wildcard_at]
+                .iter()
+                .enumerate()
+                .all(|(i, p)| version_parts.get(i).copied().unwrap_or("0") == *p),
+        );
+    }
+
+    let ordering = compare_semver(version, body);
+    Some(match op {
+        ">=" => ordering != Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        "~" => {
+            // Same major and minor, at least the given version
+            let upper = match parts.as_slice() {
+                [major] => format!("{}", major.parse::<u64>().ok()? + 1),
+                [major, minor, ..] => format!("{}.{}", major, minor.parse::<u64>().ok()? + 1),
+                [] => return None,
+            };
+            ordering != Ordering::Less && compare_semver(version, &upper) == Ordering::Less
+        }
+        "^" => {
+            // Same major version, at least the given version
+            let upper = format!("{}", parts[0].parse::<u64>().ok()? + 1);
+            ordering != Ordering::Less && compare_semver(version, &upper) == Ordering::Less
+        }
+        _ => ordering == Ordering::Equal,
+    })
+}
+
+/// Maven qualifiers that sort before the release they qualify
+fn is_prerelease_qualifier(text: &str) -> bool {
+    [
+        "alpha",
+        "a",
+        "beta",
+        "b",
+        "milestone",
+        "m",
+        "rc",
+        "cr",
+        "pre",
+        "snapshot",
+    ]
+    .iter()
+    .any(|q| {
+        text == *q || (text.starts_with(q) && text[q.len()..].chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Compare versions the way Maven (and Forge) does, roughly
+fn compare_maven(a: &str, b: &str) -> Ordering {
+    let separators = ['.', '-', '_', '+'];
+    let x = parse_segments(a, &separators);
+    let y = parse_segments(b, &separators);
+    for i in 0..x.len().max(y.len()) {
+        let ordering = match (x.get(i), y.get(i)) {
+            (Some(p), Some(q)) => compare_segment(p, q),
+            (Some(Segment::Number(n)), None) => n.cmp(&0),
+            (None, Some(Segment::Number(n))) => 0.cmp(n),
+            // "1.0-beta" < "1.0" < "1.0-sp1"
+            (Some(Segment::Text(t)), None) => {
+                if is_prerelease_qualifier(t) {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            }
+            (None, Some(Segment::Text(t))) => {
+                if is_prerelease_qualifier(t) {
+                    Ordering::Greater
+                } else {
+                    Ordering::Less
+                }
+            }
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Evaluate a Maven version range such as "[1.0,2.0)" or "[1.20.1]"
+fn maven_range_matches(range: &str, version: &str) -> Option<bool> {
+    // A bare version is only a recommendation, anything satisfies it
+    if !range.starts_with('[') && !range.starts_with('(') {
+        return Some(true);
+    }
+
+    let mut result = false;
+    let mut rest = range;
+    while let Some(start) = rest.find(['[', '(']) {
+        let lower_inclusive = rest[start..].starts_with('[');
+        let end = start + rest[start..].find([']', ')'])?;
+        let upper_inclusive = rest[end..].starts_with(']');
+        let body = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let matches = match body.split_once(',') {
+            None => compare_maven(version, body.trim()) == Ordering::Equal,
+            Some((lower, upper)) => {
+                let (lower, upper) = (lower.trim(), upper.trim());
+                let above = lower.is_empty()
+                    || match compare_maven(version, lower) {
+                        Ordering::Greater => true,
+                        Ordering::Equal => lower_inclusive,
+                        Ordering::Less => false,
+                    };
+                let below = upper.is_empty()
+                    || match compare_maven(version, upper) {
+                        Ordering::Less => true,
+                        Ordering::Equal => upper_inclusive,
+                        Ordering::Greater => false,
+                    };
+                above && below
+            }
+        };
+        result |= matches;
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::jar_metadata::{BundledMod, JarDependency};
+
+    fn jar(
+        format: JarMetadataFormat,
+        id: &str,
+        version: &str,
+        deps: Vec<JarDependency>,
+    ) -> InstalledJar {
+        InstalledJar {
+            filename: format!("{}-{}.jar", id, version),
+            metadata: JarMetadata {
+                format,
+                id: id.to_string(),
+                name: None,
+                version: Some(version.to_string()),
+                description: None,
+                authors: Vec::new(),
+                dependencies: deps,
+                side: None,
+                icon: None,
+                provides: Vec::new(),
+                bundled: Vec::new(),
+                other_formats: Vec::new(),
+            },
+        }
+    }
+
+    fn dep(id: &str, range: Option<&str>, kind: DependencyKind) -> JarDependency {
+        JarDependency {
+            id: id.to_string(),
+            version_range: range.map(str::to_string),
+            kind,
+        }
+    }
+
+    const FABRIC: AnalysisTarget<'static> = AnalysisTarget {
+        mc_version: "1.20.4",
+        loader: Some("fabric"),
+        loader_version: Some("0.15.7"),
+    };
+
+    #[test]
+    fn test_fabric_predicates() {
+        assert_eq!(fabric_range_matches(">=0.15.0", "0.15.7"), Some(true));
+        assert_eq!(fabric_range_matches(">=0.16", "0.15.7"), Some(false));
+        assert_eq!(fabric_range_matches(">=1.20.2 <1.21", "1.20.4"), Some(true));
+        assert_eq!(fabric_range_matches("1.20.x", "1.20.4"), Some(true));
+        assert_eq!(fabric_range_matches("1.19.x", "1.20.4"), Some(false));
+        assert_eq!(fabric_range_matches("~1.20.2", "1.20.4"), Some(true));
+        assert_eq!(fabric_range_matches("~1.20.2", "1.21"), Some(false));
+        assert_eq!(fabric_range_matches("^2.1", "2.9.0"), Some(true));
+        assert_eq!(fabric_range_matches("^2.1", "3.0.0"), Some(false));
+        assert_eq!(
+            fabric_range_matches("1.20.3 || 1.20.4", "1.20.4"),
+            Some(true)
+        );
+        assert_eq!(fabric_range_matches(">=1.20.5-", "1.20.4"), Some(false));
+        assert_eq!(
+            fabric_range_matches(">=0.5.0", "0.5.8+mc1.20.4"),
+            Some(true)
+        );
+        assert_eq!(fabric_range_matches(">=1.0.0", "1.0.0-beta.2"), Some(false));
+    }
+
+    #[test]
+    fn test_maven_ranges() {
+        assert_eq!(maven_range_matches("[47,)", "47.2.0"), Some(true));
+        assert_eq!(maven_range_matches("[48,)", "47.2.0"), Some(false));
+        assert_eq!(maven_range_matches("[1.20.1,1.21)", "1.20.1"), Some(true));
+        assert_eq!(maven_range_matches("[1.20.1,1.21)", "1.21"), Some(false));
+        assert_eq!(maven_range_matches("(,1.20]", "1.20"), Some(true));
+        assert_eq!(maven_range_matches("[1.20.1]", "1.20.1"), Some(true));
+        assert_eq!(maven_range_matches("[1.0,2.0),[3.0,)", "3.1"), Some(true));
+        assert_eq!(maven_range_matches("[1.0,)", "1.0-beta"), Some(false));
+        assert_eq!(maven_range_matches("1.0", "0.1"), Some(true));
+    }
+
+    #[test]
+    fn test_missing_and_satisfied_dependencies() {
+        let mut api = jar(
+            JarMetadataFormat::Fabric,
+            "fabric-api",
+            "0.96.0+1.20.4",
+            vec![],
+        );
+        api.metadata.bundled.push(BundledMod {
+            id: "fabric-api-base".to_string(),
+            version: Some("0.4.36".to_string()),
+        });
+        let jars = vec![
+            api,
+            jar(
+                JarMetadataFormat::Fabric,
+                "sodium",
+                "0.5.8",
+                vec![
+                    dep("fabricloader", Some(">=0.12.0"), DependencyKind::Required),
+                    dep("minecraft", Some("1.20.x"), DependencyKind::Required),
+                    dep("fabric-api-base", Some("*"), DependencyKind::Required),
+                    dep("fabric-renderer-api-v1", None, DependencyKind::Required),
+                    dep("modmenu", None, DependencyKind::Optional),
+                ],
+            ),
+        ];
+        let issues = analyze(&jars, &FABRIC);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::MissingDependency);
+        assert_eq!(
+            issues[0].required_mod_id.as_deref(),
+            Some("fabric-renderer-api-v1")
+        );
+        assert_eq!(
+            issues[0].suggested_action,
+            SuggestedAction::InstallDependency
+        );
+    }
+
+    #[test]
+    fn test_version_mismatches() {
+        let jars = vec![
+            jar(JarMetadataFormat::Fabric, "cloth-config", "11.0.0", vec![]),
+            jar(
+                JarMetadataFormat::Fabric,
+                "example",
+                "1.0.0",
+                vec![
+                    dep("cloth-config", Some(">=13.0.0"), DependencyKind::Required),
+                    dep("minecraft", Some(">=1.20.5"), DependencyKind::Required),
+                    dep("fabricloader", Some(">=0.16.0"), DependencyKind::Required),
+                ],
+            ),
+        ];
+        let issues = analyze(&jars, &FABRIC);
+        let types: Vec<_> = issues.iter().map(|i| i.issue_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                IssueType::VersionMismatch,
+                IssueType::MinecraftVersionMismatch,
+                IssueType::LoaderVersionMismatch
+            ]
+        );
+        assert_eq!(issues[0].current_version.as_deref(), Some("11.0.0"));
+        assert!(issues.iter().all(is_fatal));
+    }
+
+    #[test]
+    fn test_conflicts_and_duplicates() {
+        let jars = vec![
+            jar(JarMetadataFormat::Fabric, "optifabric", "1.14.0", vec![]),
+            jar(
+                JarMetadataFormat::Fabric,
+                "sodium",
+                "0.5.8",
+                vec![dep("optifabric", Some("*"), DependencyKind::Incompatible)],
+            ),
+            jar(JarMetadataFormat::Fabric, "sodium", "0.5.3", vec![]),
+        ];
+        let issues = analyze(&jars, &FABRIC);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].issue_type, IssueType::DuplicateMod);
+        assert_eq!(issues[0].suggested_action, SuggestedAction::RemoveDuplicate);
+        assert_eq!(issues[1].issue_type, IssueType::ModConflict);
+        assert_eq!(issues[1].required_mod_id.as_deref(), Some("optifabric"));
+    }
+
+    #[test]
+    fn test_discouraged_mods_are_not_fatal() {
+        let neoforge = AnalysisTarget {
+            mc_version: "1.21.1",
+            loader: Some("neoforge"),
+            loader_version: Some("21.1.77"),
+        };
+        let jars = vec![
+            jar(
+                JarMetadataFormat::NeoForge,
+                "roughlyenoughitems",
+                "16.0.0",
+                vec![],
+            ),
+            jar(
+                JarMetadataFormat::NeoForge,
+                "jei",
+                "19.0.0",
+                vec![dep("roughlyenoughitems", None, DependencyKind::Discouraged)],
+            ),
+        ];
+        let issues = analyze(&jars, &neoforge);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::DiscouragedMod);
+        assert!(!is_fatal(&issues[0]));
+
+        // Fabric "conflicts" only applies inside its range
+        let jars = vec![
+            jar(JarMetadataFormat::Fabric, "iris", "1.7.0", vec![]),
+            jar(
+                JarMetadataFormat::Fabric,
+                "sodium",
+                "0.5.8",
+                vec![dep("iris", Some("<1.6"), DependencyKind::Discouraged)],
+            ),
+        ];
+        assert!(analyze(&jars, &FABRIC).is_empty());
+    }
+
+    #[test]
+    fn test_wrong_loader() {
+        let target = AnalysisTarget {
+            mc_version: "1.20.1",
+            loader: Some("forge"),
+            loader_version: Some("1.20.1-47.2.0"),
+        };
+        let jars = vec![
+            jar(JarMetadataFormat::Fabric, "sodium", "0.5.8", vec![]),
+            jar(
+                JarMetadataFormat::Forge,
+                "jei",
+                "15.2.0",
+                vec![
+                    dep("forge", Some("[47,)"), DependencyKind::Required),
+                    dep(
+                        "minecraft",
+                        Some("[1.20.1,1.20.2)"),
+                        DependencyKind::Required,
+                    ),
+                ],
+            ),
+        ];
+        let issues = analyze(&jars, &target);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_type, IssueType::LoaderVersionMismatch);
+        assert_eq!(issues[0].mod_id.as_deref(), Some("sodium"));
+
+        // Quilt loads Fabric mods
+        let quilt = AnalysisTarget {
+            loader: Some("quilt"),
+            ..FABRIC
+        };
+        let jars = vec![jar(JarMetadataFormat::Fabric, "sodium", "0.5.8", vec![])];
+        assert!(analyze(&jars, &quilt).is_empty());
+    }
+}
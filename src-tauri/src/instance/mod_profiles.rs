@@ -0,0 +1,399 @@
+//! Mod profiles
+//!
+//! Named sets of enabled mods within one instance, e.g. "performance",
+//! "recording" and "debug". A profile records whether each jar of the content
+//! folder is enabled (`.jar`) or disabled (`.jar.disabled`), and for clients
+//! the resource pack order of `options.txt`. Switching applies every change or
+//! none of them.
+
+use crate::error::{AppError, AppResult};
+use crate::instance::game_options;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Profiles of an instance, stored in its game directory
+pub const PROFILES_FILE: &str = "mod-profiles.json";
+
+const DISABLED_SUFFIX: &str = ".disabled";
+
+/// One named set of enabled mods
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModProfile {
+    pub name: String,
+    /// Jar filename (without `.disabled`) to enabled state
+    pub mods: BTreeMap<String, bool>,
+    /// Resource packs in `options.txt` order, `None` leaves the packs untouched
+    #[serde(default)]
+    pub resource_packs: Option<Vec<String>>,
+    pub updated_at: String,
+}
+
+/// All profiles of an instance and the one applied last
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModProfiles {
+    pub active: Option<String>,
+    pub profiles: Vec<ModProfile>,
+}
+
+/// Profiles with whether the content folder still matches the active one
+#[derive(Debug, Clone, Serialize)]
+pub struct ModProfilesInfo {
+    pub active: Option<String>,
+    /// False once mods were toggled, added or removed after the last switch
+    pub active_in_sync: bool,
+    pub profiles: Vec<ModProfile>,
+}
+
+/// Renames needed to apply a profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProfileSwitchPlan {
+    /// (from, to) filenames in the content folder
+    pub renames: Vec<(String, String)>,
+    /// Jars of the profile that are no longer installed
+    pub missing: Vec<String>,
+    /// Installed jars the profile does not know, left as they are
+    pub untracked: Vec<String>,
+}
+
+/// Outcome of a profile switch
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileSwitchResult {
+    pub profile: String,
+    pub enabled: usize,
+    pub disabled: usize,
+    pub missing: Vec<String>,
+    pub untracked: Vec<String>,
+    pub resource_packs_applied: bool,
+}
+
+impl ModProfiles {
+    pub async fn load(game_dir: &Path) -> AppResult<Self> {
+        match tokio::fs::read_to_string(game_dir.join(PROFILES_FILE)).await {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(AppError::Io(format!("Failed to read mod profiles: {}", e))),
+        }
+    }
+
+    pub async fn save(&self, game_dir: &Path) -> AppResult<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(game_dir.join(PROFILES_FILE), json)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to save mod profiles: {}", e)))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ModProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Add a profile, replacing the one with the same name
+    pub fn upsert(&mut self, profile: ModProfile) {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.profiles.len();
+        self.profiles.retain(|p| p.name != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        self.profiles.len() != before
+    }
+}
+
+/// Profile names are shown in the launcher and stored as keys
+pub fn validate_name(name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Instance(
+            "Profile name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > 64 {
+        return Err(AppError::Instance(
+            "Profile name cannot be longer than 64 characters".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Enabled state of each jar of a content folder, keyed by its enabled filename
+pub async fn scan_mods(content_dir: &Path) -> AppResult<BTreeMap<String, bool>> {
+    let mut mods = BTreeMap::new();
+    let mut entries = match tokio::fs::read_dir(content_dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(mods),
+        Err(e) => return Err(AppError::Io(format!("Failed to read mods folder: {}", e))),
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if !entry
+            .file_type()
+            .await
+            .map(|t| t.is_file())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        let filename = entry.file_name().to_string_lossy().to_string();
+        if filename.ends_with(".jar") {
+            mods.insert(filename, true);
+        } else if let Some(name) = filename.strip_suffix(DISABLED_SUFFIX) {
+            // An enabled copy of the same jar wins
+            if name.ends_with(".jar") {
+                mods.entry(name.to_string()).or_insert(false);
+            }
+        }
+    }
+
+    Ok(mods)
+}
+
+/// Record the current state of an instance as a profile
+pub async fn capture(
+    name: &str,
+    game_dir: &Path,
+    content_dir: &Path,
+    include_resource_packs: bool,
+) -> AppResult<ModProfile> {
+    let resource_packs = if include_resource_packs {
+        Some(game_options::read_resource_packs(game_dir).await?)
+    } else {
+        None
+    };
+
+    Ok(ModProfile {
+        name: validate_name(name)?,
+        mods: scan_mods(content_dir).await?,
+        resource_packs,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Renames that take the content folder from `current` to the profile
+pub fn plan_switch(profile: &ModProfile, current: &BTreeMap<String, bool>) -> ProfileSwitchPlan {
+    let mut plan = ProfileSwitchPlan::default();
+
+    for (name, &enabled) in &profile.mods {
+        match current.get(name) {
+            None => plan.missing.push(name.clone()),
+            Some(&state) if state == enabled => {}
+            Some(_) => {
+                let disabled = format!("{}{}", name, DISABLED_SUFFIX);
+                plan.renames.push(if enabled {
+                    (disabled, name.clone())
+                } else {
+                    (name.clone(), disabled)
+                });
+            }
+        }
+    }
+
+    plan.untracked = current
+        .keys()
+        .filter(|name| !profile.mods.contains_key(*name))
+        .cloned()
+        .collect();
+
+    plan
+}
+
+/// Whether the content folder and resource packs still match a profile
+pub fn is_in_sync(
+    profile: &ModProfile,
+    current: &BTreeMap<String, bool>,
+    resource_packs: Option<&[String]>,
+) -> bool {
+    let packs_match = match (&profile.resource_packs, resource_packs) {
+        (Some(expected), Some(actual)) => expected.as_slice() == actual,
+        _ => true,
+    };
+    packs_match && profile.mods == *current
+}
+
+/// Undo renames that were already applied, most recent first
+async fn rollback(content_dir: &Path, applied: &[(String, String)]) {
+    for (from, to) in applied.iter().rev() {
+        if let Err(e) = tokio::fs::rename(content_dir.join(to), content_dir.join(from)).await {
+            log::error!("Failed to restore {} while rolling back: {}", from, e);
+        }
+    }
+}
+
+/// Apply a profile: every rename and the resource pack order, or nothing.
+/// The caller makes sure the instance is not running
+pub async fn apply(
+    game_dir: &Path,
+    content_dir: &Path,
+    profile: &ModProfile,
+    include_resource_packs: bool,
+) -> AppResult<ProfileSwitchResult> {
+    let current = scan_mods(content_dir).await?;
+    let plan = plan_switch(profile, &current);
+
+    // Check every target before touching anything
+    for (from, to) in &plan.renames {
+        if content_dir.join(to).exists() {
+            return Err(AppError::Instance(format!(
+                "Cannot switch profile: both {} and {} exist in the mods folder",
+                from, to
+            )));
+        }
+    }
+
+    let mut applied: Vec<(String, String)> = Vec::with_capacity(plan.renames.len());
+    for (from, to) in &plan.renames {
+        if let Err(e) = tokio::fs::rename(content_dir.join(from), content_dir.join(to)).await {
+            rollback(content_dir, &applied).await;
+            return Err(AppError::Io(format!(
+                "Failed to rename {} (no changes were kept): {}",
+                from, e
+            )));
+        }
+        applied.push((from.clone(), to.clone()));
+    }
+
+    let mut resource_packs_applied = false;
+    if let Some(packs) = profile
+        .resource_packs
+        .as_ref()
+        .filter(|_| include_resource_packs)
+    {
+        let options = game_options::read_options(game_dir).await;
+        let result = match options {
+            Ok(contents) => {
+                let updated = game_options::set_option(
+                    &contents,
+                    game_options::RESOURCE_PACKS_KEY,
+                    &game_options::format_pack_list(packs),
+                );
+                game_options::write_options(game_dir, &updated).await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            rollback(content_dir, &applied).await;
+            return Err(e);
+        }
+        resource_packs_applied = true;
+    }
+
+    let enabled = plan
+        .renames
+        .iter()
+        .filter(|(from, _)| from.ends_with(DISABLED_SUFFIX))
+        .count();
+
+    log::info!(
+        "Applied mod profile '{}' ({} enabled, {} disabled, {} missing)",
+        profile.name,
+        enabled,
+        plan.renames.len() - enabled,
+        plan.missing.len()
+    );
+
+    Ok(ProfileSwitchResult {
+        profile: profile.name.clone(),
+        enabled,
+        disabled: plan.renames.len() - enabled,
+        missing: plan.missing,
+        untracked: plan.untracked,
+        resource_packs_applied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(mods: &[(&str, bool)]) -> ModProfile {
+        ModProfile {
+            name: "performance".to_string(),
+            mods: mods.iter().map(|(n, e)| (n.to_string(), *e)).collect(),
+            resource_packs: None,
+            updated_at: String::new(),
+        }
+    }
+
+    fn state(mods: &[(&str, bool)]) -> BTreeMap<String, bool> {
+        mods.iter().map(|(n, e)| (n.to_string(), *e)).collect()
+    }
+
+    #[test]
+    fn test_plan_switch() {
+        let target = profile(&[
+            ("sodium.jar", true),
+            ("replaymod.jar", false),
+            ("lithium.jar", true),
+            ("gone.jar", true),
+        ]);
+        let current = state(&[
+            ("sodium.jar", true),
+            ("replaymod.jar", true),
+            ("lithium.jar", false),
+            ("new.jar", true),
+        ]);
+
+        let plan = plan_switch(&target, &current);
+        assert_eq!(
+            plan.renames,
+            vec![
+                (
+                    "lithium.jar.disabled".to_string(),
+                    "lithium.jar".to_string()
+                ),
+                (
+                    "replaymod.jar".to_string(),
+                    "replaymod.jar.disabled".to_string()
+                ),
+            ]
+        );
+        assert_eq!(plan.missing, vec!["gone.jar"]);
+        assert_eq!(plan.untracked, vec!["new.jar"]);
+    }
+
+    #[test]
+    fn test_is_in_sync() {
+        let mut target = profile(&[("sodium.jar", true)]);
+        assert!(is_in_sync(&target, &state(&[("sodium.jar", true)]), None));
+        assert!(!is_in_sync(&target, &state(&[("sodium.jar", false)]), None));
+        assert!(!is_in_sync(
+            &target,
+            &state(&[("sodium.jar", true), ("new.jar", true)]),
+            None
+        ));
+
+        target.resource_packs = Some(vec!["vanilla".to_string()]);
+        let packs = vec!["vanilla".to_string(), "file/x.zip".to_string()];
+        assert!(!is_in_sync(
+            &target,
+            &state(&[("sodium.jar", true)]),
+            Some(&packs)
+        ));
+    }
+
+    #[test]
+    fn test_profiles_upsert_and_remove() {
+        let mut profiles = ModProfiles::default();
+        profiles.upsert(profile(&[("a.jar", true)]));
+        profiles.upsert(profile(&[("a.jar", false)]));
+        assert_eq!(profiles.profiles.len(), 1);
+        assert!(!profiles.get("performance").unwrap().mods["a.jar"]);
+
+        profiles.active = Some("performance".to_string());
+        assert!(profiles.remove("performance"));
+        assert!(profiles.active.is_none());
+        assert!(!profiles.remove("performance"));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert_eq!(validate_name("  debug ").unwrap(), "debug");
+        assert!(validate_name("   ").is_err());
+        assert!(validate_name(&"x".repeat(65)).is_err());
+    }
+}
@@ -0,0 +1,525 @@
+//! Enabling and ordering the resource packs of a client instance
+//!
+//! The game keeps the enabled packs in the `resourcePacks` key of
+//! `options.txt`, lowest priority first. Packs from the `resourcepacks` folder
+//! are listed as `file/<name>`, next to built-in and mod-provided packs such as
+//! `vanilla`. On load the game drops enabled packs made for another version,
+//! unless they are also listed in `incompatibleResourcePacks`, so packs enabled
+//! from the launcher despite a mismatch are added there as well.
+
+use crate::error::{AppError, AppResult};
+use crate::instance::game_options::{self, INCOMPATIBLE_RESOURCE_PACKS_KEY, RESOURCE_PACKS_KEY};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+
+pub const RESOURCE_PACKS_FOLDER: &str = "resourcepacks";
+
+/// Prefix of the ids of packs in the resource packs folder
+const FILE_PREFIX: &str = "file/";
+
+/// The game's own pack, always enabled and applied first
+const VANILLA_PACK: &str = "vanilla";
+
+/// `pack.mcmeta` and `pack.png` files larger than this are not read
+const MAX_FILE_SIZE: u64 = 512 * 1024;
+
+/// A release version as `(major, minor, patch)`
+type Release = (u32, u32, u32);
+
+/// Resource pack format of each range of Minecraft releases
+const PACK_FORMATS: &[(Release, Release, u32)] = &[
+    ((1, 6, 1), (1, 8, 9), 1),
+    ((1, 9, 0), (1, 10, 2), 2),
+    ((1, 11, 0), (1, 12, 2), 3),
+    ((1, 13, 0), (1, 14, 4), 4),
+    ((1, 15, 0), (1, 16, 1), 5),
+    ((1, 16, 2), (1, 16, 5), 6),
+    ((1, 17, 0), (1, 17, 1), 7),
+    ((1, 18, 0), (1, 18, 2), 8),
+    ((1, 19, 0), (1, 19, 2), 9),
+    ((1, 19, 3), (1, 19, 3), 12),
+    ((1, 19, 4), (1, 19, 4), 13),
+    ((1, 20, 0), (1, 20, 1), 15),
+    ((1, 20, 2), (1, 20, 2), 18),
+    ((1, 20, 3), (1, 20, 4), 22),
+    ((1, 20, 5), (1, 20, 6), 32),
+    ((1, 21, 0), (1, 21, 1), 34),
+    ((1, 21, 2), (1, 21, 3), 42),
+    ((1, 21, 4), (1, 21, 4), 46),
+    ((1, 21, 5), (1, 21, 5), 55),
+    ((1, 21, 6), (1, 21, 6), 63),
+    ((1, 21, 7), (1, 21, 8), 64),
+    ((1, 21, 9), (1, 21, 10), 69),
+];
+
+/// Whether a pack was made for the instance's Minecraft version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackCompatibility {
+    Compatible,
+    /// Made for older versions
+    TooOld,
+    /// Made for newer versions
+    TooNew,
+    /// No readable `pack.mcmeta`, or a Minecraft version without a known format
+    Unknown,
+}
+
+/// What `pack.mcmeta` says about a pack
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackMeta {
+    pub pack_format: Option<u32>,
+    /// Formats the pack declares support for, inclusive
+    pub supported_formats: Option<(u32, u32)>,
+    pub description: Option<String>,
+}
+
+/// A resource pack of an instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourcePackInfo {
+    /// Id used in `options.txt`, e.g. `file/Faithful.zip` or `vanilla`
+    pub id: String,
+    /// File or folder name in `resourcepacks`, `None` for built-in and mod packs
+    pub filename: Option<String>,
+    pub enabled: bool,
+    pub pack_format: Option<u32>,
+    pub supported_formats: Option<(u32, u32)>,
+    pub description: Option<String>,
+    /// Base64 encoded pack.png data URL, if present
+    pub icon_data_url: Option<String>,
+    pub compatibility: PackCompatibility,
+}
+
+/// Parse a release version such as `1.20.1`
+fn parse_release(version: &str) -> Option<Release> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    parts.next().is_none().then_some((major, minor, patch))
+}
+
+/// Resource pack format the game expects, `None` for snapshots and unknown versions
+pub fn pack_format_for(mc_version: &str) -> Option<u32> {
+    let version = parse_release(mc_version)?;
+    PACK_FORMATS
+        .iter()
+        .find(|(first, last, _)| (*first..=*last).contains(&version))
+        .map(|(_, _, format)| *format)
+}
+
+/// A format number, or the major part of a `[major, minor]` format
+fn format_number(value: &serde_json::Value) -> Option<u32> {
+    match value {
+        serde_json::Value::Array(parts) => parts.first()?.as_u64(),
+        other => other.as_u64(),
+    }
+    .and_then(|n| u32::try_from(n).ok())
+}
+
+/// `supported_formats` as a single format, `[min, max]` or an object with
+/// `min_inclusive` and `max_inclusive`
+fn format_range(value: &serde_json::Value) -> Option<(u32, u32)> {
+    match value {
+        serde_json::Value::Array(bounds) if bounds.len() == 2 => {
+            Some((format_number(&bounds[0])?, format_number(&bounds[1])?))
+        }
+        serde_json::Value::Object(bounds) => Some((
+            format_number(bounds.get("min_inclusive")?)?,
+            format_number(bounds.get("max_inclusive")?)?,
+        )),
+        other => format_number(other).map(|n| (n, n)),
+    }
+}
+
+/// Plain text of a description, which may be a text component or a list of them
+fn component_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(parts) => parts.iter().map(component_text).collect(),
+        serde_json::Value::Object(component) => {
+            let text = component
+                .get("text")
+                .or_else(|| component.get("translate"))
+                .and_then(|t| t.as_str())
+                .unwrap_or_default();
+            let extra: String = component
+                .get("extra")
+                .map(component_text)
+                .unwrap_or_default();
+            format!("{}{}", text, extra)
+        }
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Parse the `pack` section of a `pack.mcmeta`
+pub fn parse_pack_mcmeta(contents: &[u8]) -> Option<PackMeta> {
+    let json: serde_json::Value = serde_json::from_slice(contents).ok()?;
+    let pack = json.get("pack")?;
+
+    let pack_format = pack.get("pack_format").and_then(format_number);
+    // Packs for 1.21.9 and later declare `min_format` and `max_format` instead
+    let min_max = pack
+        .get("min_format")
+        .and_then(format_number)
+        .zip(pack.get("max_format").and_then(format_number));
+    let supported_formats = pack
+        .get("supported_formats")
+        .and_then(format_range)
+        .or(min_max);
+
+    Some(PackMeta {
+        pack_format: pack_format.or(supported_formats.map(|(_, max)| max)),
+        supported_formats,
+        description: pack
+            .get("description")
+            .map(component_text)
+            .filter(|d| !d.is_empty()),
+    })
+}
+
+/// Compare the formats a pack supports with the one the instance expects
+pub fn compatibility(meta: Option<&PackMeta>, expected: Option<u32>) -> PackCompatibility {
+    let Some(expected) = expected else {
+        return PackCompatibility::Unknown;
+    };
+    let range = meta.and_then(|m| m.supported_formats.or(m.pack_format.map(|f| (f, f))));
+    match range {
+        Some((min, _)) if expected < min => PackCompatibility::TooNew,
+        Some((_, max)) if expected > max => PackCompatibility::TooOld,
+        Some(_) => PackCompatibility::Compatible,
+        None => PackCompatibility::Unknown,
+    }
+}
+
+/// Enabled pack ids as they should be written: without duplicates and with
+/// the vanilla pack first
+pub fn normalize_enabled(packs: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut result = vec![VANILLA_PACK.to_string()];
+    seen.insert(VANILLA_PACK);
+    for pack in packs {
+        if seen.insert(pack.as_str()) {
+            result.push(pack.clone());
+        }
+    }
+    result
+}
+
+/// `pack.mcmeta` and `pack.png` of a zipped pack
+fn read_zip_pack(path: &Path) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    let Some(mut archive) = std::fs::File::open(path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+    else {
+        return (None, None);
+    };
+
+    let mut read = |name: &str| {
+        let entry = archive.by_name(name).ok()?;
+        if entry.size() > MAX_FILE_SIZE {
+            return None;
+        }
+        let mut contents = Vec::new();
+        entry.take(MAX_FILE_SIZE).read_to_end(&mut contents).ok()?;
+        Some(contents)
+    };
+    let mcmeta = read("pack.mcmeta");
+    let icon = read("pack.png");
+    (mcmeta, icon)
+}
+
+/// `pack.mcmeta` and `pack.png` of a folder pack
+fn read_folder_pack(path: &Path) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    let read = |name: &str| {
+        let file = path.join(name);
+        let size = std::fs::metadata(&file).ok()?.len();
+        (size <= MAX_FILE_SIZE)
+            .then(|| std::fs::read(&file).ok())
+            .flatten()
+    };
+    (read("pack.mcmeta"), read("pack.png"))
+}
+
+/// Whether a pack file or folder works with a Minecraft version
+pub fn pack_compatibility(path: &Path, mc_version: &str) -> PackCompatibility {
+    let (mcmeta, _) = if path.is_dir() {
+        read_folder_pack(path)
+    } else {
+        read_zip_pack(path)
+    };
+    let meta = mcmeta.as_deref().and_then(parse_pack_mcmeta);
+    compatibility(meta.as_ref(), pack_format_for(mc_version))
+}
+
+/// Packs of the resource packs folder, with their metadata
+fn scan_folder(dir: &Path, expected: Option<u32>) -> Vec<ResourcePackInfo> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut packs = Vec::new();
+    for entry in entries.flatten() {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        let (mcmeta, icon) = if path.is_dir() {
+            if !path.join("pack.mcmeta").exists() {
+                continue;
+            }
+            read_folder_pack(&path)
+        } else if filename.to_lowercase().ends_with(".zip") {
+            read_zip_pack(&path)
+        } else {
+            continue;
+        };
+
+        let meta = mcmeta.as_deref().and_then(parse_pack_mcmeta);
+        packs.push(ResourcePackInfo {
+            id: format!("{}{}", FILE_PREFIX, filename),
+            filename: Some(filename),
+            enabled: false,
+            pack_format: meta.as_ref().and_then(|m| m.pack_format),
+            supported_formats: meta.as_ref().and_then(|m| m.supported_formats),
+            description: meta.as_ref().and_then(|m| m.description.clone()),
+            icon_data_url: icon.map(|png| format!("data:image/png;base64,{}", BASE64.encode(png))),
+            compatibility: compatibility(meta.as_ref(), expected),
+        });
+    }
+    packs.sort_by_key(|p| p.id.to_lowercase());
+    packs
+}
+
+/// Resource packs of a game directory: the enabled ones first, lowest priority
+/// first, then the other packs of the folder by name. Enabled packs whose file
+/// is gone are left out, the game drops them too
+pub async fn list(game_dir: &Path, mc_version: &str) -> AppResult<Vec<ResourcePackInfo>> {
+    let contents = game_options::read_options(game_dir).await?;
+    let enabled = game_options::get_option(&contents, RESOURCE_PACKS_KEY)
+        .map(game_options::parse_pack_list)
+        .unwrap_or_default();
+
+    let dir = game_dir.join(RESOURCE_PACKS_FOLDER);
+    let expected = pack_format_for(mc_version);
+    let mut available = tokio::task::spawn_blocking(move || scan_folder(&dir, expected))
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to read resource packs: {}", e)))?;
+
+    let mut packs = Vec::new();
+    for id in &enabled {
+        if let Some(pos) = available.iter().position(|p| &p.id == id) {
+            let mut pack = available.remove(pos);
+            pack.enabled = true;
+            packs.push(pack);
+        } else if !id.starts_with(FILE_PREFIX) {
+            packs.push(ResourcePackInfo {
+                id: id.clone(),
+                filename: None,
+                enabled: true,
+                pack_format: None,
+                supported_formats: None,
+                description: None,
+                icon_data_url: None,
+                compatibility: PackCompatibility::Compatible,
+            });
+        }
+    }
+    packs.extend(available);
+    Ok(packs)
+}
+
+/// Write the enabled packs of a game directory, lowest priority first.
+/// Packs made for another version are also marked as accepted, so the game
+/// keeps them enabled
+pub async fn set_enabled(
+    game_dir: &Path,
+    mc_version: &str,
+    enabled: &[String],
+) -> AppResult<Vec<ResourcePackInfo>> {
+    let packs = list(game_dir, mc_version).await?;
+    for id in enabled.iter().filter(|id| id.starts_with(FILE_PREFIX)) {
+        if !packs.iter().any(|p| &p.id == id) {
+            return Err(AppError::Instance(format!(
+                "Resource pack {} not found",
+                id.trim_start_matches(FILE_PREFIX)
+            )));
+        }
+    }
+
+    let enabled = normalize_enabled(enabled);
+    let incompatible: Vec<String> = packs
+        .iter()
+        .filter(|p| {
+            matches!(
+                p.compatibility,
+                PackCompatibility::TooOld | PackCompatibility::TooNew
+            )
+        })
+        .filter(|p| enabled.contains(&p.id))
+        .map(|p| p.id.clone())
+        .collect();
+
+    let contents = game_options::read_options(game_dir).await?;
+    let contents = game_options::set_option(
+        &contents,
+        RESOURCE_PACKS_KEY,
+        &game_options::format_pack_list(&enabled),
+    );
+    let contents = game_options::set_option(
+        &contents,
+        INCOMPATIBLE_RESOURCE_PACKS_KEY,
+        &game_options::format_pack_list(&incompatible),
+    );
+    game_options::write_options(game_dir, &contents).await?;
+
+    list(game_dir, mc_version).await
+}
+
+/// Enable a pack on top of the others, or disable it
+pub async fn set_pack_enabled(
+    game_dir: &Path,
+    mc_version: &str,
+    id: &str,
+    enable: bool,
+) -> AppResult<Vec<ResourcePackInfo>> {
+    if !enable && id == VANILLA_PACK {
+        return Err(AppError::Instance(
+            "The vanilla resource pack cannot be disabled".to_string(),
+        ));
+    }
+
+    let mut enabled: Vec<String> = list(game_dir, mc_version)
+        .await?
+        .into_iter()
+        .filter(|p| p.enabled && p.id != id)
+        .map(|p| p.id)
+        .collect();
+    if enable {
+        enabled.push(id.to_string());
+    }
+    set_enabled(game_dir, mc_version, &enabled).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_format_for() {
+        assert_eq!(pack_format_for("1.8.9"), Some(1));
+        assert_eq!(pack_format_for("1.12"), Some(3));
+        assert_eq!(pack_format_for("1.20.1"), Some(15));
+        assert_eq!(pack_format_for("1.21"), Some(34));
+        assert_eq!(pack_format_for("1.21.4"), Some(46));
+        assert_eq!(pack_format_for("24w14a"), None);
+        assert_eq!(pack_format_for("1.5.2"), None);
+        assert_eq!(pack_format_for("1.99"), None);
+    }
+
+    #[test]
+    fn test_parse_pack_mcmeta() {
+        let meta = parse_pack_mcmeta(br#"{"pack":{"pack_format":15,"description":"Faithful"}}"#);
+        assert_eq!(
+            meta,
+            Some(PackMeta {
+                pack_format: Some(15),
+                supported_formats: None,
+                description: Some("Faithful".to_string()),
+            })
+        );
+
+        let meta = parse_pack_mcmeta(
+            br#"{"pack":{"pack_format":18,"supported_formats":{"min_inclusive":15,"max_inclusive":22},
+                "description":[{"text":"Fresh ","extra":[{"text":"Animations"}]},"!"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(meta.supported_formats, Some((15, 22)));
+        assert_eq!(meta.description.as_deref(), Some("Fresh Animations!"));
+
+        let meta = parse_pack_mcmeta(br#"{"pack":{"min_format":[69,0],"max_format":75}}"#).unwrap();
+        assert_eq!(meta.pack_format, Some(75));
+        assert_eq!(meta.supported_formats, Some((69, 75)));
+
+        assert_eq!(parse_pack_mcmeta(b"{}"), None);
+        assert_eq!(parse_pack_mcmeta(b"not json"), None);
+    }
+
+    #[test]
+    fn test_compatibility() {
+        let meta = |format, supported| PackMeta {
+            pack_format: Some(format),
+            supported_formats: supported,
+            description: None,
+        };
+        let c = |m: &PackMeta, expected| compatibility(Some(m), expected);
+
+        assert_eq!(c(&meta(15, None), Some(15)), PackCompatibility::Compatible);
+        assert_eq!(c(&meta(15, None), Some(34)), PackCompatibility::TooOld);
+        assert_eq!(c(&meta(34, None), Some(15)), PackCompatibility::TooNew);
+        assert_eq!(
+            c(&meta(18, Some((15, 22))), Some(15)),
+            PackCompatibility::Compatible
+        );
+        assert_eq!(c(&meta(15, None), None), PackCompatibility::Unknown);
+        assert_eq!(compatibility(None, Some(15)), PackCompatibility::Unknown);
+    }
+
+    #[test]
+    fn test_normalize_enabled() {
+        let packs = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            normalize_enabled(&packs(&["file/a.zip", "vanilla", "file/a.zip", "fabric"])),
+            packs(&["vanilla", "file/a.zip", "fabric"])
+        );
+        assert_eq!(normalize_enabled(&[]), packs(&["vanilla"]));
+    }
+
+    #[tokio::test]
+    async fn test_set_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let packs_dir = dir.path().join(RESOURCE_PACKS_FOLDER);
+        std::fs::create_dir_all(packs_dir.join("Folder Pack")).unwrap();
+        std::fs::write(
+            packs_dir.join("Folder Pack/pack.mcmeta"),
+            br#"{"pack":{"pack_format":6,"description":"Old"}}"#,
+        )
+        .unwrap();
+        std::fs::write(packs_dir.join("notes.txt"), b"").unwrap();
+        std::fs::write(
+            dir.path().join("options.txt"),
+            "lang:en_us\nresourcePacks:[\"vanilla\",\"file/gone.zip\"]\n",
+        )
+        .unwrap();
+
+        let packs = set_pack_enabled(dir.path(), "1.20.1", "file/Folder Pack", true)
+            .await
+            .unwrap();
+        assert_eq!(packs.len(), 2);
+        assert_eq!(packs[1].id, "file/Folder Pack");
+        assert!(packs[1].enabled);
+        assert_eq!(packs[1].compatibility, PackCompatibility::TooOld);
+
+        let options = std::fs::read_to_string(dir.path().join("options.txt")).unwrap();
+        assert_eq!(
+            options,
+            "lang:en_us\nresourcePacks:[\"vanilla\",\"file/Folder Pack\"]\n\
+             incompatibleResourcePacks:[\"file/Folder Pack\"]\n"
+        );
+
+        assert!(
+            set_enabled(dir.path(), "1.20.1", &["file/missing.zip".to_string()])
+                .await
+                .is_err()
+        );
+        assert!(set_pack_enabled(dir.path(), "1.20.1", "vanilla", false)
+            .await
+            .is_err());
+    }
+}
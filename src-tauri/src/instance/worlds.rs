@@ -778,7 +778,7 @@ pub async fn duplicate_world(
 }
 
 /// Recursively copy a directory (skips symlinks to avoid loops)
-async fn copy_directory(src: &Path, dst: &Path) -> AppResult<()> {
+pub async fn copy_directory(src: &Path, dst: &Path) -> AppResult<()> {
     fs::create_dir_all(dst)
         .await
         .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
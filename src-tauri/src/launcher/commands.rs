@@ -1,10 +1,15 @@
 use crate::auth::{microsoft, minecraft, xbox};
 use crate::crypto;
 use crate::db::accounts::Account;
+use crate::db::command_history::CommandHistoryEntry;
 use crate::db::instances::Instance;
+use crate::db::server_eula::{EulaAcceptance, MINECRAFT_EULA_URL};
 use crate::error::{AppError, AppResult};
+use crate::instance::commands::{self as instance_commands, get_content_folder};
+use crate::instance::datapacks;
+use crate::instance::mod_analyzer;
 use crate::launcher::runner::LaunchProgressEvent;
-use crate::launcher::{java, runner};
+use crate::launcher::{console, java, runner};
 use crate::minecraft::{installer, versions};
 use crate::modloader::{self, paper, LoaderType};
 use crate::state::SharedState;
@@ -30,6 +35,27 @@ fn get_loader_version<'a>(instance: &'a Instance, loader_name: &str) -> AppResul
         .ok_or_else(|| AppError::Instance(format!("{} requires a loader version", loader_name)))
 }
 
+/// Whether a loader is a proxy (Velocity, BungeeCord, Waterfall)
+fn is_proxy_loader(loader: Option<&str>) -> bool {
+    matches!(
+        loader.map(|l| l.to_lowercase()).as_deref(),
+        Some("velocity") | Some("bungeecord") | Some("waterfall")
+    )
+}
+
+/// Write eula.txt in the format the Minecraft server expects
+async fn write_eula_file(instance_dir: &Path, accepted: bool) -> AppResult<()> {
+    let content = format!(
+        "#By changing the setting below to TRUE you are indicating your agreement to our EULA ({}).\n#Written by Kaizen Launcher on {}\neula={}\n",
+        MINECRAFT_EULA_URL,
+        Utc::now().to_rfc3339(),
+        accepted
+    );
+    fs::write(instance_dir.join("eula.txt"), content)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write eula.txt: {}", e)))
+}
+
 /// Internal function to refresh token without needing Tauri State
 /// This is used during game launch to automatically refresh expired tokens
 async fn refresh_token_internal(
@@ -130,7 +156,14 @@ pub async fn install_instance(
     // (instance.is_server is set when creating the instance in the UI)
     if instance.is_server {
         // Install server (Vanilla, Paper, Fabric, Forge, NeoForge, Velocity, BungeeCord, Waterfall)
-        install_server_instance(&state_guard.http_client, &instance_dir, &instance, &app).await?;
+        install_server_instance(
+            &state_guard.http_client,
+            &state_guard.db,
+            &instance_dir,
+            &instance,
+            &app,
+        )
+        .await?;
     } else {
         // Install client (Vanilla, Fabric, Forge, NeoForge, Quilt)
         install_client_instance(&state_guard, &instance_dir, &instance, &app).await?;
@@ -246,6 +279,7 @@ async fn install_client_instance(
 /// Install a server instance (Vanilla, Paper, Fabric, Forge, NeoForge, Velocity, BungeeCord, Waterfall)
 async fn install_server_instance(
     client: &reqwest::Client,
+    db: &SqlitePool,
     instance_dir: &std::path::Path,
     instance: &Instance,
     app: &tauri::AppHandle,
@@ -383,11 +417,11 @@ async fn install_server_instance(
         }
     }
 
-    // Create eula.txt (accepted)
-    let eula_path = instance_dir.join("eula.txt");
-    fs::write(&eula_path, "eula=true\n")
-        .await
-        .map_err(|e| AppError::Io(format!("Failed to write eula.txt: {}", e)))?;
+    // Only write an accepted eula.txt once the user has accepted the EULA (proxies have none)
+    if !is_proxy_loader(Some(loader_str)) {
+        let eula_accepted = EulaAcceptance::is_accepted(db, &instance.id).await?;
+        write_eula_file(instance_dir, eula_accepted).await?;
+    }
 
     // Create server.properties with default values (only for non-proxy servers)
     if !matches!(loader_str, "velocity" | "bungeecord" | "waterfall") {
@@ -1596,6 +1630,7 @@ pub async fn launch_instance(
     app: tauri::AppHandle,
     instance_id: String,
     account_id: String,
+    mod_profile: Option<String>,
 ) -> AppResult<()> {
     let instance_id_clone = instance_id.clone();
     let total_steps: u8 = 4;
@@ -1683,6 +1718,38 @@ pub async fn launch_instance(
         ));
     }
 
+    // Switch to the mod profile picked at launch, before the mods are analyzed
+    if let Some(profile) = mod_profile.as_deref().filter(|p| !p.is_empty()) {
+        instance_commands::switch_mod_profile_for(&state_guard, &instance, profile).await?;
+    }
+
+    // Worlds created since the last launch receive the global datapacks
+    if let Err(e) = datapacks::sync_global_datapacks(&instance_dir, instance.is_server).await {
+        tracing::warn!("Failed to copy global datapacks: {}", e);
+    }
+
+    // Refuse to start with mod problems that would make the loader crash, when enabled
+    if instance.mc_version != "proxy" && mod_analyzer::block_launch_enabled(&state_guard.db).await {
+        let content_dir = instance_dir.join(get_content_folder(
+            instance.loader.as_deref(),
+            instance.is_server,
+        ));
+        let issues =
+            mod_analyzer::analyze_instance(&state_guard.data_dir, &content_dir, &instance).await?;
+        let fatal: Vec<&str> = issues
+            .iter()
+            .filter(|issue| mod_analyzer::is_fatal(issue))
+            .map(|issue| issue.description.as_str())
+            .collect();
+        if !fatal.is_empty() {
+            return Err(AppError::Launcher(format!(
+                "Launch blocked by {} mod issue(s):\n{}",
+                fatal.len(),
+                fatal.join("\n")
+            )));
+        }
+    }
+
     // Get running instances tracker
     let running_instances = state_guard.running_instances.clone();
 
@@ -1691,6 +1758,16 @@ pub async fn launch_instance(
 
     // Check if this is a server/proxy instance using instance flag
     if instance.is_server {
+        // Proxies don't have an EULA, game servers refuse to start without it
+        if !is_proxy_loader(instance.loader.as_deref())
+            && !EulaAcceptance::is_accepted(&state_guard.db, &instance_id).await?
+        {
+            return Err(AppError::EulaRequired);
+        }
+
+        // Plugins may change between runs, rediscover commands for completions
+        console::clear_plugin_commands(&instance_id);
+
         // Step 2: Checking Java for server
         emit_progress("checking_java", 2);
 
@@ -2131,6 +2208,15 @@ pub async fn send_server_command(
             .flush()
             .await
             .map_err(|e| AppError::Io(format!("Failed to flush command: {}", e)))?;
+
+        let command = command.trim();
+        if !command.is_empty() {
+            if let Err(e) =
+                CommandHistoryEntry::record(&state_guard.db, &instance_id, command).await
+            {
+                tracing::warn!("[CONSOLE] Failed to save command history: {}", e);
+            }
+        }
         Ok(())
     } else {
         Err(AppError::Instance(
@@ -2139,6 +2225,108 @@ pub async fn send_server_command(
     }
 }
 
+/// Get the console command history of a server, most recent first
+#[tauri::command]
+pub async fn get_server_command_history(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    query: Option<String>,
+    limit: Option<i64>,
+) -> AppResult<Vec<CommandHistoryEntry>> {
+    let state_guard = state.read().await;
+
+    CommandHistoryEntry::search(
+        &state_guard.db,
+        &instance_id,
+        query.as_deref().filter(|q| !q.trim().is_empty()),
+        limit.unwrap_or(100),
+    )
+    .await
+    .map_err(AppError::from)
+}
+
+/// Delete the console command history of a server
+#[tauri::command]
+pub async fn clear_server_command_history(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+
+    CommandHistoryEntry::clear(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Suggest completions for a partially typed console command
+///
+/// Suggestions come from the command history, the vanilla command catalog of the
+/// server version, players online according to the log, and the commands the
+/// server reports through RCON when it is enabled.
+#[tauri::command]
+pub async fn get_server_command_completions(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    input: String,
+) -> AppResult<Vec<console::CommandSuggestion>> {
+    const MAX_HISTORY_SUGGESTIONS: i64 = 5;
+    const MAX_SUGGESTIONS: usize = 50;
+
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+    let is_running = state_guard
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance_id);
+
+    let mut suggestions: Vec<console::CommandSuggestion> = CommandHistoryEntry::with_prefix(
+        &state_guard.db,
+        &instance_id,
+        input.trim_start_matches('/'),
+        MAX_HISTORY_SUGGESTIONS,
+    )
+    .await
+    .map_err(AppError::from)?
+    .into_iter()
+    .filter(|entry| entry.command != input)
+    .map(|entry| console::CommandSuggestion {
+        text: entry.command.clone(),
+        label: entry.command,
+        kind: console::SuggestionKind::History,
+    })
+    .collect();
+
+    let (players, plugin_commands) = if is_running {
+        (
+            console::online_players(&instance_dir).await,
+            console::plugin_commands(&instance_id, &instance_dir).await,
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    let context = console::CompletionContext {
+        mc_version: &instance.mc_version,
+        is_proxy: instance.is_proxy,
+        players: &players,
+        plugin_commands: &plugin_commands,
+    };
+    suggestions.extend(console::complete(&input, &context));
+    suggestions.truncate(MAX_SUGGESTIONS);
+
+    Ok(suggestions)
+}
+
 /// Batch check which instances are running (returns list of running instance IDs)
 #[tauri::command]
 pub async fn get_running_instances(state: State<'_, SharedState>) -> AppResult<Vec<String>> {
@@ -2167,3 +2355,133 @@ pub async fn check_instances_installed(
 
     Ok(result)
 }
+
+/// EULA status of a server instance
+#[derive(serde::Serialize)]
+pub struct ServerEulaStatus {
+    pub required: bool,
+    pub accepted: bool,
+    pub accepted_at: Option<String>,
+    pub eula_url: String,
+}
+
+/// Get whether the Minecraft EULA has been accepted for a server instance
+#[tauri::command]
+pub async fn get_server_eula_status(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<ServerEulaStatus> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let acceptance = EulaAcceptance::get(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?;
+
+    Ok(ServerEulaStatus {
+        required: instance.is_server && !is_proxy_loader(instance.loader.as_deref()),
+        accepted: acceptance.is_some(),
+        accepted_at: acceptance.map(|a| a.accepted_at),
+        eula_url: MINECRAFT_EULA_URL.to_string(),
+    })
+}
+
+/// Record the user's acceptance of the Minecraft EULA and write eula.txt
+#[tauri::command]
+pub async fn accept_server_eula(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<EulaAcceptance> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    if !instance.is_server {
+        return Err(AppError::Instance(
+            "The EULA only applies to server instances".to_string(),
+        ));
+    }
+
+    let acceptance = EulaAcceptance::record(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?;
+
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+    if instance_dir.exists() {
+        write_eula_file(&instance_dir, true).await?;
+    }
+
+    tracing::info!(
+        "[EULA] Accepted for server {} at {}",
+        instance.name,
+        acceptance.accepted_at
+    );
+
+    Ok(acceptance)
+}
+
+/// Run a headless first boot that stops as soon as the server is done loading,
+/// so server.properties and plugin/mod configs exist before the first real start
+#[tauri::command]
+pub async fn bootstrap_server_configs(
+    state: State<'_, SharedState>,
+    app: tauri::AppHandle,
+    instance_id: String,
+) -> AppResult<runner::ServerBootstrapResult> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    if !instance.is_server {
+        return Err(AppError::Instance(
+            "Only server instances can be bootstrapped".to_string(),
+        ));
+    }
+
+    if !is_proxy_loader(instance.loader.as_deref())
+        && !EulaAcceptance::is_accepted(&state_guard.db, &instance_id).await?
+    {
+        return Err(AppError::Instance(
+            "The Minecraft EULA must be accepted before starting this server".to_string(),
+        ));
+    }
+
+    {
+        let running = state_guard.running_instances.read().await;
+        if running.contains_key(&instance_id) {
+            return Err(AppError::Instance(
+                "Cette instance est deja en cours d'execution.".to_string(),
+            ));
+        }
+    }
+
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+
+    if !installer::is_instance_installed(&instance_dir).await {
+        return Err(AppError::Instance(
+            "Instance is not installed. Please install first.".to_string(),
+        ));
+    }
+
+    let data_dir = state_guard.data_dir.clone();
+    let running_instances = state_guard.running_instances.clone();
+    drop(state_guard);
+
+    runner::bootstrap_server(&instance_dir, &data_dir, &instance, &app, running_instances).await
+}
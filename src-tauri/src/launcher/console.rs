@@ -0,0 +1,639 @@
+// Server console completions: vanilla command catalog, online players and plugin commands
+
+use crate::discord::hooks::parse_player_event;
+use crate::launcher::rcon::{RconClient, RconSettings};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long commands discovered through RCON are reused before asking the server again
+const PLUGIN_COMMANDS_TTL: Duration = Duration::from_secs(300);
+
+/// Maximum number of Bukkit help pages fetched
+const MAX_HELP_PAGES: u32 = 20;
+
+/// Commands discovered per instance, with the time they were fetched
+static PLUGIN_COMMANDS_CACHE: Lazy<Mutex<HashMap<String, (Instant, Vec<String>)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Kind of value expected by a command argument
+#[derive(Debug, Clone, Copy)]
+enum Arg {
+    Player,
+    Choice(&'static [&'static str]),
+    Any,
+}
+
+/// A vanilla command and the versions it exists in
+struct VanillaCommand {
+    name: &'static str,
+    /// First release with the command (`None` = always available)
+    since: Option<(u32, u32, u32)>,
+    /// First release without the command
+    until: Option<(u32, u32, u32)>,
+    args: &'static [Arg],
+}
+
+const GAMEMODES: &[&str] = &["survival", "creative", "adventure", "spectator"];
+const DIFFICULTIES: &[&str] = &["peaceful", "easy", "normal", "hard"];
+
+macro_rules! cmd {
+    ($name:expr, $args:expr) => {
+        cmd!($name, None, None, $args)
+    };
+    ($name:expr, $since:expr, $until:expr, $args:expr) => {
+        VanillaCommand {
+            name: $name,
+            since: $since,
+            until: $until,
+            args: $args,
+        }
+    };
+}
+
+/// Console commands of the vanilla dedicated server
+const VANILLA_COMMANDS: &[VanillaCommand] = &[
+    cmd!(
+        "achievement",
+        None,
+        Some((1, 12, 0)),
+        &[Arg::Choice(&["give", "take"])]
+    ),
+    cmd!(
+        "advancement",
+        Some((1, 12, 0)),
+        None,
+        &[Arg::Choice(&["grant", "revoke"]), Arg::Player]
+    ),
+    cmd!("attribute", Some((1, 16, 0)), None, &[Arg::Player]),
+    cmd!("ban", &[Arg::Player]),
+    cmd!("ban-ip", &[Arg::Player]),
+    cmd!("banlist", &[Arg::Choice(&["ips", "players"])]),
+    cmd!(
+        "bossbar",
+        Some((1, 13, 0)),
+        None,
+        &[Arg::Choice(&["add", "get", "list", "remove", "set"])]
+    ),
+    cmd!("clear", &[Arg::Player]),
+    cmd!("clone", Some((1, 8, 0)), None, &[]),
+    cmd!("damage", Some((1, 19, 4)), None, &[Arg::Player]),
+    cmd!(
+        "data",
+        Some((1, 13, 0)),
+        None,
+        &[Arg::Choice(&["get", "merge", "modify", "remove"])]
+    ),
+    cmd!(
+        "datapack",
+        Some((1, 13, 0)),
+        None,
+        &[Arg::Choice(&["disable", "enable", "list"])]
+    ),
+    cmd!("debug", &[Arg::Choice(&["start", "stop", "function"])]),
+    cmd!("defaultgamemode", &[Arg::Choice(GAMEMODES)]),
+    cmd!("deop", &[Arg::Player]),
+    cmd!("difficulty", &[Arg::Choice(DIFFICULTIES)]),
+    cmd!("effect", &[Arg::Choice(&["give", "clear"]), Arg::Player]),
+    cmd!("enchant", &[Arg::Player]),
+    cmd!(
+        "execute",
+        Some((1, 8, 0)),
+        None,
+        &[Arg::Choice(&[
+            "align",
+            "anchored",
+            "as",
+            "at",
+            "facing",
+            "if",
+            "in",
+            "positioned",
+            "rotated",
+            "run",
+            "store",
+            "unless"
+        ])]
+    ),
+    cmd!(
+        "experience",
+        Some((1, 13, 0)),
+        None,
+        &[Arg::Choice(&["add", "query", "set"]), Arg::Player]
+    ),
+    cmd!("fill", Some((1, 8, 0)), None, &[]),
+    cmd!("fillbiome", Some((1, 19, 3)), None, &[]),
+    cmd!(
+        "forceload",
+        Some((1, 14, 4)),
+        None,
+        &[Arg::Choice(&["add", "query", "remove"])]
+    ),
+    cmd!("function", Some((1, 12, 0)), None, &[]),
+    cmd!("gamemode", &[Arg::Choice(GAMEMODES), Arg::Player]),
+    cmd!("gamerule", &[]),
+    cmd!("give", &[Arg::Player]),
+    cmd!("help", &[]),
+    cmd!(
+        "item",
+        Some((1, 17, 0)),
+        None,
+        &[Arg::Choice(&["modify", "replace"])]
+    ),
+    cmd!(
+        "jfr",
+        Some((1, 18, 0)),
+        None,
+        &[Arg::Choice(&["start", "stop"])]
+    ),
+    cmd!("kick", &[Arg::Player]),
+    cmd!("kill", &[Arg::Player]),
+    cmd!("list", &[Arg::Choice(&["uuids"])]),
+    cmd!("locate", Some((1, 11, 0)), None, &[]),
+    cmd!(
+        "loot",
+        Some((1, 14, 0)),
+        None,
+        &[Arg::Choice(&["give", "insert", "replace", "spawn"])]
+    ),
+    cmd!("me", &[]),
+    cmd!("msg", &[Arg::Player]),
+    cmd!("op", &[Arg::Player]),
+    cmd!("pardon", &[]),
+    cmd!("pardon-ip", &[]),
+    cmd!("particle", &[]),
+    cmd!(
+        "perf",
+        Some((1, 17, 0)),
+        None,
+        &[Arg::Choice(&["start", "stop"])]
+    ),
+    cmd!(
+        "place",
+        Some((1, 19, 0)),
+        None,
+        &[Arg::Choice(&["feature", "jigsaw", "structure", "template"])]
+    ),
+    cmd!("playsound", &[]),
+    cmd!(
+        "random",
+        Some((1, 20, 2)),
+        None,
+        &[Arg::Choice(&["roll", "value", "reset"])]
+    ),
+    cmd!(
+        "recipe",
+        Some((1, 12, 0)),
+        None,
+        &[Arg::Choice(&["give", "take"]), Arg::Player]
+    ),
+    cmd!("reload", &[]),
+    cmd!(
+        "replaceitem",
+        None,
+        Some((1, 17, 0)),
+        &[Arg::Choice(&["block", "entity"])]
+    ),
+    cmd!("return", Some((1, 20, 2)), None, &[]),
+    cmd!(
+        "ride",
+        Some((1, 19, 4)),
+        None,
+        &[Arg::Player, Arg::Choice(&["mount", "dismount"])]
+    ),
+    cmd!("save-all", &[Arg::Choice(&["flush"])]),
+    cmd!("save-off", &[]),
+    cmd!("save-on", &[]),
+    cmd!("say", &[]),
+    cmd!(
+        "schedule",
+        Some((1, 14, 0)),
+        None,
+        &[Arg::Choice(&["function", "clear"])]
+    ),
+    cmd!("scoreboard", &[Arg::Choice(&["objectives", "players"])]),
+    cmd!("seed", &[]),
+    cmd!("setblock", &[]),
+    cmd!("setidletimeout", &[]),
+    cmd!("setworldspawn", &[]),
+    cmd!("spawnpoint", &[Arg::Player]),
+    cmd!("spectate", Some((1, 15, 0)), None, &[Arg::Any, Arg::Player]),
+    cmd!("spreadplayers", &[]),
+    cmd!("stop", &[]),
+    cmd!("stopsound", Some((1, 9, 0)), None, &[Arg::Player]),
+    cmd!("summon", &[]),
+    cmd!(
+        "tag",
+        Some((1, 13, 0)),
+        None,
+        &[Arg::Player, Arg::Choice(&["add", "list", "remove"])]
+    ),
+    cmd!(
+        "team",
+        Some((1, 13, 0)),
+        None,
+        &[Arg::Choice(&[
+            "add", "empty", "join", "leave", "list", "modify", "remove"
+        ])]
+    ),
+    cmd!("teammsg", Some((1, 13, 0)), None, &[]),
+    cmd!("teleport", &[Arg::Player, Arg::Player]),
+    cmd!("tell", &[Arg::Player]),
+    cmd!("tellraw", &[Arg::Player]),
+    cmd!("testfor", None, Some((1, 13, 0)), &[Arg::Player]),
+    cmd!(
+        "tick",
+        Some((1, 20, 3)),
+        None,
+        &[Arg::Choice(&[
+            "freeze", "query", "rate", "sprint", "step", "unfreeze"
+        ])]
+    ),
+    cmd!("time", &[Arg::Choice(&["add", "query", "set"])]),
+    cmd!(
+        "title",
+        &[
+            Arg::Player,
+            Arg::Choice(&["actionbar", "clear", "reset", "subtitle", "times", "title"])
+        ]
+    ),
+    cmd!("tm", Some((1, 13, 0)), None, &[]),
+    cmd!("toggledownfall", None, Some((1, 13, 0)), &[]),
+    cmd!("tp", &[Arg::Player, Arg::Player]),
+    cmd!("transfer", Some((1, 20, 5)), None, &[]),
+    cmd!("trigger", Some((1, 13, 0)), None, &[]),
+    cmd!("w", &[Arg::Player]),
+    cmd!("weather", &[Arg::Choice(&["clear", "rain", "thunder"])]),
+    cmd!(
+        "whitelist",
+        &[
+            Arg::Choice(&["add", "list", "off", "on", "reload", "remove"]),
+            Arg::Player
+        ]
+    ),
+    cmd!(
+        "worldborder",
+        &[Arg::Choice(&[
+            "add", "center", "damage", "get", "set", "warning"
+        ])]
+    ),
+    cmd!("xp", &[Arg::Player]),
+];
+
+/// Where a completion suggestion comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    History,
+    Command,
+    PluginCommand,
+    Player,
+    Argument,
+}
+
+/// A completion for the console input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandSuggestion {
+    /// Full console input after accepting the suggestion
+    pub text: String,
+    /// The completed word (or full command for history entries)
+    pub label: String,
+    pub kind: SuggestionKind,
+}
+
+/// Parse a release version ("1.20.4") into comparable parts
+///
+/// Snapshots and other non-release ids return `None` and are treated as the newest version.
+fn parse_release(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(p) => p.parse().ok()?,
+        None => 0,
+    };
+    Some((major, minor, patch))
+}
+
+fn is_available(command: &VanillaCommand, version: Option<(u32, u32, u32)>) -> bool {
+    match version {
+        Some(v) => {
+            command.since.is_none_or(|since| v >= since)
+                && command.until.is_none_or(|until| v < until)
+        }
+        None => command.until.is_none(),
+    }
+}
+
+/// Vanilla console command names for a Minecraft version
+pub fn vanilla_commands(mc_version: &str) -> Vec<&'static str> {
+    let version = parse_release(mc_version);
+    VANILLA_COMMANDS
+        .iter()
+        .filter(|c| is_available(c, version))
+        .map(|c| c.name)
+        .collect()
+}
+
+/// Players currently online according to a server log (join/leave events)
+pub fn online_players_from_log(log: &str) -> Vec<String> {
+    let mut players = BTreeSet::new();
+    for line in log.lines() {
+        match parse_player_event(line) {
+            Some(("join", player)) => {
+                players.insert(player);
+            }
+            Some(("leave", player)) => {
+                players.remove(&player);
+            }
+            _ => {}
+        }
+    }
+    players.into_iter().collect()
+}
+
+/// Read the online players from the current session's log file
+pub async fn online_players(instance_dir: &Path) -> Vec<String> {
+    match tokio::fs::read_to_string(instance_dir.join("logs").join("latest.log")).await {
+        Ok(log) => online_players_from_log(&log),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Remove Minecraft formatting codes (§x)
+fn strip_formatting(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Leading command name of a help entry ("ban" in "ban <targets>")
+fn leading_command_name(entry: &str) -> &str {
+    let end = entry
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '.')))
+        .unwrap_or(entry.len());
+    &entry[..end]
+}
+
+/// Extract command names from the output of `help`
+///
+/// Handles the Bukkit format ("/ban: Bans a player", one per line) and the
+/// vanilla format, where RCON joins the usage lines without separators
+/// ("/ban <targets>/ban-ip <target>").
+pub fn parse_help_commands(output: &str) -> Vec<String> {
+    let output = strip_formatting(output);
+    let mut commands = BTreeSet::new();
+
+    for line in output.lines() {
+        let Some(rest) = line.trim().strip_prefix('/') else {
+            continue;
+        };
+
+        let first = leading_command_name(rest);
+        let entries: Vec<&str> = if first.ends_with(':') {
+            // Bukkit: the description may contain slashes, only the name counts
+            vec![first.trim_end_matches(':')]
+        } else {
+            rest.split('/').map(leading_command_name).collect()
+        };
+
+        for name in entries {
+            if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                commands.insert(name.to_lowercase());
+            }
+        }
+    }
+
+    commands.into_iter().collect()
+}
+
+/// Number of pages announced by a Bukkit help header ("Help: Index (1/5)")
+pub fn parse_help_page_count(output: &str) -> Option<u32> {
+    let output = strip_formatting(output);
+    let start = output.find("Index (")? + "Index (".len();
+    let rest = &output[start..];
+    let end = rest.find(')')?;
+    let (_, total) = rest[..end].split_once('/')?;
+    total.trim().parse().ok()
+}
+
+/// Ask the running server for its commands through RCON (`help`)
+async fn fetch_help_commands(settings: &RconSettings) -> crate::error::AppResult<Vec<String>> {
+    let mut client = RconClient::connect(settings).await?;
+    let first_page = client.command("help").await?;
+
+    let mut commands: BTreeSet<String> = parse_help_commands(&first_page).into_iter().collect();
+    let pages = parse_help_page_count(&first_page)
+        .unwrap_or(1)
+        .min(MAX_HELP_PAGES);
+    for page in 2..=pages {
+        let output = client.command(&format!("help {}", page)).await?;
+        commands.extend(parse_help_commands(&output));
+    }
+
+    Ok(commands.into_iter().collect())
+}
+
+/// Commands registered on a running server (vanilla and plugins), when RCON is enabled
+pub async fn plugin_commands(instance_id: &str, instance_dir: &Path) -> Vec<String> {
+    if let Ok(cache) = PLUGIN_COMMANDS_CACHE.lock() {
+        if let Some((fetched_at, commands)) = cache.get(instance_id) {
+            if fetched_at.elapsed() < PLUGIN_COMMANDS_TTL {
+                return commands.clone();
+            }
+        }
+    }
+
+    let Some(settings) = RconSettings::for_instance(instance_dir).await else {
+        return Vec::new();
+    };
+
+    match fetch_help_commands(&settings).await {
+        Ok(commands) => {
+            if let Ok(mut cache) = PLUGIN_COMMANDS_CACHE.lock() {
+                cache.insert(instance_id.to_string(), (Instant::now(), commands.clone()));
+            }
+            commands
+        }
+        Err(e) => {
+            tracing::debug!("[CONSOLE] RCON command discovery failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Forget the commands discovered for an instance (e.g. after a restart)
+pub fn clear_plugin_commands(instance_id: &str) {
+    if let Ok(mut cache) = PLUGIN_COMMANDS_CACHE.lock() {
+        cache.remove(instance_id);
+    }
+}
+
+/// Completion sources for one instance
+pub struct CompletionContext<'a> {
+    pub mc_version: &'a str,
+    pub is_proxy: bool,
+    pub players: &'a [String],
+    pub plugin_commands: &'a [String],
+}
+
+/// Complete the current word of a console input
+///
+/// Only the last word is completed, every suggestion carries the full new input.
+pub fn complete(input: &str, context: &CompletionContext<'_>) -> Vec<CommandSuggestion> {
+    let slash = if input.starts_with('/') { "/" } else { "" };
+    let line = &input[slash.len()..];
+
+    let words: Vec<&str> = line.split(' ').collect();
+    let current = words.last().copied().unwrap_or("");
+    let current_lower = current.to_lowercase();
+    let prefix = &input[..input.len() - current.len()];
+
+    let suggest = |label: &str, kind: SuggestionKind| CommandSuggestion {
+        text: format!("{}{}", prefix, label),
+        label: label.to_string(),
+        kind,
+    };
+
+    let mut suggestions = Vec::new();
+
+    if words.len() <= 1 {
+        let mut seen = BTreeSet::new();
+        let vanilla = if context.is_proxy {
+            Vec::new()
+        } else {
+            vanilla_commands(context.mc_version)
+        };
+
+        for name in vanilla {
+            if name.starts_with(&current_lower) && seen.insert(name.to_string()) {
+                suggestions.push(suggest(name, SuggestionKind::Command));
+            }
+        }
+        for name in context.plugin_commands {
+            if name.starts_with(&current_lower) && seen.insert(name.clone()) {
+                suggestions.push(suggest(name, SuggestionKind::PluginCommand));
+            }
+        }
+        return suggestions;
+    }
+
+    let command_name = words[0].to_lowercase();
+    let arg_index = words.len() - 2;
+    let arg = match VANILLA_COMMANDS.iter().find(|c| c.name == command_name) {
+        Some(command) => command.args.get(arg_index).copied().unwrap_or(Arg::Any),
+        // Plugin commands most often take a player as first argument
+        None if arg_index == 0 => Arg::Player,
+        None => Arg::Any,
+    };
+
+    match arg {
+        Arg::Player => {
+            for player in context.players {
+                if player.to_lowercase().starts_with(&current_lower) {
+                    suggestions.push(suggest(player, SuggestionKind::Player));
+                }
+            }
+        }
+        Arg::Choice(choices) => {
+            for choice in choices {
+                if choice.starts_with(&current_lower) {
+                    suggestions.push(suggest(choice, SuggestionKind::Argument));
+                }
+            }
+        }
+        Arg::Any => {}
+    }
+
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context<'a>(players: &'a [String], plugin_commands: &'a [String]) -> CompletionContext<'a> {
+        CompletionContext {
+            mc_version: "1.20.4",
+            is_proxy: false,
+            players,
+            plugin_commands,
+        }
+    }
+
+    #[test]
+    fn test_vanilla_commands_by_version() {
+        let old = vanilla_commands("1.12.2");
+        assert!(old.contains(&"toggledownfall"));
+        assert!(old.contains(&"advancement"));
+        assert!(!old.contains(&"datapack"));
+
+        let new = vanilla_commands("1.20.4");
+        assert!(new.contains(&"tick"));
+        assert!(!new.contains(&"transfer"));
+        assert!(!new.contains(&"toggledownfall"));
+
+        // Snapshots get the newest catalog
+        assert!(vanilla_commands("24w14a").contains(&"transfer"));
+    }
+
+    #[test]
+    fn test_complete_command_name() {
+        let plugin_commands = vec!["essentials:warp".to_string(), "warp".to_string()];
+        let suggestions = complete("wa", &context(&[], &plugin_commands));
+        let labels: Vec<&str> = suggestions.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(labels, vec!["warp"]);
+        assert_eq!(suggestions[0].kind, SuggestionKind::PluginCommand);
+
+        let suggestions = complete("/gamem", &context(&[], &[]));
+        assert_eq!(suggestions[0].text, "/gamemode");
+    }
+
+    #[test]
+    fn test_complete_arguments() {
+        let players = vec!["Notch".to_string(), "jeb_".to_string()];
+
+        let suggestions = complete("gamemode cr", &context(&players, &[]));
+        assert_eq!(suggestions[0].text, "gamemode creative");
+
+        let suggestions = complete("gamemode creative n", &context(&players, &[]));
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].text, "gamemode creative Notch");
+        assert_eq!(suggestions[0].kind, SuggestionKind::Player);
+
+        let suggestions = complete("kick ", &context(&players, &[]));
+        assert_eq!(suggestions.len(), 2);
+    }
+
+    #[test]
+    fn test_online_players_from_log() {
+        let log = "[12:00:00] [Server thread/INFO]: Notch joined the game\n\
+                   [12:00:05] [Server thread/INFO]: jeb_ joined the game\n\
+                   [12:01:00] [Server thread/INFO]: Notch left the game\n";
+        assert_eq!(online_players_from_log(log), vec!["jeb_".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_help_commands() {
+        let vanilla = "/advancement (grant|revoke)/ban <targets> [<reason>]/save-off/seed";
+        assert_eq!(
+            parse_help_commands(vanilla),
+            vec!["advancement", "ban", "save-off", "seed"]
+        );
+
+        let bukkit = "§e--------- §fHelp: Index (1/3) §e---------\n§6/ban: §fBans a player\n§6/warp: §fTeleport to a warp";
+        let commands = parse_help_commands(bukkit);
+        assert_eq!(commands, vec!["ban".to_string(), "warp".to_string()]);
+        assert_eq!(parse_help_page_count(bukkit), Some(3));
+    }
+}
@@ -1,3 +1,5 @@
 pub mod commands;
+pub mod console;
 pub mod java;
+pub mod rcon;
 pub mod runner;
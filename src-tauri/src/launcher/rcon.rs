@@ -0,0 +1,181 @@
+// Minimal Source RCON client used to query a running server
+// Protocol: https://minecraft.wiki/w/RCON
+
+use crate::error::{AppError, AppResult};
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const PACKET_AUTH: i32 = 3;
+const PACKET_COMMAND: i32 = 2;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time to wait for further fragments of a response split over several packets
+const FRAGMENT_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Largest packet the server may send (4096 byte payload + header)
+const MAX_PACKET_SIZE: i32 = 4110;
+
+/// RCON settings read from server.properties
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RconSettings {
+    pub port: u16,
+    pub password: String,
+}
+
+impl RconSettings {
+    /// Parse server.properties, returning `None` when RCON is disabled or has no password
+    pub fn from_properties(content: &str) -> Option<Self> {
+        let mut enabled = false;
+        let mut port = 25575;
+        let mut password = String::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key.trim() {
+                "enable-rcon" => enabled = value.trim() == "true",
+                "rcon.port" => port = value.trim().parse().unwrap_or(port),
+                "rcon.password" => password = value.trim().to_string(),
+                _ => {}
+            }
+        }
+
+        (enabled && !password.is_empty()).then_some(Self { port, password })
+    }
+
+    /// Read the RCON settings of a server instance
+    pub async fn for_instance(instance_dir: &Path) -> Option<Self> {
+        let content = tokio::fs::read_to_string(instance_dir.join("server.properties"))
+            .await
+            .ok()?;
+        Self::from_properties(&content)
+    }
+}
+
+/// An authenticated RCON connection
+pub struct RconClient {
+    stream: TcpStream,
+    next_id: i32,
+}
+
+impl RconClient {
+    /// Connect to a local server and authenticate
+    pub async fn connect(settings: &RconSettings) -> AppResult<Self> {
+        let stream = tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            TcpStream::connect(("127.0.0.1", settings.port)),
+        )
+        .await
+        .map_err(|_| AppError::Network("RCON connection timed out".to_string()))?
+        .map_err(|e| AppError::Network(format!("RCON connection failed: {}", e)))?;
+
+        let mut client = Self { stream, next_id: 1 };
+
+        let id = client.send_packet(PACKET_AUTH, &settings.password).await?;
+        let (response_id, _) = client.read_packet(RESPONSE_TIMEOUT).await?;
+        if response_id == -1 || response_id != id {
+            return Err(AppError::Auth("RCON authentication failed".to_string()));
+        }
+
+        Ok(client)
+    }
+
+    /// Run a command and return its output
+    pub async fn command(&mut self, command: &str) -> AppResult<String> {
+        let id = self.send_packet(PACKET_COMMAND, command).await?;
+
+        let mut output = String::new();
+        let mut timeout = RESPONSE_TIMEOUT;
+        // Long outputs are split over several packets without an end marker
+        while let Ok((response_id, body)) = self.read_packet(timeout).await {
+            if response_id == id {
+                output.push_str(&body);
+            }
+            timeout = FRAGMENT_TIMEOUT;
+        }
+
+        Ok(output)
+    }
+
+    async fn send_packet(&mut self, packet_type: i32, body: &str) -> AppResult<i32> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let payload = body.as_bytes();
+        let length = (4 + 4 + payload.len() + 2) as i32;
+
+        let mut packet = Vec::with_capacity(length as usize + 4);
+        packet.extend_from_slice(&length.to_le_bytes());
+        packet.extend_from_slice(&id.to_le_bytes());
+        packet.extend_from_slice(&packet_type.to_le_bytes());
+        packet.extend_from_slice(payload);
+        packet.extend_from_slice(&[0, 0]);
+
+        self.stream
+            .write_all(&packet)
+            .await
+            .map_err(|e| AppError::Network(format!("RCON write failed: {}", e)))?;
+
+        Ok(id)
+    }
+
+    async fn read_packet(&mut self, timeout: Duration) -> AppResult<(i32, String)> {
+        tokio::time::timeout(timeout, async {
+            let length = self.stream.read_i32_le().await?;
+            if !(10..=MAX_PACKET_SIZE).contains(&length) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("invalid packet length {}", length),
+                ));
+            }
+
+            let mut data = vec![0u8; length as usize];
+            self.stream.read_exact(&mut data).await?;
+
+            let id = i32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+            // Skip id and type, drop the two trailing null bytes
+            let body = String::from_utf8_lossy(&data[8..data.len() - 2]).to_string();
+            Ok((id, body))
+        })
+        .await
+        .map_err(|_| AppError::Network("RCON response timed out".to_string()))?
+        .map_err(|e| AppError::Network(format!("RCON read failed: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rcon_settings_from_properties() {
+        let properties = "#Minecraft server properties\nenable-rcon=true\nrcon.port=25580\nrcon.password=secret\n";
+        assert_eq!(
+            RconSettings::from_properties(properties),
+            Some(RconSettings {
+                port: 25580,
+                password: "secret".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_rcon_settings_disabled_or_without_password() {
+        assert_eq!(
+            RconSettings::from_properties("enable-rcon=false\nrcon.password=secret\n"),
+            None
+        );
+        assert_eq!(
+            RconSettings::from_properties("enable-rcon=true\nrcon.password=\n"),
+            None
+        );
+    }
+}
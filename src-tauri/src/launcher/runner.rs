@@ -725,27 +725,8 @@ async fn read_mcp_version(instance_dir: &Path) -> Option<String> {
     None
 }
 
-/// Launch a server instance (Vanilla, Paper, Fabric, Forge, NeoForge, Velocity, BungeeCord, Waterfall)
-pub async fn launch_server(
-    instance_dir: &Path,
-    data_dir: &Path,
-    instance: &Instance,
-    app: &AppHandle,
-    running_instances: RunningInstances,
-    stdin_handles: ServerStdinHandles,
-    db: SqlitePool,
-    running_tunnels: RunningTunnels,
-) -> AppResult<()> {
-    info!("Launching server from: {:?}", instance_dir);
-
-    // Find Java
-    let java_path = java::check_java_installed(data_dir)
-        .map(|j| j.path)
-        .or_else(find_system_java)
-        .ok_or_else(|| AppError::Instance("Java not found".to_string()))?;
-
-    info!("Using Java: {}", java_path);
-
+/// Build the java arguments used to start a server instance
+fn build_server_args(instance_dir: &Path, instance: &Instance) -> AppResult<Vec<String>> {
     // Build JVM args
     let min_memory = instance.memory_min_mb;
     let max_memory = instance.memory_max_mb;
@@ -837,6 +818,32 @@ pub async fn launch_server(
         }
     }
 
+    Ok(args)
+}
+
+/// Launch a server instance (Vanilla, Paper, Fabric, Forge, NeoForge, Velocity, BungeeCord, Waterfall)
+pub async fn launch_server(
+    instance_dir: &Path,
+    data_dir: &Path,
+    instance: &Instance,
+    app: &AppHandle,
+    running_instances: RunningInstances,
+    stdin_handles: ServerStdinHandles,
+    db: SqlitePool,
+    running_tunnels: RunningTunnels,
+) -> AppResult<()> {
+    info!("Launching server from: {:?}", instance_dir);
+
+    // Find Java
+    let java_path = java::check_java_installed(data_dir)
+        .map(|j| j.path)
+        .or_else(find_system_java)
+        .ok_or_else(|| AppError::Instance("Java not found".to_string()))?;
+
+    info!("Using Java: {}", java_path);
+
+    let args = build_server_args(instance_dir, instance)?;
+
     debug!("Server args: {:?}", args);
 
     // Spawn the server process
@@ -1070,6 +1077,185 @@ pub async fn launch_server(
     Ok(())
 }
 
+/// Maximum time a headless config-generation boot may take
+const BOOTSTRAP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Outcome of a headless first boot used to generate server configuration files
+#[derive(Clone, Serialize)]
+pub struct ServerBootstrapResult {
+    /// Whether the server reached "Done (" before being stopped
+    pub completed: bool,
+    pub duration_seconds: u64,
+    /// Files created by the boot, relative to the instance directory
+    pub generated_files: Vec<String>,
+}
+
+/// List files in the instance root and config folders (used to diff before/after a boot)
+fn list_config_files(instance_dir: &Path) -> std::collections::HashSet<String> {
+    walkdir::WalkDir::new(instance_dir)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let rel = e.path().strip_prefix(instance_dir).ok()?;
+            let first = rel.components().next()?.as_os_str().to_string_lossy().to_string();
+            if matches!(
+                first.as_str(),
+                "libraries" | "versions" | "cache" | "logs" | "world" | "world_nether" | "world_the_end"
+            ) {
+                return None;
+            }
+            Some(rel.to_string_lossy().replace('\\', "/"))
+        })
+        .collect()
+}
+
+/// Start a server headlessly, wait for it to finish loading and stop it again
+///
+/// This generates `server.properties`, `config/` and plugin configuration files
+/// before the first real start. The instance is marked as running meanwhile so
+/// it cannot be launched twice.
+pub async fn bootstrap_server(
+    instance_dir: &Path,
+    data_dir: &Path,
+    instance: &Instance,
+    app: &AppHandle,
+    running_instances: RunningInstances,
+) -> AppResult<ServerBootstrapResult> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    info!("Bootstrapping server configs in: {:?}", instance_dir);
+
+    let java_path = java::check_java_installed(data_dir)
+        .map(|j| j.path)
+        .or_else(find_system_java)
+        .ok_or_else(|| AppError::Instance("Java not found".to_string()))?;
+
+    let args = build_server_args(instance_dir, instance)?;
+    let before = list_config_files(instance_dir);
+
+    let mut cmd = Command::new(&java_path);
+    cmd.args(&args)
+        .current_dir(instance_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .kill_on_drop(true);
+
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| AppError::Io(format!("Failed to start server: {}", e)))?;
+
+    let pid = child.id().unwrap_or(0);
+    {
+        let mut running = running_instances.write().await;
+        running.insert(instance.id.clone(), pid);
+    }
+
+    let start_time = Instant::now();
+    let stop_command = match instance.loader.as_deref().map(|l| l.to_lowercase()).as_deref() {
+        Some("velocity") => "shutdown",
+        Some("bungeecord") | Some("waterfall") => "end",
+        _ => "stop",
+    };
+
+    // Forward stderr to the console view
+    if let Some(stderr) = child.stderr.take() {
+        let app_stderr = app.clone();
+        let instance_id = instance.id.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let _ = app_stderr.emit(
+                    "instance-log",
+                    InstanceLogEvent {
+                        instance_id: instance_id.clone(),
+                        line,
+                        is_error: true,
+                    },
+                );
+            }
+        });
+    }
+
+    let mut stdin = child.stdin.take();
+    let mut completed = false;
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        let wait_for_done = async {
+            while let Ok(Some(line)) = lines.next_line().await {
+                // "Done (" for vanilla/Paper/Forge/Fabric/Velocity, "Listening on" for BungeeCord
+                let done = line.contains("Done (") || line.contains("Listening on /");
+                let _ = app.emit(
+                    "instance-log",
+                    InstanceLogEvent {
+                        instance_id: instance.id.clone(),
+                        line,
+                        is_error: false,
+                    },
+                );
+                if done {
+                    return true;
+                }
+            }
+            false
+        };
+
+        completed = tokio::time::timeout(BOOTSTRAP_TIMEOUT, wait_for_done)
+            .await
+            .unwrap_or(false);
+
+        // Keep draining stdout so the server never blocks on a full pipe while stopping
+        tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+    }
+
+    if completed {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin
+                .write_all(format!("{}\n", stop_command).as_bytes())
+                .await;
+            let _ = stdin.flush().await;
+        }
+    }
+
+    // Give the server time to save and exit, then make sure it is gone
+    match tokio::time::timeout(std::time::Duration::from_secs(60), child.wait()).await {
+        Ok(_) => {}
+        Err(_) => {
+            error!("Server did not stop after bootstrap, killing it");
+            let _ = child.kill().await;
+        }
+    }
+
+    {
+        let mut running = running_instances.write().await;
+        running.remove(&instance.id);
+    }
+
+    let after = list_config_files(instance_dir);
+    let mut generated_files: Vec<String> = after.difference(&before).cloned().collect();
+    generated_files.sort();
+
+    info!(
+        "Server bootstrap finished (completed: {}, {} new files)",
+        completed,
+        generated_files.len()
+    );
+
+    Ok(ServerBootstrapResult {
+        completed,
+        duration_seconds: start_time.elapsed().as_secs(),
+        generated_files,
+    })
+}
+
 /// Helper function to get tunnel config if enabled and auto_start is true
 async fn get_tunnel_config_if_autostart(
     db: &SqlitePool,
@@ -1,7 +1,9 @@
 mod auth;
 pub mod cache;
 mod cloud_storage;
+mod content;
 pub mod crypto;
+mod curseforge;
 mod db;
 mod devtools;
 mod discord;
@@ -14,6 +16,8 @@ mod minecraft;
 mod modloader;
 mod modpacks;
 mod modrinth;
+mod network;
+mod plugins;
 mod schematics;
 mod sharing;
 mod skins;
@@ -160,6 +164,12 @@ pub fn run() {
             info!("Kaizen Launcher starting up");
             info!("Data directory: {:?}", state.data_dir);
 
+            // Keep Modrinth responses across restarts and for offline browsing
+            modrinth::init_disk_cache(&state.data_dir);
+
+            // Send requests to the configured mirrors before the official services
+            runtime.block_on(download::endpoints::load(&state.db));
+
             let shared_state: SharedState = Arc::new(RwLock::new(state));
             app.handle().manage(shared_state.clone());
 
@@ -169,6 +179,9 @@ pub fn run() {
 
             info!("Application initialized successfully");
 
+            // Look for new releases of installed Modrinth projects in the background
+            modrinth::feed::start_background_checks(app.handle().clone(), shared_state.clone());
+
             // Initialize Discord Rich Presence (Idle state)
             tauri::async_runtime::spawn(async move {
                 let state = shared_state.read().await;
@@ -204,6 +217,10 @@ pub fn run() {
             instance::commands::update_instance_settings,
             instance::commands::get_instance_mods,
             instance::commands::toggle_mod,
+            instance::commands::get_mod_profiles,
+            instance::commands::save_mod_profile,
+            instance::commands::delete_mod_profile,
+            instance::commands::switch_mod_profile,
             instance::commands::delete_mod,
             instance::commands::open_mods_folder,
             instance::commands::get_mods_folder_path,
@@ -235,6 +252,20 @@ pub fn run() {
             instance::commands::get_instance_resourcepacks,
             instance::commands::get_instance_shaders,
             instance::commands::get_instance_datapacks,
+            instance::commands::get_world_datapacks,
+            instance::commands::get_global_datapacks,
+            instance::commands::remove_global_datapack,
+            instance::commands::get_resource_packs,
+            instance::commands::set_enabled_resource_packs,
+            instance::commands::set_resource_pack_enabled,
+            instance::commands::get_jar_scan_results,
+            instance::commands::scan_instance_jars,
+            instance::commands::scan_all_instance_jars,
+            instance::commands::restore_quarantined_jar,
+            instance::commands::delete_quarantined_jar,
+            instance::commands::get_dedup_report,
+            instance::commands::deduplicate_instance_files,
+            instance::commands::undo_instance_deduplication,
             // World management commands
             instance::commands::get_instance_worlds,
             instance::commands::get_world_backups,
@@ -265,7 +296,12 @@ pub fn run() {
             instance::commands::create_server_from_client,
             instance::commands::check_server_dependencies,
             instance::commands::analyze_instance_logs,
+            instance::commands::analyze_instance_mods,
             instance::commands::change_instance_version,
+            instance::commands::preview_version_migration,
+            instance::commands::migrate_instance_version,
+            instance::commands::get_version_migration,
+            instance::commands::revert_version_migration,
             // Minecraft version commands
             minecraft::commands::get_minecraft_versions,
             minecraft::commands::get_minecraft_version_details,
@@ -281,37 +317,84 @@ pub fn run() {
             launcher::commands::check_java,
             launcher::commands::install_java,
             launcher::commands::send_server_command,
+            launcher::commands::get_server_command_history,
+            launcher::commands::clear_server_command_history,
+            launcher::commands::get_server_command_completions,
             launcher::commands::get_server_properties,
             launcher::commands::save_server_properties,
             launcher::commands::get_server_stats,
+            launcher::commands::get_server_eula_status,
+            launcher::commands::accept_server_eula,
+            launcher::commands::bootstrap_server_configs,
             launcher::commands::get_java_installations,
             launcher::commands::get_available_java_versions,
             launcher::commands::install_java_version,
             launcher::commands::uninstall_java_version,
+            // Proxy network commands
+            network::commands::get_networks,
+            network::commands::create_network,
+            network::commands::update_network,
+            network::commands::delete_network,
+            network::commands::apply_network_config,
+            network::commands::start_network,
+            network::commands::stop_network,
             // Download commands
             download::commands::get_download_queue,
+            download::commands::get_endpoint_mirrors,
+            download::commands::set_endpoint_mirrors,
             // Modloader commands
             modloader::commands::get_loader_versions,
             modloader::commands::is_loader_supported,
             modloader::commands::get_recommended_loader_version,
             modloader::commands::get_loader_mc_versions,
             modloader::commands::get_available_loaders,
+            modloader::commands::check_server_update,
+            modloader::commands::upgrade_server,
+            modloader::commands::rollback_server_upgrade,
             // Modrinth commands
             modrinth::commands::search_modrinth_mods,
             modrinth::commands::get_modrinth_mod_versions,
             modrinth::commands::install_modrinth_mod,
+            modrinth::commands::install_manual_download,
             modrinth::commands::get_modrinth_mod_details,
             modrinth::commands::get_mod_dependencies,
             modrinth::commands::install_modrinth_mods_batch,
+            modrinth::commands::get_shader_loader_status,
+            modrinth::commands::install_shader_loader,
+            modrinth::commands::get_modrinth_account,
+            modrinth::commands::set_modrinth_token,
+            modrinth::commands::preview_collection_import,
+            modrinth::commands::preview_followed_import,
+            modrinth::commands::import_modrinth_projects,
             modrinth::commands::get_installed_mod_ids,
+            modrinth::commands::get_modrinth_modpack_plan,
             modrinth::commands::install_modrinth_modpack,
             modrinth::commands::check_mod_updates,
             modrinth::commands::update_mod,
+            modrinth::commands::get_update_preferences,
+            modrinth::commands::set_update_preferences,
+            modrinth::commands::preview_bulk_update,
+            modrinth::commands::bulk_update_content,
+            modrinth::commands::get_latest_update_snapshot,
+            modrinth::commands::rollback_bulk_update,
             modrinth::commands::lookup_mod_by_hash,
             modrinth::commands::enrich_instance_mods,
             modrinth::commands::analyze_mods_for_server_detailed,
             modrinth::commands::sync_mods_metadata,
             modrinth::commands::check_mods_version_compatibility,
+            modrinth::commands::get_release_feed,
+            modrinth::commands::set_release_feed_read,
+            modrinth::commands::mark_release_feed_read,
+            modrinth::commands::check_release_feed,
+            modrinth::commands::get_release_feed_settings,
+            modrinth::commands::set_release_feed_interval,
+            // Plugin commands
+            plugins::commands::search_plugins,
+            plugins::commands::get_plugin_versions,
+            plugins::commands::resolve_plugin_install,
+            plugins::commands::install_plugin,
+            plugins::commands::check_plugin_updates,
+            plugins::commands::update_plugin,
             // Tunnel commands
             tunnel::commands::check_tunnel_agent,
             tunnel::commands::install_tunnel_agent,
@@ -386,6 +469,17 @@ pub fn run() {
             sharing::commands::fetch_share_manifest,
             sharing::commands::restore_shares,
             sharing::commands::get_shares_for_package,
+            // Modpack export, update and lockfile commands
+            modpacks::commands::list_modpack_export_entries,
+            modpacks::commands::export_instance_mrpack,
+            modpacks::commands::export_instance_curseforge,
+            modpacks::commands::check_modpack_update,
+            modpacks::commands::update_modpack_instance,
+            modpacks::commands::get_instance_lockfile,
+            modpacks::commands::generate_instance_lockfile,
+            modpacks::commands::sync_instance_lockfile,
+            modpacks::commands::export_instance_packwiz,
+            modpacks::commands::import_packwiz_pack,
             // Skin manager commands
             skins::commands::get_skin_profile,
             skins::commands::apply_skin,
@@ -1,3 +1,4 @@
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -168,9 +169,7 @@ pub struct JavaVersion {
 
 /// Fetch the version manifest from Mojang
 pub async fn fetch_version_manifest(client: &reqwest::Client) -> AppResult<VersionManifest> {
-    let response = client
-        .get(VERSION_MANIFEST_URL)
-        .send()
+    let response = endpoints::get(client, VERSION_MANIFEST_URL)
         .await
         .map_err(|e| AppError::Network(format!("Failed to fetch version manifest: {}", e)))?;
 
@@ -194,9 +193,7 @@ pub async fn fetch_version_details(
     client: &reqwest::Client,
     version_url: &str,
 ) -> AppResult<VersionDetails> {
-    let response = client
-        .get(version_url)
-        .send()
+    let response = endpoints::get(client, version_url)
         .await
         .map_err(|e| AppError::Network(format!("Failed to fetch version details: {}", e)))?;
 
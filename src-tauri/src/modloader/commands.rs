@@ -1,9 +1,11 @@
 //! Tauri commands for modloader operations
 
 use crate::cache::ApiCache;
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 use crate::modloader::paper::{PaperProject, SpongeProject};
-use crate::modloader::{fabric, forge, neoforge, paper, quilt, LoaderType, LoaderVersion};
+use crate::modloader::{
+    fabric, forge, neoforge, paper, quilt, server_update, LoaderType, LoaderVersion,
+};
 use crate::state::SharedState;
 use std::time::Duration;
 use tauri::State;
@@ -350,3 +352,156 @@ pub struct LoaderInfo {
     pub is_server: bool,
     pub is_proxy: bool,
 }
+
+/// Resolve a server instance and its directory for update operations
+async fn get_updatable_server(
+    state: &crate::state::AppState,
+    instance_id: &str,
+) -> AppResult<(
+    crate::db::instances::Instance,
+    std::path::PathBuf,
+    server_update::ServerBuildRef,
+)> {
+    let instance = crate::db::instances::Instance::get_by_id(&state.db, instance_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    if !instance.is_server {
+        return Err(AppError::Instance(
+            "Only server instances can be updated".to_string(),
+        ));
+    }
+
+    let build_ref = server_update::ServerBuildRef::parse(
+        instance.loader.as_deref().unwrap_or("vanilla"),
+        &instance.mc_version,
+        instance.loader_version.as_deref().unwrap_or(""),
+    )
+    .ok_or_else(|| {
+        AppError::Instance(format!(
+            "Build updates are not supported for {}",
+            instance.loader.as_deref().unwrap_or("vanilla")
+        ))
+    })?;
+
+    let instance_dir = state.data_dir.join("instances").join(&instance.game_dir);
+    Ok((instance, instance_dir, build_ref))
+}
+
+/// Refuse to touch the server jar while the server is up
+async fn ensure_server_stopped(state: &crate::state::AppState, instance_id: &str) -> AppResult<()> {
+    let running = state.running_instances.read().await;
+    if running.contains_key(instance_id) {
+        return Err(AppError::Instance(
+            "Stop the server before changing its build".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Check for newer builds of the same Minecraft/proxy version (Paper, Folia, Purpur, Velocity, Waterfall)
+#[tauri::command]
+pub async fn check_server_update(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<server_update::ServerUpdateInfo> {
+    let state = state.read().await;
+    let (_, instance_dir, build_ref) = get_updatable_server(&state, &instance_id).await?;
+
+    let builds = server_update::fetch_newer_builds(&state.http_client, &build_ref).await?;
+    let latest_build = builds.first().map(|b| b.build);
+
+    Ok(server_update::ServerUpdateInfo {
+        instance_id,
+        source: build_ref.source,
+        api_version: build_ref.api_version.clone(),
+        current_build: build_ref.build,
+        latest_build,
+        update_available: latest_build.is_some(),
+        builds,
+        can_rollback: server_update::can_rollback(&instance_dir),
+    })
+}
+
+/// Upgrade a server to a newer build (latest stable when `build` is not given)
+///
+/// The current jar is kept as `server.jar.previous` for rollback.
+#[tauri::command]
+pub async fn upgrade_server(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    build: Option<i32>,
+) -> AppResult<String> {
+    let state = state.read().await;
+    ensure_server_stopped(&state, &instance_id).await?;
+    let (instance, instance_dir, build_ref) = get_updatable_server(&state, &instance_id).await?;
+
+    let builds = server_update::fetch_newer_builds(&state.http_client, &build_ref).await?;
+    let target = server_update::select_target_build(&builds, build).ok_or_else(|| {
+        AppError::Instance(match build {
+            Some(b) => format!("Build {} is not newer than the installed build", b),
+            None => "The server is already on the latest build".to_string(),
+        })
+    })?;
+
+    let downloaded =
+        server_update::download_build(&state.http_client, &instance_dir, target).await?;
+
+    // The server may have been started while the build was downloading
+    if let Err(e) = ensure_server_stopped(&state, &instance_id).await {
+        let _ = tokio::fs::remove_file(&downloaded).await;
+        return Err(e);
+    }
+
+    let current_loader_version = instance.loader_version.clone().unwrap_or_default();
+    server_update::install_build(&instance_dir, &downloaded, &current_loader_version, target)
+        .await?;
+
+    crate::db::instances::Instance::update_version(
+        &state.db,
+        &instance.id,
+        &instance.mc_version,
+        instance.loader.as_deref(),
+        Some(&target.loader_version),
+    )
+    .await?;
+
+    tracing::info!(
+        "[SERVER_UPDATE] {} upgraded from {} to {}",
+        instance.name,
+        current_loader_version,
+        target.loader_version
+    );
+
+    Ok(target.loader_version.clone())
+}
+
+/// Restore the server jar that was replaced by the last upgrade
+#[tauri::command]
+pub async fn rollback_server_upgrade(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<String> {
+    let state = state.read().await;
+    ensure_server_stopped(&state, &instance_id).await?;
+    let (instance, instance_dir, _) = get_updatable_server(&state, &instance_id).await?;
+
+    let previous_loader_version = server_update::restore_previous_build(&instance_dir).await?;
+
+    crate::db::instances::Instance::update_version(
+        &state.db,
+        &instance.id,
+        &instance.mc_version,
+        instance.loader.as_deref(),
+        Some(&previous_loader_version),
+    )
+    .await?;
+
+    tracing::info!(
+        "[SERVER_UPDATE] {} rolled back to {}",
+        instance.name,
+        previous_loader_version
+    );
+
+    Ok(previous_loader_version)
+}
@@ -1,6 +1,7 @@
 //! Fabric Loader API client
 //! API: https://meta.fabricmc.net/
 
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
 use crate::modloader::LoaderVersion;
 use serde::Deserialize;
@@ -48,10 +49,9 @@ pub struct FabricLibrary {
 pub async fn fetch_loader_versions(client: &reqwest::Client) -> AppResult<Vec<LoaderVersion>> {
     let url = format!("{}/versions/loader", FABRIC_META_API);
 
-    let response =
-        client.get(&url).send().await.map_err(|e| {
-            AppError::Network(format!("Failed to fetch Fabric loader versions: {}", e))
-        })?;
+    let response = endpoints::get(client, &url)
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch Fabric loader versions: {}", e)))?;
 
     let versions: Vec<FabricLoaderVersion> = response
         .json()
@@ -73,10 +73,9 @@ pub async fn fetch_loader_versions(client: &reqwest::Client) -> AppResult<Vec<Lo
 pub async fn fetch_game_versions(client: &reqwest::Client) -> AppResult<Vec<String>> {
     let url = format!("{}/versions/game", FABRIC_META_API);
 
-    let response =
-        client.get(&url).send().await.map_err(|e| {
-            AppError::Network(format!("Failed to fetch Fabric game versions: {}", e))
-        })?;
+    let response = endpoints::get(client, &url)
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch Fabric game versions: {}", e)))?;
 
     let versions: Vec<FabricGameVersion> = response
         .json()
@@ -103,9 +102,7 @@ pub async fn fetch_profile(
         FABRIC_META_API, mc_version, loader_version
     );
 
-    let response = client
-        .get(&url)
-        .send()
+    let response = endpoints::get(client, &url)
         .await
         .map_err(|e| AppError::Network(format!("Failed to fetch Fabric profile: {}", e)))?;
 
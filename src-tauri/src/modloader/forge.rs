@@ -1,6 +1,7 @@
 //! Forge Loader API client
 //! API: https://files.minecraftforge.net/
 
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
 use crate::modloader::LoaderVersion;
 use serde::Deserialize;
@@ -25,9 +26,7 @@ pub struct ForgeVersionList {
 
 /// Fetch Forge promotions (recommended/latest versions per MC version)
 pub async fn fetch_promotions(client: &reqwest::Client) -> AppResult<ForgePromotions> {
-    let response = client
-        .get(FORGE_PROMOTIONS)
-        .send()
+    let response = endpoints::get(client, FORGE_PROMOTIONS)
         .await
         .map_err(|e| AppError::Network(format!("Failed to fetch Forge promotions: {}", e)))?;
 
@@ -2,6 +2,7 @@
 //! Handles installing Fabric, Quilt, Forge, NeoForge loaders
 
 use crate::download::client::download_file;
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
 use crate::minecraft::versions::VersionDetails;
 use crate::modloader::{fabric, forge, neoforge, quilt, LoaderType};
@@ -461,9 +462,7 @@ async fn install_neoforge(
 async fn download_installer_bytes(client: &reqwest::Client, url: &str) -> AppResult<Vec<u8>> {
     println!("[LOADER] Downloading installer from: {}", url);
 
-    let response = client
-        .get(url)
-        .send()
+    let response = endpoints::get(client, url)
         .await
         .map_err(|e| AppError::Network(format!("Failed to download installer: {}", e)))?;
 
@@ -12,6 +12,7 @@ pub mod neoforge;
 pub mod neoforge_processor;
 pub mod paper;
 pub mod quilt;
+pub mod server_update;
 
 use serde::{Deserialize, Serialize};
 
@@ -1,6 +1,7 @@
 //! NeoForge Loader API client
 //! API: https://maven.neoforged.net/
 
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
 use crate::modloader::LoaderVersion;
 use serde::Deserialize;
@@ -21,9 +22,7 @@ pub struct NeoForgeVersionsResponse {
 /// Fetch available NeoForge versions (including legacy 1.20.1 versions)
 pub async fn fetch_versions(client: &reqwest::Client) -> AppResult<Vec<LoaderVersion>> {
     // Fetch new NeoForge versions (1.20.2+)
-    let response = client
-        .get(NEOFORGE_API)
-        .send()
+    let response = endpoints::get(client, NEOFORGE_API)
         .await
         .map_err(|e| AppError::Network(format!("Failed to fetch NeoForge versions: {}", e)))?;
 
@@ -51,7 +50,7 @@ pub async fn fetch_versions(client: &reqwest::Client) -> AppResult<Vec<LoaderVer
 
     // Fetch legacy NeoForge versions (1.20.1)
     tracing::info!("[NeoForge] Fetching legacy versions from: {}", NEOFORGE_LEGACY_API);
-    match client.get(NEOFORGE_LEGACY_API).send().await {
+    match endpoints::get(client, NEOFORGE_LEGACY_API).await {
         Ok(legacy_response) => {
             match legacy_response.json::<NeoForgeVersionsResponse>().await {
                 Ok(legacy_data) => {
@@ -55,14 +55,25 @@ pub struct BuildInfo {
     pub project_id: String,
     #[allow(dead_code)]
     pub version: String,
-    #[allow(dead_code)]
     pub build: i32,
-    #[allow(dead_code)]
     pub time: String,
     pub channel: String,
+    #[serde(default)]
+    pub changes: Vec<BuildChange>,
     pub downloads: BuildDownloads,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildChange {
+    pub commit: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VersionBuildsDetailed {
+    pub builds: Vec<BuildInfo>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BuildDownloads {
     pub application: DownloadInfo,
@@ -71,7 +82,6 @@ pub struct BuildDownloads {
 #[derive(Debug, Deserialize)]
 pub struct DownloadInfo {
     pub name: String,
-    #[allow(dead_code)]
     pub sha256: String,
 }
 
@@ -165,6 +175,38 @@ pub async fn fetch_build_info(
     })
 }
 
+/// Fetch all builds of a version including channel, changes and download info
+pub async fn fetch_builds_detailed(
+    client: &reqwest::Client,
+    project: PaperProject,
+    version: &str,
+) -> AppResult<Vec<BuildInfo>> {
+    let url = format!(
+        "{}/projects/{}/versions/{}/builds",
+        PAPER_API,
+        project.as_str(),
+        version
+    );
+
+    let response = client.get(&url).send().await.map_err(|e| {
+        AppError::Network(format!(
+            "Failed to fetch {} builds: {}",
+            project.as_str(),
+            e
+        ))
+    })?;
+
+    let data: VersionBuildsDetailed = response.json().await.map_err(|e| {
+        AppError::Network(format!(
+            "Failed to parse {} builds: {}",
+            project.as_str(),
+            e
+        ))
+    })?;
+
+    Ok(data.builds)
+}
+
 /// Get the download URL for a specific build
 pub fn get_download_url(
     project: PaperProject,
@@ -378,6 +420,68 @@ pub async fn fetch_purpur_builds(
     Ok(versions)
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct PurpurCommit {
+    pub description: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurpurBuildInfo {
+    #[serde(default)]
+    pub commits: Vec<PurpurCommit>,
+    #[serde(default)]
+    pub result: String,
+    #[serde(default)]
+    pub timestamp: i64,
+}
+
+/// Get the list of all Purpur builds for a version
+pub async fn fetch_purpur_build_numbers(
+    client: &reqwest::Client,
+    version: &str,
+) -> AppResult<Vec<String>> {
+    let url = format!("{}/purpur/{}", PURPUR_API, version);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch Purpur builds: {}", e)))?;
+
+    let data: PurpurBuilds = response
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to parse Purpur builds: {}", e)))?;
+
+    Ok(data.builds.all)
+}
+
+/// Fetch details (commits, result) of a single Purpur build
+pub async fn fetch_purpur_build_info(
+    client: &reqwest::Client,
+    version: &str,
+    build: &str,
+) -> AppResult<PurpurBuildInfo> {
+    let url = format!("{}/purpur/{}/{}", PURPUR_API, version, build);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch Purpur build info: {}", e)))?;
+
+    response
+        .json()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to parse Purpur build info: {}", e)))
+}
+
+/// Get the download URL for a Purpur build
+pub fn get_purpur_download_url(version: &str, build: &str) -> String {
+    format!("{}/purpur/{}/{}/download", PURPUR_API, version, build)
+}
+
 /// Fetch all Purpur loader versions
 pub async fn fetch_purpur_loader_versions(
     client: &reqwest::Client,
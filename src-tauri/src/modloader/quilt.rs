@@ -1,6 +1,7 @@
 //! Quilt Loader API client
 //! API: https://meta.quiltmc.org/
 
+use crate::download::endpoints;
 use crate::error::{AppError, AppResult};
 use crate::modloader::LoaderVersion;
 use serde::Deserialize;
@@ -47,10 +48,9 @@ pub struct QuiltLibrary {
 pub async fn fetch_loader_versions(client: &reqwest::Client) -> AppResult<Vec<LoaderVersion>> {
     let url = format!("{}/versions/loader", QUILT_META_API);
 
-    let response =
-        client.get(&url).send().await.map_err(|e| {
-            AppError::Network(format!("Failed to fetch Quilt loader versions: {}", e))
-        })?;
+    let response = endpoints::get(client, &url)
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch Quilt loader versions: {}", e)))?;
 
     let versions: Vec<QuiltLoaderVersion> = response
         .json()
@@ -73,10 +73,9 @@ pub async fn fetch_loader_versions(client: &reqwest::Client) -> AppResult<Vec<Lo
 pub async fn fetch_game_versions(client: &reqwest::Client) -> AppResult<Vec<String>> {
     let url = format!("{}/versions/game", QUILT_META_API);
 
-    let response =
-        client.get(&url).send().await.map_err(|e| {
-            AppError::Network(format!("Failed to fetch Quilt game versions: {}", e))
-        })?;
+    let response = endpoints::get(client, &url)
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to fetch Quilt game versions: {}", e)))?;
 
     let versions: Vec<QuiltGameVersion> = response
         .json()
@@ -103,9 +102,7 @@ pub async fn fetch_profile(
         QUILT_META_API, mc_version, loader_version
     );
 
-    let response = client
-        .get(&url)
-        .send()
+    let response = endpoints::get(client, &url)
         .await
         .map_err(|e| AppError::Network(format!("Failed to fetch Quilt profile: {}", e)))?;
 
@@ -0,0 +1,405 @@
+//! Update checks and in-place upgrades for Paper-family server jars
+//!
+//! Supported: Paper, Folia, Purpur, Velocity, Waterfall. Only newer builds of
+//! the same Minecraft (or proxy) version are considered.
+
+use crate::error::{AppError, AppResult};
+use crate::modloader::paper::{self, PaperProject};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Previous jar kept for rollback, next to `server.jar`
+pub const PREVIOUS_JAR: &str = "server.jar.previous";
+
+/// Rollback metadata written on upgrade
+const UPGRADE_STATE_FILE: &str = ".server_upgrade.json";
+
+/// Temporary file used while downloading the new jar
+const DOWNLOAD_TMP: &str = "server.jar.download";
+
+/// Where a server build comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerBuildSource {
+    Paper,
+    Folia,
+    Purpur,
+    Velocity,
+    Waterfall,
+}
+
+/// The build an instance is currently on, parsed from its loader version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerBuildRef {
+    pub source: ServerBuildSource,
+    /// Version as known by the build API (MC version, or proxy version)
+    pub api_version: String,
+    pub build: i32,
+}
+
+impl ServerBuildRef {
+    /// Parse the stored loader version of a server instance
+    ///
+    /// Formats written by the installers:
+    /// - Paper / Purpur: `build-123`
+    /// - Folia: `1.20.4-25`
+    /// - Velocity / Waterfall: `3.3.0-123`, `1.21-123`
+    pub fn parse(loader: &str, mc_version: &str, loader_version: &str) -> Option<Self> {
+        let source = match loader.to_lowercase().as_str() {
+            "paper" => ServerBuildSource::Paper,
+            "folia" => ServerBuildSource::Folia,
+            "purpur" => ServerBuildSource::Purpur,
+            "velocity" => ServerBuildSource::Velocity,
+            "waterfall" => ServerBuildSource::Waterfall,
+            _ => return None,
+        };
+
+        let (prefix, build) = loader_version.rsplit_once('-')?;
+        let build: i32 = build.parse().ok()?;
+
+        let api_version = match source {
+            ServerBuildSource::Paper | ServerBuildSource::Purpur | ServerBuildSource::Folia => {
+                mc_version.to_string()
+            }
+            ServerBuildSource::Velocity | ServerBuildSource::Waterfall => prefix.to_string(),
+        };
+
+        Some(Self {
+            source,
+            api_version,
+            build,
+        })
+    }
+
+    /// Loader version string for another build, in the same format as the installers
+    pub fn loader_version_for(&self, build: i32) -> String {
+        match self.source {
+            ServerBuildSource::Paper | ServerBuildSource::Purpur => format!("build-{}", build),
+            ServerBuildSource::Folia
+            | ServerBuildSource::Velocity
+            | ServerBuildSource::Waterfall => format!("{}-{}", self.api_version, build),
+        }
+    }
+
+    fn paper_project(&self) -> Option<PaperProject> {
+        match self.source {
+            ServerBuildSource::Paper => Some(PaperProject::Paper),
+            ServerBuildSource::Folia => Some(PaperProject::Folia),
+            ServerBuildSource::Velocity => Some(PaperProject::Velocity),
+            ServerBuildSource::Waterfall => Some(PaperProject::Waterfall),
+            ServerBuildSource::Purpur => None,
+        }
+    }
+}
+
+/// A single change (commit) in a build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerBuildChange {
+    pub commit: String,
+    pub summary: String,
+}
+
+/// A build newer than the installed one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerBuildSummary {
+    pub build: i32,
+    pub loader_version: String,
+    pub time: Option<String>,
+    /// "default" (stable) or "experimental" for PaperMC projects
+    pub channel: String,
+    pub changes: Vec<ServerBuildChange>,
+    #[serde(skip)]
+    download_url: String,
+    #[serde(skip)]
+    sha256: Option<String>,
+}
+
+/// Result of an update check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerUpdateInfo {
+    pub instance_id: String,
+    pub source: ServerBuildSource,
+    pub api_version: String,
+    pub current_build: i32,
+    pub latest_build: Option<i32>,
+    pub update_available: bool,
+    /// Newer builds, newest first
+    pub builds: Vec<ServerBuildSummary>,
+    /// Whether a previous jar is available for rollback
+    pub can_rollback: bool,
+}
+
+/// Rollback information saved next to the jar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerUpgradeState {
+    pub previous_loader_version: String,
+    pub upgraded_loader_version: String,
+    pub upgraded_at: String,
+}
+
+/// Fetch builds newer than `current`, newest first
+pub async fn fetch_newer_builds(
+    client: &reqwest::Client,
+    current: &ServerBuildRef,
+) -> AppResult<Vec<ServerBuildSummary>> {
+    let mut builds = Vec::new();
+
+    if let Some(project) = current.paper_project() {
+        for info in paper::fetch_builds_detailed(client, project, &current.api_version).await? {
+            if info.build <= current.build {
+                continue;
+            }
+            builds.push(ServerBuildSummary {
+                build: info.build,
+                loader_version: current.loader_version_for(info.build),
+                time: Some(info.time.clone()),
+                channel: info.channel.clone(),
+                changes: info
+                    .changes
+                    .iter()
+                    .map(|c| ServerBuildChange {
+                        commit: c.commit.clone(),
+                        summary: c.summary.clone(),
+                    })
+                    .collect(),
+                download_url: paper::get_download_url(
+                    project,
+                    &current.api_version,
+                    info.build,
+                    &info.downloads.application.name,
+                ),
+                sha256: Some(info.downloads.application.sha256.clone()),
+            });
+        }
+    } else {
+        // Purpur: list build numbers, then fetch details of the newer ones
+        let numbers = paper::fetch_purpur_build_numbers(client, &current.api_version).await?;
+        for number in numbers {
+            let Ok(build) = number.parse::<i32>() else {
+                continue;
+            };
+            if build <= current.build {
+                continue;
+            }
+            let info = paper::fetch_purpur_build_info(client, &current.api_version, &number).await?;
+            if !info.result.is_empty() && info.result != "SUCCESS" {
+                continue;
+            }
+            builds.push(ServerBuildSummary {
+                build,
+                loader_version: current.loader_version_for(build),
+                time: chrono::DateTime::<chrono::Utc>::from_timestamp_millis(info.timestamp)
+                    .map(|t| t.to_rfc3339()),
+                channel: "default".to_string(),
+                changes: info
+                    .commits
+                    .into_iter()
+                    .map(|c| ServerBuildChange {
+                        commit: c.hash,
+                        summary: c.description,
+                    })
+                    .collect(),
+                download_url: paper::get_purpur_download_url(&current.api_version, &number),
+                sha256: None,
+            });
+        }
+    }
+
+    builds.sort_by(|a, b| b.build.cmp(&a.build));
+    Ok(builds)
+}
+
+/// Pick the build to upgrade to: the requested one, or the newest stable build
+pub fn select_target_build(
+    builds: &[ServerBuildSummary],
+    requested: Option<i32>,
+) -> Option<&ServerBuildSummary> {
+    match requested {
+        Some(build) => builds.iter().find(|b| b.build == build),
+        None => builds
+            .iter()
+            .find(|b| b.channel == "default")
+            .or_else(|| builds.first()),
+    }
+}
+
+/// Whether a previous jar is available for rollback
+pub fn can_rollback(instance_dir: &Path) -> bool {
+    instance_dir.join(PREVIOUS_JAR).exists() && instance_dir.join(UPGRADE_STATE_FILE).exists()
+}
+
+/// Download and verify a build next to `server.jar` without touching the installed jar
+///
+/// Returns the path of the downloaded file, to be passed to [`install_build`].
+pub async fn download_build(
+    client: &reqwest::Client,
+    instance_dir: &Path,
+    target: &ServerBuildSummary,
+) -> AppResult<PathBuf> {
+    tracing::info!(
+        "[SERVER_UPDATE] Downloading build {} from {}",
+        target.build,
+        target.download_url
+    );
+
+    let response = client
+        .get(&target.download_url)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to download server build: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::Network(format!(
+            "Failed to download server build: HTTP {}",
+            response.status()
+        )));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to read server build: {}", e)))?;
+
+    // Jars are zip files
+    if !bytes.starts_with(b"PK") {
+        return Err(AppError::Download(
+            "Downloaded server build is not a valid jar".to_string(),
+        ));
+    }
+
+    if let Some(expected) = &target.sha256 {
+        let actual = hex::encode(Sha256::digest(&bytes));
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(AppError::Download(format!(
+                "Checksum mismatch for build {} (expected {}, got {})",
+                target.build, expected, actual
+            )));
+        }
+    }
+
+    let tmp_path = instance_dir.join(DOWNLOAD_TMP);
+    fs::write(&tmp_path, &bytes)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write server build: {}", e)))?;
+
+    Ok(tmp_path)
+}
+
+/// Swap a downloaded build in as `server.jar`, keeping the old jar for rollback
+///
+/// The new jar was fully downloaded and verified beforehand, so it is moved
+/// over `server.jar` with a single rename.
+pub async fn install_build(
+    instance_dir: &Path,
+    downloaded: &Path,
+    previous_loader_version: &str,
+    target: &ServerBuildSummary,
+) -> AppResult<()> {
+    let server_jar = instance_dir.join("server.jar");
+    if server_jar.exists() {
+        fs::copy(&server_jar, instance_dir.join(PREVIOUS_JAR))
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to keep previous server.jar: {}", e)))?;
+    }
+
+    fs::rename(downloaded, &server_jar)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to replace server.jar: {}", e)))?;
+
+    let upgrade_state = ServerUpgradeState {
+        previous_loader_version: previous_loader_version.to_string(),
+        upgraded_loader_version: target.loader_version.clone(),
+        upgraded_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let json = serde_json::to_string_pretty(&upgrade_state)?;
+    fs::write(instance_dir.join(UPGRADE_STATE_FILE), json)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write upgrade state: {}", e)))?;
+
+    Ok(())
+}
+
+/// Move the previous jar back over `server.jar` and return the loader version to restore
+pub async fn restore_previous_build(instance_dir: &Path) -> AppResult<String> {
+    let state_path = instance_dir.join(UPGRADE_STATE_FILE);
+    let previous_jar = instance_dir.join(PREVIOUS_JAR);
+
+    if !previous_jar.exists() || !state_path.exists() {
+        return Err(AppError::Instance(
+            "No previous server build to roll back to".to_string(),
+        ));
+    }
+
+    let content = fs::read_to_string(&state_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to read upgrade state: {}", e)))?;
+    let upgrade_state: ServerUpgradeState = serde_json::from_str(&content)?;
+
+    fs::rename(&previous_jar, instance_dir.join("server.jar"))
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to restore previous server.jar: {}", e)))?;
+    let _ = fs::remove_file(&state_path).await;
+
+    Ok(upgrade_state.previous_loader_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_paper_and_purpur() {
+        let paper = ServerBuildRef::parse("paper", "1.21.1", "build-120").unwrap();
+        assert_eq!(paper.source, ServerBuildSource::Paper);
+        assert_eq!(paper.api_version, "1.21.1");
+        assert_eq!(paper.build, 120);
+        assert_eq!(paper.loader_version_for(125), "build-125");
+
+        let purpur = ServerBuildRef::parse("Purpur", "1.20.4", "build-2176").unwrap();
+        assert_eq!(purpur.source, ServerBuildSource::Purpur);
+        assert_eq!(purpur.build, 2176);
+    }
+
+    #[test]
+    fn test_parse_folia_and_proxies() {
+        let folia = ServerBuildRef::parse("folia", "1.20.4", "1.20.4-25").unwrap();
+        assert_eq!(folia.api_version, "1.20.4");
+        assert_eq!(folia.loader_version_for(30), "1.20.4-30");
+
+        let velocity = ServerBuildRef::parse("velocity", "proxy", "3.3.0-SNAPSHOT-436").unwrap();
+        assert_eq!(velocity.api_version, "3.3.0-SNAPSHOT");
+        assert_eq!(velocity.build, 436);
+        assert_eq!(velocity.loader_version_for(440), "3.3.0-SNAPSHOT-440");
+
+        let waterfall = ServerBuildRef::parse("waterfall", "proxy", "1.21-577").unwrap();
+        assert_eq!(waterfall.api_version, "1.21");
+    }
+
+    #[test]
+    fn test_parse_unsupported() {
+        assert!(ServerBuildRef::parse("fabric", "1.21", "0.16.0").is_none());
+        assert!(ServerBuildRef::parse("paper", "1.21", "latest").is_none());
+    }
+
+    fn summary(build: i32, channel: &str) -> ServerBuildSummary {
+        ServerBuildSummary {
+            build,
+            loader_version: format!("build-{}", build),
+            time: None,
+            channel: channel.to_string(),
+            changes: vec![],
+            download_url: String::new(),
+            sha256: None,
+        }
+    }
+
+    #[test]
+    fn test_select_target_prefers_stable() {
+        let builds = vec![summary(12, "experimental"), summary(11, "default")];
+        assert_eq!(select_target_build(&builds, None).unwrap().build, 11);
+        assert_eq!(select_target_build(&builds, Some(12)).unwrap().build, 12);
+        assert!(select_target_build(&builds, Some(5)).is_none());
+    }
+}
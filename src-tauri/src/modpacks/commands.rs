@@ -0,0 +1,182 @@
+//! Tauri commands for modpack export, updates and lockfiles
+
+use super::lockfile::{InstanceLockfile, LockfileSyncReport};
+use super::packwiz::{PackwizExportOptions, PackwizImportResult};
+use super::update::{ModpackUpdateInfo, ModpackUpdateResult};
+use super::{ExportCandidate, ModpackExportOptions, ModpackExportResult};
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::state::SharedState;
+use tauri::{AppHandle, State};
+
+async fn load_instance(state: &crate::state::AppState, instance_id: &str) -> AppResult<Instance> {
+    Instance::get_by_id(&state.db, instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))
+}
+
+/// List the top-level files and folders of an instance for the export selection
+#[tauri::command]
+pub async fn list_modpack_export_entries(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<ExportCandidate>> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+
+    super::list_export_candidates(&instance_dir).await
+}
+
+/// Export an instance as a Modrinth modpack (.mrpack)
+#[tauri::command]
+pub async fn export_instance_mrpack(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    options: ModpackExportOptions,
+) -> AppResult<ModpackExportResult> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+
+    super::modrinth::export_mrpack(&state.http_client, &instance, &instance_dir, &options).await
+}
+
+/// Export an instance as a CurseForge modpack (.zip with manifest.json).
+/// Unknown jars go to the overrides unless `include_unresolved` is false
+#[tauri::command]
+pub async fn export_instance_curseforge(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    options: ModpackExportOptions,
+    include_unresolved: Option<bool>,
+) -> AppResult<ModpackExportResult> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+
+    let api_key = crate::curseforge::api_key(&state.db).await.ok_or_else(|| {
+        AppError::Network(
+            "CurseForge is not configured: add an API key in the settings".to_string(),
+        )
+    })?;
+    let client = crate::curseforge::CurseForgeClient::new(&state.http_client, api_key);
+
+    super::curseforge::export_curseforge(
+        &client,
+        &instance,
+        &instance_dir,
+        &options,
+        include_unresolved.unwrap_or(true),
+    )
+    .await
+}
+
+/// List the versions of the modpack an instance was installed from
+#[tauri::command]
+pub async fn check_modpack_update(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<ModpackUpdateInfo> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.data_dir.join("instances").join(&instance.game_dir);
+
+    super::update::check_update(&state.http_client, &instance, &instance_dir).await
+}
+
+/// Update an instance to another version of its modpack, keeping the user's changes.
+/// A backup of the instance is taken first
+#[tauri::command]
+pub async fn update_modpack_instance(
+    state: State<'_, SharedState>,
+    app: AppHandle,
+    instance_id: String,
+    version_id: String,
+    installed_version_id: Option<String>,
+) -> AppResult<ModpackUpdateResult> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+
+    super::update::update_instance(
+        &state,
+        &app,
+        &instance,
+        &version_id,
+        installed_version_id.as_deref(),
+    )
+    .await
+}
+
+/// Get the lockfile of an instance, if it has one
+#[tauri::command]
+pub async fn get_instance_lockfile(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Option<InstanceLockfile>> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+
+    InstanceLockfile::load(&instance_dir).await
+}
+
+/// Write the lockfile of an instance from its current content
+#[tauri::command]
+pub async fn generate_instance_lockfile(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<InstanceLockfile> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+
+    let lockfile = super::lockfile::generate(&state, &instance, &instance_dir).await?;
+    lockfile.save(&instance_dir).await?;
+    Ok(lockfile)
+}
+
+/// Make an instance match its lockfile
+#[tauri::command]
+pub async fn sync_instance_lockfile(
+    state: State<'_, SharedState>,
+    app: AppHandle,
+    instance_id: String,
+) -> AppResult<LockfileSyncReport> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+
+    let lockfile = InstanceLockfile::load(&instance_dir)
+        .await?
+        .ok_or_else(|| AppError::Instance("This instance has no lockfile".to_string()))?;
+    super::lockfile::sync(&state, &app, &instance, &instance_dir, &lockfile).await
+}
+
+/// Export an instance as a packwiz pack folder
+#[tauri::command]
+pub async fn export_instance_packwiz(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    options: PackwizExportOptions,
+) -> AppResult<String> {
+    let state = state.read().await;
+    let instance = load_instance(&state, &instance_id).await?;
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+
+    let output_dir = super::packwiz::export(&state, &instance, &instance_dir, &options).await?;
+    Ok(output_dir.to_string_lossy().to_string())
+}
+
+/// Create an instance from a packwiz pack (local pack.toml or HTTPS URL)
+#[tauri::command]
+pub async fn import_packwiz_pack(
+    state: State<'_, SharedState>,
+    app: AppHandle,
+    source: String,
+    instance_name: Option<String>,
+) -> AppResult<PackwizImportResult> {
+    let state = state.read().await;
+    super::packwiz::import(&state, &app, &source, instance_name).await
+}
@@ -0,0 +1,295 @@
+// CurseForge modpack (.zip with manifest.json) export
+
+use super::{ExportFile, ExportSide, ModpackExportOptions, ModpackExportResult};
+use crate::curseforge::CurseForgeClient;
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::external_import::parsers::curseforge::{
+    CurseForgeFile, CurseForgeManifest, CurseForgeMinecraft, CurseForgeModLoader,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MANIFEST_TYPE: &str = "minecraftModpack";
+const MANIFEST_VERSION: u32 = 1;
+const OVERRIDES_FOLDER: &str = "overrides";
+
+/// Loader id in `manifest.json`, e.g. "fabric-0.15.11"
+fn mod_loader_id(instance: &Instance) -> AppResult<Option<String>> {
+    let Some(loader) = instance.loader.as_deref().filter(|l| !l.is_empty()) else {
+        return Ok(None);
+    };
+
+    let prefix = match loader.to_lowercase().as_str() {
+        "forge" => "forge",
+        "neoforge" => "neoforge",
+        "fabric" => "fabric",
+        "quilt" => "quilt",
+        _ => {
+            return Err(AppError::Instance(format!(
+                "CurseForge modpacks do not support {} instances",
+                loader
+            )))
+        }
+    };
+
+    let version = instance
+        .loader_version
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| {
+            AppError::Instance(format!(
+                "The {} version of this instance is unknown",
+                loader
+            ))
+        })?;
+
+    Ok(Some(format!("{}-{}", prefix, version)))
+}
+
+/// Files resolved to CurseForge project and file ids, keyed by their index in the export list
+async fn resolve_files(
+    client: &CurseForgeClient<'_>,
+    files: &[ExportFile],
+    warnings: &mut Vec<String>,
+) -> HashMap<usize, CurseForgeFile> {
+    let candidates: Vec<(usize, std::path::PathBuf)> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.is_resolvable())
+        .map(|(i, f)| (i, f.source.clone()))
+        .collect();
+
+    if candidates.is_empty() {
+        return HashMap::new();
+    }
+
+    // Fingerprint in a blocking task, jars can be large
+    let fingerprinted: Vec<(usize, u32)> = tokio::task::spawn_blocking(move || {
+        candidates
+            .into_iter()
+            .filter_map(|(i, path)| {
+                let bytes = std::fs::read(&path).ok()?;
+                Some((i, crate::curseforge::fingerprint(&bytes)))
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default();
+
+    let fingerprints: Vec<u32> = fingerprinted.iter().map(|(_, f)| *f).collect();
+    let matches = match client.get_fingerprint_matches(&fingerprints).await {
+        Ok(matches) => matches,
+        Err(e) => {
+            warnings.push(format!("Could not reach CurseForge: {}", e));
+            return HashMap::new();
+        }
+    };
+
+    let by_fingerprint: HashMap<u32, (u64, u64)> = matches
+        .exact_matches
+        .into_iter()
+        .map(|m| {
+            (
+                m.file.file_fingerprint,
+                (u64::from(m.file.mod_id), u64::from(m.file.id)),
+            )
+        })
+        .collect();
+
+    fingerprinted
+        .into_iter()
+        .filter_map(|(i, fingerprint)| {
+            let (project_id, file_id) = *by_fingerprint.get(&fingerprint)?;
+            Some((
+                i,
+                CurseForgeFile {
+                    project_id,
+                    file_id,
+                    required: Some(true),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Export an instance as a CurseForge modpack.
+/// Jars that CurseForge does not know are added to the overrides when
+/// `include_unresolved` is set, and only reported otherwise
+pub async fn export_curseforge(
+    client: &CurseForgeClient<'_>,
+    instance: &Instance,
+    instance_dir: &Path,
+    options: &ModpackExportOptions,
+    include_unresolved: bool,
+) -> AppResult<ModpackExportResult> {
+    let mod_loader = mod_loader_id(instance)?;
+    let files = super::collect_export_files(instance_dir, &options.entries)?;
+
+    let mut warnings = Vec::new();
+    let mut resolved = resolve_files(client, &files, &mut warnings).await;
+
+    let mut manifest_files = Vec::new();
+    let mut overrides = Vec::new();
+    let mut sided_overrides = 0;
+    for (i, mut file) in files.into_iter().enumerate() {
+        if let Some(entry) = resolved.remove(&i) {
+            manifest_files.push(entry);
+            continue;
+        }
+
+        if file.is_resolvable() {
+            if include_unresolved {
+                warnings.push(format!(
+                    "{} is not on CurseForge and was added to the overrides",
+                    file.relative
+                ));
+            } else {
+                warnings.push(format!(
+                    "{} is not on CurseForge and was left out of the pack",
+                    file.relative
+                ));
+                continue;
+            }
+        }
+
+        // CurseForge packs have a single overrides folder
+        if file.side != ExportSide::Both {
+            sided_overrides += 1;
+            file.side = ExportSide::Both;
+        }
+        overrides.push(file);
+    }
+
+    if sided_overrides > 0 {
+        warnings.push(format!(
+            "CurseForge modpacks have no client or server overrides, {} file(s) were added to the shared overrides",
+            sided_overrides
+        ));
+    }
+
+    manifest_files.sort_by_key(|f| (f.project_id, f.file_id));
+    manifest_files.dedup_by_key(|f| (f.project_id, f.file_id));
+
+    let name = options
+        .name
+        .clone()
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| instance.name.clone());
+    let manifest = CurseForgeManifest {
+        minecraft: CurseForgeMinecraft {
+            version: instance.mc_version.clone(),
+            mod_loaders: mod_loader
+                .into_iter()
+                .map(|id| CurseForgeModLoader {
+                    id,
+                    primary: Some(true),
+                })
+                .collect(),
+        },
+        manifest_type: Some(MANIFEST_TYPE.to_string()),
+        manifest_version: Some(MANIFEST_VERSION),
+        name,
+        version: Some(options.version.clone()),
+        author: options.author.clone().filter(|a| !a.is_empty()),
+        files: manifest_files,
+        overrides: Some(OVERRIDES_FOLDER.to_string()),
+    };
+
+    let manifest_json = serde_json::to_string_pretty(&manifest)?;
+    let output_path = std::path::PathBuf::from(&options.output_path);
+    let resolved_files = manifest.files.len();
+    let override_files = overrides.len();
+
+    let path = output_path.clone();
+    tokio::task::spawn_blocking(move || {
+        super::write_pack_archive(&path, MANIFEST_FILE, &manifest_json, &overrides)
+    })
+    .await
+    .map_err(|e| AppError::Io(format!("Modpack export task failed: {}", e)))??;
+
+    log::info!(
+        "Exported {} as a CurseForge modpack ({} files, {} overrides) to {}",
+        instance.name,
+        resolved_files,
+        override_files,
+        output_path.display()
+    );
+
+    Ok(ModpackExportResult {
+        path: output_path.to_string_lossy().to_string(),
+        resolved_files,
+        override_files,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(loader: Option<&str>, loader_version: Option<&str>) -> Instance {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "name": "Pack",
+            "icon_path": null,
+            "mc_version": "1.20.1",
+            "loader": loader,
+            "loader_version": loader_version,
+            "java_path": null,
+            "memory_min_mb": 1024,
+            "memory_max_mb": 4096,
+            "jvm_args": "",
+            "game_dir": "pack",
+            "created_at": "2024-01-01T00:00:00Z",
+            "last_played": null,
+            "total_playtime_seconds": 0,
+            "modrinth_project_id": null,
+            "color": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mod_loader_id() {
+        assert_eq!(
+            mod_loader_id(&instance(Some("Fabric"), Some("0.15.11"))).unwrap(),
+            Some("fabric-0.15.11".to_string())
+        );
+        assert_eq!(mod_loader_id(&instance(None, None)).unwrap(), None);
+        assert!(mod_loader_id(&instance(Some("forge"), None)).is_err());
+        assert!(mod_loader_id(&instance(Some("paper"), Some("1"))).is_err());
+    }
+
+    #[test]
+    fn test_manifest_serialization() {
+        let manifest = CurseForgeManifest {
+            minecraft: CurseForgeMinecraft {
+                version: "1.20.1".to_string(),
+                mod_loaders: vec![CurseForgeModLoader {
+                    id: "forge-47.2.0".to_string(),
+                    primary: Some(true),
+                }],
+            },
+            manifest_type: Some(MANIFEST_TYPE.to_string()),
+            manifest_version: Some(MANIFEST_VERSION),
+            name: "Pack".to_string(),
+            version: Some("1.0.0".to_string()),
+            author: None,
+            files: vec![CurseForgeFile {
+                project_id: 238222,
+                file_id: 4593548,
+                required: Some(true),
+            }],
+            overrides: Some(OVERRIDES_FOLDER.to_string()),
+        };
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["manifestType"], "minecraftModpack");
+        assert_eq!(json["minecraft"]["modLoaders"][0]["id"], "forge-47.2.0");
+        assert_eq!(json["files"][0]["projectID"], 238222);
+        assert_eq!(json["files"][0]["fileID"], 4593548);
+        assert_eq!(json["overrides"], "overrides");
+    }
+}
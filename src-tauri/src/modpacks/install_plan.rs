@@ -0,0 +1,223 @@
+// What a Modrinth modpack installs on each side. Files carry an `env` with
+// `required`, `optional` or `unsupported` for the client and the server;
+// files without one are required on both sides
+
+use crate::error::{AppError, AppResult};
+use crate::modrinth::commands::{extract_modrinth_ids, ModpackFile, ModpackIndex};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// How a pack file is treated on one side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSupport {
+    Required,
+    Optional,
+    Unsupported,
+}
+
+pub fn file_support(file: &ModpackFile, is_server: bool) -> FileSupport {
+    let side = file.env.as_ref().and_then(|env| {
+        if is_server {
+            env.server.as_deref()
+        } else {
+            env.client.as_deref()
+        }
+    });
+    match side {
+        Some("optional") => FileSupport::Optional,
+        Some("unsupported") => FileSupport::Unsupported,
+        _ => FileSupport::Required,
+    }
+}
+
+/// A downloaded file of the pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedFile {
+    pub path: String,
+    pub file_size: u64,
+    /// Modrinth project, when the file is served by the Modrinth CDN
+    pub project_id: Option<String>,
+}
+
+/// Files of the pack for one side
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SidePlan {
+    pub required: Vec<PlannedFile>,
+    /// Offered to the user, installed only when chosen
+    pub optional: Vec<PlannedFile>,
+    pub unsupported: Vec<PlannedFile>,
+}
+
+impl SidePlan {
+    pub fn new(index: &ModpackIndex, is_server: bool) -> Self {
+        let mut plan = Self::default();
+        for file in &index.files {
+            let planned = PlannedFile {
+                path: file.path.clone(),
+                file_size: file.file_size,
+                project_id: file
+                    .downloads
+                    .first()
+                    .and_then(|url| extract_modrinth_ids(url))
+                    .map(|(project_id, _)| project_id),
+            };
+            match file_support(file, is_server) {
+                FileSupport::Required => plan.required.push(planned),
+                FileSupport::Optional => plan.optional.push(planned),
+                FileSupport::Unsupported => plan.unsupported.push(planned),
+            }
+        }
+        plan
+    }
+}
+
+/// What installing a pack version would do, for both sides
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackInstallPlan {
+    pub name: String,
+    pub version_number: String,
+    pub mc_version: String,
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+    pub client: SidePlan,
+    pub server: SidePlan,
+}
+
+/// Files to install on one side: the required ones and the chosen optional ones.
+/// `optional` set to `None` installs every optional file. Choosing a file that
+/// is not optional on this side is an error
+pub fn select_files<'a>(
+    index: &'a ModpackIndex,
+    is_server: bool,
+    optional: Option<&[String]>,
+) -> AppResult<Vec<&'a ModpackFile>> {
+    if let Some(chosen) = optional {
+        for path in chosen {
+            let is_optional = index
+                .files
+                .iter()
+                .any(|f| &f.path == path && file_support(f, is_server) == FileSupport::Optional);
+            if !is_optional {
+                return Err(AppError::Instance(format!(
+                    "{} is not an optional file of this modpack",
+                    path
+                )));
+            }
+        }
+    }
+
+    Ok(index
+        .files
+        .iter()
+        .filter(|f| match file_support(f, is_server) {
+            FileSupport::Required => true,
+            FileSupport::Optional => optional.is_none_or(|chosen| chosen.contains(&f.path)),
+            FileSupport::Unsupported => false,
+        })
+        .collect())
+}
+
+/// Optional files of one side that are not in `selected`
+pub fn declined_files(
+    index: &ModpackIndex,
+    is_server: bool,
+    selected: &[&ModpackFile],
+) -> BTreeSet<String> {
+    index
+        .files
+        .iter()
+        .filter(|f| file_support(f, is_server) == FileSupport::Optional)
+        .filter(|f| !selected.iter().any(|s| s.path == f.path))
+        .map(|f| f.path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modrinth::commands::{ModpackFileEnv, ModpackFileHashes};
+
+    fn file(path: &str, client: Option<&str>, server: Option<&str>) -> ModpackFile {
+        ModpackFile {
+            path: path.to_string(),
+            hashes: ModpackFileHashes {
+                sha1: "a".to_string(),
+                sha512: "b".to_string(),
+            },
+            downloads: vec![format!(
+                "https://cdn.modrinth.com/data/P{}/versions/V/{}",
+                path.len(),
+                path
+            )],
+            file_size: 10,
+            env: (client.is_some() || server.is_some()).then(|| ModpackFileEnv {
+                client: client.map(str::to_string),
+                server: server.map(str::to_string),
+            }),
+        }
+    }
+
+    fn index() -> ModpackIndex {
+        ModpackIndex {
+            format_version: 1,
+            game: "minecraft".to_string(),
+            version_id: "1.0".to_string(),
+            name: "Pack".to_string(),
+            summary: None,
+            files: vec![
+                file("mods/lithium.jar", None, None),
+                file("mods/sodium.jar", Some("required"), Some("unsupported")),
+                file("mods/zoomify.jar", Some("optional"), Some("unsupported")),
+                file("mods/spark.jar", Some("optional"), Some("optional")),
+                file("mods/ledger.jar", Some("unsupported"), Some("required")),
+            ],
+            dependencies: Default::default(),
+        }
+    }
+
+    fn paths(files: &[&ModpackFile]) -> Vec<String> {
+        files.iter().map(|f| f.path.clone()).collect()
+    }
+
+    #[test]
+    fn test_side_plan() {
+        let index = index();
+        let client = SidePlan::new(&index, false);
+        assert_eq!(client.required.len(), 2);
+        assert_eq!(client.optional.len(), 2);
+        assert_eq!(client.unsupported[0].path, "mods/ledger.jar");
+
+        let server = SidePlan::new(&index, true);
+        assert_eq!(server.required.len(), 2);
+        assert_eq!(server.optional[0].path, "mods/spark.jar");
+        assert_eq!(server.unsupported.len(), 2);
+        assert!(server.required[0].project_id.is_some());
+    }
+
+    #[test]
+    fn test_select_files() {
+        let index = index();
+
+        let all = select_files(&index, false, None).unwrap();
+        assert_eq!(all.len(), 4);
+
+        let chosen = vec!["mods/spark.jar".to_string()];
+        let client = select_files(&index, false, Some(&chosen)).unwrap();
+        assert_eq!(
+            paths(&client),
+            vec!["mods/lithium.jar", "mods/sodium.jar", "mods/spark.jar"]
+        );
+        assert_eq!(
+            declined_files(&index, false, &client),
+            BTreeSet::from(["mods/zoomify.jar".to_string()])
+        );
+
+        let server = select_files(&index, true, Some(&[])).unwrap();
+        assert_eq!(paths(&server), vec!["mods/lithium.jar", "mods/ledger.jar"]);
+
+        // Required and unsupported files cannot be chosen
+        assert!(select_files(&index, false, Some(&["mods/sodium.jar".to_string()])).is_err());
+        assert!(select_files(&index, true, Some(&["mods/zoomify.jar".to_string()])).is_err());
+    }
+}
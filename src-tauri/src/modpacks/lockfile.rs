@@ -0,0 +1,660 @@
+// Instance lockfile: a text file listing every content file of an instance with
+// where it comes from and its hashes, so the instance can be rebuilt exactly
+// (e.g. from a version-controlled copy shared by a team)
+
+use crate::content::ContentSource;
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::instance::commands::{get_content_folder, ChangeVersionRequest};
+use crate::instance::dedup;
+use crate::modrinth::commands::ModMetadata;
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Lockfile name, in the instance directory
+pub const LOCKFILE_NAME: &str = "kaizen.lock.json";
+const LOCKFILE_VERSION: u32 = 1;
+
+/// Folders covered by the lockfile besides the mods/plugins folder
+const EXTRA_CONTENT_FOLDERS: &[&str] = &["resourcepacks", "shaderpacks"];
+
+/// Folders a lockfile entry may point into, whatever the instance's loader
+const LOCKABLE_FOLDERS: &[&str] = &["mods", "plugins", "resourcepacks", "shaderpacks"];
+
+/// Where a locked file is downloaded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockSource {
+    Modrinth,
+    CurseForge,
+    /// Any HTTPS URL
+    Url,
+    /// Not downloadable, only verified
+    Local,
+}
+
+/// Side a locked file is installed on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockSide {
+    #[default]
+    Both,
+    Client,
+    Server,
+}
+
+impl LockSide {
+    /// Side from Modrinth's client_side/server_side values
+    pub fn from_env(client_side: Option<&str>, server_side: Option<&str>) -> Self {
+        match (client_side, server_side) {
+            (Some("unsupported"), _) => Self::Server,
+            (_, Some("unsupported")) => Self::Client,
+            _ => Self::Both,
+        }
+    }
+
+    pub fn applies_to(&self, is_server: bool) -> bool {
+        match self {
+            Self::Both => true,
+            Self::Client => !is_server,
+            Self::Server => is_server,
+        }
+    }
+}
+
+/// A content file of the instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFile {
+    /// Path relative to the instance directory
+    pub path: String,
+    pub source: LockSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha512: Option<String>,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub side: LockSide,
+}
+
+impl LockedFile {
+    /// Check file contents against every hash of the entry
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        use sha2::Digest;
+        let checks = [
+            (&self.sha1, hex::encode(sha1::Sha1::digest(bytes))),
+            (&self.sha256, hex::encode(sha2::Sha256::digest(bytes))),
+            (&self.sha512, hex::encode(sha2::Sha512::digest(bytes))),
+        ];
+        let mut any = false;
+        for (expected, actual) in checks {
+            if let Some(expected) = expected {
+                any = true;
+                if !expected.eq_ignore_ascii_case(&actual) {
+                    return false;
+                }
+            }
+        }
+        any
+    }
+}
+
+/// Lockfile of an instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceLockfile {
+    pub lockfile_version: u32,
+    pub minecraft: String,
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+    /// Sorted by path
+    pub files: Vec<LockedFile>,
+}
+
+impl InstanceLockfile {
+    pub fn new(instance: &Instance, mut files: Vec<LockedFile>) -> Self {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self {
+            lockfile_version: LOCKFILE_VERSION,
+            minecraft: instance.mc_version.clone(),
+            loader: instance.loader.clone(),
+            loader_version: instance.loader_version.clone(),
+            files,
+        }
+    }
+
+    pub async fn load(instance_dir: &Path) -> AppResult<Option<Self>> {
+        let path = instance_dir.join(LOCKFILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to read {}: {}", LOCKFILE_NAME, e)))?;
+        let lockfile: Self = serde_json::from_str(&content)
+            .map_err(|e| AppError::Instance(format!("Invalid {}: {}", LOCKFILE_NAME, e)))?;
+        lockfile.validate()?;
+        Ok(Some(lockfile))
+    }
+
+    pub async fn save(&self, instance_dir: &Path) -> AppResult<()> {
+        let mut content = serde_json::to_string_pretty(self)?;
+        content.push('\n');
+        tokio::fs::write(instance_dir.join(LOCKFILE_NAME), content)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to write {}: {}", LOCKFILE_NAME, e)))
+    }
+
+    /// Reject lockfiles that could write outside the instance or cannot be verified
+    pub fn validate(&self) -> AppResult<()> {
+        if self.lockfile_version != LOCKFILE_VERSION {
+            return Err(AppError::Instance(format!(
+                "Unsupported lockfile version {}",
+                self.lockfile_version
+            )));
+        }
+        let mut paths = HashSet::new();
+        for file in &self.files {
+            if !super::is_safe_relative_path(&file.path) {
+                return Err(AppError::Instance(format!(
+                    "Lockfile path escapes the instance: {}",
+                    file.path
+                )));
+            }
+            if !is_lockable_path(&file.path) {
+                return Err(AppError::Instance(format!(
+                    "Lockfile path is not a content file: {}",
+                    file.path
+                )));
+            }
+            if !paths.insert(file.path.as_str()) {
+                return Err(AppError::Instance(format!(
+                    "Duplicate lockfile path: {}",
+                    file.path
+                )));
+            }
+            if file.sha1.is_none() && file.sha256.is_none() && file.sha512.is_none() {
+                return Err(AppError::Instance(format!("No hash for {}", file.path)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fields of `.meta.json` used by the lockfile
+#[derive(Debug, Deserialize)]
+struct StoredMeta {
+    project_id: String,
+    version_id: Option<String>,
+    #[serde(default)]
+    client_side: Option<String>,
+    #[serde(default)]
+    server_side: Option<String>,
+    #[serde(default)]
+    source: Option<String>,
+}
+
+/// Folders whose files the lockfile covers
+pub fn content_folders(instance: &Instance) -> Vec<&'static str> {
+    let mut folders = vec![get_content_folder(
+        instance.loader.as_deref(),
+        instance.is_server,
+    )];
+    if !instance.is_server {
+        folders.extend(EXTRA_CONTENT_FOLDERS);
+    }
+    folders
+}
+
+fn is_content_file(name: &str) -> bool {
+    name.ends_with(".jar") || name.ends_with(".zip")
+}
+
+/// Whether a path is a content file directly inside a folder lockfiles cover
+pub fn is_lockable_path(path: &str) -> bool {
+    path.split_once('/').is_some_and(|(folder, name)| {
+        LOCKABLE_FOLDERS.contains(&folder) && !name.contains('/') && is_content_file(name)
+    })
+}
+
+/// Enabled content files (relative paths) of the covered folders
+async fn list_content_files(instance_dir: &Path, folders: &[&str]) -> Vec<String> {
+    let mut files = Vec::new();
+    for folder in folders {
+        let Ok(mut entries) = tokio::fs::read_dir(instance_dir.join(folder)).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_content_file(&name) && entry.path().is_file() {
+                files.push(format!("{}/{}", folder, name));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn meta_path(instance_dir: &Path, relative: &str) -> std::path::PathBuf {
+    let base = relative.trim_end_matches(".jar").trim_end_matches(".zip");
+    instance_dir.join(format!("{}.meta.json", base))
+}
+
+async fn read_meta(instance_dir: &Path, relative: &str) -> Option<StoredMeta> {
+    let content = tokio::fs::read_to_string(meta_path(instance_dir, relative))
+        .await
+        .ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Build the lockfile from the content files of an instance.
+/// Files are resolved through their `.meta.json` source, or by hash on Modrinth
+pub async fn generate(
+    state: &AppState,
+    instance: &Instance,
+    instance_dir: &Path,
+) -> AppResult<InstanceLockfile> {
+    let relative_paths = list_content_files(instance_dir, &content_folders(instance)).await;
+
+    // Hash in a blocking task, jars can be large
+    let dir = instance_dir.to_path_buf();
+    let hashed: Vec<(String, String, String, u64)> = tokio::task::spawn_blocking(move || {
+        use sha2::Digest;
+        relative_paths
+            .into_iter()
+            .filter_map(|relative| {
+                let bytes = std::fs::read(dir.join(&relative)).ok()?;
+                Some((
+                    relative,
+                    hex::encode(sha1::Sha1::digest(&bytes)),
+                    hex::encode(sha2::Sha512::digest(&bytes)),
+                    bytes.len() as u64,
+                ))
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| AppError::Io(format!("Failed to hash content files: {}", e)))?;
+
+    // Modrinth knows most files by hash, including ones installed by hand
+    let sha512s: Vec<String> = hashed.iter().map(|(_, _, h, _)| h.clone()).collect();
+    let modrinth = crate::modrinth::ModrinthClient::new(&state.http_client);
+    let modrinth_versions = modrinth
+        .get_versions_by_hashes(&sha512s, "sha512")
+        .await
+        .unwrap_or_default();
+
+    let curseforge_key = crate::curseforge::api_key(&state.db).await;
+    let curseforge =
+        curseforge_key.map(|key| crate::curseforge::CurseForgeClient::new(&state.http_client, key));
+
+    let mut files = Vec::new();
+    for (relative, sha1, sha512, size) in hashed {
+        let meta = read_meta(instance_dir, &relative).await;
+        let side = meta
+            .as_ref()
+            .map(|m| LockSide::from_env(m.client_side.as_deref(), m.server_side.as_deref()))
+            .unwrap_or_default();
+
+        let mut file = LockedFile {
+            path: relative,
+            source: LockSource::Local,
+            project_id: None,
+            version_id: None,
+            url: None,
+            sha1: Some(sha1),
+            sha256: None,
+            sha512: Some(sha512.clone()),
+            size,
+            side,
+        };
+
+        let meta_source = meta
+            .as_ref()
+            .and_then(|m| ContentSource::from_meta(m.source.as_deref()));
+        if let (Some(meta), Some(ContentSource::CurseForge)) = (&meta, meta_source) {
+            file.source = LockSource::CurseForge;
+            file.project_id = Some(meta.project_id.clone());
+            file.version_id = meta.version_id.clone();
+            if let (Some(client), Some(file_id)) = (&curseforge, &meta.version_id) {
+                if let Ok(cf_file) = client.get_mod_file(&meta.project_id, file_id).await {
+                    file.url = cf_file.download_url;
+                }
+            }
+        } else if let Some(version) = modrinth_versions.get(&sha512) {
+            file.source = LockSource::Modrinth;
+            file.project_id = Some(version.project_id.clone());
+            file.version_id = Some(version.id.clone());
+            file.url = version
+                .files
+                .iter()
+                .find(|f| f.hashes.sha512 == sha512)
+                .map(|f| f.url.clone());
+        }
+
+        files.push(file);
+    }
+
+    Ok(InstanceLockfile::new(instance, files))
+}
+
+/// Result of a sync
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LockfileSyncReport {
+    /// Files already matching the lockfile
+    pub verified: Vec<String>,
+    pub downloaded: Vec<String>,
+    /// Content files not in the lockfile
+    pub removed: Vec<String>,
+    /// Local files missing from the instance
+    pub missing: Vec<String>,
+    /// Files that could not be downloaded or failed verification: (path, error)
+    pub failed: Vec<(String, String)>,
+    pub version_changed: bool,
+}
+
+/// Download a locked file and check its hashes
+pub(crate) async fn download_locked_file(
+    http_client: &reqwest::Client,
+    file: &LockedFile,
+    dest: &Path,
+) -> AppResult<()> {
+    let url = file
+        .url
+        .as_deref()
+        .ok_or_else(|| AppError::Download("No download URL".to_string()))?;
+
+    match file.source {
+        LockSource::Modrinth | LockSource::CurseForge => {
+            crate::modrinth::validate_download_url(url)
+                .map_err(|e| AppError::Network(e.to_string()))?;
+        }
+        // Arbitrary hosts are fine, the hashes pin the contents
+        _ => {
+            if !url.starts_with("https://") {
+                return Err(AppError::Download(format!("Not an HTTPS URL: {}", url)));
+            }
+        }
+    }
+
+    let response = http_client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(AppError::Download(format!(
+            "Download returned status {}",
+            response.status()
+        )));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    if !file.verify(&bytes) {
+        return Err(AppError::Download("Hash mismatch".to_string()));
+    }
+
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
+    }
+    dedup::unlink_shared(dest)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", file.path, e)))?;
+    tokio::fs::write(dest, &bytes)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", file.path, e)))
+}
+
+/// Write the `.meta.json` of a file downloaded from Modrinth or CurseForge,
+/// like installs from the content browser do
+async fn write_locked_meta(
+    state: &AppState,
+    file: &LockedFile,
+    instance_dir: &Path,
+) -> AppResult<()> {
+    let source = match file.source {
+        LockSource::Modrinth => ContentSource::Modrinth,
+        LockSource::CurseForge => ContentSource::CurseForge,
+        LockSource::Url | LockSource::Local => return Ok(()),
+    };
+    let (Some(project_id), Some(version_id), Some((folder, filename))) = (
+        &file.project_id,
+        &file.version_id,
+        file.path.split_once('/'),
+    ) else {
+        return Ok(());
+    };
+
+    let provider = crate::content::provider_for_state(state, source).await?;
+    let project = provider.fetch_project(project_id).await?;
+    let version = provider.fetch_version(project_id, version_id).await?;
+    ModMetadata::from_content(&project, &version)
+        .write_for(&instance_dir.join(folder), filename)
+        .await;
+    Ok(())
+}
+
+/// Make the instance directory match its lockfile: switch game versions if
+/// needed, download missing or changed files and remove content files the
+/// lockfile does not list
+pub async fn sync(
+    state: &AppState,
+    app: &AppHandle,
+    instance: &Instance,
+    instance_dir: &Path,
+    lockfile: &InstanceLockfile,
+) -> AppResult<LockfileSyncReport> {
+    lockfile.validate()?;
+
+    if state
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot sync while the instance is running".to_string(),
+        ));
+    }
+
+    let mut report = LockfileSyncReport::default();
+
+    if lockfile.minecraft != instance.mc_version
+        || lockfile.loader != instance.loader
+        || lockfile.loader_version != instance.loader_version
+    {
+        crate::instance::commands::change_version(
+            state,
+            app,
+            &ChangeVersionRequest {
+                instance_id: instance.id.clone(),
+                new_mc_version: lockfile.minecraft.clone(),
+                new_loader: lockfile.loader.clone(),
+                new_loader_version: lockfile.loader_version.clone(),
+                mods_to_update: Vec::new(),
+            },
+        )
+        .await?;
+        report.version_changed = true;
+    }
+
+    let wanted: BTreeMap<&str, &LockedFile> = lockfile
+        .files
+        .iter()
+        .filter(|f| f.side.applies_to(instance.is_server))
+        .map(|f| (f.path.as_str(), f))
+        .collect();
+
+    let folders = content_folders(instance);
+    for (relative, file) in &wanted {
+        let in_content_folder = relative
+            .split_once('/')
+            .is_some_and(|(folder, _)| folders.contains(&folder));
+        if !in_content_folder {
+            report.failed.push((
+                relative.to_string(),
+                "Not a content folder of this instance".to_string(),
+            ));
+            continue;
+        }
+
+        let dest = instance_dir.join(relative);
+        if let Ok(bytes) = tokio::fs::read(&dest).await {
+            if file.verify(&bytes) {
+                report.verified.push(relative.to_string());
+                continue;
+            }
+        }
+
+        if file.source == LockSource::Local || file.url.is_none() {
+            report.missing.push(relative.to_string());
+            continue;
+        }
+
+        match download_locked_file(&state.http_client, file, &dest).await {
+            Ok(()) => {
+                // Metadata of the file that was there before no longer applies
+                let _ = tokio::fs::remove_file(meta_path(instance_dir, relative)).await;
+                if let Err(e) = write_locked_meta(state, file, instance_dir).await {
+                    log::warn!("Failed to write metadata for {}: {}", relative, e);
+                }
+                report.downloaded.push(relative.to_string());
+            }
+            Err(e) => report.failed.push((relative.to_string(), e.to_string())),
+        }
+    }
+
+    // Remove content the lockfile does not list (disabled files are left alone)
+    for relative in list_content_files(instance_dir, &folders).await {
+        if wanted.contains_key(relative.as_str()) {
+            continue;
+        }
+        let _ = tokio::fs::remove_file(meta_path(instance_dir, &relative)).await;
+        match tokio::fs::remove_file(instance_dir.join(&relative)).await {
+            Ok(()) => report.removed.push(relative),
+            Err(e) => report.failed.push((relative, e.to_string())),
+        }
+    }
+
+    log::info!(
+        "Synced {} with its lockfile: {} verified, {} downloaded, {} removed, {} missing, {} failed",
+        instance.name,
+        report.verified.len(),
+        report.downloaded.len(),
+        report.removed.len(),
+        report.missing.len(),
+        report.failed.len()
+    );
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked(path: &str) -> LockedFile {
+        LockedFile {
+            path: path.to_string(),
+            source: LockSource::Local,
+            project_id: None,
+            version_id: None,
+            url: None,
+            // sha1("abc")
+            sha1: Some("a9993e364706816aba3e25717850c26c9cd0d89d".to_string()),
+            sha256: None,
+            sha512: None,
+            size: 3,
+            side: LockSide::Both,
+        }
+    }
+
+    fn lockfile(files: Vec<LockedFile>) -> InstanceLockfile {
+        InstanceLockfile {
+            lockfile_version: 1,
+            minecraft: "1.20.1".to_string(),
+            loader: Some("fabric".to_string()),
+            loader_version: Some("0.15.11".to_string()),
+            files,
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut file = locked("mods/a.jar");
+        assert!(file.verify(b"abc"));
+        assert!(!file.verify(b"abd"));
+
+        file.sha256 =
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string());
+        assert!(file.verify(b"abc"));
+
+        file.sha1 = None;
+        file.sha256 = None;
+        assert!(!file.verify(b"abc"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(lockfile(vec![locked("mods/a.jar"), locked("mods/b.jar")])
+            .validate()
+            .is_ok());
+        assert!(lockfile(vec![locked("../a.jar")]).validate().is_err());
+        assert!(lockfile(vec![locked("saves/x.zip")]).validate().is_err());
+        assert!(lockfile(vec![locked("config/a.jar")]).validate().is_err());
+        assert!(lockfile(vec![locked("mods/sub/a.jar")]).validate().is_err());
+        assert!(lockfile(vec![locked("mods/a.txt")]).validate().is_err());
+        assert!(
+            lockfile(vec![locked("plugins/a.jar"), locked("shaderpacks/b.zip")])
+                .validate()
+                .is_ok()
+        );
+        assert!(lockfile(vec![locked("mods/a.jar"), locked("mods/a.jar")])
+            .validate()
+            .is_err());
+
+        let mut unhashed = locked("mods/a.jar");
+        unhashed.sha1 = None;
+        assert!(lockfile(vec![unhashed]).validate().is_err());
+    }
+
+    #[test]
+    fn test_side() {
+        assert_eq!(
+            LockSide::from_env(Some("required"), Some("unsupported")),
+            LockSide::Client
+        );
+        assert_eq!(
+            LockSide::from_env(Some("unsupported"), Some("required")),
+            LockSide::Server
+        );
+        assert_eq!(LockSide::from_env(None, None), LockSide::Both);
+        assert!(!LockSide::Client.applies_to(true));
+        assert!(LockSide::Both.applies_to(true));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let lockfile = lockfile(vec![locked("mods/a.jar")]);
+        let json = serde_json::to_string_pretty(&lockfile).unwrap();
+        assert!(!json.contains("project_id"));
+        let parsed: InstanceLockfile = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, lockfile);
+    }
+}
@@ -1,3 +1,358 @@
-// TODO: Implement modpack modules in Phase 7
-// pub mod modrinth;
-// pub mod curseforge;
+// Modpacks: exporting instances as packs other launchers can install, updating
+// instances installed from a pack, and instance lockfiles
+
+pub mod commands;
+pub mod curseforge;
+pub mod install_plan;
+pub mod lockfile;
+pub mod modrinth;
+pub mod packwiz;
+pub mod update;
+
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Folders whose files can be replaced by a download from a content provider
+const RESOLVABLE_FOLDERS: &[&str] = &["mods", "resourcepacks", "shaderpacks"];
+
+/// Top-level entries that are never worth exporting
+const IGNORED_ENTRIES: &[&str] = &[
+    "logs",
+    "crash-reports",
+    "natives",
+    ".fabric",
+    ".quilt",
+    ".mixin.out",
+    "debug",
+    ".cache",
+];
+
+/// Top-level entries selected by default
+const DEFAULT_ENTRIES: &[&str] = &[
+    "mods",
+    "config",
+    "defaultconfigs",
+    "kubejs",
+    "resourcepacks",
+    "shaderpacks",
+    "options.txt",
+];
+
+/// Which side an exported path is meant for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportSide {
+    #[default]
+    Both,
+    Client,
+    Server,
+}
+
+impl ExportSide {
+    /// Override folder used in the archive
+    pub fn overrides_folder(&self) -> &'static str {
+        match self {
+            Self::Both => "overrides",
+            Self::Client => "client-overrides",
+            Self::Server => "server-overrides",
+        }
+    }
+}
+
+/// A path of the instance selected for export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportEntry {
+    /// Path relative to the instance directory (file or folder)
+    pub path: String,
+    #[serde(default)]
+    pub side: ExportSide,
+}
+
+/// Options shared by all modpack exporters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackExportOptions {
+    /// Pack name, defaults to the instance name
+    pub name: Option<String>,
+    /// Pack version, e.g. "1.0.0"
+    pub version: String,
+    pub summary: Option<String>,
+    pub author: Option<String>,
+    pub entries: Vec<ExportEntry>,
+    /// Where to write the pack
+    pub output_path: String,
+}
+
+/// Outcome of an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackExportResult {
+    pub path: String,
+    /// Files listed as downloads in the manifest
+    pub resolved_files: usize,
+    /// Files copied into the archive
+    pub override_files: usize,
+    /// Files that could not be resolved, and other things the user should know
+    pub warnings: Vec<String>,
+}
+
+/// A top-level entry of an instance that can be exported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportCandidate {
+    pub path: String,
+    pub is_dir: bool,
+    pub size_bytes: u64,
+    pub default_selected: bool,
+}
+
+/// A file selected for export
+#[derive(Debug, Clone)]
+pub struct ExportFile {
+    pub source: PathBuf,
+    /// Path relative to the instance directory, with forward slashes
+    pub relative: String,
+    pub side: ExportSide,
+    pub size: u64,
+}
+
+impl ExportFile {
+    /// Whether the file sits directly in a mods/resourcepacks/shaderpacks folder
+    pub fn is_resolvable(&self) -> bool {
+        let mut parts = self.relative.split('/');
+        let (Some(folder), Some(name), None) = (parts.next(), parts.next(), parts.next()) else {
+            return false;
+        };
+        RESOLVABLE_FOLDERS.contains(&folder) && (name.ends_with(".jar") || name.ends_with(".zip"))
+    }
+
+    /// Path of the file inside the archive's override folder
+    pub fn archive_path(&self) -> String {
+        format!("{}/{}", self.side.overrides_folder(), self.relative)
+    }
+}
+
+/// List the top-level entries of an instance for the export selection
+pub async fn list_export_candidates(instance_dir: &Path) -> AppResult<Vec<ExportCandidate>> {
+    let mut entries = tokio::fs::read_dir(instance_dir)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to read instance directory: {}", e)))?;
+
+    let mut candidates = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if IGNORED_ENTRIES.contains(&name.as_str()) {
+            continue;
+        }
+
+        let path = entry.path();
+        let is_dir = path.is_dir();
+        let size_bytes = if is_dir {
+            crate::instance::worlds::get_directory_size(&path)
+                .await
+                .unwrap_or(0)
+        } else {
+            entry.metadata().await.map(|m| m.len()).unwrap_or(0)
+        };
+
+        candidates.push(ExportCandidate {
+            default_selected: DEFAULT_ENTRIES.contains(&name.as_str()),
+            path: name,
+            is_dir,
+            size_bytes,
+        });
+    }
+
+    candidates.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.path.cmp(&b.path)));
+    Ok(candidates)
+}
+
+/// Check that a user-provided relative path stays inside the instance
+pub(crate) fn is_safe_relative_path(path: &str) -> bool {
+    let path = Path::new(path);
+    !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Expand the selected entries to the files they contain.
+/// Launcher metadata (`.meta.json`) is skipped; a file selected twice keeps its first side
+pub fn collect_export_files(
+    instance_dir: &Path,
+    entries: &[ExportEntry],
+) -> AppResult<Vec<ExportFile>> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+
+    for entry in entries {
+        if !is_safe_relative_path(&entry.path) {
+            return Err(AppError::Instance(format!(
+                "Invalid export path: {}",
+                entry.path
+            )));
+        }
+
+        let root = instance_dir.join(&entry.path);
+        if !root.exists() {
+            continue;
+        }
+
+        for item in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
+            if !item.file_type().is_file() {
+                continue;
+            }
+
+            let name = item.file_name().to_string_lossy();
+            if name.ends_with(".meta.json") {
+                continue;
+            }
+
+            let Ok(relative) = item.path().strip_prefix(instance_dir) else {
+                continue;
+            };
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if !seen.insert(relative.clone()) {
+                continue;
+            }
+
+            files.push(ExportFile {
+                source: item.path().to_path_buf(),
+                size: item.metadata().map(|m| m.len()).unwrap_or(0),
+                relative,
+                side: entry.side,
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+/// Write a modpack archive: the manifest at the root followed by the override files
+pub fn write_pack_archive(
+    output_path: &Path,
+    manifest_name: &str,
+    manifest_json: &str,
+    overrides: &[ExportFile],
+) -> AppResult<()> {
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::Io(format!("Failed to create output directory: {}", e)))?;
+    }
+
+    let file = std::fs::File::create(output_path)
+        .map_err(|e| AppError::Io(format!("Failed to create modpack file: {}", e)))?;
+
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .compression_level(Some(6));
+
+    zip.start_file(manifest_name, options)
+        .map_err(|e| AppError::Io(format!("Failed to start {}: {}", manifest_name, e)))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", manifest_name, e)))?;
+
+    for file in overrides {
+        let archive_path = file.archive_path();
+        let mut source = std::fs::File::open(&file.source).map_err(|e| {
+            AppError::Io(format!("Failed to open {}: {}", file.source.display(), e))
+        })?;
+
+        zip.start_file(archive_path.as_str(), options)
+            .map_err(|e| AppError::Io(format!("Failed to start {}: {}", archive_path, e)))?;
+        std::io::copy(&mut source, &mut zip)
+            .map_err(|e| AppError::Io(format!("Failed to write {}: {}", archive_path, e)))?;
+    }
+
+    zip.finish()
+        .map_err(|e| AppError::Io(format!("Failed to finish modpack: {}", e)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export_file(relative: &str, side: ExportSide) -> ExportFile {
+        ExportFile {
+            source: PathBuf::from(relative),
+            relative: relative.to_string(),
+            side,
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn test_is_resolvable() {
+        assert!(export_file("mods/sodium.jar", ExportSide::Both).is_resolvable());
+        assert!(export_file("resourcepacks/faithful.zip", ExportSide::Both).is_resolvable());
+        assert!(!export_file("mods/sub/inner.jar", ExportSide::Both).is_resolvable());
+        assert!(!export_file("config/sodium.json", ExportSide::Both).is_resolvable());
+        assert!(!export_file("mods/old.jar.disabled", ExportSide::Both).is_resolvable());
+    }
+
+    #[test]
+    fn test_archive_path_uses_side_folder() {
+        assert_eq!(
+            export_file("config/a.toml", ExportSide::Server).archive_path(),
+            "server-overrides/config/a.toml"
+        );
+        assert_eq!(
+            export_file("options.txt", ExportSide::Both).archive_path(),
+            "overrides/options.txt"
+        );
+    }
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert!(is_safe_relative_path("config"));
+        assert!(is_safe_relative_path("saves/My World"));
+        assert!(!is_safe_relative_path("../other"));
+        assert!(!is_safe_relative_path("/etc"));
+        assert!(!is_safe_relative_path(""));
+    }
+
+    #[test]
+    fn test_collect_export_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("mods")).unwrap();
+        std::fs::create_dir_all(dir.join("config/sub")).unwrap();
+        std::fs::write(dir.join("mods/a.jar"), b"a").unwrap();
+        std::fs::write(dir.join("mods/a.meta.json"), b"{}").unwrap();
+        std::fs::write(dir.join("config/sub/b.toml"), b"b").unwrap();
+
+        let entries = vec![
+            ExportEntry {
+                path: "mods".to_string(),
+                side: ExportSide::Both,
+            },
+            ExportEntry {
+                path: "config".to_string(),
+                side: ExportSide::Client,
+            },
+            ExportEntry {
+                path: "config/sub".to_string(),
+                side: ExportSide::Server,
+            },
+        ];
+
+        let mut files = collect_export_files(dir, &entries).unwrap();
+        files.sort_by(|a, b| a.relative.cmp(&b.relative));
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].relative, "config/sub/b.toml");
+        assert_eq!(files[0].side, ExportSide::Client);
+        assert_eq!(files[1].relative, "mods/a.jar");
+    }
+}
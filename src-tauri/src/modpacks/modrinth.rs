@@ -0,0 +1,455 @@
+// Modrinth modpack (.mrpack) export
+// Format: https://support.modrinth.com/en/articles/8802351-modrinth-modpack-format-mrpack
+
+use super::{ExportFile, ExportSide, ModpackExportOptions, ModpackExportResult};
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::modrinth::commands::{ModpackFile, ModpackFileEnv, ModpackFileHashes, ModpackIndex};
+use crate::modrinth::ModrinthClient;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+const INDEX_FILE: &str = "modrinth.index.json";
+const FORMAT_VERSION: u32 = 1;
+
+/// Hosts the format allows in `downloads`
+const ALLOWED_DOWNLOAD_HOSTS: &[&str] = &[
+    "cdn.modrinth.com",
+    "github.com",
+    "raw.githubusercontent.com",
+    "gitlab.com",
+];
+
+/// Dependency keys the format allows
+const ALLOWED_DEPENDENCIES: &[&str] = &[
+    "minecraft",
+    "forge",
+    "neoforge",
+    "fabric-loader",
+    "quilt-loader",
+];
+
+/// Dependency key of a loader in `modrinth.index.json`
+pub fn loader_dependency_key(loader: &str) -> Option<&'static str> {
+    match loader.to_lowercase().as_str() {
+        "fabric" => Some("fabric-loader"),
+        "quilt" => Some("quilt-loader"),
+        "forge" => Some("forge"),
+        "neoforge" => Some("neoforge"),
+        _ => None,
+    }
+}
+
+/// Build the `dependencies` map from the instance's game and loader versions
+fn index_dependencies(instance: &Instance) -> AppResult<HashMap<String, String>> {
+    let mut dependencies = HashMap::new();
+    dependencies.insert("minecraft".to_string(), instance.mc_version.clone());
+
+    if let Some(loader) = instance.loader.as_deref().filter(|l| !l.is_empty()) {
+        let key = loader_dependency_key(loader).ok_or_else(|| {
+            AppError::Instance(format!(
+                "Modrinth modpacks do not support {} instances",
+                loader
+            ))
+        })?;
+        let version = instance
+            .loader_version
+            .clone()
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| {
+                AppError::Instance(format!(
+                    "The {} version of this instance is unknown",
+                    loader
+                ))
+            })?;
+        dependencies.insert(key.to_string(), version);
+    }
+
+    Ok(dependencies)
+}
+
+/// Map a Modrinth project side to an `env` value
+fn env_value(project_side: Option<&str>) -> String {
+    match project_side {
+        Some("optional") => "optional",
+        Some("unsupported") => "unsupported",
+        _ => "required",
+    }
+    .to_string()
+}
+
+/// `env` of a resolved file, restricted by the side the user exported it for
+fn file_env(
+    client_side: Option<&str>,
+    server_side: Option<&str>,
+    side: ExportSide,
+) -> ModpackFileEnv {
+    let mut client = env_value(client_side);
+    let mut server = env_value(server_side);
+    match side {
+        ExportSide::Client => server = "unsupported".to_string(),
+        ExportSide::Server => client = "unsupported".to_string(),
+        ExportSide::Both => {}
+    }
+    ModpackFileEnv {
+        client: Some(client),
+        server: Some(server),
+    }
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_valid_pack_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.contains('\\')
+        && !path.contains(':')
+        && path
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..")
+}
+
+/// Check an index against the format spec, returning every problem found
+pub fn validate_index(index: &ModpackIndex, override_paths: &[String]) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if index.format_version != FORMAT_VERSION {
+        errors.push(format!(
+            "Unsupported formatVersion {}",
+            index.format_version
+        ));
+    }
+    if index.game != "minecraft" {
+        errors.push(format!("Unsupported game '{}'", index.game));
+    }
+    if index.version_id.trim().is_empty() {
+        errors.push("versionId must not be empty".to_string());
+    }
+    if index.name.trim().is_empty() {
+        errors.push("name must not be empty".to_string());
+    }
+
+    if !index.dependencies.contains_key("minecraft") {
+        errors.push("dependencies must include minecraft".to_string());
+    }
+    for key in index.dependencies.keys() {
+        if !ALLOWED_DEPENDENCIES.contains(&key.as_str()) {
+            errors.push(format!("Unknown dependency '{}'", key));
+        }
+    }
+    let loaders = index
+        .dependencies
+        .keys()
+        .filter(|k| k.as_str() != "minecraft")
+        .count();
+    if loaders > 1 {
+        errors.push("Only one mod loader can be listed in dependencies".to_string());
+    }
+
+    let mut paths = HashSet::new();
+    for file in &index.files {
+        if !is_valid_pack_path(&file.path) {
+            errors.push(format!("Invalid file path '{}'", file.path));
+        }
+        if !paths.insert(file.path.as_str()) {
+            errors.push(format!("Duplicate file path '{}'", file.path));
+        }
+        if !is_hex(&file.hashes.sha1, 40) {
+            errors.push(format!("Invalid sha1 for '{}'", file.path));
+        }
+        if !is_hex(&file.hashes.sha512, 128) {
+            errors.push(format!("Invalid sha512 for '{}'", file.path));
+        }
+        if file.downloads.is_empty() {
+            errors.push(format!("No download URL for '{}'", file.path));
+        }
+        for download in &file.downloads {
+            let host = url::Url::parse(download)
+                .ok()
+                .filter(|u| u.scheme() == "https")
+                .and_then(|u| u.host_str().map(str::to_string));
+            if !host.is_some_and(|h| ALLOWED_DOWNLOAD_HOSTS.contains(&h.as_str())) {
+                errors.push(format!(
+                    "Download URL of '{}' is not on an allowed host",
+                    file.path
+                ));
+            }
+        }
+        if let Some(env) = &file.env {
+            for value in [&env.client, &env.server].into_iter().flatten() {
+                if !matches!(value.as_str(), "required" | "optional" | "unsupported") {
+                    errors.push(format!("Invalid env '{}' for '{}'", value, file.path));
+                }
+            }
+        }
+    }
+
+    for path in override_paths {
+        if !is_valid_pack_path(path) {
+            errors.push(format!("Invalid override path '{}'", path));
+        }
+        // Same destination as a downloaded file: the override would silently win
+        if paths.contains(path.as_str()) {
+            errors.push(format!("'{}' is both downloaded and overridden", path));
+        }
+    }
+
+    errors
+}
+
+/// Files resolved to Modrinth downloads, keyed by their index in the export list
+async fn resolve_files(
+    client: &ModrinthClient<'_>,
+    files: &[ExportFile],
+    warnings: &mut Vec<String>,
+) -> HashMap<usize, ModpackFile> {
+    let candidates: Vec<(usize, std::path::PathBuf)> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| f.is_resolvable())
+        .map(|(i, f)| (i, f.source.clone()))
+        .collect();
+
+    if candidates.is_empty() {
+        return HashMap::new();
+    }
+
+    // Hash in a blocking task, jars can be large
+    let hashed: Vec<(usize, String)> = tokio::task::spawn_blocking(move || {
+        use sha1::{Digest, Sha1};
+        candidates
+            .into_iter()
+            .filter_map(|(i, path)| {
+                let bytes = std::fs::read(&path).ok()?;
+                Some((i, hex::encode(Sha1::digest(&bytes))))
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default();
+
+    let hashes: Vec<String> = hashed.iter().map(|(_, h)| h.clone()).collect();
+    let versions = match client.get_versions_by_hashes(&hashes, "sha1").await {
+        Ok(versions) => versions,
+        Err(e) => {
+            warnings.push(format!(
+                "Could not reach Modrinth, all files were added as overrides: {}",
+                e
+            ));
+            return HashMap::new();
+        }
+    };
+
+    let project_ids: Vec<&str> = versions
+        .values()
+        .map(|v| v.project_id.as_str())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let sides: HashMap<String, (String, String)> = client
+        .get_projects(&project_ids)
+        .await
+        .map(|projects| {
+            projects
+                .into_iter()
+                .map(|p| (p.id, (p.client_side, p.server_side)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut resolved = HashMap::new();
+    for (i, sha1) in hashed {
+        let Some(version) = versions.get(&sha1) else {
+            continue;
+        };
+        let Some(version_file) = version.files.iter().find(|f| f.hashes.sha1 == sha1) else {
+            continue;
+        };
+
+        let file = &files[i];
+        let (client_side, server_side) = sides
+            .get(&version.project_id)
+            .map(|(c, s)| (Some(c.as_str()), Some(s.as_str())))
+            .unwrap_or((None, None));
+
+        resolved.insert(
+            i,
+            ModpackFile {
+                path: file.relative.clone(),
+                hashes: ModpackFileHashes {
+                    sha1,
+                    sha512: version_file.hashes.sha512.clone(),
+                },
+                downloads: vec![version_file.url.clone()],
+                file_size: version_file.size,
+                env: Some(file_env(client_side, server_side, file.side)),
+            },
+        );
+    }
+
+    resolved
+}
+
+/// Export an instance as a `.mrpack`
+pub async fn export_mrpack(
+    http_client: &reqwest::Client,
+    instance: &Instance,
+    instance_dir: &Path,
+    options: &ModpackExportOptions,
+) -> AppResult<ModpackExportResult> {
+    let dependencies = index_dependencies(instance)?;
+    let files = super::collect_export_files(instance_dir, &options.entries)?;
+
+    let client = ModrinthClient::new(http_client);
+    let mut warnings = Vec::new();
+    let mut resolved = resolve_files(&client, &files, &mut warnings).await;
+
+    let mut index_files = Vec::new();
+    let mut overrides = Vec::new();
+    for (i, file) in files.into_iter().enumerate() {
+        match resolved.remove(&i) {
+            Some(entry) => index_files.push(entry),
+            None => {
+                if file.is_resolvable() {
+                    warnings.push(format!(
+                        "{} is not on Modrinth and was added to {}",
+                        file.relative,
+                        file.side.overrides_folder()
+                    ));
+                }
+                overrides.push(file);
+            }
+        }
+    }
+    index_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let index = ModpackIndex {
+        format_version: FORMAT_VERSION,
+        game: "minecraft".to_string(),
+        version_id: options.version.clone(),
+        name: options
+            .name
+            .clone()
+            .unwrap_or_else(|| instance.name.clone()),
+        summary: options.summary.clone().filter(|s| !s.is_empty()),
+        files: index_files,
+        dependencies,
+    };
+
+    let override_paths: Vec<String> = overrides.iter().map(|f| f.relative.clone()).collect();
+    let errors = validate_index(&index, &override_paths);
+    if !errors.is_empty() {
+        return Err(AppError::Instance(format!(
+            "The modpack does not match the .mrpack format:\n{}",
+            errors.join("\n")
+        )));
+    }
+
+    let index_json = serde_json::to_string_pretty(&index)?;
+    let output_path = std::path::PathBuf::from(&options.output_path);
+    let resolved_files = index.files.len();
+    let override_files = overrides.len();
+
+    let path = output_path.clone();
+    tokio::task::spawn_blocking(move || {
+        super::write_pack_archive(&path, INDEX_FILE, &index_json, &overrides)
+    })
+    .await
+    .map_err(|e| AppError::Io(format!("Modpack export task failed: {}", e)))??;
+
+    log::info!(
+        "Exported {} as .mrpack ({} downloads, {} overrides) to {}",
+        instance.name,
+        resolved_files,
+        override_files,
+        output_path.display()
+    );
+
+    Ok(ModpackExportResult {
+        path: output_path.to_string_lossy().to_string(),
+        resolved_files,
+        override_files,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_file(path: &str) -> ModpackFile {
+        ModpackFile {
+            path: path.to_string(),
+            hashes: ModpackFileHashes {
+                sha1: "a".repeat(40),
+                sha512: "b".repeat(128),
+            },
+            downloads: vec![format!(
+                "https://cdn.modrinth.com/data/abc/versions/1/{}",
+                path
+            )],
+            file_size: 10,
+            env: Some(file_env(
+                Some("required"),
+                Some("unsupported"),
+                ExportSide::Both,
+            )),
+        }
+    }
+
+    fn index(files: Vec<ModpackFile>) -> ModpackIndex {
+        let mut dependencies = HashMap::new();
+        dependencies.insert("minecraft".to_string(), "1.20.1".to_string());
+        dependencies.insert("fabric-loader".to_string(), "0.15.11".to_string());
+        ModpackIndex {
+            format_version: 1,
+            game: "minecraft".to_string(),
+            version_id: "1.0.0".to_string(),
+            name: "Pack".to_string(),
+            summary: None,
+            files,
+            dependencies,
+        }
+    }
+
+    #[test]
+    fn test_valid_index() {
+        let index = index(vec![valid_file("mods/sodium.jar")]);
+        assert!(validate_index(&index, &["config/sodium.json".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_index() {
+        let mut bad = valid_file("../escape.jar");
+        bad.hashes.sha1 = "xyz".to_string();
+        bad.downloads = vec!["https://example.com/a.jar".to_string()];
+
+        let mut index = index(vec![bad, valid_file("mods/a.jar")]);
+        index
+            .dependencies
+            .insert("forge".to_string(), "47.2.0".to_string());
+
+        let errors = validate_index(&index, &["mods/a.jar".to_string()]);
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_file_env() {
+        let env = file_env(Some("required"), Some("optional"), ExportSide::Both);
+        assert_eq!(env.client.as_deref(), Some("required"));
+        assert_eq!(env.server.as_deref(), Some("optional"));
+
+        let env = file_env(Some("unknown"), Some("required"), ExportSide::Client);
+        assert_eq!(env.client.as_deref(), Some("required"));
+        assert_eq!(env.server.as_deref(), Some("unsupported"));
+    }
+
+    #[test]
+    fn test_loader_dependency_key() {
+        assert_eq!(loader_dependency_key("Fabric"), Some("fabric-loader"));
+        assert_eq!(loader_dependency_key("neoforge"), Some("neoforge"));
+        assert_eq!(loader_dependency_key("paper"), None);
+    }
+}
@@ -0,0 +1,791 @@
+// packwiz pack layout (pack.toml, index.toml and .pw.toml metafiles), so packs
+// can be hosted as static files. Spec: https://packwiz.infra.link/reference/pack-format/
+
+use super::lockfile::{
+    self, InstanceLockfile, LockSide, LockSource, LockedFile, LockfileSyncReport,
+};
+use super::ExportEntry;
+use crate::db::instances::{CreateInstance, Instance};
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const PACK_FORMAT: &str = "packwiz:1.1.0";
+const PACK_FILE: &str = "pack.toml";
+const INDEX_FILE: &str = "index.toml";
+const METAFILE_SUFFIX: &str = ".pw.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackToml {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pack_format: Option<String>,
+    pub index: PackIndexRef,
+    /// "minecraft" plus one of "fabric", "forge", "neoforge", "quilt"
+    pub versions: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackIndexRef {
+    pub file: String,
+    pub hash_format: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexToml {
+    pub hash_format: String,
+    #[serde(default)]
+    pub files: Vec<IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexEntry {
+    pub file: String,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metafile: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetafileToml {
+    pub name: String,
+    pub filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<String>,
+    pub download: MetafileDownload,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update: Option<MetafileUpdate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetafileDownload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub hash_format: String,
+    pub hash: String,
+    /// "metadata:curseforge" when the URL comes from the CurseForge API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetafileUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<ModrinthUpdate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<CurseForgeUpdate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModrinthUpdate {
+    pub mod_id: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CurseForgeUpdate {
+    pub project_id: u64,
+    pub file_id: u64,
+}
+
+/// Options of a packwiz export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackwizExportOptions {
+    /// Defaults to the instance name
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    /// Other files and folders to include as plain files (e.g. "config")
+    #[serde(default)]
+    pub extra_paths: Vec<String>,
+    /// Folder to write the pack to
+    pub output_dir: String,
+}
+
+/// Outcome of a packwiz import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackwizImportResult {
+    pub instance: Instance,
+    pub sync: LockfileSyncReport,
+}
+
+fn toml_error(file: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::Instance(format!("Invalid {}: {}", file, e))
+}
+
+/// Hash bytes in a packwiz hash format
+fn hash_bytes(format: &str, bytes: &[u8]) -> AppResult<String> {
+    use sha2::Digest;
+    Ok(match format {
+        "sha1" => hex::encode(sha1::Sha1::digest(bytes)),
+        "sha256" => hex::encode(sha2::Sha256::digest(bytes)),
+        "sha512" => hex::encode(sha2::Sha512::digest(bytes)),
+        "murmur2" => crate::curseforge::fingerprint(bytes).to_string(),
+        _ => {
+            return Err(AppError::Instance(format!(
+                "Unsupported packwiz hash format: {}",
+                format
+            )))
+        }
+    })
+}
+
+/// Loader name used in `[versions]`
+fn loader_key(loader: &str) -> Option<&'static str> {
+    match loader.to_lowercase().as_str() {
+        "fabric" => Some("fabric"),
+        "quilt" => Some("quilt"),
+        "forge" => Some("forge"),
+        "neoforge" => Some("neoforge"),
+        _ => None,
+    }
+}
+
+/// Mod loader and version from `[versions]`
+fn versions_loader(versions: &BTreeMap<String, String>) -> (Option<String>, Option<String>) {
+    ["fabric", "quilt", "forge", "neoforge"]
+        .into_iter()
+        .find_map(|key| {
+            versions
+                .get(key)
+                .map(|v| (Some(key.to_string()), Some(v.clone())))
+        })
+        .unwrap_or((None, None))
+}
+
+/// Metafile for a locked file, `None` when the file has to be shipped as is
+fn metafile_for(file: &LockedFile) -> Option<MetafileToml> {
+    let filename = file.path.rsplit('/').next()?.to_string();
+    let name = filename
+        .trim_end_matches(".jar")
+        .trim_end_matches(".zip")
+        .to_string();
+    let side = Some(
+        match file.side {
+            LockSide::Both => "both",
+            LockSide::Client => "client",
+            LockSide::Server => "server",
+        }
+        .to_string(),
+    );
+
+    match file.source {
+        LockSource::Modrinth | LockSource::Url => {
+            let url = file.url.clone()?;
+            let (hash_format, hash) = match (&file.sha512, &file.sha1) {
+                (Some(sha512), _) => ("sha512", sha512.clone()),
+                (None, Some(sha1)) => ("sha1", sha1.clone()),
+                _ => return None,
+            };
+            let update = match (&file.source, &file.project_id, &file.version_id) {
+                (LockSource::Modrinth, Some(project_id), Some(version_id)) => {
+                    Some(MetafileUpdate {
+                        modrinth: Some(ModrinthUpdate {
+                            mod_id: project_id.clone(),
+                            version: version_id.clone(),
+                        }),
+                        curseforge: None,
+                    })
+                }
+                _ => None,
+            };
+            Some(MetafileToml {
+                name,
+                filename,
+                side,
+                download: MetafileDownload {
+                    url: Some(url),
+                    hash_format: hash_format.to_string(),
+                    hash,
+                    mode: None,
+                },
+                update,
+            })
+        }
+        LockSource::CurseForge => {
+            let project_id = file.project_id.as_deref()?.parse().ok()?;
+            let file_id = file.version_id.as_deref()?.parse().ok()?;
+            Some(MetafileToml {
+                name,
+                filename,
+                side,
+                download: MetafileDownload {
+                    url: None,
+                    hash_format: "sha1".to_string(),
+                    hash: file.sha1.clone()?,
+                    mode: Some("metadata:curseforge".to_string()),
+                },
+                update: Some(MetafileUpdate {
+                    modrinth: None,
+                    curseforge: Some(CurseForgeUpdate {
+                        project_id,
+                        file_id,
+                    }),
+                }),
+            })
+        }
+        LockSource::Local => None,
+    }
+}
+
+/// Export the content of an instance as a packwiz pack
+pub async fn export(
+    state: &AppState,
+    instance: &Instance,
+    instance_dir: &Path,
+    options: &PackwizExportOptions,
+) -> AppResult<PathBuf> {
+    let output_dir = PathBuf::from(&options.output_dir);
+    let lockfile = lockfile::generate(state, instance, instance_dir).await?;
+
+    // Relative path -> contents of every file of the pack besides pack.toml and index.toml
+    let mut pack_files: BTreeMap<String, (Vec<u8>, bool)> = BTreeMap::new();
+    for file in &lockfile.files {
+        match metafile_for(file) {
+            Some(metafile) => {
+                let folder = file.path.rsplit_once('/').map(|(f, _)| f).unwrap_or("");
+                let path = format!("{}/{}{}", folder, metafile.name, METAFILE_SUFFIX);
+                let content = toml::to_string(&metafile).map_err(|e| toml_error(&path, e))?;
+                pack_files.insert(path, (content.into_bytes(), true));
+            }
+            None => {
+                let bytes = tokio::fs::read(instance_dir.join(&file.path))
+                    .await
+                    .map_err(|e| AppError::Io(format!("Failed to read {}: {}", file.path, e)))?;
+                pack_files.insert(file.path.clone(), (bytes, false));
+            }
+        }
+    }
+
+    let entries: Vec<ExportEntry> = options
+        .extra_paths
+        .iter()
+        .map(|path| ExportEntry {
+            path: path.clone(),
+            side: Default::default(),
+        })
+        .collect();
+    for file in super::collect_export_files(instance_dir, &entries)? {
+        if lockfile.files.iter().any(|f| f.path == file.relative) {
+            continue;
+        }
+        let bytes = tokio::fs::read(&file.source)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to read {}: {}", file.relative, e)))?;
+        pack_files.entry(file.relative).or_insert((bytes, false));
+    }
+
+    let mut index = IndexToml {
+        hash_format: "sha256".to_string(),
+        files: Vec::new(),
+    };
+    for (path, (bytes, metafile)) in &pack_files {
+        index.files.push(IndexEntry {
+            file: path.clone(),
+            hash: hash_bytes("sha256", bytes)?,
+            hash_format: None,
+            metafile: *metafile,
+        });
+    }
+    let index_content = toml::to_string(&index).map_err(|e| toml_error(INDEX_FILE, e))?;
+
+    let mut versions = BTreeMap::new();
+    versions.insert("minecraft".to_string(), instance.mc_version.clone());
+    if let (Some(loader), Some(loader_version)) = (
+        instance.loader.as_deref().and_then(loader_key),
+        instance.loader_version.clone(),
+    ) {
+        versions.insert(loader.to_string(), loader_version);
+    }
+
+    let pack = PackToml {
+        name: options
+            .name
+            .clone()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| instance.name.clone()),
+        author: options.author.clone().filter(|a| !a.is_empty()),
+        version: options.version.clone().filter(|v| !v.is_empty()),
+        pack_format: Some(PACK_FORMAT.to_string()),
+        index: PackIndexRef {
+            file: INDEX_FILE.to_string(),
+            hash_format: "sha256".to_string(),
+            hash: hash_bytes("sha256", index_content.as_bytes())?,
+        },
+        versions,
+    };
+    let pack_content = toml::to_string(&pack).map_err(|e| toml_error(PACK_FILE, e))?;
+
+    for (path, (bytes, _)) in &pack_files {
+        super::update::write_pack_file(&output_dir, path, bytes).await?;
+    }
+    super::update::write_pack_file(&output_dir, INDEX_FILE, index_content.as_bytes()).await?;
+    super::update::write_pack_file(&output_dir, PACK_FILE, pack_content.as_bytes()).await?;
+
+    log::info!(
+        "Exported {} as a packwiz pack ({} files) to {}",
+        instance.name,
+        pack_files.len(),
+        output_dir.display()
+    );
+
+    Ok(output_dir)
+}
+
+/// Where a pack is read from
+enum PackLocation {
+    Dir(PathBuf),
+    Url(url::Url),
+}
+
+impl PackLocation {
+    /// `source` is a pack.toml path or URL, or a folder containing pack.toml
+    fn parse(source: &str) -> AppResult<(Self, String)> {
+        if source.starts_with("http://") || source.starts_with("https://") {
+            let url = url::Url::parse(source)
+                .map_err(|e| AppError::Instance(format!("Invalid pack URL: {}", e)))?;
+            if url.scheme() != "https" {
+                return Err(AppError::Instance(
+                    "Packs must be served over HTTPS".to_string(),
+                ));
+            }
+            let file = url
+                .path_segments()
+                .and_then(|mut s| s.next_back())
+                .unwrap_or(PACK_FILE)
+                .to_string();
+            return Ok((Self::Url(url), file));
+        }
+
+        let path = PathBuf::from(source);
+        if path.is_dir() {
+            return Ok((Self::Dir(path), PACK_FILE.to_string()));
+        }
+        let file = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| PACK_FILE.to_string());
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok((Self::Dir(dir), file))
+    }
+
+    async fn read(&self, http_client: &reqwest::Client, relative: &str) -> AppResult<Vec<u8>> {
+        if !super::is_safe_relative_path(relative) {
+            return Err(AppError::Instance(format!(
+                "Pack file path escapes the pack: {}",
+                relative
+            )));
+        }
+        match self {
+            Self::Dir(dir) => tokio::fs::read(dir.join(relative))
+                .await
+                .map_err(|e| AppError::Io(format!("Failed to read {}: {}", relative, e))),
+            Self::Url(base) => {
+                let url = base
+                    .join(relative)
+                    .map_err(|e| AppError::Instance(format!("Invalid pack URL: {}", e)))?;
+                let response = http_client
+                    .get(url)
+                    .send()
+                    .await
+                    .map_err(|e| AppError::Network(e.to_string()))?;
+                if !response.status().is_success() {
+                    return Err(AppError::Network(format!(
+                        "Failed to fetch {}: HTTP {}",
+                        relative,
+                        response.status()
+                    )));
+                }
+                response
+                    .bytes()
+                    .await
+                    .map(|b| b.to_vec())
+                    .map_err(|e| AppError::Network(e.to_string()))
+            }
+        }
+    }
+
+    /// URL of a pack file, for files synced later
+    fn url_of(&self, relative: &str) -> Option<String> {
+        match self {
+            Self::Url(base) => base.join(relative).ok().map(|u| u.to_string()),
+            Self::Dir(_) => None,
+        }
+    }
+}
+
+/// Read a file of the pack and check it against its index hash
+async fn read_verified(
+    location: &PackLocation,
+    http_client: &reqwest::Client,
+    relative: &str,
+    hash_format: &str,
+    hash: &str,
+) -> AppResult<Vec<u8>> {
+    let bytes = location.read(http_client, relative).await?;
+    if !hash_bytes(hash_format, &bytes)?.eq_ignore_ascii_case(hash) {
+        return Err(AppError::Instance(format!(
+            "Hash mismatch for {}",
+            relative
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Locked file for a metafile at `metafile_path`
+fn locked_from_metafile(metafile_path: &str, metafile: &MetafileToml) -> AppResult<LockedFile> {
+    if metafile.filename.contains('/') || !super::is_safe_relative_path(&metafile.filename) {
+        return Err(AppError::Instance(format!(
+            "Invalid filename in {}",
+            metafile_path
+        )));
+    }
+    let folder = metafile_path.rsplit_once('/').map(|(f, _)| f);
+    let path = match folder {
+        Some(folder) => format!("{}/{}", folder, metafile.filename),
+        None => metafile.filename.clone(),
+    };
+
+    let side = match metafile.side.as_deref() {
+        Some("client") => LockSide::Client,
+        Some("server") => LockSide::Server,
+        _ => LockSide::Both,
+    };
+
+    let download = &metafile.download;
+    let hash = Some(download.hash.to_lowercase());
+    let (sha1, sha256, sha512) = match download.hash_format.as_str() {
+        "sha1" => (hash, None, None),
+        "sha256" => (None, hash, None),
+        "sha512" => (None, None, hash),
+        other => {
+            return Err(AppError::Instance(format!(
+                "Unsupported hash format {} in {}",
+                other, metafile_path
+            )))
+        }
+    };
+
+    let update = metafile.update.clone().unwrap_or_default();
+    let (source, project_id, version_id) = if let Some(cf) = update.curseforge {
+        (
+            LockSource::CurseForge,
+            Some(cf.project_id.to_string()),
+            Some(cf.file_id.to_string()),
+        )
+    } else if let Some(modrinth) = update.modrinth {
+        (
+            LockSource::Modrinth,
+            Some(modrinth.mod_id),
+            Some(modrinth.version),
+        )
+    } else {
+        (LockSource::Url, None, None)
+    };
+
+    Ok(LockedFile {
+        path,
+        source,
+        project_id,
+        version_id,
+        url: download.url.clone(),
+        sha1,
+        sha256,
+        sha512,
+        size: 0,
+        side,
+    })
+}
+
+/// Create an instance from a packwiz pack, then download its content
+pub async fn import(
+    state: &AppState,
+    app: &AppHandle,
+    source: &str,
+    instance_name: Option<String>,
+) -> AppResult<PackwizImportResult> {
+    let http_client = &state.http_client;
+    let (location, pack_file) = PackLocation::parse(source)?;
+
+    let pack_bytes = location.read(http_client, &pack_file).await?;
+    let pack: PackToml = toml::from_str(&String::from_utf8_lossy(&pack_bytes))
+        .map_err(|e| toml_error(PACK_FILE, e))?;
+    let mc_version = pack
+        .versions
+        .get("minecraft")
+        .cloned()
+        .ok_or_else(|| AppError::Instance("Pack has no minecraft version".to_string()))?;
+    let (loader, loader_version) = versions_loader(&pack.versions);
+
+    let index_bytes = read_verified(
+        &location,
+        http_client,
+        &pack.index.file,
+        &pack.index.hash_format,
+        &pack.index.hash,
+    )
+    .await?;
+    let index: IndexToml = toml::from_str(&String::from_utf8_lossy(&index_bytes))
+        .map_err(|e| toml_error(INDEX_FILE, e))?;
+
+    // Index paths are relative to the index file
+    let index_base = pack.index.file.rsplit_once('/').map(|(dir, _)| dir);
+    let pack_path = |file: &str| match index_base {
+        Some(dir) => format!("{}/{}", dir, file),
+        None => file.to_string(),
+    };
+
+    let curseforge = crate::curseforge::api_key(&state.db)
+        .await
+        .map(|key| crate::curseforge::CurseForgeClient::new(http_client, key));
+
+    let mut locked = Vec::new();
+    // Metafiles pointing outside the folders a lockfile covers, downloaded once
+    let mut unlocked = Vec::new();
+    let mut plain_files = Vec::new();
+    for entry in &index.files {
+        if !super::is_safe_relative_path(&entry.file) {
+            return Err(AppError::Instance(format!(
+                "Pack file path escapes the instance: {}",
+                entry.file
+            )));
+        }
+        let hash_format = entry.hash_format.as_deref().unwrap_or(&index.hash_format);
+        let bytes = read_verified(
+            &location,
+            http_client,
+            &pack_path(&entry.file),
+            hash_format,
+            &entry.hash,
+        )
+        .await?;
+
+        if !entry.metafile && !entry.file.ends_with(METAFILE_SUFFIX) {
+            plain_files.push((entry.file.clone(), bytes));
+            continue;
+        }
+
+        let metafile: MetafileToml = toml::from_str(&String::from_utf8_lossy(&bytes))
+            .map_err(|e| toml_error(&entry.file, e))?;
+        let mut file = locked_from_metafile(&entry.file, &metafile)?;
+
+        // CurseForge metafiles leave the URL to the API
+        if file.url.is_none() && file.source == LockSource::CurseForge {
+            if let (Some(client), Some(project_id), Some(file_id)) =
+                (&curseforge, &file.project_id, &file.version_id)
+            {
+                if let Ok(cf_file) = client.get_mod_file(project_id, file_id).await {
+                    file.url = cf_file.download_url;
+                    file.size = cf_file.file_length;
+                }
+            }
+        }
+        if lockfile::is_lockable_path(&file.path) {
+            locked.push(file);
+        } else {
+            unlocked.push(file);
+        }
+    }
+
+    let instance = Instance::create(
+        &state.db,
+        CreateInstance {
+            name: instance_name
+                .filter(|n| !n.trim().is_empty())
+                .unwrap_or_else(|| pack.name.clone()),
+            mc_version,
+            loader,
+            loader_version,
+            is_server: false,
+            is_proxy: false,
+            server_port: 25565,
+            modrinth_project_id: None,
+        },
+    )
+    .await
+    .map_err(AppError::from)?;
+
+    let instance_dir = state.get_instances_dir().await.join(&instance.game_dir);
+    tokio::fs::create_dir_all(&instance_dir)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to create instance directory: {}", e)))?;
+
+    let content_folders = lockfile::content_folders(&instance);
+    for (relative, bytes) in plain_files {
+        super::update::write_pack_file(&instance_dir, &relative, &bytes).await?;
+
+        // Content shipped in the pack itself must survive the sync
+        let top_level_content = relative.split_once('/').is_some_and(|(folder, name)| {
+            content_folders.contains(&folder)
+                && !name.contains('/')
+                && (name.ends_with(".jar") || name.ends_with(".zip"))
+        });
+        if top_level_content {
+            let mut file = LockedFile {
+                path: relative.clone(),
+                source: LockSource::Local,
+                project_id: None,
+                version_id: None,
+                url: location.url_of(&pack_path(&relative)),
+                sha1: None,
+                sha256: Some(hash_bytes("sha256", &bytes)?),
+                sha512: None,
+                size: bytes.len() as u64,
+                side: LockSide::Both,
+            };
+            if file.url.is_some() {
+                file.source = LockSource::Url;
+            }
+            locked.push(file);
+        }
+    }
+
+    let lockfile = InstanceLockfile::new(&instance, locked);
+    lockfile.save(&instance_dir).await?;
+    let mut sync = lockfile::sync(state, app, &instance, &instance_dir, &lockfile).await?;
+
+    for file in unlocked {
+        let dest = instance_dir.join(&file.path);
+        match lockfile::download_locked_file(http_client, &file, &dest).await {
+            Ok(()) => sync.downloaded.push(file.path),
+            Err(e) => sync.failed.push((file.path, e.to_string())),
+        }
+    }
+
+    log::info!(
+        "Imported packwiz pack {} as instance {}",
+        pack.name,
+        instance.name
+    );
+
+    Ok(PackwizImportResult { instance, sync })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_metafile() {
+        let metafile: MetafileToml = toml::from_str(
+            r#"
+name = "Sodium"
+filename = "sodium-fabric-0.5.8.jar"
+side = "client"
+
+[download]
+url = "https://cdn.modrinth.com/data/AANobbMI/versions/abc/sodium-fabric-0.5.8.jar"
+hash-format = "sha512"
+hash = "ABCDEF"
+
+[update]
+[update.modrinth]
+mod-id = "AANobbMI"
+version = "abc"
+"#,
+        )
+        .unwrap();
+
+        let file = locked_from_metafile("mods/sodium.pw.toml", &metafile).unwrap();
+        assert_eq!(file.path, "mods/sodium-fabric-0.5.8.jar");
+        assert_eq!(file.source, LockSource::Modrinth);
+        assert_eq!(file.project_id.as_deref(), Some("AANobbMI"));
+        assert_eq!(file.sha512.as_deref(), Some("abcdef"));
+        assert_eq!(file.side, LockSide::Client);
+    }
+
+    #[test]
+    fn test_curseforge_metafile() {
+        let metafile: MetafileToml = toml::from_str(
+            r#"
+name = "JEI"
+filename = "jei.jar"
+
+[download]
+hash-format = "sha1"
+hash = "aa"
+mode = "metadata:curseforge"
+
+[update.curseforge]
+file-id = 4593548
+project-id = 238222
+"#,
+        )
+        .unwrap();
+
+        let file = locked_from_metafile("mods/jei.pw.toml", &metafile).unwrap();
+        assert_eq!(file.source, LockSource::CurseForge);
+        assert_eq!(file.project_id.as_deref(), Some("238222"));
+        assert_eq!(file.version_id.as_deref(), Some("4593548"));
+        assert!(file.url.is_none());
+
+        // And back
+        let round_trip = metafile_for(&file).unwrap();
+        assert_eq!(
+            round_trip.download.mode.as_deref(),
+            Some("metadata:curseforge")
+        );
+        assert_eq!(round_trip.filename, "jei.jar");
+    }
+
+    #[test]
+    fn test_metafile_filename_cannot_escape() {
+        let metafile = MetafileToml {
+            name: "x".to_string(),
+            filename: "../x.jar".to_string(),
+            side: None,
+            download: MetafileDownload {
+                url: None,
+                hash_format: "sha1".to_string(),
+                hash: "aa".to_string(),
+                mode: None,
+            },
+            update: None,
+        };
+        assert!(locked_from_metafile("mods/x.pw.toml", &metafile).is_err());
+    }
+
+    #[test]
+    fn test_pack_toml() {
+        let pack: PackToml = toml::from_str(
+            r#"
+name = "Team Pack"
+pack-format = "packwiz:1.1.0"
+
+[index]
+file = "index.toml"
+hash-format = "sha256"
+hash = "00"
+
+[versions]
+fabric = "0.15.11"
+minecraft = "1.20.1"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            versions_loader(&pack.versions),
+            (Some("fabric".to_string()), Some("0.15.11".to_string()))
+        );
+        assert_eq!(hash_bytes("sha256", b"abc").unwrap().len(), 64);
+        assert!(hash_bytes("md5", b"abc").is_err());
+    }
+}
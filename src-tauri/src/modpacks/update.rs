@@ -0,0 +1,817 @@
+// Updating instances installed from a Modrinth modpack to another version of the pack.
+// The install records the hash of every file the pack wrote, so files the user
+// changed, added or removed since can be told apart from pack files and left alone
+
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::instance::commands::ChangeVersionRequest;
+use crate::instance::dedup;
+use crate::modpacks::install_plan::{file_support, FileSupport};
+use crate::modrinth::commands::{ModpackFile, ModpackIndex};
+use crate::modrinth::{ModrinthClient, Version};
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+/// Install record, in the instance directory
+const STATE_FILE: &str = ".modpack.json";
+
+/// Files a modpack installed into an instance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstalledModpack {
+    pub project_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    /// Downloaded files: path -> sha1
+    pub files: BTreeMap<String, String>,
+    /// Files extracted from the overrides: path -> sha1
+    pub overrides: BTreeMap<String, String>,
+    /// Optional files the user chose not to install, left out of updates too
+    #[serde(default)]
+    pub declined: BTreeSet<String>,
+}
+
+impl InstalledModpack {
+    /// Read the install record of an instance, if it has one
+    pub async fn load(instance_dir: &Path) -> Option<Self> {
+        let content = tokio::fs::read_to_string(instance_dir.join(STATE_FILE))
+            .await
+            .ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub async fn save(&self, instance_dir: &Path) -> AppResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(instance_dir.join(STATE_FILE), content)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to write {}: {}", STATE_FILE, e)))
+    }
+
+    /// Every path the pack owns with its expected hash
+    fn all_files(&self) -> BTreeMap<String, String> {
+        let mut all = self.files.clone();
+        all.extend(self.overrides.clone());
+        all
+    }
+}
+
+pub fn sha1_hex(bytes: &[u8]) -> String {
+    use sha1::{Digest, Sha1};
+    hex::encode(Sha1::digest(bytes))
+}
+
+/// Hashes of the downloaded files a pack installs on one side
+pub fn pack_file_hashes(index: &ModpackIndex, is_server: bool) -> BTreeMap<String, String> {
+    file_hashes(
+        index
+            .files
+            .iter()
+            .filter(|f| file_support(f, is_server) != FileSupport::Unsupported),
+    )
+}
+
+pub fn file_hashes<'a>(
+    files: impl IntoIterator<Item = &'a ModpackFile>,
+) -> BTreeMap<String, String> {
+    files
+        .into_iter()
+        .map(|f| (f.path.clone(), f.hashes.sha1.to_lowercase()))
+        .collect()
+}
+
+/// Read the override files of a pack for one side. Side-specific overrides win
+/// over `overrides/`; entries escaping the instance directory are skipped
+pub async fn read_overrides(
+    mrpack: Vec<u8>,
+    is_server: bool,
+) -> AppResult<BTreeMap<String, Vec<u8>>> {
+    tokio::task::spawn_blocking(move || {
+        use std::io::{Cursor, Read};
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(mrpack))
+            .map_err(|e| AppError::Instance(format!("Invalid modpack archive: {}", e)))?;
+        let side_prefix = if is_server {
+            "server-overrides/"
+        } else {
+            "client-overrides/"
+        };
+
+        let mut shared = BTreeMap::new();
+        let mut sided = BTreeMap::new();
+        for i in 0..archive.len() {
+            let Ok(mut file) = archive.by_index(i) else {
+                continue;
+            };
+            if file.is_dir() {
+                continue;
+            }
+
+            let name = file.name().to_string();
+            let (target, relative) = if let Some(rest) = name.strip_prefix("overrides/") {
+                (&mut shared, rest)
+            } else if let Some(rest) = name.strip_prefix(side_prefix) {
+                (&mut sided, rest)
+            } else {
+                continue;
+            };
+
+            if file.enclosed_name().is_none() || !super::is_safe_relative_path(relative) {
+                log::warn!("Skipping unsafe modpack override: {}", name);
+                continue;
+            }
+
+            let mut contents = Vec::new();
+            if file.read_to_end(&mut contents).is_ok() {
+                target.insert(relative.to_string(), contents);
+            }
+        }
+
+        shared.extend(sided);
+        Ok(shared)
+    })
+    .await
+    .map_err(|e| AppError::Instance(format!("Failed to read overrides: {}", e)))?
+}
+
+/// Write a file of the pack, creating its parent folders
+pub async fn write_pack_file(
+    instance_dir: &Path,
+    relative: &str,
+    contents: &[u8],
+) -> AppResult<()> {
+    let dest = instance_dir.join(relative);
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
+    }
+    dedup::unlink_shared(&dest)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", relative, e)))?;
+    tokio::fs::write(&dest, contents)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", relative, e)))
+}
+
+/// A pack file as found on disk. Disabled mods (`.disabled`) count as present
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalFile {
+    pub sha1: String,
+    pub disabled: bool,
+}
+
+/// What an update does to the instance
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModpackUpdatePlan {
+    /// Files new in the pack
+    pub add: Vec<String>,
+    /// Pack files the new version changes
+    pub replace: Vec<String>,
+    /// Pack files the new version drops
+    pub remove: Vec<String>,
+    /// Files the pack changes or drops but the user modified, added or deleted; left as they are
+    pub kept: Vec<String>,
+}
+
+/// Compare the files of the installed and new pack versions with the files on disk
+pub fn plan_update(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+    local: &BTreeMap<String, LocalFile>,
+) -> ModpackUpdatePlan {
+    let mut plan = ModpackUpdatePlan::default();
+
+    for (path, new_hash) in new {
+        let current = local.get(path).map(|f| f.sha1.as_str());
+        match old.get(path) {
+            // Unchanged in the pack: whatever the user did stays
+            Some(old_hash) if old_hash == new_hash => {}
+            Some(old_hash) => match current {
+                Some(hash) if hash == new_hash => {}
+                Some(hash) if hash == old_hash => plan.replace.push(path.clone()),
+                _ => plan.kept.push(path.clone()),
+            },
+            None => match current {
+                None => plan.add.push(path.clone()),
+                Some(hash) if hash == new_hash => {}
+                // The user put their own file there
+                Some(_) => plan.kept.push(path.clone()),
+            },
+        }
+    }
+
+    for (path, old_hash) in old {
+        if new.contains_key(path) {
+            continue;
+        }
+        match local.get(path).map(|f| f.sha1.as_str()) {
+            None => {}
+            Some(hash) if hash == old_hash => plan.remove.push(path.clone()),
+            Some(_) => plan.kept.push(path.clone()),
+        }
+    }
+
+    plan
+}
+
+/// Path of a file with its `.disabled` suffix
+fn disabled_path(relative: &str) -> String {
+    format!("{}.disabled", relative)
+}
+
+/// Hash the files at the given paths (or their disabled variant)
+async fn hash_local_files(
+    instance_dir: &Path,
+    paths: BTreeSet<String>,
+) -> AppResult<BTreeMap<String, LocalFile>> {
+    let instance_dir = instance_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .filter_map(|relative| {
+                let (bytes, disabled) = match std::fs::read(instance_dir.join(&relative)) {
+                    Ok(bytes) => (bytes, false),
+                    Err(_) => (
+                        std::fs::read(instance_dir.join(disabled_path(&relative))).ok()?,
+                        true,
+                    ),
+                };
+                Some((
+                    relative,
+                    LocalFile {
+                        sha1: sha1_hex(&bytes),
+                        disabled,
+                    },
+                ))
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| AppError::Io(format!("Failed to hash instance files: {}", e)))
+}
+
+/// A version of a modpack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackVersionSummary {
+    pub id: String,
+    pub name: String,
+    pub version_number: String,
+    pub version_type: String,
+    pub game_versions: Vec<String>,
+    pub loaders: Vec<String>,
+    pub date_published: String,
+}
+
+impl From<&Version> for ModpackVersionSummary {
+    fn from(v: &Version) -> Self {
+        Self {
+            id: v.id.clone(),
+            name: v.name.clone(),
+            version_number: v.version_number.clone(),
+            version_type: v.version_type.clone(),
+            game_versions: v.game_versions.clone(),
+            loaders: v.loaders.clone(),
+            date_published: v.date_published.clone(),
+        }
+    }
+}
+
+/// Versions of the pack an instance was installed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackUpdateInfo {
+    pub project_id: String,
+    /// `None` for instances installed before install records were kept
+    pub installed_version_id: Option<String>,
+    pub installed_version_number: Option<String>,
+    /// All versions, newest first
+    pub versions: Vec<ModpackVersionSummary>,
+    pub update_available: bool,
+}
+
+/// Outcome of a modpack update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackUpdateResult {
+    pub version_number: String,
+    pub plan: ModpackUpdatePlan,
+    /// Backup taken before the update
+    pub backup_filename: String,
+    pub version_changed: bool,
+}
+
+/// Download a pack version and read its index
+pub(crate) async fn download_mrpack(
+    http_client: &reqwest::Client,
+    version: &Version,
+) -> AppResult<(Vec<u8>, ModpackIndex)> {
+    use sha2::{Digest, Sha512};
+
+    let file = version
+        .files
+        .iter()
+        .find(|f| f.filename.ends_with(".mrpack"))
+        .or_else(|| version.files.first())
+        .ok_or_else(|| AppError::Instance("No modpack file found".to_string()))?;
+
+    let response = http_client
+        .get(&file.url)
+        .send()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to download modpack: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::Network(format!(
+            "Failed to download modpack: HTTP {}",
+            response.status()
+        )));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to read modpack: {}", e)))?
+        .to_vec();
+
+    let hash = hex::encode(Sha512::digest(&bytes));
+    if !hash.eq_ignore_ascii_case(&file.hashes.sha512) {
+        return Err(AppError::Instance(format!(
+            "Modpack hash mismatch: expected {}, got {}",
+            file.hashes.sha512, hash
+        )));
+    }
+
+    let archive_bytes = bytes.clone();
+    let index = tokio::task::spawn_blocking(move || -> AppResult<ModpackIndex> {
+        use std::io::{Cursor, Read};
+        let mut archive = zip::ZipArchive::new(Cursor::new(archive_bytes))
+            .map_err(|e| AppError::Instance(format!("Invalid modpack archive: {}", e)))?;
+        let mut index_file = archive
+            .by_name("modrinth.index.json")
+            .map_err(|_| AppError::Instance("Missing modrinth.index.json".to_string()))?;
+        let mut contents = String::new();
+        index_file
+            .read_to_string(&mut contents)
+            .map_err(|e| AppError::Instance(format!("Failed to read modpack index: {}", e)))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| AppError::Instance(format!("Invalid modpack index: {}", e)))
+    })
+    .await
+    .map_err(|e| AppError::Instance(format!("Failed to parse modpack: {}", e)))??;
+
+    if let Some(file) = index
+        .files
+        .iter()
+        .find(|f| !super::is_safe_relative_path(&f.path))
+    {
+        return Err(AppError::Instance(format!(
+            "Modpack file path escapes the instance: {}",
+            file.path
+        )));
+    }
+
+    Ok((bytes, index))
+}
+
+/// Build the install record of a pack version without installing it.
+/// Used for instances installed before install records were kept
+async fn record_for_version(
+    http_client: &reqwest::Client,
+    project_id: &str,
+    version: &Version,
+    is_server: bool,
+) -> AppResult<InstalledModpack> {
+    let (bytes, index) = download_mrpack(http_client, version).await?;
+    let overrides = read_overrides(bytes, is_server).await?;
+    Ok(InstalledModpack {
+        project_id: project_id.to_string(),
+        version_id: version.id.clone(),
+        version_number: version.version_number.clone(),
+        files: pack_file_hashes(&index, is_server),
+        overrides: overrides
+            .iter()
+            .map(|(path, contents)| (path.clone(), sha1_hex(contents)))
+            .collect(),
+        declined: BTreeSet::new(),
+    })
+}
+
+/// Mod loader and version from the index dependencies
+pub(crate) fn index_loader(index: &ModpackIndex) -> (Option<String>, Option<String>) {
+    for (key, loader) in [
+        ("fabric-loader", "fabric"),
+        ("forge", "forge"),
+        ("neoforge", "neoforge"),
+        ("quilt-loader", "quilt"),
+    ] {
+        if let Some(version) = index.dependencies.get(key) {
+            return (Some(loader.to_string()), Some(version.clone()));
+        }
+    }
+    (None, None)
+}
+
+/// List the versions of the pack an instance was installed from
+pub async fn check_update(
+    http_client: &reqwest::Client,
+    instance: &Instance,
+    instance_dir: &Path,
+) -> AppResult<ModpackUpdateInfo> {
+    let project_id = instance.modrinth_project_id.clone().ok_or_else(|| {
+        AppError::Instance("This instance was not installed from a modpack".to_string())
+    })?;
+
+    let versions = ModrinthClient::new(http_client)
+        .get_project_versions(&project_id, None, None)
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    let installed = InstalledModpack::load(instance_dir).await;
+    let update_available = match (&installed, versions.first()) {
+        (Some(installed), Some(latest)) => installed.version_id != latest.id,
+        _ => false,
+    };
+
+    Ok(ModpackUpdateInfo {
+        project_id,
+        installed_version_id: installed.as_ref().map(|i| i.version_id.clone()),
+        installed_version_number: installed.map(|i| i.version_number),
+        versions: versions.iter().map(ModpackVersionSummary::from).collect(),
+        update_available,
+    })
+}
+
+fn emit_progress(app: &AppHandle, instance_id: &str, stage: &str, progress: u32, message: &str) {
+    let _ = app.emit(
+        "modpack-update-progress",
+        serde_json::json!({
+            "stage": stage,
+            "message": message,
+            "progress": progress,
+            "instance_id": instance_id
+        }),
+    );
+}
+
+/// Move an instance to another version of its modpack.
+/// `installed_version_id` is only needed for instances without an install record
+pub async fn update_instance(
+    state: &AppState,
+    app: &AppHandle,
+    instance: &Instance,
+    version_id: &str,
+    installed_version_id: Option<&str>,
+) -> AppResult<ModpackUpdateResult> {
+    let project_id = instance.modrinth_project_id.clone().ok_or_else(|| {
+        AppError::Instance("This instance was not installed from a modpack".to_string())
+    })?;
+
+    if state
+        .running_instances
+        .read()
+        .await
+        .contains_key(&instance.id)
+    {
+        return Err(AppError::Instance(
+            "Cannot update the modpack while the instance is running".to_string(),
+        ));
+    }
+
+    let instances_dir = state.data_dir.join("instances");
+    let instance_dir = instances_dir.join(&instance.game_dir);
+    let http_client = &state.http_client;
+    let client = ModrinthClient::new(http_client);
+    let is_server = instance.is_server;
+
+    emit_progress(
+        app,
+        &instance.id,
+        "fetching",
+        5,
+        "Fetching modpack version...",
+    );
+    let version = client
+        .get_version(version_id)
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to get modpack version: {}", e)))?;
+    if version.project_id != project_id {
+        return Err(AppError::Instance(
+            "This version belongs to another modpack".to_string(),
+        ));
+    }
+
+    let installed = match InstalledModpack::load(&instance_dir).await {
+        Some(installed) => installed,
+        None => {
+            let installed_version_id = installed_version_id.ok_or_else(|| {
+                AppError::Instance(
+                    "This instance has no modpack install record: select the installed pack version"
+                        .to_string(),
+                )
+            })?;
+            let installed_version = client
+                .get_version(installed_version_id)
+                .await
+                .map_err(|e| AppError::Network(format!("Failed to get modpack version: {}", e)))?;
+            record_for_version(http_client, &project_id, &installed_version, is_server).await?
+        }
+    };
+
+    emit_progress(
+        app,
+        &instance.id,
+        "downloading",
+        15,
+        "Downloading modpack...",
+    );
+    let (mrpack_bytes, index) = download_mrpack(http_client, &version).await?;
+    let overrides = read_overrides(mrpack_bytes, is_server).await?;
+
+    // Optional files declined at install stay out while they remain optional
+    let declined: BTreeSet<String> = index
+        .files
+        .iter()
+        .filter(|f| {
+            installed.declined.contains(&f.path)
+                && file_support(f, is_server) == FileSupport::Optional
+        })
+        .map(|f| f.path.clone())
+        .collect();
+    let mut new_files = pack_file_hashes(&index, is_server);
+    new_files.retain(|path, _| !declined.contains(path));
+    let mut new_all = new_files.clone();
+    new_all.extend(
+        overrides
+            .iter()
+            .map(|(path, contents)| (path.clone(), sha1_hex(contents))),
+    );
+    let old_all = installed.all_files();
+
+    let paths: BTreeSet<String> = old_all.keys().chain(new_all.keys()).cloned().collect();
+    let local = hash_local_files(&instance_dir, paths).await?;
+    let plan = plan_update(&old_all, &new_all, &local);
+
+    // Nothing is touched before the backup exists
+    emit_progress(
+        app,
+        &instance.id,
+        "backup",
+        25,
+        "Backing up the instance...",
+    );
+    let backup = crate::instance::instance_backup::create_instance_backup(
+        &instances_dir,
+        &state.data_dir,
+        instance,
+        Some(app),
+    )
+    .await?;
+
+    let mc_version = index
+        .dependencies
+        .get("minecraft")
+        .cloned()
+        .ok_or_else(|| AppError::Instance("Modpack missing minecraft version".to_string()))?;
+    let (loader, loader_version) = index_loader(&index);
+    let version_changed = mc_version != instance.mc_version
+        || loader != instance.loader
+        || loader_version != instance.loader_version;
+    if version_changed {
+        emit_progress(
+            app,
+            &instance.id,
+            "changing_version",
+            40,
+            "Changing game version...",
+        );
+        crate::instance::commands::change_version(
+            state,
+            app,
+            &ChangeVersionRequest {
+                instance_id: instance.id.clone(),
+                new_mc_version: mc_version,
+                new_loader: loader,
+                new_loader_version: loader_version,
+                mods_to_update: Vec::new(),
+            },
+        )
+        .await?;
+    }
+
+    emit_progress(
+        app,
+        &instance.id,
+        "removing",
+        45,
+        "Removing dropped files...",
+    );
+    for relative in plan.remove.iter().chain(&plan.replace) {
+        let disabled = local.get(relative).is_some_and(|f| f.disabled);
+        let path = if disabled {
+            instance_dir.join(disabled_path(relative))
+        } else {
+            instance_dir.join(relative)
+        };
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            log::warn!("Failed to remove {}: {}", relative, e);
+        }
+        if relative.starts_with("mods/") {
+            let meta =
+                instance_dir.join(format!("{}.meta.json", relative.trim_end_matches(".jar")));
+            let _ = tokio::fs::remove_file(meta).await;
+        }
+    }
+
+    // Replaced files keep their disabled state
+    let target_path = |relative: &String| {
+        if local.get(relative).is_some_and(|f| f.disabled) {
+            instance_dir.join(disabled_path(relative))
+        } else {
+            instance_dir.join(relative)
+        }
+    };
+
+    let mut downloads = Vec::new();
+    let mut mod_files = Vec::new();
+    for relative in plan.add.iter().chain(&plan.replace) {
+        if let Some(contents) = overrides.get(relative) {
+            write_pack_file(&instance_dir, relative, contents).await?;
+            continue;
+        }
+        let Some(file) = index.files.iter().find(|f| &f.path == relative) else {
+            continue;
+        };
+        let Some(url) = file.downloads.first() else {
+            continue;
+        };
+        let dest = target_path(relative);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
+        }
+        downloads.push((url.clone(), dest, Some(file.hashes.sha512.clone())));
+
+        if let (Some(filename), Some((mod_project_id, mod_version_id))) = (
+            relative
+                .strip_prefix("mods/")
+                .filter(|f| f.ends_with(".jar")),
+            crate::modrinth::commands::extract_modrinth_ids(url),
+        ) {
+            mod_files.push((mod_project_id, mod_version_id, filename.to_string()));
+        }
+    }
+
+    if !downloads.is_empty() {
+        let app_clone = app.clone();
+        let instance_id = instance.id.clone();
+        crate::download::client::download_files_parallel_sha512(
+            http_client,
+            downloads,
+            8,
+            move |completed, total| {
+                let progress = 50 + ((completed as f32 / total as f32) * 40.0) as u32;
+                emit_progress(
+                    &app_clone,
+                    &instance_id,
+                    "downloading_files",
+                    progress,
+                    &format!("Downloading files ({}/{})", completed, total),
+                );
+            },
+        )
+        .await?;
+    }
+
+    if !mod_files.is_empty() {
+        emit_progress(
+            app,
+            &instance.id,
+            "fetching_metadata",
+            92,
+            "Fetching mod metadata...",
+        );
+        crate::modrinth::commands::write_modpack_mod_metadata(
+            http_client,
+            &instance_dir.join("mods"),
+            mod_files,
+            |_, _| {},
+        )
+        .await;
+    }
+
+    InstalledModpack {
+        project_id,
+        version_id: version.id.clone(),
+        version_number: version.version_number.clone(),
+        files: new_files,
+        overrides: overrides
+            .iter()
+            .map(|(path, contents)| (path.clone(), sha1_hex(contents)))
+            .collect(),
+        declined,
+    }
+    .save(&instance_dir)
+    .await?;
+
+    emit_progress(app, &instance.id, "complete", 100, "Modpack updated");
+    log::info!(
+        "Updated modpack instance {} from {} to {} ({} added, {} replaced, {} removed, {} kept)",
+        instance.name,
+        installed.version_number,
+        version.version_number,
+        plan.add.len(),
+        plan.replace.len(),
+        plan.remove.len(),
+        plan.kept.len()
+    );
+
+    Ok(ModpackUpdateResult {
+        version_number: version.version_number,
+        plan,
+        backup_filename: backup.filename,
+        version_changed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn local(entries: &[(&str, &str)]) -> BTreeMap<String, LocalFile> {
+        entries
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    LocalFile {
+                        sha1: v.to_string(),
+                        disabled: false,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_update() {
+        let old = map(&[
+            ("mods/a.jar", "a1"),
+            ("mods/b.jar", "b1"),
+            ("mods/c.jar", "c1"),
+            ("config/x.toml", "x1"),
+            ("config/y.toml", "y1"),
+            ("mods/gone.jar", "g1"),
+        ]);
+        let new = map(&[
+            ("mods/a.jar", "a1"),
+            ("mods/b.jar", "b2"),
+            ("mods/c.jar", "c2"),
+            ("config/x.toml", "x2"),
+            ("mods/new.jar", "n1"),
+            ("mods/mine.jar", "m2"),
+        ]);
+        let on_disk = local(&[
+            // unchanged pack file the user modified: untouched
+            ("mods/a.jar", "edited"),
+            // unmodified, changed by the pack
+            ("mods/b.jar", "b1"),
+            // c.jar was deleted by the user
+            // modified by the user and changed by the pack
+            ("config/x.toml", "mine"),
+            // unmodified, dropped by the pack
+            ("config/y.toml", "y1"),
+            // the user's own file where the pack now adds one
+            ("mods/mine.jar", "m1"),
+            // modified and dropped
+            ("mods/gone.jar", "changed"),
+        ]);
+
+        let plan = plan_update(&old, &new, &on_disk);
+        assert_eq!(plan.add, vec!["mods/new.jar"]);
+        assert_eq!(plan.replace, vec!["mods/b.jar"]);
+        assert_eq!(plan.remove, vec!["config/y.toml"]);
+        assert_eq!(
+            plan.kept,
+            vec![
+                "config/x.toml",
+                "mods/c.jar",
+                "mods/mine.jar",
+                "mods/gone.jar"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_update_already_current() {
+        let old = map(&[("mods/a.jar", "a1")]);
+        let new = map(&[("mods/a.jar", "a2")]);
+        let plan = plan_update(&old, &new, &local(&[("mods/a.jar", "a2")]));
+        assert_eq!(plan, ModpackUpdatePlan::default());
+    }
+}
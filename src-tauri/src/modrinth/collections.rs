@@ -0,0 +1,323 @@
+//! Importing Modrinth collections and followed projects into an instance
+//!
+//! Every project of the list is matched against the instance's Minecraft
+//! version and loader. Projects with a compatible version are offered for
+//! install, the others are reported with the reason they were left out.
+//! Required dependencies of the picked versions are resolved at install time.
+
+use super::{ModrinthClient, Project, Version};
+use crate::content;
+use crate::crypto;
+use crate::db::instances::Instance;
+use crate::db::settings::get_setting;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// Settings key holding the encrypted personal access token
+pub const ACCESS_TOKEN_SETTING: &str = "modrinth_access_token";
+
+/// Projects whose versions are looked up at the same time
+const CONCURRENT_LOOKUPS: usize = 8;
+
+/// The decrypted personal access token, if one is configured
+pub async fn access_token(state: &AppState) -> AppResult<Option<String>> {
+    match get_setting(&state.db, ACCESS_TOKEN_SETTING).await? {
+        Some(enc) if crypto::is_encrypted(&enc) => {
+            Ok(Some(crypto::decrypt(&state.encryption_key, &enc)?))
+        }
+        Some(token) => Ok(Some(token)),
+        None => Ok(None),
+    }
+}
+
+/// Collection id from a collection URL (`https://modrinth.com/collection/<id>`)
+/// or a bare id
+pub fn parse_collection_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let id = match input.find("collection/") {
+        Some(pos) => {
+            let rest = &input[pos + "collection/".len()..];
+            rest.split(['/', '?', '#']).next().unwrap_or_default()
+        }
+        None if !input.contains('/') => input,
+        None => return None,
+    };
+
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())).then(|| id.to_string())
+}
+
+/// Version loaders to ask Modrinth for, for a project type in an instance.
+/// `None` when the instance cannot use the project type at all, an empty
+/// list when versions are not filtered by loader
+fn version_loaders(
+    project_type: &str,
+    loader: Option<&str>,
+    is_server: bool,
+) -> Option<Vec<String>> {
+    let loader = loader.map(|l| l.to_lowercase());
+    let loaders: &[&str] = match (project_type, loader.as_deref()) {
+        ("mod", Some(l @ ("fabric" | "forge" | "neoforge" | "quilt"))) => &[l],
+        // Bukkit-family servers also run plugins built for the APIs they implement
+        ("plugin", Some("purpur")) => &["purpur", "paper", "spigot", "bukkit"],
+        ("plugin", Some("paper")) => &["paper", "spigot", "bukkit"],
+        ("plugin", Some("spigot")) => &["spigot", "bukkit"],
+        ("plugin", Some(l @ ("bukkit" | "velocity" | "bungeecord" | "waterfall"))) => &[l],
+        ("datapack", _) => &["datapack"],
+        ("resourcepack", _) if !is_server => &["minecraft"],
+        ("shader", _) if !is_server => &[],
+        _ => return None,
+    };
+    Some(loaders.iter().map(|l| l.to_string()).collect())
+}
+
+/// A project version that can be installed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCandidate {
+    pub project_id: String,
+    pub title: String,
+    pub icon_url: Option<String>,
+    pub project_type: String,
+    pub version_id: String,
+    pub version_number: String,
+}
+
+/// Why a project is not offered for install
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Already installed in the instance
+    Installed,
+    /// Modpacks, or content the instance cannot use
+    UnsupportedType,
+    /// No version for the instance's Minecraft version and loader
+    NoCompatibleVersion,
+}
+
+/// A project left out of an import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedProject {
+    pub project_id: String,
+    pub title: String,
+    pub icon_url: Option<String>,
+    pub project_type: String,
+    pub reason: SkipReason,
+}
+
+/// What importing a list of projects would install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportPreview {
+    /// Collection name, or a label for followed projects
+    pub name: String,
+    pub candidates: Vec<ImportCandidate>,
+    pub skipped: Vec<SkippedProject>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    /// Installed files
+    pub installed: Vec<String>,
+    /// Required dependencies that were installed along with the selection
+    pub dependencies: Vec<ImportCandidate>,
+}
+
+/// Latest version of a project compatible with an instance
+async fn compatible_version(
+    client: &ModrinthClient<'_>,
+    project: &Project,
+    instance: &Instance,
+) -> AppResult<Result<Version, SkipReason>> {
+    let Some(loaders) = version_loaders(
+        &project.project_type,
+        instance.loader.as_deref(),
+        instance.is_server,
+    ) else {
+        return Ok(Err(SkipReason::UnsupportedType));
+    };
+
+    let loaders: Vec<&str> = loaders.iter().map(String::as_str).collect();
+    let versions = client
+        .get_project_versions(
+            &project.id,
+            (!loaders.is_empty()).then_some(loaders.as_slice()),
+            Some(&[instance.mc_version.as_str()]),
+        )
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    Ok(content::pick_version(versions, |v| &v.version_type).ok_or(SkipReason::NoCompatibleVersion))
+}
+
+fn candidate(project: &Project, version: Version) -> ImportCandidate {
+    ImportCandidate {
+        project_id: project.id.clone(),
+        title: project.title.clone(),
+        icon_url: project.icon_url.clone(),
+        project_type: project.project_type.clone(),
+        version_id: version.id,
+        version_number: version.version_number,
+    }
+}
+
+/// Sort projects into the ones to offer and the ones to leave out
+pub async fn preview(
+    client: &ModrinthClient<'_>,
+    name: String,
+    projects: Vec<Project>,
+    instance: &Instance,
+    installed: &HashSet<String>,
+) -> AppResult<ImportPreview> {
+    let outcomes = stream::iter(&projects)
+        .map(|project| async move {
+            if installed.contains(&project.id) {
+                return Ok(Err(SkipReason::Installed));
+            }
+            compatible_version(client, project, instance).await
+        })
+        .buffered(CONCURRENT_LOOKUPS)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut result = ImportPreview {
+        name,
+        candidates: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for (project, outcome) in projects.iter().zip(outcomes) {
+        match outcome? {
+            Ok(version) => result.candidates.push(candidate(project, version)),
+            Err(reason) => result.skipped.push(SkippedProject {
+                project_id: project.id.clone(),
+                title: project.title.clone(),
+                icon_url: project.icon_url.clone(),
+                project_type: project.project_type.clone(),
+                reason,
+            }),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Required dependencies of the selected versions, and of those dependencies,
+/// that are neither installed nor selected. Dependencies without a compatible
+/// version are logged and left out
+pub async fn resolve_dependencies(
+    client: &ModrinthClient<'_>,
+    selected: &[ImportCandidate],
+    instance: &Instance,
+    installed: &HashSet<String>,
+) -> AppResult<Vec<ImportCandidate>> {
+    let mut seen: HashSet<String> = installed
+        .iter()
+        .cloned()
+        .chain(selected.iter().map(|c| c.project_id.clone()))
+        .collect();
+    let mut queue: VecDeque<String> = selected.iter().map(|c| c.version_id.clone()).collect();
+    let mut dependencies = Vec::new();
+
+    while let Some(version_id) = queue.pop_front() {
+        let version = client
+            .get_version(&version_id)
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        for dep in version
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == "required")
+        {
+            let Some(project_id) = &dep.project_id else {
+                continue;
+            };
+            if !seen.insert(project_id.clone()) {
+                continue;
+            }
+
+            let project = client
+                .get_project(project_id)
+                .await
+                .map_err(|e| AppError::Network(e.to_string()))?;
+            // Installed projects are recorded by id, not by slug
+            if installed.contains(&project.id) {
+                continue;
+            }
+
+            let resolved = match &dep.version_id {
+                Some(pinned) => Ok(client
+                    .get_version(pinned)
+                    .await
+                    .map_err(|e| AppError::Network(e.to_string()))?),
+                None => compatible_version(client, &project, instance).await?,
+            };
+            match resolved {
+                Ok(dep_version) => {
+                    queue.push_back(dep_version.id.clone());
+                    dependencies.push(candidate(&project, dep_version));
+                }
+                Err(reason) => log::warn!(
+                    "Skipping dependency {} of {}: {:?}",
+                    project.title,
+                    version.project_id,
+                    reason
+                ),
+            }
+        }
+    }
+
+    Ok(dependencies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_collection_id() {
+        let parse = |input| parse_collection_id(input);
+        assert_eq!(
+            parse("https://modrinth.com/collection/AbCd1234").as_deref(),
+            Some("AbCd1234")
+        );
+        assert_eq!(
+            parse(" modrinth.com/collection/AbCd1234/?tab=mods ").as_deref(),
+            Some("AbCd1234")
+        );
+        assert_eq!(parse("AbCd1234").as_deref(), Some("AbCd1234"));
+        assert_eq!(parse("https://modrinth.com/mod/sodium"), None);
+        assert_eq!(parse("https://modrinth.com/collection/"), None);
+        assert_eq!(parse("../etc"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn test_version_loaders() {
+        let loaders = |ptype, loader, is_server| version_loaders(ptype, loader, is_server);
+        assert_eq!(
+            loaders("mod", Some("Fabric"), false),
+            Some(vec!["fabric".to_string()])
+        );
+        assert_eq!(loaders("mod", None, false), None);
+        assert_eq!(loaders("mod", Some("paper"), true), None);
+        assert_eq!(
+            loaders("plugin", Some("paper"), true),
+            Some(vec![
+                "paper".to_string(),
+                "spigot".to_string(),
+                "bukkit".to_string()
+            ])
+        );
+        assert_eq!(loaders("plugin", Some("fabric"), false), None);
+        assert_eq!(
+            loaders("datapack", None, true),
+            Some(vec!["datapack".to_string()])
+        );
+        assert_eq!(loaders("shader", Some("fabric"), false), Some(vec![]));
+        assert_eq!(loaders("shader", Some("fabric"), true), None);
+        assert_eq!(loaders("resourcepack", None, true), None);
+        assert_eq!(loaders("modpack", Some("fabric"), false), None);
+    }
+}
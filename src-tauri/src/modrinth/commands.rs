@@ -1,11 +1,23 @@
+use crate::crypto;
 use crate::db::instances::Instance;
+use crate::db::release_feed::ReleaseFeedEntry;
+use crate::db::settings::set_setting;
 use crate::error::{AppError, AppResult};
+use crate::instance::datapacks::{self, DatapackTarget};
+use crate::instance::dedup;
+use crate::instance::malware_scan;
+use crate::modpacks::install_plan;
 use crate::state::SharedState;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tauri::State;
 use tracing::debug;
 
-use super::{build_facets, ModrinthClient, SearchHit, SearchQuery, Version, VersionFile};
+use super::{collections, feed, ModrinthClient, SearchQuery};
+use crate::content::{
+    self, shader_loader, updates, ContentDependency, ContentFile, ContentProject, ContentProvider,
+    ContentQuery, ContentSearchHit, ContentSource, ContentVersion,
+};
 
 /// Determine the content folder name based on project type and loader
 fn get_content_folder(
@@ -55,8 +67,8 @@ pub struct ModSearchResult {
     pub loaders: Vec<String>,
 }
 
-impl From<SearchHit> for ModSearchResult {
-    fn from(hit: SearchHit) -> Self {
+impl From<ContentSearchHit> for ModSearchResult {
+    fn from(hit: ContentSearchHit) -> Self {
         Self {
             project_id: hit.project_id,
             slug: hit.slug,
@@ -66,7 +78,7 @@ impl From<SearchHit> for ModSearchResult {
             downloads: hit.downloads,
             icon_url: hit.icon_url,
             categories: hit.categories,
-            game_versions: hit.versions,
+            game_versions: hit.game_versions,
             loaders: vec![],
         }
     }
@@ -75,6 +87,7 @@ impl From<SearchHit> for ModSearchResult {
 /// Simplified version info returned to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModVersionInfo {
+    pub source: ContentSource,
     pub id: String,
     pub name: String,
     pub version_number: String,
@@ -87,9 +100,10 @@ pub struct ModVersionInfo {
     pub dependencies: Vec<ModDependency>,
 }
 
-impl From<Version> for ModVersionInfo {
-    fn from(v: Version) -> Self {
+impl From<ContentVersion> for ModVersionInfo {
+    fn from(v: ContentVersion) -> Self {
         Self {
+            source: v.source,
             id: v.id,
             name: v.name,
             version_number: v.version_number,
@@ -110,23 +124,24 @@ impl From<Version> for ModVersionInfo {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModFileInfo {
-    pub url: String,
+    /// `None` when the file has to be downloaded manually
+    pub url: Option<String>,
     pub filename: String,
     pub primary: bool,
     pub size: u64,
-    pub sha1: String,
-    pub sha512: String,
+    pub sha1: Option<String>,
+    pub sha512: Option<String>,
 }
 
-impl From<VersionFile> for ModFileInfo {
-    fn from(f: VersionFile) -> Self {
+impl From<ContentFile> for ModFileInfo {
+    fn from(f: ContentFile) -> Self {
         Self {
             url: f.url,
             filename: f.filename,
             primary: f.primary,
             size: f.size,
-            sha1: f.hashes.sha1,
-            sha512: f.hashes.sha512,
+            sha1: f.sha1,
+            sha512: f.sha512,
         }
     }
 }
@@ -138,8 +153,8 @@ pub struct ModDependency {
     pub dependency_type: String,
 }
 
-impl From<super::Dependency> for ModDependency {
-    fn from(d: super::Dependency) -> Self {
+impl From<ContentDependency> for ModDependency {
+    fn from(d: ContentDependency) -> Self {
         Self {
             project_id: d.project_id,
             version_id: d.version_id,
@@ -155,9 +170,11 @@ pub struct ModSearchResponse {
     pub total_hits: u32,
     pub offset: u32,
     pub limit: u32,
+    /// Cached results shown because the provider could not be reached
+    pub stale: bool,
 }
 
-/// Search for mods on Modrinth
+/// Search for mods on Modrinth or CurseForge
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub async fn search_modrinth_mods(
@@ -170,52 +187,24 @@ pub async fn search_modrinth_mods(
     sort_by: Option<String>,
     offset: Option<u32>,
     limit: Option<u32>,
+    source: Option<ContentSource>,
 ) -> AppResult<ModSearchResponse> {
     let state = state.read().await;
-    let client = ModrinthClient::new(&state.http_client);
-
-    // Build facets for filtering
-    let game_versions = game_version.as_ref().map(|v| vec![v.as_str()]);
-
-    // Convert categories to &str slice
-    let categories_strs: Option<Vec<&str>> = categories
-        .as_ref()
-        .map(|cats| cats.iter().map(|s| s.as_str()).collect());
-
-    // Default to "mod" if not specified, but allow "plugin" for servers
-    let ptype = project_type.as_deref().unwrap_or("mod");
+    let provider = content::provider_for_state(&state, source.unwrap_or_default()).await?;
 
-    // Only include loaders for mods and plugins - shaders, resourcepacks, datapacks don't use loaders
-    let loaders = match ptype {
-        "mod" | "plugin" => loader.as_ref().map(|l| vec![l.as_str()]),
-        _ => None, // resourcepack, shader, datapack don't need loader filter
+    let content_query = ContentQuery {
+        query,
+        // Default to "mod" if not specified, but allow "plugin" for servers
+        project_type: project_type.unwrap_or_else(|| "mod".to_string()),
+        game_version,
+        loader,
+        categories: categories.unwrap_or_default(),
+        sort_by,
+        offset,
+        limit,
     };
 
-    let facets = build_facets(
-        Some(ptype),
-        categories_strs.as_deref(),
-        game_versions.as_deref(),
-        loaders.as_deref(),
-    );
-
-    // Sort index: relevance, downloads, follows, newest, updated
-    let sort_index = sort_by.as_deref().unwrap_or("relevance");
-
-    let mut search_query = SearchQuery::new(&query)
-        .with_facets(&facets)
-        .with_index(sort_index);
-
-    if let Some(off) = offset {
-        search_query = search_query.with_offset(off);
-    }
-    if let Some(lim) = limit {
-        search_query = search_query.with_limit(lim);
-    }
-
-    let response = client
-        .search(&search_query)
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    let response = provider.search_projects(&content_query).await?;
 
     Ok(ModSearchResponse {
         results: response
@@ -226,6 +215,7 @@ pub async fn search_modrinth_mods(
         total_hits: response.total_hits,
         offset: response.offset,
         limit: response.limit,
+        stale: response.stale,
     })
 }
 
@@ -237,24 +227,21 @@ pub async fn get_modrinth_mod_versions(
     game_version: Option<String>,
     loader: Option<String>,
     project_type: Option<String>,
+    source: Option<ContentSource>,
 ) -> AppResult<Vec<ModVersionInfo>> {
     let state = state.read().await;
-    let client = ModrinthClient::new(&state.http_client);
-
-    let game_versions = game_version.as_ref().map(|v| vec![v.as_str()]);
+    let provider = content::provider_for_state(&state, source.unwrap_or_default()).await?;
 
     // Only include loaders for mods and plugins - shaders, resourcepacks, datapacks don't use loaders
     let ptype = project_type.as_deref().unwrap_or("mod");
-    let normalized_loader = loader.map(|l| l.to_lowercase());
-    let loaders = match ptype {
-        "mod" | "plugin" => normalized_loader.as_ref().map(|l| vec![l.as_str()]),
+    let loader = match ptype {
+        "mod" | "plugin" => loader.as_deref(),
         _ => None, // resourcepack, shader, datapack don't need loader filter
     };
 
-    let versions = client
-        .get_project_versions(&project_id, loaders.as_deref(), game_versions.as_deref())
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    let versions = provider
+        .fetch_versions(&project_id, loader, game_version.as_deref())
+        .await?;
 
     Ok(versions.into_iter().map(ModVersionInfo::from).collect())
 }
@@ -268,47 +255,119 @@ pub struct StoredDependency {
 
 /// Metadata saved for mods installed from Modrinth
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct ModMetadata {
-    name: String,
-    version: String,
-    project_id: String,
-    version_id: Option<String>,
-    icon_url: Option<String>,
+pub(crate) struct ModMetadata {
+    pub(crate) name: String,
+    pub(crate) version: String,
+    pub(crate) project_id: String,
+    pub(crate) version_id: Option<String>,
+    pub(crate) icon_url: Option<String>,
     /// Server-side compatibility: "required", "optional", or "unsupported"
     #[serde(default)]
-    server_side: Option<String>,
+    pub(crate) server_side: Option<String>,
     /// Client-side compatibility: "required", "optional", or "unsupported"
     #[serde(default)]
-    client_side: Option<String>,
+    pub(crate) client_side: Option<String>,
     /// Dependencies (project_id and type)
     #[serde(default)]
-    dependencies: Vec<StoredDependency>,
+    pub(crate) dependencies: Vec<StoredDependency>,
+    /// Where the file was installed from ("curseforge", "hangar"), `None` for Modrinth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) source: Option<String>,
 }
 
-/// Helper function to find the first world folder in saves/
-async fn find_world_folder(instance_dir: &std::path::Path) -> Option<String> {
-    let saves_dir = instance_dir.join("saves");
-    if !saves_dir.exists() {
-        return None;
+impl ModMetadata {
+    /// Metadata for a version installed from a content provider
+    pub(crate) fn from_content(project: &ContentProject, version: &ContentVersion) -> Self {
+        // Extract required/optional dependencies
+        let dependencies = version
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == "required" || d.dependency_type == "optional")
+            .filter_map(|d| {
+                d.project_id.as_ref().map(|pid| StoredDependency {
+                    project_id: pid.clone(),
+                    dependency_type: d.dependency_type.clone(),
+                })
+            })
+            .collect();
+
+        Self {
+            name: project.title.clone(),
+            version: version.version_number.clone(),
+            project_id: project.id.clone(),
+            version_id: Some(version.id.clone()),
+            icon_url: project.icon_url.clone(),
+            server_side: project.server_side.clone(),
+            client_side: project.client_side.clone(),
+            dependencies,
+            source: project.source.meta_value(),
+        }
     }
 
-    let mut entries = match tokio::fs::read_dir(&saves_dir).await {
-        Ok(e) => e,
-        Err(_) => return None,
-    };
+    /// Write the metadata next to the content file it describes
+    pub(crate) async fn write_for(&self, target_dir: &std::path::Path, filename: &str) {
+        // Strip appropriate extension based on file type
+        let base_filename = filename.trim_end_matches(".jar").trim_end_matches(".zip");
+        let meta_path = target_dir.join(format!("{}.meta.json", base_filename));
 
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        if let Ok(file_type) = entry.file_type().await {
-            if file_type.is_dir() {
-                return Some(entry.file_name().to_string_lossy().to_string());
-            }
+        if let Ok(meta_json) = serde_json::to_string_pretty(self) {
+            let _ = tokio::fs::write(&meta_path, meta_json).await;
         }
     }
+}
 
-    None
+/// Folder content of a project type is installed to.
+/// Datapacks go into the server world, or the first world of a client
+async fn content_target_dir(
+    instance_dir: &std::path::Path,
+    is_server: bool,
+    project_type: Option<&str>,
+    folder_name: &str,
+) -> std::path::PathBuf {
+    if project_type == Some("datapack") {
+        datapacks::default_datapacks_dir(instance_dir, is_server).await
+    } else {
+        instance_dir.join(folder_name)
+    }
+}
+
+/// Folders content is installed to, with datapacks going to the picked worlds
+/// or to the global datapacks
+async fn content_target_dirs(
+    instance_dir: &std::path::Path,
+    is_server: bool,
+    project_type: Option<&str>,
+    folder_name: &str,
+    datapack_target: Option<&DatapackTarget>,
+) -> AppResult<Vec<std::path::PathBuf>> {
+    if project_type == Some("datapack") {
+        datapacks::target_dirs(instance_dir, is_server, datapack_target).await
+    } else {
+        Ok(vec![instance_dir.join(folder_name)])
+    }
+}
+
+/// Copy a datapack installed in the first target folder to the other ones,
+/// and start tracking worlds when it was added to the global datapacks
+async fn install_datapack_copies(
+    instance_dir: &std::path::Path,
+    is_server: bool,
+    target_dirs: &[std::path::PathBuf],
+    filename: &str,
+    datapack_target: Option<&DatapackTarget>,
+) -> AppResult<()> {
+    if let Some((first, others)) = target_dirs.split_first() {
+        for dir in others {
+            datapacks::copy_datapack(first, dir, filename).await?;
+        }
+    }
+    if datapack_target == Some(&DatapackTarget::Global) {
+        datapacks::init_global_datapacks(instance_dir, is_server).await?;
+    }
+    Ok(())
 }
 
-/// Install a mod from Modrinth to an instance
+/// Install a mod from Modrinth or CurseForge to an instance
 #[tauri::command]
 pub async fn install_modrinth_mod(
     state: State<'_, SharedState>,
@@ -316,9 +375,11 @@ pub async fn install_modrinth_mod(
     project_id: String,
     version_id: String,
     project_type: Option<String>,
-) -> AppResult<String> {
+    source: Option<ContentSource>,
+    datapack_target: Option<DatapackTarget>,
+) -> AppResult<InstalledContent> {
     let state_guard = state.read().await;
-    let client = ModrinthClient::new(&state_guard.http_client);
+    let provider = content::provider_for_state(&state_guard, source.unwrap_or_default()).await?;
 
     // Get the instance
     let instance = Instance::get_by_id(&state_guard.db, &instance_id)
@@ -327,23 +388,14 @@ pub async fn install_modrinth_mod(
         .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
 
     // Get the project info (for icon_url and title)
-    let project = client
-        .get_project(&project_id)
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    let project = provider.fetch_project(&project_id).await?;
 
     // Get the version info
-    let version = client
-        .get_version(&version_id)
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    let version = provider.fetch_version(&project_id, &version_id).await?;
 
     // Find the primary file
     let file = version
-        .files
-        .iter()
-        .find(|f| f.primary)
-        .or_else(|| version.files.first())
+        .primary_file()
         .ok_or_else(|| AppError::Instance("No files found for this version".to_string()))?;
 
     // Determine destination folder based on project type and loader
@@ -355,77 +407,60 @@ pub async fn install_modrinth_mod(
         .join("instances")
         .join(&instance.game_dir);
 
-    // Handle datapacks specially - they go into saves/<world>/datapacks/
-    let target_dir = if ptype == Some("datapack") {
-        // Find existing world folder or use default "world"
-        let world_name = find_world_folder(&instance_dir)
-            .await
-            .unwrap_or_else(|| "world".to_string());
-        instance_dir
-            .join("saves")
-            .join(&world_name)
-            .join("datapacks")
-    } else {
-        instance_dir.join(folder_name)
+    let target_dirs = content_target_dirs(
+        &instance_dir,
+        instance.is_server,
+        ptype,
+        folder_name,
+        datapack_target.as_ref(),
+    )
+    .await?;
+
+    // Authors can disallow downloads from third-party launchers (CurseForge)
+    if file.url.is_none() {
+        return Err(content::manual_download_error(
+            &project,
+            &version.id,
+            folder_name,
+        ));
+    }
+
+    // Check if file already exists, datapacks may already be in some of the worlds
+    let target_dirs: Vec<_> = target_dirs
+        .into_iter()
+        .filter(|dir| !dir.join(&file.filename).exists())
+        .collect();
+    let Some(target_dir) = target_dirs.first() else {
+        return Err(AppError::Instance(format!(
+            "File {} already exists",
+            file.filename
+        )));
     };
 
     // Create directory if it doesn't exist
-    tokio::fs::create_dir_all(&target_dir)
+    tokio::fs::create_dir_all(target_dir)
         .await
         .map_err(|e| AppError::Io(format!("Failed to create {} directory: {}", folder_name, e)))?;
 
     let dest_path = target_dir.join(&file.filename);
 
-    // Check if file already exists
-    if dest_path.exists() {
-        return Err(AppError::Instance(format!(
-            "File {} already exists",
-            file.filename
-        )));
-    }
-
     // Download the file
-    client
-        .download_file(file, &dest_path)
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    content::download_content_file(&state_guard.http_client, file, &dest_path).await?;
+    malware_scan::check_added_file(&state_guard, &instance_id, &instance_dir, &dest_path).await?;
 
     // Save metadata file with icon_url, compatibility info, and dependencies
-    // Strip appropriate extension based on file type
-    let base_filename = file
-        .filename
-        .trim_end_matches(".jar")
-        .trim_end_matches(".zip");
-    let meta_filename = format!("{}.meta.json", base_filename);
-    let meta_path = target_dir.join(&meta_filename);
-
-    // Extract required/optional dependencies
-    let dependencies: Vec<StoredDependency> = version
-        .dependencies
-        .iter()
-        .filter(|d| d.dependency_type == "required" || d.dependency_type == "optional")
-        .filter_map(|d| {
-            d.project_id.as_ref().map(|pid| StoredDependency {
-                project_id: pid.clone(),
-                dependency_type: d.dependency_type.clone(),
-            })
-        })
-        .collect();
-
-    let metadata = ModMetadata {
-        name: project.title,
-        version: version.version_number.clone(),
-        project_id: project_id.clone(),
-        version_id: Some(version_id.clone()),
-        icon_url: project.icon_url,
-        server_side: Some(project.server_side),
-        client_side: Some(project.client_side),
-        dependencies,
-    };
-
-    if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
-        let _ = tokio::fs::write(&meta_path, meta_json).await;
-    }
+    ModMetadata::from_content(&project, &version)
+        .write_for(target_dir, &file.filename)
+        .await;
+
+    install_datapack_copies(
+        &instance_dir,
+        instance.is_server,
+        &target_dirs,
+        &file.filename,
+        datapack_target.as_ref(),
+    )
+    .await?;
 
     let content_type_name = match ptype {
         Some("resourcepack") => "resource pack",
@@ -442,14 +477,139 @@ pub async fn install_modrinth_mod(
     };
 
     log::info!(
-        "Installed {} {} (version {}) to instance {} (folder: {})",
+        "Installed {} {} (version {}) from {} to instance {} (folder: {})",
         content_type_name,
         project_id,
         version_id,
+        provider.source().display_name(),
         instance_id,
         target_dir.display()
     );
 
+    // Shader packs do nothing without a shader loader, tell what is missing
+    let shader_loader = if ptype == Some("shader") {
+        match shader_loader_status(&state_guard, &instance).await {
+            Ok(status) => Some(status),
+            Err(e) => {
+                log::warn!(
+                    "Failed to check the shader loader of {}: {}",
+                    instance_id,
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(InstalledContent {
+        filename: file.filename.clone(),
+        shader_loader,
+    })
+}
+
+/// A file installed from a content provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledContent {
+    pub filename: String,
+    /// Shader packs only: whether the instance can load them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shader_loader: Option<ShaderLoaderStatus>,
+}
+
+/// Install a file the user downloaded by hand, for projects that disallow
+/// downloads from third-party launchers. The file must match the expected version
+#[tauri::command]
+pub async fn install_manual_download(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    project_id: String,
+    version_id: String,
+    file_path: String,
+    project_type: Option<String>,
+    source: Option<ContentSource>,
+    datapack_target: Option<DatapackTarget>,
+) -> AppResult<String> {
+    let state_guard = state.read().await;
+    let provider = content::provider_for_state(&state_guard, source.unwrap_or_default()).await?;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let project = provider.fetch_project(&project_id).await?;
+    let version = provider.fetch_version(&project_id, &version_id).await?;
+    let file = version
+        .primary_file()
+        .ok_or_else(|| AppError::Instance("No files found for this version".to_string()))?;
+
+    let bytes = tokio::fs::read(&file_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
+
+    // Make sure the user picked the right file
+    let hashes = file.hashes();
+    content::verify_file_hash(&file.filename, hashes, &bytes).map_err(|e| {
+        if hashes.is_empty() {
+            return e;
+        }
+        AppError::Instance(format!(
+            "The selected file is not {} ({})",
+            file.filename, version.version_number
+        ))
+    })?;
+
+    let ptype = project_type.as_deref();
+    let folder_name = get_content_folder(ptype, instance.loader.as_deref(), instance.is_server);
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+    let target_dirs = content_target_dirs(
+        &instance_dir,
+        instance.is_server,
+        ptype,
+        folder_name,
+        datapack_target.as_ref(),
+    )
+    .await?;
+    let target_dir = &target_dirs[0];
+
+    tokio::fs::create_dir_all(target_dir)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to create {} directory: {}", folder_name, e)))?;
+
+    let dest_path = target_dir.join(&file.filename);
+    dedup::unlink_shared(&dest_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to copy {}: {}", file.filename, e)))?;
+    tokio::fs::write(&dest_path, &bytes)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to copy {}: {}", file.filename, e)))?;
+    malware_scan::check_added_file(&state_guard, &instance_id, &instance_dir, &dest_path).await?;
+
+    ModMetadata::from_content(&project, &version)
+        .write_for(target_dir, &file.filename)
+        .await;
+
+    install_datapack_copies(
+        &instance_dir,
+        instance.is_server,
+        &target_dirs,
+        &file.filename,
+        datapack_target.as_ref(),
+    )
+    .await?;
+
+    log::info!(
+        "Installed manually downloaded {} ({}) to instance {}",
+        project.title,
+        version.version_number,
+        instance_id
+    );
+
     Ok(file.filename.clone())
 }
 
@@ -476,17 +636,8 @@ pub async fn get_installed_mod_ids(
         .join(&instance.game_dir);
 
     // Handle datapacks specially
-    let content_dir = if ptype == Some("datapack") {
-        let world_name = find_world_folder(&instance_dir)
-            .await
-            .unwrap_or_else(|| "world".to_string());
-        instance_dir
-            .join("saves")
-            .join(&world_name)
-            .join("datapacks")
-    } else {
-        instance_dir.join(folder_name)
-    };
+    let content_dir =
+        content_target_dir(&instance_dir, instance.is_server, ptype, folder_name).await;
 
     if !content_dir.exists() {
         return Ok(vec![]);
@@ -631,17 +782,8 @@ pub async fn install_modrinth_mods_batch(
         .join(&instance.game_dir);
 
     // Handle datapacks specially
-    let target_dir = if ptype == Some("datapack") {
-        let world_name = find_world_folder(&instance_dir)
-            .await
-            .unwrap_or_else(|| "world".to_string());
-        instance_dir
-            .join("saves")
-            .join(&world_name)
-            .join("datapacks")
-    } else {
-        instance_dir.join(folder_name)
-    };
+    let target_dir =
+        content_target_dir(&instance_dir, instance.is_server, ptype, folder_name).await;
 
     // Create directory if it doesn't exist
     tokio::fs::create_dir_all(&target_dir)
@@ -697,6 +839,14 @@ pub async fn install_modrinth_mods_batch(
             continue;
         }
 
+        if let Err(e) =
+            malware_scan::check_added_file(&state_guard, &instance_id, &instance_dir, &dest_path)
+                .await
+        {
+            log::warn!("Not installing {}: {}", file.filename, e);
+            continue;
+        }
+
         // Save metadata with compatibility info and dependencies
         let meta_filename = format!("{}.meta.json", file.filename.trim_end_matches(".jar"));
         let meta_path = target_dir.join(&meta_filename);
@@ -723,6 +873,7 @@ pub async fn install_modrinth_mods_batch(
             server_side: Some(project.server_side.clone()),
             client_side: Some(project.client_side.clone()),
             dependencies,
+            source: None,
         };
 
         if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
@@ -736,6 +887,333 @@ pub async fn install_modrinth_mods_batch(
     Ok(installed_files)
 }
 
+// ============= Shader Loaders =============
+
+/// Whether the shader packs of an instance can work, and what to install if not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShaderLoaderStatus {
+    pub state: shader_loader::ShaderLoaderState,
+    pub installed: Option<shader_loader::InstalledShaderLoader>,
+    /// Compatible shader loader from Modrinth, when none is installed
+    pub offer: Option<shader_loader::ShaderLoaderOffer>,
+}
+
+/// Check the shader loader of an instance, resolving one to offer when it is missing.
+/// A missing or disabled shader loader is only reported when there are shader packs
+async fn shader_loader_status(
+    state: &crate::state::AppState,
+    instance: &Instance,
+) -> AppResult<ShaderLoaderStatus> {
+    let loader = match instance.loader.as_deref() {
+        Some(loader) if !instance.is_server && !shader_loader::candidates(loader).is_empty() => {
+            loader
+        }
+        _ => {
+            return Ok(ShaderLoaderStatus {
+                state: shader_loader::ShaderLoaderState::Unsupported,
+                installed: None,
+                offer: None,
+            })
+        }
+    };
+
+    let instance_dir = state.data_dir.join("instances").join(&instance.game_dir);
+    let mods_dir = instance_dir.join(get_content_folder(Some("mod"), Some(loader), false));
+    let shaderpacks_dir =
+        instance_dir.join(get_content_folder(Some("shader"), Some(loader), false));
+    let scan = shader_loader::scan(&mods_dir).await?;
+    let mut loader_state = scan.state();
+    if loader_state != shader_loader::ShaderLoaderState::Enabled
+        && !shader_loader::has_shader_packs(&shaderpacks_dir).await
+    {
+        loader_state = shader_loader::ShaderLoaderState::NoShaderPacks;
+    }
+
+    let offer = if loader_state == shader_loader::ShaderLoaderState::Missing {
+        let client = ModrinthClient::new(&state.http_client);
+        match shader_loader::resolve(&client, &instance.mc_version, loader).await {
+            Ok(offer) => offer,
+            Err(e) => {
+                log::warn!(
+                    "Failed to resolve a shader loader for {}: {}",
+                    instance.id,
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(ShaderLoaderStatus {
+        state: loader_state,
+        installed: scan.shader_loader,
+        offer,
+    })
+}
+
+/// Check the shader loader of an instance, resolving one to offer when it is missing
+#[tauri::command]
+pub async fn get_shader_loader_status(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<ShaderLoaderStatus> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    shader_loader_status(&state_guard, &instance).await
+}
+
+/// Install the shader loader offered for an instance and its required dependencies
+#[tauri::command]
+pub async fn install_shader_loader(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<String>> {
+    let status = get_shader_loader_status(state.clone(), instance_id.clone()).await?;
+    let offer = match status.state {
+        shader_loader::ShaderLoaderState::Missing => status.offer.ok_or_else(|| {
+            AppError::Instance(
+                "No shader loader is available for this Minecraft version and loader".to_string(),
+            )
+        })?,
+        shader_loader::ShaderLoaderState::Unsupported => {
+            return Err(AppError::Instance(
+                "Shader packs are not supported by this instance".to_string(),
+            ))
+        }
+        _ => return Ok(vec![]),
+    };
+
+    let installed_ids = {
+        let state_guard = state.read().await;
+        let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+        let mods_dir = state_guard
+            .data_dir
+            .join("instances")
+            .join(&instance.game_dir)
+            .join(get_content_folder(
+                Some("mod"),
+                instance.loader.as_deref(),
+                false,
+            ));
+        shader_loader::scan(&mods_dir).await?.project_ids
+    };
+
+    // Same path as dependencies picked in the browser
+    install_modrinth_mods_batch(
+        state,
+        instance_id,
+        offer.to_install(&installed_ids),
+        Some("mod".to_string()),
+    )
+    .await
+}
+
+// ============= Collections =============
+
+/// Project ids installed in an instance, across content types
+async fn installed_project_ids(
+    state: &State<'_, SharedState>,
+    instance_id: &str,
+) -> AppResult<HashSet<String>> {
+    let mut installed = HashSet::new();
+    for project_type in [None, Some("resourcepack"), Some("shader"), Some("datapack")] {
+        installed.extend(
+            get_installed_mod_ids(
+                state.clone(),
+                instance_id.to_string(),
+                project_type.map(str::to_string),
+            )
+            .await?,
+        );
+    }
+    Ok(installed)
+}
+
+/// The Modrinth account of the stored access token, if one is configured
+#[tauri::command]
+pub async fn get_modrinth_account(state: State<'_, SharedState>) -> AppResult<Option<super::User>> {
+    let state_guard = state.read().await;
+    let Some(token) = collections::access_token(&state_guard).await? else {
+        return Ok(None);
+    };
+
+    let client = ModrinthClient::new(&state_guard.http_client);
+    let user = client
+        .get_current_user(&token)
+        .await
+        .map_err(|e| AppError::Auth(e.to_string()))?;
+    Ok(Some(user))
+}
+
+/// Store a Modrinth personal access token (encrypted), or remove it with `None`
+/// The token is checked against the API before it is saved
+#[tauri::command]
+pub async fn set_modrinth_token(
+    state: State<'_, SharedState>,
+    token: Option<String>,
+) -> AppResult<Option<super::User>> {
+    let state_guard = state.read().await;
+
+    match token.as_deref().map(str::trim) {
+        Some(token) if !token.is_empty() => {
+            let client = ModrinthClient::new(&state_guard.http_client);
+            let user = client
+                .get_current_user(token)
+                .await
+                .map_err(|e| AppError::Auth(e.to_string()))?;
+
+            let encrypted = crypto::encrypt(&state_guard.encryption_key, token)?;
+            set_setting(
+                &state_guard.db,
+                collections::ACCESS_TOKEN_SETTING,
+                &encrypted,
+            )
+            .await?;
+            Ok(Some(user))
+        }
+        _ => {
+            sqlx::query("DELETE FROM settings WHERE key = ?")
+                .bind(collections::ACCESS_TOKEN_SETTING)
+                .execute(&state_guard.db)
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Match the projects of a Modrinth collection against an instance
+#[tauri::command]
+pub async fn preview_collection_import(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    collection: String,
+) -> AppResult<collections::ImportPreview> {
+    let collection_id = collections::parse_collection_id(&collection)
+        .ok_or_else(|| AppError::Custom("Invalid collection URL or id".to_string()))?;
+    let installed = installed_project_ids(&state, &instance_id).await?;
+
+    let state_guard = state.read().await;
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    // Private collections can be read with their owner's token
+    let token = collections::access_token(&state_guard).await?;
+    let client = ModrinthClient::new(&state_guard.http_client);
+    let collection = client
+        .get_collection(&collection_id, token.as_deref())
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    let ids: Vec<&str> = collection.projects.iter().map(String::as_str).collect();
+    let projects = client
+        .get_projects_batched(&ids)
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    collections::preview(&client, collection.name, projects, &instance, &installed).await
+}
+
+/// Match the projects followed by the Modrinth account of the stored token
+/// against an instance
+#[tauri::command]
+pub async fn preview_followed_import(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<collections::ImportPreview> {
+    let installed = installed_project_ids(&state, &instance_id).await?;
+
+    let state_guard = state.read().await;
+    let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    let token = collections::access_token(&state_guard)
+        .await?
+        .ok_or_else(|| AppError::Auth("No Modrinth access token configured".to_string()))?;
+    let client = ModrinthClient::new(&state_guard.http_client);
+    let user = client
+        .get_current_user(&token)
+        .await
+        .map_err(|e| AppError::Auth(e.to_string()))?;
+    let projects = client
+        .get_followed_projects(&user.id, &token)
+        .await
+        .map_err(|e| AppError::Network(e.to_string()))?;
+
+    collections::preview(
+        &client,
+        format!("Followed by {}", user.username),
+        projects,
+        &instance,
+        &installed,
+    )
+    .await
+}
+
+/// Install the candidates picked from an import preview, with their required dependencies
+#[tauri::command]
+pub async fn import_modrinth_projects(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    candidates: Vec<collections::ImportCandidate>,
+) -> AppResult<collections::ImportResult> {
+    let installed = installed_project_ids(&state, &instance_id).await?;
+
+    let dependencies = {
+        let state_guard = state.read().await;
+        let instance = Instance::get_by_id(&state_guard.db, &instance_id)
+            .await
+            .map_err(AppError::from)?
+            .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+        let client = ModrinthClient::new(&state_guard.http_client);
+        collections::resolve_dependencies(&client, &candidates, &instance, &installed).await?
+    };
+
+    // One batch per content folder
+    let mut by_type: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for c in candidates.iter().chain(&dependencies) {
+        if installed.contains(&c.project_id) {
+            continue;
+        }
+        let pair = (c.project_id.clone(), c.version_id.clone());
+        match by_type.iter_mut().find(|(t, _)| *t == c.project_type) {
+            Some((_, pairs)) => pairs.push(pair),
+            None => by_type.push((c.project_type.clone(), vec![pair])),
+        }
+    }
+
+    let mut files = Vec::new();
+    for (project_type, pairs) in by_type {
+        files.extend(
+            install_modrinth_mods_batch(
+                state.clone(),
+                instance_id.clone(),
+                pairs,
+                Some(project_type),
+            )
+            .await?,
+        );
+    }
+
+    Ok(collections::ImportResult {
+        installed: files,
+        dependencies,
+    })
+}
+
 // ============= Modpack Installation =============
 
 /// Modrinth modpack index format
@@ -747,6 +1225,7 @@ pub struct ModpackIndex {
     #[serde(rename = "versionId")]
     pub version_id: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
     pub files: Vec<ModpackFile>,
     pub dependencies: std::collections::HashMap<String, String>,
@@ -759,7 +1238,7 @@ pub struct ModpackFile {
     pub downloads: Vec<String>,
     #[serde(rename = "fileSize")]
     pub file_size: u64,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<ModpackFileEnv>,
 }
 
@@ -786,14 +1265,54 @@ pub struct ModpackInstallResult {
     pub files_count: usize,
 }
 
-/// Install a modpack from Modrinth and create a new instance
+/// List the required, optional and unsupported files of a modpack version,
+/// for the client and the server, before installing it
 #[tauri::command]
+pub async fn get_modrinth_modpack_plan(
+    state: State<'_, SharedState>,
+    version_id: String,
+) -> AppResult<install_plan::ModpackInstallPlan> {
+    let http_client = state.read().await.http_client.clone();
+    let version = ModrinthClient::new(&http_client)
+        .get_version(&version_id)
+        .await
+        .map_err(|e| AppError::Network(format!("Failed to get modpack version: {}", e)))?;
+
+    let (_, index) = crate::modpacks::update::download_mrpack(&http_client, &version).await?;
+    let mc_version = index
+        .dependencies
+        .get("minecraft")
+        .cloned()
+        .ok_or_else(|| AppError::Instance("Modpack missing minecraft version".to_string()))?;
+    let (loader, loader_version) = crate::modpacks::update::index_loader(&index);
+
+    Ok(install_plan::ModpackInstallPlan {
+        name: index.name.clone(),
+        version_number: version.version_number,
+        mc_version,
+        loader,
+        loader_version,
+        client: install_plan::SidePlan::new(&index, false),
+        server: install_plan::SidePlan::new(&index, true),
+    })
+}
+
+/// Install a modpack from Modrinth and create a new instance.
+/// `optional_files` are the optional files to install (all of them when `None`),
+/// and `server` creates a server instance with the server-side files and
+/// `server-overrides/` instead of a client. Servers can only start once
+/// `eula_accepted` is given here or through `accept_server_eula`
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn install_modrinth_modpack(
     state: State<'_, SharedState>,
     app: tauri::AppHandle,
     project_id: String,
     version_id: String,
     instance_name: Option<String>,
+    optional_files: Option<Vec<String>>,
+    server: Option<bool>,
+    eula_accepted: Option<bool>,
 ) -> AppResult<ModpackInstallResult> {
     use crate::db::instances::Instance;
     use crate::download::client::download_files_parallel_sha512;
@@ -915,6 +1434,18 @@ pub async fn install_modrinth_modpack(
     .map_err(|e| AppError::Instance(format!("Failed to parse modpack: {}", e)))?
     .map_err(|e| AppError::Instance(format!("Failed to parse modpack: {}", e)))?;
 
+    let is_server = server.unwrap_or(false);
+    let selected_files = install_plan::select_files(&index, is_server, optional_files.as_deref())?;
+    if let Some(file) = selected_files
+        .iter()
+        .find(|f| !crate::modpacks::is_safe_relative_path(&f.path))
+    {
+        return Err(AppError::Instance(format!(
+            "Modpack file path escapes the instance: {}",
+            file.path
+        )));
+    }
+
     // Extract loader info from dependencies
     let mc_version = index
         .dependencies
@@ -955,7 +1486,7 @@ pub async fn install_modrinth_modpack(
         mc_version: mc_version.clone(),
         loader: loader.clone(),
         loader_version: loader_version.clone(),
-        is_server: false,
+        is_server,
         is_proxy: false,
         server_port: 25565,
         modrinth_project_id: Some(project_id.clone()),
@@ -964,6 +1495,13 @@ pub async fn install_modrinth_modpack(
         .await
         .map_err(AppError::from)?;
 
+    // Record the EULA acceptance shown before installing as a server
+    if is_server && eula_accepted.unwrap_or(false) {
+        crate::db::server_eula::EulaAcceptance::record(&state_guard.db, &instance.id)
+            .await
+            .map_err(AppError::from)?;
+    }
+
     // Create instance directory
     let instance_dir = state_guard
         .data_dir
@@ -1062,40 +1600,11 @@ pub async fn install_modrinth_modpack(
         }),
     );
 
-    // Helper function to extract version hash from Modrinth CDN URL
-    // URL format: https://cdn.modrinth.com/data/{project_id}/versions/{version_id}/{filename}
-    fn extract_modrinth_ids(url: &str) -> Option<(String, String)> {
-        if url.contains("cdn.modrinth.com/data/") {
-            let parts: Vec<&str> = url.split('/').collect();
-            // Find "data" index and extract project_id and version_id
-            if let Some(data_idx) = parts.iter().position(|&p| p == "data") {
-                if parts.len() > data_idx + 4 {
-                    let project_id = parts[data_idx + 1].to_string();
-                    // version_id is after "versions"
-                    if let Some(ver_idx) = parts.iter().position(|&p| p == "versions") {
-                        if parts.len() > ver_idx + 1 {
-                            let version_id = parts[ver_idx + 1].to_string();
-                            return Some((project_id, version_id));
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
     // Collect all files to download and prepare for parallel download
     let mut files_to_download: Vec<(String, std::path::PathBuf, Option<String>)> = Vec::new();
     let mut mod_files_to_fetch: Vec<(String, String, String)> = Vec::new(); // (project_id, version_id, filename)
 
-    for file in &index.files {
-        // Skip server-only files
-        if let Some(env) = &file.env {
-            if env.client.as_deref() == Some("unsupported") {
-                continue;
-            }
-        }
-
+    for file in &selected_files {
         let file_path = instance_dir.join(&file.path);
 
         // Create parent directory upfront
@@ -1180,113 +1689,51 @@ pub async fn install_modrinth_modpack(
         log::info!("Parallel download completed for modpack {}", project_id);
     }
 
-    // Save modpack project_id before it gets shadowed in the loop
-    let modpack_project_id = project_id.clone();
-
-    // Fetch metadata for mods in parallel (icons, names, etc.)
-    if !mod_files_to_fetch.is_empty() {
-        use std::sync::atomic::{AtomicUsize, Ordering};
-        use std::sync::Arc;
-        use futures_util::stream::{FuturesUnordered, StreamExt};
-        use tokio::sync::Semaphore;
-
-        let total_mods = mod_files_to_fetch.len();
-        log::info!("Fetching metadata for {} mods in parallel", total_mods);
-
-        let _ = app.emit(
-            "modpack-progress",
-            serde_json::json!({
-                "stage": "fetching_metadata",
-                "message": format!("Fetching mod metadata (0/{})...", total_mods),
-                "progress": 78,
-                "current": 0,
-                "total": total_mods,
-                "project_id": &modpack_project_id,
-                "instance_id": &instance.id
-            }),
-        );
-
-        let fetched_count = Arc::new(AtomicUsize::new(0));
-        let semaphore = Arc::new(Semaphore::new(10)); // Limit to 10 concurrent API requests
-        let mut futures = FuturesUnordered::new();
-
-        for (mod_project_id, version_id, filename) in mod_files_to_fetch {
-            let http_client = http_client.clone();
-            let mods_dir = mods_dir.clone();
-            let app = app.clone();
-            let modpack_project_id = modpack_project_id.clone();
-            let instance_id = instance.id.clone();
-            let fetched_count = Arc::clone(&fetched_count);
-            let semaphore = Arc::clone(&semaphore);
-            let total_mods_copy = total_mods;
-
-            futures.push(async move {
-                let _permit = semaphore.acquire().await.ok()?;
-                let client = ModrinthClient::new(&http_client);
-
-                // Fetch project and version info concurrently
-                let project_future = client.get_project(&mod_project_id);
-                let version_future = client.get_version(&version_id);
-                let (project_result, version_result): (Result<super::Project, _>, Result<super::Version, _>) =
-                    tokio::join!(project_future, version_future);
-
-                if let Ok(project_info) = project_result {
-                    let meta_filename = format!("{}.meta.json", filename.trim_end_matches(".jar"));
-                    let meta_path = mods_dir.join(&meta_filename);
-
-                    let dependencies = match version_result {
-                        Ok(version_info) => version_info
-                            .dependencies
-                            .iter()
-                            .filter(|d| d.dependency_type == "required" || d.dependency_type == "optional")
-                            .filter_map(|d| {
-                                d.project_id.as_ref().map(|pid| StoredDependency {
-                                    project_id: pid.clone(),
-                                    dependency_type: d.dependency_type.clone(),
-                                })
-                            })
-                            .collect(),
-                        Err(_) => Vec::new(),
-                    };
-
-                    let metadata = ModMetadata {
-                        name: project_info.title,
-                        version: "".to_string(),
-                        project_id: mod_project_id.clone(),
-                        version_id: Some(version_id.clone()),
-                        icon_url: project_info.icon_url,
-                        server_side: Some(project_info.server_side),
-                        client_side: Some(project_info.client_side),
-                        dependencies,
-                    };
-
-                    if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
-                        let _ = tokio::fs::write(&meta_path, meta_json).await;
-                    }
-                }
-
-                let current = fetched_count.fetch_add(1, Ordering::SeqCst) + 1;
-                if current % 5 == 0 || current == total_mods_copy {
-                    let progress = 78 + ((current as f32 / total_mods_copy as f32) * 7.0) as u32;
-                    let _ = app.emit("modpack-progress", serde_json::json!({
-                        "stage": "fetching_metadata",
-                        "message": format!("Fetching mod metadata ({}/{})", current, total_mods_copy),
-                        "progress": progress,
-                        "current": current,
-                        "total": total_mods_copy,
-                        "project_id": modpack_project_id,
-                        "instance_id": instance_id
-                    }));
-                }
-
-                Some(())
-            });
-        }
+    // Save modpack project_id before it gets shadowed in the loop
+    let modpack_project_id = project_id.clone();
 
-        // Wait for all metadata fetches to complete
-        while futures.next().await.is_some() {}
+    // Fetch metadata for mods in parallel (icons, names, etc.)
+    if !mod_files_to_fetch.is_empty() {
+        let total_mods = mod_files_to_fetch.len();
+        let _ = app.emit(
+            "modpack-progress",
+            serde_json::json!({
+                "stage": "fetching_metadata",
+                "message": format!("Fetching mod metadata (0/{})...", total_mods),
+                "progress": 78,
+                "current": 0,
+                "total": total_mods,
+                "project_id": &modpack_project_id,
+                "instance_id": &instance.id
+            }),
+        );
 
-        log::info!("Metadata fetching completed for {} mods", total_mods);
+        let app_clone = app.clone();
+        let project_id_for_progress = modpack_project_id.clone();
+        let instance_id_for_progress = instance.id.clone();
+        write_modpack_mod_metadata(
+            &http_client,
+            &mods_dir,
+            mod_files_to_fetch,
+            move |current, total| {
+                if current % 5 == 0 || current == total {
+                    let progress = 78 + ((current as f32 / total as f32) * 7.0) as u32;
+                    let _ = app_clone.emit(
+                        "modpack-progress",
+                        serde_json::json!({
+                            "stage": "fetching_metadata",
+                            "message": format!("Fetching mod metadata ({}/{})", current, total),
+                            "progress": progress,
+                            "current": current,
+                            "total": total,
+                            "project_id": project_id_for_progress.clone(),
+                            "instance_id": instance_id_for_progress.clone()
+                        }),
+                    );
+                }
+            },
+        )
+        .await;
     }
 
     let _ = app.emit(
@@ -1300,68 +1747,30 @@ pub async fn install_modrinth_modpack(
         }),
     );
 
-    // Extract overrides in a blocking task
-    let instance_dir_clone = instance_dir.clone();
-    tokio::task::spawn_blocking(move || {
-        use std::io::{Cursor, Read};
-        use zip::ZipArchive;
-
-        let cursor = Cursor::new(mrpack_bytes);
-        let mut archive = match ZipArchive::new(cursor) {
-            Ok(a) => a,
-            Err(_) => return,
-        };
-
-        for i in 0..archive.len() {
-            let mut file = match archive.by_index(i) {
-                Ok(f) => f,
-                Err(_) => continue,
-            };
-
-            let name = file.name().to_string();
-
-            // Check for overrides or client-overrides
-            let override_prefix = if name.starts_with("overrides/") {
-                Some("overrides/")
-            } else if name.starts_with("client-overrides/") {
-                Some("client-overrides/")
-            } else {
-                None
-            };
-
-            if let Some(prefix) = override_prefix {
-                let relative_path = &name[prefix.len()..];
-                if relative_path.is_empty() {
-                    continue;
-                }
-
-                let dest_path = instance_dir_clone.join(relative_path);
-
-                if file.is_dir() {
-                    if let Err(e) = std::fs::create_dir_all(&dest_path) {
-                        tracing::warn!("Failed to create directory {:?}: {}", dest_path, e);
-                    }
-                } else {
-                    // Create parent directory
-                    if let Some(parent) = dest_path.parent() {
-                        if let Err(e) = std::fs::create_dir_all(parent) {
-                            tracing::warn!("Failed to create parent directory {:?}: {}", parent, e);
-                        }
-                    }
-
-                    // Extract file
-                    let mut contents = Vec::new();
-                    if file.read_to_end(&mut contents).is_ok() {
-                        if let Err(e) = std::fs::write(&dest_path, &contents) {
-                            tracing::warn!("Failed to write file {:?}: {}", dest_path, e);
-                        }
-                    }
-                }
-            }
+    // Extract overrides (client-overrides or server-overrides win over overrides)
+    let overrides = crate::modpacks::update::read_overrides(mrpack_bytes, is_server).await?;
+    let mut override_hashes = std::collections::BTreeMap::new();
+    for (relative_path, contents) in overrides {
+        if let Err(e) =
+            crate::modpacks::update::write_pack_file(&instance_dir, &relative_path, &contents).await
+        {
+            tracing::warn!("Failed to write override {}: {}", relative_path, e);
         }
-    })
-    .await
-    .map_err(|e| AppError::Instance(format!("Failed to extract overrides: {}", e)))?;
+        override_hashes.insert(relative_path, crate::modpacks::update::sha1_hex(&contents));
+    }
+
+    // Record what the pack installed so the instance can be updated later
+    let installed = crate::modpacks::update::InstalledModpack {
+        project_id: modpack_project_id.clone(),
+        version_id: version.id.clone(),
+        version_number: version.version_number.clone(),
+        files: crate::modpacks::update::file_hashes(selected_files.iter().copied()),
+        overrides: override_hashes,
+        declined: install_plan::declined_files(&index, is_server, &selected_files),
+    };
+    if let Err(e) = installed.save(&instance_dir).await {
+        tracing::warn!("Failed to save modpack install record: {}", e);
+    }
 
     let _ = app.emit(
         "modpack-progress",
@@ -1387,12 +1796,122 @@ pub async fn install_modrinth_modpack(
     })
 }
 
+/// Extract the project and version ids from a Modrinth CDN URL
+/// URL format: https://cdn.modrinth.com/data/{project_id}/versions/{version_id}/{filename}
+pub(crate) fn extract_modrinth_ids(url: &str) -> Option<(String, String)> {
+    if url.contains("cdn.modrinth.com/data/") {
+        let parts: Vec<&str> = url.split('/').collect();
+        // Find "data" index and extract project_id and version_id
+        if let Some(data_idx) = parts.iter().position(|&p| p == "data") {
+            if parts.len() > data_idx + 4 {
+                let project_id = parts[data_idx + 1].to_string();
+                // version_id is after "versions"
+                if let Some(ver_idx) = parts.iter().position(|&p| p == "versions") {
+                    if parts.len() > ver_idx + 1 {
+                        let version_id = parts[ver_idx + 1].to_string();
+                        return Some((project_id, version_id));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Write `.meta.json` files for mods downloaded from a modpack, fetching project
+/// and version info in parallel. `mod_files` holds (project_id, version_id, filename)
+pub(crate) async fn write_modpack_mod_metadata<F>(
+    http_client: &reqwest::Client,
+    mods_dir: &std::path::Path,
+    mod_files: Vec<(String, String, String)>,
+    on_progress: F,
+) where
+    F: Fn(usize, usize) + Send + Sync,
+{
+    use futures_util::stream::{FuturesUnordered, StreamExt};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+
+    let total_mods = mod_files.len();
+    log::info!("Fetching metadata for {} mods in parallel", total_mods);
+
+    let fetched_count = Arc::new(AtomicUsize::new(0));
+    let semaphore = Arc::new(Semaphore::new(10)); // Limit to 10 concurrent API requests
+    let on_progress = &on_progress;
+    let mut futures = FuturesUnordered::new();
+
+    for (mod_project_id, version_id, filename) in mod_files {
+        let fetched_count = Arc::clone(&fetched_count);
+        let semaphore = Arc::clone(&semaphore);
+
+        futures.push(async move {
+            let _permit = semaphore.acquire().await.ok()?;
+            let client = ModrinthClient::new(http_client);
+
+            // Fetch project and version info concurrently
+            let (project_result, version_result) = tokio::join!(
+                client.get_project(&mod_project_id),
+                client.get_version(&version_id)
+            );
+
+            if let Ok(project_info) = project_result {
+                let meta_filename = format!("{}.meta.json", filename.trim_end_matches(".jar"));
+                let meta_path = mods_dir.join(&meta_filename);
+
+                let dependencies = match version_result {
+                    Ok(version_info) => version_info
+                        .dependencies
+                        .iter()
+                        .filter(|d| d.dependency_type == "required" || d.dependency_type == "optional")
+                        .filter_map(|d| {
+                            d.project_id.as_ref().map(|pid| StoredDependency {
+                                project_id: pid.clone(),
+                                dependency_type: d.dependency_type.clone(),
+                            })
+                        })
+                        .collect(),
+                    Err(_) => Vec::new(),
+                };
+
+                let metadata = ModMetadata {
+                    name: project_info.title,
+                    version: "".to_string(),
+                    project_id: mod_project_id.clone(),
+                    version_id: Some(version_id.clone()),
+                    icon_url: project_info.icon_url,
+                    server_side: Some(project_info.server_side),
+                    client_side: Some(project_info.client_side),
+                    dependencies,
+                    source: None,
+                };
+
+                if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
+                    let _ = tokio::fs::write(&meta_path, meta_json).await;
+                }
+            }
+
+            let current = fetched_count.fetch_add(1, Ordering::SeqCst) + 1;
+            on_progress(current, total_mods);
+
+            Some(())
+        });
+    }
+
+    // Wait for all metadata fetches to complete
+    while futures.next().await.is_some() {}
+
+    log::info!("Metadata fetching completed for {} mods", total_mods);
+}
+
 // ============= Mod Metadata Enrichment =============
 
 /// Result of looking up a mod by hash
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HashLookupResult {
     pub found: bool,
+    /// Provider the file was found on
+    pub source: Option<ContentSource>,
     pub project_id: Option<String>,
     pub version_id: Option<String>,
     pub name: Option<String>,
@@ -1402,67 +1921,80 @@ pub struct HashLookupResult {
     pub dependencies: Vec<StoredDependency>,
 }
 
-/// Look up a mod file by its SHA-512 hash
-/// This can identify mods that were not installed through Modrinth
+/// Look up a mod file by its hash (SHA-512 on Modrinth, fingerprint on CurseForge)
+/// This can identify mods that were not installed through the launcher.
+/// Without a source, Modrinth is tried first, then CurseForge when it is configured
 #[tauri::command]
 pub async fn lookup_mod_by_hash(
     state: State<'_, SharedState>,
     file_path: String,
+    source: Option<ContentSource>,
 ) -> AppResult<HashLookupResult> {
-    use sha2::{Digest, Sha512};
-
     let state_guard = state.read().await;
-    let client = ModrinthClient::new(&state_guard.http_client);
 
-    // Read file and compute SHA-512
     let file_bytes = tokio::fs::read(&file_path)
         .await
         .map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
 
-    let mut hasher = Sha512::new();
-    hasher.update(&file_bytes);
-    let hash = format!("{:x}", hasher.finalize());
+    let explicit_source = source.is_some();
+    let sources = match source {
+        Some(source) => vec![source],
+        None => vec![ContentSource::Modrinth, ContentSource::CurseForge],
+    };
+    let curseforge_key = crate::curseforge::api_key(&state_guard.db).await;
+
+    for source in sources {
+        let provider = match content::provider_for(
+            source,
+            &state_guard.http_client,
+            curseforge_key.as_deref(),
+        ) {
+            Ok(provider) => provider,
+            // Only fail for a provider that was asked for explicitly
+            Err(_) if !explicit_source => continue,
+            Err(e) => return Err(e),
+        };
 
-    // Look up on Modrinth
-    match client.get_version_by_hash(&hash).await {
-        Ok(version) => {
-            // Get project details for additional info
-            let project = client.get_project(&version.project_id).await.ok();
+        let version = match provider.identify_file(&file_bytes).await {
+            Ok(Some(version)) => version,
+            Ok(None) => continue,
+            Err(e) => {
+                debug!("{} lookup failed: {}", source.display_name(), e);
+                continue;
+            }
+        };
 
-            let dependencies: Vec<StoredDependency> = version
-                .dependencies
-                .iter()
-                .filter(|d| d.dependency_type == "required" || d.dependency_type == "optional")
-                .filter_map(|d| {
-                    d.project_id.as_ref().map(|pid| StoredDependency {
-                        project_id: pid.clone(),
-                        dependency_type: d.dependency_type.clone(),
-                    })
-                })
-                .collect();
-
-            Ok(HashLookupResult {
-                found: true,
-                project_id: Some(version.project_id.clone()),
-                version_id: Some(version.id),
-                name: project.as_ref().map(|p| p.title.clone()),
-                icon_url: project.as_ref().and_then(|p| p.icon_url.clone()),
-                server_side: project.as_ref().map(|p| p.server_side.clone()),
-                client_side: project.as_ref().map(|p| p.client_side.clone()),
-                dependencies,
-            })
-        }
-        Err(_) => Ok(HashLookupResult {
-            found: false,
-            project_id: None,
-            version_id: None,
-            name: None,
-            icon_url: None,
-            server_side: None,
-            client_side: None,
-            dependencies: vec![],
-        }),
+        // Get project details for additional info
+        let project = provider.fetch_project(&version.project_id).await.ok();
+        let dependencies = project
+            .as_ref()
+            .map(|p| ModMetadata::from_content(p, &version).dependencies)
+            .unwrap_or_default();
+
+        return Ok(HashLookupResult {
+            found: true,
+            source: Some(source),
+            project_id: Some(version.project_id.clone()),
+            version_id: Some(version.id),
+            name: project.as_ref().map(|p| p.title.clone()),
+            icon_url: project.as_ref().and_then(|p| p.icon_url.clone()),
+            server_side: project.as_ref().and_then(|p| p.server_side.clone()),
+            client_side: project.as_ref().and_then(|p| p.client_side.clone()),
+            dependencies,
+        });
     }
+
+    Ok(HashLookupResult {
+        found: false,
+        source: None,
+        project_id: None,
+        version_id: None,
+        name: None,
+        icon_url: None,
+        server_side: None,
+        client_side: None,
+        dependencies: vec![],
+    })
 }
 
 /// Result of enriching mods for an instance
@@ -1606,6 +2138,7 @@ pub async fn enrich_instance_mods(
                             server_side: Some(project.server_side),
                             client_side: Some(project.client_side),
                             dependencies,
+                            source: None,
                         };
 
                         if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
@@ -1744,9 +2277,18 @@ pub async fn analyze_mods_for_server_detailed(
 
 // ============= Mod Update Feature =============
 
+/// The content file a `.meta.json` describes, enabled or disabled
+pub(crate) fn find_content_file(content_dir: &std::path::Path, base_name: &str) -> Option<String> {
+    ["jar", "zip", "jar.disabled", "zip.disabled"]
+        .iter()
+        .map(|ext| format!("{}.{}", base_name, ext))
+        .find(|filename| content_dir.join(filename).exists())
+}
+
 /// Information about a mod that has an update available
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModUpdateInfo {
+    pub source: ContentSource,
     pub project_id: String,
     pub filename: String,
     pub current_version: String,
@@ -1755,6 +2297,8 @@ pub struct ModUpdateInfo {
     pub latest_version_id: String,
     pub name: String,
     pub icon_url: Option<String>,
+    /// Checked against cached versions because the provider could not be reached
+    pub stale: bool,
 }
 
 /// Check for updates for all mods in an instance
@@ -1765,7 +2309,7 @@ pub async fn check_mod_updates(
     project_type: Option<String>,
 ) -> AppResult<Vec<ModUpdateInfo>> {
     let state_guard = state.read().await;
-    let client = ModrinthClient::new(&state_guard.http_client);
+    let curseforge_key = crate::curseforge::api_key(&state_guard.db).await;
 
     let instance = Instance::get_by_id(&state_guard.db, &instance_id)
         .await
@@ -1781,17 +2325,8 @@ pub async fn check_mod_updates(
         .join(&instance.game_dir);
 
     // Handle datapacks specially
-    let content_dir = if ptype == Some("datapack") {
-        let world_name = find_world_folder(&instance_dir)
-            .await
-            .unwrap_or_else(|| "world".to_string());
-        instance_dir
-            .join("saves")
-            .join(&world_name)
-            .join("datapacks")
-    } else {
-        instance_dir.join(folder_name)
-    };
+    let content_dir =
+        content_target_dir(&instance_dir, instance.is_server, ptype, folder_name).await;
 
     if !content_dir.exists() {
         return Ok(vec![]);
@@ -1803,7 +2338,7 @@ pub async fn check_mod_updates(
         .map_err(|e| AppError::Io(format!("Failed to read {} directory: {}", folder_name, e)))?;
 
     // Collect all mods with their metadata
-    let mut mods_to_check: Vec<(String, ModMetadata)> = Vec::new();
+    let mut mods_to_check: Vec<(String, ModMetadata, ContentSource)> = Vec::new();
 
     while let Some(entry) = entries
         .next_entry()
@@ -1817,57 +2352,48 @@ pub async fn check_mod_updates(
             let meta_path = entry.path();
             if let Ok(content) = tokio::fs::read_to_string(&meta_path).await {
                 if let Ok(meta) = serde_json::from_str::<ModMetadata>(&content) {
+                    // Files from other sources (e.g. Hangar) have their own update checks
+                    let Some(source) = ContentSource::from_meta(meta.source.as_deref())
+                        .filter(ContentSource::has_content_provider)
+                    else {
+                        continue;
+                    };
+
                     // Find the corresponding mod file
                     let base_name = filename.trim_end_matches(".meta.json");
-                    let jar_filename = format!("{}.jar", base_name);
-                    let zip_filename = format!("{}.zip", base_name);
-
-                    let mod_filename = if content_dir.join(&jar_filename).exists() {
-                        jar_filename
-                    } else if content_dir.join(&zip_filename).exists() {
-                        zip_filename
-                    } else if content_dir
-                        .join(format!("{}.jar.disabled", base_name))
-                        .exists()
-                    {
-                        format!("{}.jar.disabled", base_name)
-                    } else if content_dir
-                        .join(format!("{}.zip.disabled", base_name))
-                        .exists()
-                    {
-                        format!("{}.zip.disabled", base_name)
-                    } else {
+                    let Some(mod_filename) = find_content_file(&content_dir, base_name) else {
                         continue;
                     };
 
-                    mods_to_check.push((mod_filename, meta));
+                    mods_to_check.push((mod_filename, meta, source));
                 }
             }
         }
     }
 
-    // Check each mod for updates
-    for (filename, meta) in mods_to_check {
-        // Get the latest versions for this project
-        let game_versions = Some(vec![instance.mc_version.as_str()]);
+    // Only include loaders for mods and plugins
+    let loader = match ptype {
+        Some("mod") | Some("plugin") | None => instance.loader.as_deref(),
+        _ => None,
+    };
 
-        // Only include loaders for mods and plugins
-        let loaders = match ptype {
-            Some("mod") | Some("plugin") | None => {
-                instance.loader.as_ref().map(|l| vec![l.to_lowercase()])
+    // Check each mod for updates
+    for (filename, meta, source) in mods_to_check {
+        let provider = match content::provider_for(
+            source,
+            &state_guard.http_client,
+            curseforge_key.as_deref(),
+        ) {
+            Ok(provider) => provider,
+            Err(e) => {
+                log::warn!("Cannot check updates for {}: {}", meta.project_id, e);
+                continue;
             }
-            _ => None,
         };
-        let loaders_refs: Option<Vec<&str>> = loaders
-            .as_ref()
-            .map(|v| v.iter().map(|s| s.as_str()).collect());
 
-        match client
-            .get_project_versions(
-                &meta.project_id,
-                loaders_refs.as_deref(),
-                game_versions.as_deref(),
-            )
+        // Get the latest versions for this project
+        match provider
+            .fetch_versions(&meta.project_id, loader, Some(&instance.mc_version))
             .await
         {
             Ok(versions) => {
@@ -1880,6 +2406,7 @@ pub async fn check_mod_updates(
 
                     if needs_update {
                         updates.push(ModUpdateInfo {
+                            source,
                             project_id: meta.project_id.clone(),
                             filename: filename.clone(),
                             current_version: meta.version.clone(),
@@ -1888,6 +2415,7 @@ pub async fn check_mod_updates(
                             latest_version_id: latest.id.clone(),
                             name: meta.name.clone(),
                             icon_url: meta.icon_url.clone(),
+                            stale: latest.stale,
                         });
                     }
                 }
@@ -1910,9 +2438,10 @@ pub async fn update_mod(
     current_filename: String,
     new_version_id: String,
     project_type: Option<String>,
+    source: Option<ContentSource>,
 ) -> AppResult<String> {
     let state_guard = state.read().await;
-    let client = ModrinthClient::new(&state_guard.http_client);
+    let provider = content::provider_for_state(&state_guard, source.unwrap_or_default()).await?;
 
     let instance = Instance::get_by_id(&state_guard.db, &instance_id)
         .await
@@ -1928,49 +2457,36 @@ pub async fn update_mod(
         .join(&instance.game_dir);
 
     // Handle datapacks specially
-    let content_dir = if ptype == Some("datapack") {
-        let world_name = find_world_folder(&instance_dir)
-            .await
-            .unwrap_or_else(|| "world".to_string());
-        instance_dir
-            .join("saves")
-            .join(&world_name)
-            .join("datapacks")
-    } else {
-        instance_dir.join(folder_name)
-    };
+    let content_dir =
+        content_target_dir(&instance_dir, instance.is_server, ptype, folder_name).await;
 
     // Get project info
-    let project = client
-        .get_project(&project_id)
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    let project = provider.fetch_project(&project_id).await?;
 
     // Get the new version info
-    let version = client
-        .get_version(&new_version_id)
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    let version = provider.fetch_version(&project_id, &new_version_id).await?;
 
     // Find the primary file
     let file = version
-        .files
-        .iter()
-        .find(|f| f.primary)
-        .or_else(|| version.files.first())
+        .primary_file()
         .ok_or_else(|| AppError::Instance("No files found for this version".to_string()))?;
 
+    if file.url.is_none() {
+        return Err(content::manual_download_error(
+            &project,
+            &version.id,
+            folder_name,
+        ));
+    }
+
     let new_path = content_dir.join(&file.filename);
 
     // Download the new file
-    client
-        .download_file(file, &new_path)
-        .await
-        .map_err(|e| AppError::Network(e.to_string()))?;
+    content::download_content_file(&state_guard.http_client, file, &new_path).await?;
 
     // Delete the old file
     let old_path = content_dir.join(&current_filename);
-    if old_path.exists() {
+    if old_path.exists() && old_path != new_path {
         tokio::fs::remove_file(&old_path)
             .await
             .map_err(|e| AppError::Io(format!("Failed to delete old mod: {}", e)))?;
@@ -1987,39 +2503,9 @@ pub async fn update_mod(
     }
 
     // Save new metadata with compatibility info and dependencies
-    let new_base = file
-        .filename
-        .trim_end_matches(".jar")
-        .trim_end_matches(".zip");
-    let new_meta_path = content_dir.join(format!("{}.meta.json", new_base));
-
-    // Extract required/optional dependencies
-    let dependencies: Vec<StoredDependency> = version
-        .dependencies
-        .iter()
-        .filter(|d| d.dependency_type == "required" || d.dependency_type == "optional")
-        .filter_map(|d| {
-            d.project_id.as_ref().map(|pid| StoredDependency {
-                project_id: pid.clone(),
-                dependency_type: d.dependency_type.clone(),
-            })
-        })
-        .collect();
-
-    let metadata = ModMetadata {
-        name: project.title.clone(),
-        version: version.version_number.clone(),
-        project_id: project_id.clone(),
-        version_id: Some(new_version_id.clone()),
-        icon_url: project.icon_url,
-        server_side: Some(project.server_side),
-        client_side: Some(project.client_side),
-        dependencies,
-    };
-
-    if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
-        let _ = tokio::fs::write(&new_meta_path, meta_json).await;
-    }
+    ModMetadata::from_content(&project, &version)
+        .write_for(&content_dir, &file.filename)
+        .await;
 
     log::info!(
         "Updated mod {} from {} to {} ({})",
@@ -2032,6 +2518,91 @@ pub async fn update_mod(
     Ok(file.filename.clone())
 }
 
+/// Instance directory for the bulk update commands
+async fn bulk_update_instance(
+    state_guard: &crate::state::AppState,
+    instance_id: &str,
+) -> AppResult<(Instance, std::path::PathBuf)> {
+    let instance = Instance::get_by_id(&state_guard.db, instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+    let instance_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir);
+    Ok((instance, instance_dir))
+}
+
+/// Get the release channel and pins used by bulk updates
+#[tauri::command]
+pub async fn get_update_preferences(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<updates::UpdatePreferences> {
+    let state_guard = state.read().await;
+    let (_, instance_dir) = bulk_update_instance(&state_guard, &instance_id).await?;
+    updates::UpdatePreferences::load(&instance_dir).await
+}
+
+/// Set the release channel and pins used by bulk updates
+#[tauri::command]
+pub async fn set_update_preferences(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    preferences: updates::UpdatePreferences,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+    let (_, instance_dir) = bulk_update_instance(&state_guard, &instance_id).await?;
+    preferences.save(&instance_dir).await
+}
+
+/// List the updates "update all" would apply, with their changelogs
+#[tauri::command]
+pub async fn preview_bulk_update(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<updates::BulkUpdatePlan> {
+    let state_guard = state.read().await;
+    let (instance, instance_dir) = bulk_update_instance(&state_guard, &instance_id).await?;
+    updates::plan(&state_guard, &instance, &instance_dir).await
+}
+
+/// Update all content of an instance, snapshotting the replaced files first
+#[tauri::command]
+pub async fn bulk_update_content(
+    state: State<'_, SharedState>,
+    app: tauri::AppHandle,
+    instance_id: String,
+) -> AppResult<updates::BulkUpdateSummary> {
+    let state_guard = state.read().await;
+    let (instance, instance_dir) = bulk_update_instance(&state_guard, &instance_id).await?;
+    updates::apply(&state_guard, &app, &instance, &instance_dir).await
+}
+
+/// Get the snapshot of the last bulk update, if it can still be rolled back
+#[tauri::command]
+pub async fn get_latest_update_snapshot(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Option<updates::UpdateSnapshotInfo>> {
+    let state_guard = state.read().await;
+    let (_, instance_dir) = bulk_update_instance(&state_guard, &instance_id).await?;
+    updates::latest_snapshot(&instance_dir).await
+}
+
+/// Restore the files replaced by a bulk update
+#[tauri::command]
+pub async fn rollback_bulk_update(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    snapshot_id: String,
+) -> AppResult<usize> {
+    let state_guard = state.read().await;
+    let (instance, instance_dir) = bulk_update_instance(&state_guard, &instance_id).await?;
+    updates::rollback(&state_guard, &instance, &instance_dir, &snapshot_id).await
+}
+
 /// Result of syncing mod metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncResult {
@@ -2644,6 +3215,7 @@ async fn save_mod_metadata(
         server_side: Some(project.server_side.clone()),
         client_side: Some(project.client_side.clone()),
         dependencies,
+        source: None,
     };
 
     let meta_json = serde_json::to_string_pretty(&metadata)
@@ -2833,3 +3405,89 @@ pub async fn check_mods_version_compatibility(
 
     Ok(results)
 }
+
+// ============= Release Feed =============
+
+/// New releases of installed projects, grouped by instance
+#[tauri::command]
+pub async fn get_release_feed(
+    state: State<'_, SharedState>,
+    instance_id: Option<String>,
+    unread_only: Option<bool>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> AppResult<feed::ReleaseFeedPage> {
+    let state_guard = state.read().await;
+    feed::page(
+        &state_guard.db,
+        instance_id.as_deref(),
+        unread_only.unwrap_or(false),
+        page.unwrap_or(0),
+        page_size.unwrap_or(feed::DEFAULT_PAGE_SIZE),
+    )
+    .await
+}
+
+/// Mark releases of the feed as read or unread
+#[tauri::command]
+pub async fn set_release_feed_read(
+    state: State<'_, SharedState>,
+    entry_ids: Vec<i64>,
+    read: bool,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+    ReleaseFeedEntry::set_read(&state_guard.db, &entry_ids, read).await?;
+    Ok(())
+}
+
+/// Mark every release as read, or only those of one instance
+#[tauri::command]
+pub async fn mark_release_feed_read(
+    state: State<'_, SharedState>,
+    instance_id: Option<String>,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+    ReleaseFeedEntry::mark_all_read(&state_guard.db, instance_id.as_deref()).await?;
+    Ok(())
+}
+
+/// Look for new releases now, without waiting for the background check
+#[tauri::command]
+pub async fn check_release_feed(
+    state: State<'_, SharedState>,
+) -> AppResult<feed::FeedCheckSummary> {
+    // Release the state before the check, it can take a while
+    let (db, http_client, instances_dir) = {
+        let state_guard = state.read().await;
+        (
+            state_guard.db.clone(),
+            state_guard.http_client.clone(),
+            state_guard.get_instances_dir().await,
+        )
+    };
+    feed::check(&db, &http_client, &instances_dir).await
+}
+
+#[tauri::command]
+pub async fn get_release_feed_settings(
+    state: State<'_, SharedState>,
+) -> AppResult<feed::FeedSettings> {
+    let state_guard = state.read().await;
+    feed::FeedSettings::load(&state_guard.db).await
+}
+
+/// Minutes between background checks of the release feed, 0 turns them off
+#[tauri::command]
+pub async fn set_release_feed_interval(
+    state: State<'_, SharedState>,
+    interval_minutes: u64,
+) -> AppResult<()> {
+    let state_guard = state.read().await;
+    set_setting(
+        &state_guard.db,
+        feed::INTERVAL_SETTING,
+        &interval_minutes.to_string(),
+    )
+    .await?;
+    Ok(())
+}
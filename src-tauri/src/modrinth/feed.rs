@@ -0,0 +1,489 @@
+//! Feed of new releases of the Modrinth projects installed in any instance
+//!
+//! Installed files are identified by hash in batch requests, then the newest
+//! version of each is looked up with one batch request per Minecraft version
+//! and loaders. Releases newer than the installed ones are stored with their
+//! changelog, to be listed by instance and marked read.
+
+use super::{ModrinthClient, Version};
+use crate::db::instances::Instance;
+use crate::db::release_feed::ReleaseFeedEntry;
+use crate::db::settings::{get_setting, set_setting};
+use crate::error::{AppError, AppResult};
+use crate::instance::jar_metadata;
+use crate::state::SharedState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Settings key holding the minutes between background checks
+pub const INTERVAL_SETTING: &str = "release_feed_interval_minutes";
+/// Settings key holding the time of the last check (RFC 3339)
+pub const LAST_CHECK_SETTING: &str = "release_feed_last_check";
+
+pub const DEFAULT_INTERVAL_MINUTES: u64 = 360;
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 200;
+
+/// How often the background task looks whether a check is due
+const POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Wait before the first background check, so it does not slow down startup
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// Most hashes sent in one request
+const HASHES_BATCH_SIZE: usize = 500;
+
+/// Read releases are forgotten after this many days
+const READ_RETENTION_DAYS: i64 = 90;
+
+/// Background and manual checks never run at the same time
+static CHECK_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedSettings {
+    /// Minutes between background checks, 0 turns them off
+    pub interval_minutes: u64,
+    pub last_check: Option<String>,
+}
+
+impl FeedSettings {
+    pub async fn load(db: &SqlitePool) -> AppResult<Self> {
+        let interval_minutes = get_setting(db, INTERVAL_SETTING)
+            .await?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+        let last_check = get_setting(db, LAST_CHECK_SETTING).await?;
+        Ok(Self {
+            interval_minutes,
+            last_check,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeedCheckSummary {
+    pub checked_files: usize,
+    /// Files Modrinth knows
+    pub identified_files: usize,
+    pub new_releases: usize,
+}
+
+/// Releases of one instance
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseFeedGroup {
+    pub instance_id: String,
+    pub instance_name: String,
+    pub entries: Vec<ReleaseFeedEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseFeedPage {
+    pub page: u32,
+    pub page_size: u32,
+    /// Releases matching the filters, across all pages
+    pub total: i64,
+    pub unread: i64,
+    pub groups: Vec<ReleaseFeedGroup>,
+}
+
+/// Whether a background check should run now
+pub fn check_due(last_check: Option<&str>, interval_minutes: u64, now: DateTime<Utc>) -> bool {
+    if interval_minutes == 0 {
+        return false;
+    }
+    let Some(last) = last_check.and_then(|t| DateTime::parse_from_rfc3339(t).ok()) else {
+        return true;
+    };
+    let elapsed = now.signed_duration_since(last.with_timezone(&Utc));
+    elapsed.num_minutes() >= interval_minutes as i64
+}
+
+/// Whether `latest` was published after the installed version
+pub fn is_newer(installed: &Version, latest: &Version) -> bool {
+    if latest.id == installed.id {
+        return false;
+    }
+    let published = |v: &Version| DateTime::parse_from_rfc3339(&v.date_published).ok();
+    match (published(installed), published(latest)) {
+        (Some(installed), Some(latest)) => latest > installed,
+        _ => latest.date_published > installed.date_published,
+    }
+}
+
+/// Entries in query order, grouped by consecutive instance
+pub fn group_by_instance(
+    entries: Vec<ReleaseFeedEntry>,
+    names: &HashMap<String, String>,
+) -> Vec<ReleaseFeedGroup> {
+    let mut groups: Vec<ReleaseFeedGroup> = Vec::new();
+    for entry in entries {
+        match groups.last_mut() {
+            Some(group) if group.instance_id == entry.instance_id => group.entries.push(entry),
+            _ => groups.push(ReleaseFeedGroup {
+                instance_id: entry.instance_id.clone(),
+                instance_name: names.get(&entry.instance_id).cloned().unwrap_or_default(),
+                entries: vec![entry],
+            }),
+        }
+    }
+    groups
+}
+
+/// A content file of an instance
+struct InstalledFile {
+    instance: usize,
+    /// Relative to the instance directory
+    path: String,
+    /// In the mods or plugins folder, whose versions depend on the loader
+    mods_folder: bool,
+    sha1: String,
+}
+
+/// Minecraft version and loaders the newest version of a file is looked up for.
+/// Mods follow the instance's loader, other content keeps the loaders of the
+/// installed version (e.g. `minecraft` for resource packs, `iris` for shaders)
+fn lookup_key(
+    instance: &Instance,
+    file: &InstalledFile,
+    installed: &Version,
+) -> Option<(String, Vec<String>)> {
+    let loaders = if file.mods_folder {
+        vec![instance.loader.as_ref()?.to_lowercase()]
+    } else {
+        let mut loaders = installed.loaders.clone();
+        loaders.sort();
+        loaders
+    };
+    Some((instance.mc_version.clone(), loaders))
+}
+
+/// Enabled content files of every instance
+async fn installed_files(instances: &[Instance], instances_dir: &Path) -> Vec<InstalledFile> {
+    let mut files = Vec::new();
+    for (index, instance) in instances.iter().enumerate() {
+        let instance_dir = instances_dir.join(&instance.game_dir);
+        let folders = crate::modpacks::lockfile::content_folders(instance);
+        for folder in &folders {
+            let Ok(mut entries) = tokio::fs::read_dir(instance_dir.join(folder)).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let filename = entry.file_name().to_string_lossy().to_string();
+                if !(filename.ends_with(".jar") || filename.ends_with(".zip")) {
+                    continue;
+                }
+                match jar_metadata::jar_sha1(&entry.path()).await {
+                    Ok(sha1) => files.push(InstalledFile {
+                        instance: index,
+                        path: format!("{}/{}", folder, filename),
+                        mods_folder: *folder == folders[0],
+                        sha1,
+                    }),
+                    Err(e) => log::debug!("Skipping {} in the release feed: {}", filename, e),
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Look for new releases of the content installed in every instance
+pub async fn check(
+    db: &SqlitePool,
+    http_client: &reqwest::Client,
+    instances_dir: &Path,
+) -> AppResult<FeedCheckSummary> {
+    let _guard = CHECK_LOCK.lock().await;
+
+    let instances = Instance::get_all(db).await?;
+    let files = installed_files(&instances, instances_dir).await;
+    let client = ModrinthClient::new(http_client);
+
+    let mut hashes: Vec<String> = files.iter().map(|f| f.sha1.clone()).collect();
+    hashes.sort();
+    hashes.dedup();
+    let mut installed: HashMap<String, Version> = HashMap::new();
+    for chunk in hashes.chunks(HASHES_BATCH_SIZE) {
+        let versions = client
+            .get_versions_by_hashes_cached(chunk, "sha1")
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+        installed.extend(versions);
+    }
+
+    // One update request per Minecraft version and loaders
+    let mut groups: BTreeMap<(String, Vec<String>), Vec<String>> = BTreeMap::new();
+    for file in &files {
+        if let Some(version) = installed.get(&file.sha1) {
+            if let Some(key) = lookup_key(&instances[file.instance], file, version) {
+                groups.entry(key).or_default().push(file.sha1.clone());
+            }
+        }
+    }
+    let mut latest: HashMap<(String, Vec<String>), HashMap<String, Version>> = HashMap::new();
+    for (key, mut hashes) in groups {
+        hashes.sort();
+        hashes.dedup();
+        let mut found = HashMap::new();
+        for chunk in hashes.chunks(HASHES_BATCH_SIZE) {
+            match client
+                .get_latest_versions_by_hashes(chunk, "sha1", &key.1, std::slice::from_ref(&key.0))
+                .await
+            {
+                Ok(versions) => found.extend(versions),
+                Err(e) => log::warn!(
+                    "Failed to check releases for {} ({}): {}",
+                    key.0,
+                    key.1.join(", "),
+                    e
+                ),
+            }
+        }
+        latest.insert(key, found);
+    }
+
+    let mut releases = Vec::new();
+    for file in &files {
+        let Some(current) = installed.get(&file.sha1) else {
+            continue;
+        };
+        let newest = lookup_key(&instances[file.instance], file, current)
+            .and_then(|key| latest.get(&key))
+            .and_then(|found| found.get(&file.sha1));
+        if let Some(newest) = newest.filter(|newest| is_newer(current, newest)) {
+            releases.push((file, current, newest));
+        }
+    }
+
+    // Names and icons of the projects with new releases
+    let mut project_ids: Vec<&str> = releases
+        .iter()
+        .map(|(_, _, v)| v.project_id.as_str())
+        .collect();
+    project_ids.sort();
+    project_ids.dedup();
+    let projects: HashMap<String, (String, Option<String>)> =
+        match client.get_projects_batched(&project_ids).await {
+            Ok(projects) => projects
+                .into_iter()
+                .map(|p| (p.id, (p.title, p.icon_url)))
+                .collect(),
+            Err(e) => {
+                log::warn!("Failed to get projects for the release feed: {}", e);
+                HashMap::new()
+            }
+        };
+
+    let now = Utc::now().to_rfc3339();
+    let mut new_releases = 0;
+    for (file, current, newest) in releases {
+        let (project_name, icon_url) =
+            projects
+                .get(&newest.project_id)
+                .cloned()
+                .unwrap_or_else(|| {
+                    (
+                        file.path.rsplit('/').next().unwrap_or_default().to_string(),
+                        None,
+                    )
+                });
+        let entry = ReleaseFeedEntry {
+            id: 0,
+            instance_id: instances[file.instance].id.clone(),
+            project_id: newest.project_id.clone(),
+            project_name,
+            icon_url,
+            path: file.path.clone(),
+            installed_version_id: current.id.clone(),
+            installed_version_number: current.version_number.clone(),
+            version_id: newest.id.clone(),
+            version_number: newest.version_number.clone(),
+            version_type: newest.version_type.clone(),
+            changelog: newest.changelog.clone(),
+            date_published: newest.date_published.clone(),
+            discovered_at: now.clone(),
+            read: false,
+        };
+        if entry.insert(db).await? {
+            new_releases += 1;
+        }
+    }
+
+    let cutoff = (Utc::now() - chrono::Duration::days(READ_RETENTION_DAYS)).to_rfc3339();
+    ReleaseFeedEntry::prune_read(db, &cutoff).await?;
+    set_setting(db, LAST_CHECK_SETTING, &now).await?;
+
+    let summary = FeedCheckSummary {
+        checked_files: files.len(),
+        identified_files: files
+            .iter()
+            .filter(|f| installed.contains_key(&f.sha1))
+            .count(),
+        new_releases,
+    };
+    log::info!(
+        "Release feed: {} files checked, {} on Modrinth, {} new releases",
+        summary.checked_files,
+        summary.identified_files,
+        summary.new_releases
+    );
+    Ok(summary)
+}
+
+/// One page of the feed, grouped by instance
+pub async fn page(
+    db: &SqlitePool,
+    instance_id: Option<&str>,
+    unread_only: bool,
+    page: u32,
+    page_size: u32,
+) -> AppResult<ReleaseFeedPage> {
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let entries = ReleaseFeedEntry::get_page(
+        db,
+        instance_id,
+        unread_only,
+        page_size as i64,
+        page as i64 * page_size as i64,
+    )
+    .await?;
+    let (total, unread) = ReleaseFeedEntry::count(db, instance_id).await?;
+    let names: HashMap<String, String> = Instance::get_all(db)
+        .await?
+        .into_iter()
+        .map(|i| (i.id, i.name))
+        .collect();
+
+    Ok(ReleaseFeedPage {
+        page,
+        page_size,
+        total: if unread_only { unread } else { total },
+        unread,
+        groups: group_by_instance(entries, &names),
+    })
+}
+
+/// Check for new releases whenever the configured interval has passed.
+/// `release-feed-updated` is emitted when a check found some
+pub fn start_background_checks(app: AppHandle, state: SharedState) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            // Only clone what the check needs, the state stays unlocked while it runs
+            let (db, http_client, instances_dir) = {
+                let state = state.read().await;
+                (
+                    state.db.clone(),
+                    state.http_client.clone(),
+                    state.get_instances_dir().await,
+                )
+            };
+
+            match FeedSettings::load(&db).await {
+                Ok(settings)
+                    if check_due(
+                        settings.last_check.as_deref(),
+                        settings.interval_minutes,
+                        Utc::now(),
+                    ) =>
+                {
+                    match check(&db, &http_client, &instances_dir).await {
+                        Ok(summary) if summary.new_releases > 0 => {
+                            let _ = app.emit("release-feed-updated", &summary);
+                        }
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Release feed check failed: {}", e),
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to read release feed settings: {}", e),
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, date_published: &str) -> Version {
+        Version {
+            id: id.to_string(),
+            project_id: "p".to_string(),
+            name: id.to_string(),
+            version_number: id.to_string(),
+            changelog: None,
+            game_versions: vec!["1.21.1".to_string()],
+            version_type: "release".to_string(),
+            loaders: vec!["fabric".to_string()],
+            featured: false,
+            files: Vec::new(),
+            dependencies: Vec::new(),
+            downloads: 0,
+            date_published: date_published.to_string(),
+            stale: false,
+        }
+    }
+
+    fn entry(id: i64, instance_id: &str) -> ReleaseFeedEntry {
+        ReleaseFeedEntry {
+            id,
+            instance_id: instance_id.to_string(),
+            project_id: "p".to_string(),
+            project_name: "Project".to_string(),
+            icon_url: None,
+            path: "mods/p.jar".to_string(),
+            installed_version_id: "a".to_string(),
+            installed_version_number: "1.0".to_string(),
+            version_id: format!("v{}", id),
+            version_number: "1.1".to_string(),
+            version_type: "release".to_string(),
+            changelog: None,
+            date_published: String::new(),
+            discovered_at: String::new(),
+            read: false,
+        }
+    }
+
+    #[test]
+    fn test_check_due() {
+        let now = DateTime::parse_from_rfc3339("2026-10-18T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert!(check_due(None, 60, now));
+        assert!(check_due(Some("not a date"), 60, now));
+        assert!(check_due(Some("2026-10-18T11:00:00Z"), 60, now));
+        assert!(!check_due(Some("2026-10-18T11:30:00+00:00"), 60, now));
+        assert!(!check_due(None, 0, now));
+    }
+
+    #[test]
+    fn test_is_newer() {
+        let installed = version("a", "2024-05-01T10:00:00.000000Z");
+        assert!(is_newer(&installed, &version("b", "2024-06-01T10:00:00Z")));
+        assert!(!is_newer(&installed, &version("a", "2024-06-01T10:00:00Z")));
+        // An older version of another branch is not an update
+        assert!(!is_newer(&installed, &version("c", "2024-04-01T10:00:00Z")));
+    }
+
+    #[test]
+    fn test_group_by_instance() {
+        let names = HashMap::from([
+            ("i1".to_string(), "Alpha".to_string()),
+            ("i2".to_string(), "Beta".to_string()),
+        ]);
+        let groups =
+            group_by_instance(vec![entry(1, "i1"), entry(2, "i1"), entry(3, "i2")], &names);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].instance_name, "Alpha");
+        assert_eq!(groups[0].entries.len(), 2);
+        assert_eq!(groups[1].entries[0].id, 3);
+        assert!(group_by_instance(Vec::new(), &names).is_empty());
+    }
+}
@@ -1,12 +1,44 @@
 // Modrinth API client for searching and downloading mods
 // API Documentation: https://docs.modrinth.com/api-spec
 
+pub mod collections;
 pub mod commands;
+pub mod feed;
 
+use crate::cache::{ApiCache, Cached, HttpFetchError};
+use crate::download::endpoints;
 use reqwest::Url;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
 
 const MODRINTH_API_BASE: &str = "https://api.modrinth.com/v2";
+/// Collections are only served by the v3 API
+const MODRINTH_API_V3_BASE: &str = "https://api.modrinth.com/v3";
+
+/// Most project ids sent in one batch request
+const PROJECTS_BATCH_SIZE: usize = 100;
+
+/// Size limit of the on-disk cache of API responses
+const DISK_CACHE_MAX_BYTES: u64 = 100 * 1024 * 1024;
+
+/// How long cached responses are used without asking the API again
+const SEARCH_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+const PROJECT_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+const VERSIONS_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Search, project and version responses kept on disk, set up at startup
+static DISK_CACHE: OnceLock<ApiCache> = OnceLock::new();
+
+/// Keep Modrinth responses in `cache/modrinth` of the data directory,
+/// so they survive restarts and can be shown offline
+pub fn init_disk_cache(data_dir: &Path) {
+    let _ = DISK_CACHE.set(ApiCache::with_limit(
+        data_dir.join("cache").join("modrinth"),
+        DISK_CACHE_MAX_BYTES,
+    ));
+}
 
 /// Allowed download domains for security (prevent malicious redirects)
 const ALLOWED_DOWNLOAD_DOMAINS: &[&str] = &[
@@ -23,6 +55,7 @@ const ALLOWED_DOWNLOAD_DOMAINS: &[&str] = &[
     "maven.quiltmc.org",
     "papermc.io",
     "api.papermc.io",
+    "hangarcdn.papermc.io",
 ];
 
 /// Search response from Modrinth
@@ -32,6 +65,9 @@ pub struct SearchResponse {
     pub offset: u32,
     pub limit: u32,
     pub total_hits: u32,
+    /// Served from the cache because Modrinth could not be reached
+    #[serde(default)]
+    pub stale: bool,
 }
 
 /// A single search result
@@ -88,6 +124,9 @@ pub struct Project {
     pub published: String,
     pub updated: String,
     pub license: Option<License>,
+    /// Served from the cache because Modrinth could not be reached
+    #[serde(default)]
+    pub stale: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -130,6 +169,9 @@ pub struct Version {
     pub dependencies: Vec<Dependency>,
     pub downloads: u64,
     pub date_published: String,
+    /// Served from the cache because Modrinth could not be reached
+    #[serde(default)]
+    pub stale: bool,
 }
 
 /// File information within a version
@@ -159,6 +201,25 @@ pub struct Dependency {
     pub dependency_type: String, // required, optional, incompatible, embedded
 }
 
+/// A user-curated list of projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub icon_url: Option<String>,
+    /// Project ids
+    pub projects: Vec<String>,
+}
+
+/// The Modrinth account an access token belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub username: String,
+    pub avatar_url: Option<String>,
+}
+
 /// Search query parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchQuery {
@@ -201,6 +262,13 @@ impl SearchQuery {
     }
 }
 
+/// Body of the batch hash lookup
+#[derive(Serialize)]
+struct HashLookupRequest<'r> {
+    hashes: &'r [String],
+    algorithm: &'r str,
+}
+
 /// Client for interacting with the Modrinth API
 pub struct ModrinthClient<'a> {
     http_client: &'a reqwest::Client,
@@ -211,6 +279,34 @@ impl<'a> ModrinthClient<'a> {
         Self { http_client }
     }
 
+    /// GET a JSON endpoint through the disk cache, when it is set up
+    async fn get_cached<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        ttl: Duration,
+    ) -> Result<Cached<T>, ModrinthError> {
+        if let Some(cache) = DISK_CACHE.get() {
+            return Ok(cache.fetch_json(self.http_client, url, ttl).await?);
+        }
+
+        let response = endpoints::get(self.http_client, url)
+            .await
+            .map_err(|e| ModrinthError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(ModrinthError::Api(format!(
+                "API returned status {}",
+                response.status()
+            )));
+        }
+
+        let data = response
+            .json::<T>()
+            .await
+            .map_err(|e| ModrinthError::Parse(e.to_string()))?;
+        Ok(Cached { data, stale: false })
+    }
+
     /// Search for projects on Modrinth
     pub async fn search(&self, query: &SearchQuery) -> Result<SearchResponse, ModrinthError> {
         let mut url = format!(
@@ -232,54 +328,103 @@ impl<'a> ModrinthClient<'a> {
             url.push_str(&format!("&limit={}", limit));
         }
 
-        let response = self
-            .http_client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| ModrinthError::Network(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return Err(ModrinthError::Api(format!(
-                "API returned status {}",
-                response.status()
-            )));
-        }
-
-        response
-            .json::<SearchResponse>()
-            .await
-            .map_err(|e| ModrinthError::Parse(e.to_string()))
+        let cached = self
+            .get_cached::<SearchResponse>(&url, SEARCH_CACHE_TTL)
+            .await?;
+        Ok(SearchResponse {
+            stale: cached.stale,
+            ..cached.data
+        })
     }
 
     /// Get project details by ID or slug
     pub async fn get_project(&self, id_or_slug: &str) -> Result<Project, ModrinthError> {
         let url = format!("{}/project/{}", MODRINTH_API_BASE, id_or_slug);
 
-        let response = self
-            .http_client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| ModrinthError::Network(e.to_string()))?;
+        let cached = self.get_cached::<Project>(&url, PROJECT_CACHE_TTL).await?;
+        Ok(Project {
+            stale: cached.stale,
+            ..cached.data
+        })
+    }
 
-        if !response.status().is_success() {
+    /// GET a JSON endpoint that may need a personal access token, bypassing the cache
+    /// Authenticated requests skip the mirrors, so the token is only sent to Modrinth
+    async fn get_authenticated<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        token: Option<&str>,
+    ) -> Result<T, ModrinthError> {
+        let response = match token {
+            Some(token) => {
+                self.http_client
+                    .get(url)
+                    .header(reqwest::header::AUTHORIZATION, token)
+                    .send()
+                    .await
+            }
+            None => endpoints::get(self.http_client, url).await,
+        }
+        .map_err(|e| ModrinthError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(ModrinthError::Api(
+                "Modrinth rejected the access token".to_string(),
+            ));
+        }
+        if !status.is_success() {
             return Err(ModrinthError::Api(format!(
                 "API returned status {}",
-                response.status()
+                status
             )));
         }
 
         response
-            .json::<Project>()
+            .json::<T>()
             .await
             .map_err(|e| ModrinthError::Parse(e.to_string()))
     }
 
+    /// Get a collection by ID
+    /// Private collections are only visible with their owner's token
+    pub async fn get_collection(
+        &self,
+        id: &str,
+        token: Option<&str>,
+    ) -> Result<Collection, ModrinthError> {
+        let url = format!("{}/collection/{}", MODRINTH_API_V3_BASE, id);
+        self.get_authenticated(&url, token).await
+    }
+
+    /// Get the user a personal access token belongs to
+    pub async fn get_current_user(&self, token: &str) -> Result<User, ModrinthError> {
+        let url = format!("{}/user", MODRINTH_API_BASE);
+        self.get_authenticated(&url, Some(token)).await
+    }
+
+    /// Get the projects a user follows
+    pub async fn get_followed_projects(
+        &self,
+        user_id: &str,
+        token: &str,
+    ) -> Result<Vec<Project>, ModrinthError> {
+        let url = format!("{}/user/{}/follows", MODRINTH_API_BASE, user_id);
+        self.get_authenticated(&url, Some(token)).await
+    }
+
+    /// Get any number of projects by IDs, in batches of `PROJECTS_BATCH_SIZE`
+    pub async fn get_projects_batched(&self, ids: &[&str]) -> Result<Vec<Project>, ModrinthError> {
+        let mut projects = Vec::with_capacity(ids.len());
+        for chunk in ids.chunks(PROJECTS_BATCH_SIZE) {
+            projects.extend(self.get_projects(chunk).await?);
+        }
+        Ok(projects)
+    }
+
     /// Get multiple projects by IDs (batch request)
     /// This is more efficient than calling get_project multiple times
     /// Includes retry logic with exponential backoff for rate limiting (429)
-    #[allow(dead_code)]
     pub async fn get_projects(&self, ids: &[&str]) -> Result<Vec<Project>, ModrinthError> {
         if ids.is_empty() {
             return Ok(Vec::new());
@@ -298,10 +443,7 @@ impl<'a> ModrinthClient<'a> {
         let mut retry_delay = std::time::Duration::from_millis(500);
 
         for attempt in 0..=max_retries {
-            let response = self
-                .http_client
-                .get(&url)
-                .send()
+            let response = endpoints::get(self.http_client, &url)
                 .await
                 .map_err(|e| ModrinthError::Network(e.to_string()))?;
 
@@ -368,34 +510,24 @@ impl<'a> ModrinthClient<'a> {
             url.push_str(&params.join("&"));
         }
 
-        let response = self
-            .http_client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| ModrinthError::Network(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return Err(ModrinthError::Api(format!(
-                "API returned status {}",
-                response.status()
-            )));
-        }
-
-        response
-            .json::<Vec<Version>>()
-            .await
-            .map_err(|e| ModrinthError::Parse(e.to_string()))
+        let cached = self
+            .get_cached::<Vec<Version>>(&url, VERSIONS_CACHE_TTL)
+            .await?;
+        Ok(cached
+            .data
+            .into_iter()
+            .map(|v| Version {
+                stale: cached.stale,
+                ..v
+            })
+            .collect())
     }
 
     /// Get a specific version by ID
     pub async fn get_version(&self, version_id: &str) -> Result<Version, ModrinthError> {
         let url = format!("{}/version/{}", MODRINTH_API_BASE, version_id);
 
-        let response = self
-            .http_client
-            .get(&url)
-            .send()
+        let response = endpoints::get(self.http_client, &url)
             .await
             .map_err(|e| ModrinthError::Network(e.to_string()))?;
 
@@ -420,10 +552,7 @@ impl<'a> ModrinthClient<'a> {
             MODRINTH_API_BASE, sha512
         );
 
-        let response = self
-            .http_client
-            .get(&url)
-            .send()
+        let response = endpoints::get(self.http_client, &url)
             .await
             .map_err(|e| ModrinthError::Network(e.to_string()))?;
 
@@ -449,10 +578,7 @@ impl<'a> ModrinthClient<'a> {
             MODRINTH_API_BASE, sha1
         );
 
-        let response = self
-            .http_client
-            .get(&url)
-            .send()
+        let response = endpoints::get(self.http_client, &url)
             .await
             .map_err(|e| ModrinthError::Network(e.to_string()))?;
 
@@ -469,6 +595,35 @@ impl<'a> ModrinthClient<'a> {
             .map_err(|e| ModrinthError::Parse(e.to_string()))
     }
 
+    /// POST a JSON body through the disk cache, when it is set up
+    async fn post_cached<B: Serialize, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &B,
+        ttl: Duration,
+    ) -> Result<Cached<T>, ModrinthError> {
+        if let Some(cache) = DISK_CACHE.get() {
+            return Ok(cache.post_json(self.http_client, url, body, ttl).await?);
+        }
+
+        let response = endpoints::send(url, |url| self.http_client.post(url).json(body))
+            .await
+            .map_err(|e| ModrinthError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(ModrinthError::Api(format!(
+                "API returned status {}",
+                response.status()
+            )));
+        }
+
+        let data = response
+            .json::<T>()
+            .await
+            .map_err(|e| ModrinthError::Parse(e.to_string()))?;
+        Ok(Cached { data, stale: false })
+    }
+
     /// Batch lookup versions by multiple hashes
     /// Returns a map of hash -> Version for found files
     pub async fn get_versions_by_hashes(
@@ -481,23 +636,9 @@ impl<'a> ModrinthClient<'a> {
         }
 
         let url = format!("{}/version_files", MODRINTH_API_BASE);
+        let request_body = HashLookupRequest { hashes, algorithm };
 
-        #[derive(Serialize)]
-        struct HashLookupRequest {
-            hashes: Vec<String>,
-            algorithm: String,
-        }
-
-        let request_body = HashLookupRequest {
-            hashes: hashes.to_vec(),
-            algorithm: algorithm.to_string(),
-        };
-
-        let response = self
-            .http_client
-            .post(&url)
-            .json(&request_body)
-            .send()
+        let response = endpoints::send(&url, |url| self.http_client.post(url).json(&request_body))
             .await
             .map_err(|e| ModrinthError::Network(e.to_string()))?;
 
@@ -514,6 +655,63 @@ impl<'a> ModrinthClient<'a> {
             .map_err(|e| ModrinthError::Parse(e.to_string()))
     }
 
+    /// [`Self::get_versions_by_hashes`] through the disk cache, for background
+    /// checks that can live with results a few minutes old
+    pub async fn get_versions_by_hashes_cached(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+    ) -> Result<std::collections::HashMap<String, Version>, ModrinthError> {
+        if hashes.is_empty() {
+            return Ok(std::collections::HashMap::new());
+        }
+
+        let url = format!("{}/version_files", MODRINTH_API_BASE);
+        let request_body = HashLookupRequest { hashes, algorithm };
+
+        Ok(self
+            .post_cached(&url, &request_body, VERSIONS_CACHE_TTL)
+            .await?
+            .data)
+    }
+
+    /// Batch lookup of the newest version for the given loaders and game
+    /// versions of the projects the hashed files belong to, through the disk cache.
+    /// Returns a map of hash -> newest Version
+    pub async fn get_latest_versions_by_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+        loaders: &[String],
+        game_versions: &[String],
+    ) -> Result<std::collections::HashMap<String, Version>, ModrinthError> {
+        if hashes.is_empty() {
+            return Ok(std::collections::HashMap::new());
+        }
+
+        let url = format!("{}/version_files/update", MODRINTH_API_BASE);
+
+        #[derive(Serialize)]
+        struct LatestVersionsRequest<'r> {
+            hashes: &'r [String],
+            algorithm: &'r str,
+            loaders: &'r [String],
+            game_versions: &'r [String],
+        }
+
+        let request_body = LatestVersionsRequest {
+            hashes,
+            algorithm,
+            loaders,
+            game_versions,
+        };
+
+        Ok(self
+            .post_cached(&url, &request_body, VERSIONS_CACHE_TTL)
+            .await?
+            .data)
+    }
+
     /// Download a mod file to the specified path
     pub async fn download_file(
         &self,
@@ -521,28 +719,9 @@ impl<'a> ModrinthClient<'a> {
         dest_path: &std::path::Path,
     ) -> Result<(), ModrinthError> {
         // Security: Validate download URL domain
-        let url = Url::parse(&file.url)
-            .map_err(|e| ModrinthError::Network(format!("Invalid URL: {}", e)))?;
-
-        let host = url.host_str().ok_or_else(|| {
-            ModrinthError::Network("Download URL has no host".to_string())
-        })?;
-
-        let is_allowed = ALLOWED_DOWNLOAD_DOMAINS
-            .iter()
-            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)));
+        validate_download_url(&file.url)?;
 
-        if !is_allowed {
-            return Err(ModrinthError::Network(format!(
-                "Download blocked: untrusted domain '{}'",
-                host
-            )));
-        }
-
-        let response = self
-            .http_client
-            .get(&file.url)
-            .send()
+        let response = endpoints::get(self.http_client, &file.url)
             .await
             .map_err(|e| ModrinthError::Network(e.to_string()))?;
 
@@ -572,6 +751,9 @@ impl<'a> ModrinthClient<'a> {
         }
 
         // Write to file
+        crate::instance::dedup::unlink_shared(dest_path)
+            .await
+            .map_err(|e| ModrinthError::Io(e.to_string()))?;
         tokio::fs::write(dest_path, &bytes)
             .await
             .map_err(|e| ModrinthError::Io(e.to_string()))?;
@@ -580,6 +762,31 @@ impl<'a> ModrinthClient<'a> {
     }
 }
 
+/// Check that a download URL points to one of the trusted download domains
+pub fn validate_download_url(download_url: &str) -> Result<(), ModrinthError> {
+    let url = Url::parse(download_url)
+        .map_err(|e| ModrinthError::Network(format!("Invalid URL: {}", e)))?;
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| ModrinthError::Network("Download URL has no host".to_string()))?;
+
+    // Configured mirrors are trusted like the official hosts they stand in for
+    let is_allowed = ALLOWED_DOWNLOAD_DOMAINS
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+        || endpoints::is_mirror_host(host);
+
+    if !is_allowed {
+        return Err(ModrinthError::Network(format!(
+            "Download blocked: untrusted domain '{}'",
+            host
+        )));
+    }
+
+    Ok(())
+}
+
 /// Errors that can occur when using the Modrinth API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ModrinthError {
@@ -606,6 +813,16 @@ impl std::fmt::Display for ModrinthError {
 
 impl std::error::Error for ModrinthError {}
 
+impl From<HttpFetchError> for ModrinthError {
+    fn from(e: HttpFetchError) -> Self {
+        match e {
+            HttpFetchError::Network(msg) => Self::Network(msg),
+            HttpFetchError::Status(_) => Self::Api(e.to_string()),
+            HttpFetchError::Parse(msg) => Self::Parse(msg),
+        }
+    }
+}
+
 /// Normalize loader name to Modrinth's expected format
 fn normalize_loader_for_modrinth(loader: &str) -> String {
     // Modrinth uses lowercase loader names
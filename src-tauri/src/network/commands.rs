@@ -0,0 +1,647 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter, State};
+use tokio::fs;
+
+use crate::crypto;
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::instance::commands::get_used_server_ports;
+use crate::launcher::commands::{launch_instance, send_server_command, stop_instance};
+use crate::state::SharedState;
+
+use super::config::{self, BackendEntry};
+use super::{
+    allocate_backend_ports, db, BackendAssignment, NetworkApplyReport, NetworkMember,
+    NetworkProgressEvent, ProxyKind, ServerNetwork, FORWARDING_SECRET_FILE,
+};
+
+/// How long to wait for a backend to accept connections before giving up
+const BACKEND_READY_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// How long to wait for a graceful shutdown before killing the process
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Backend selection sent by the frontend when creating or editing a network
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkMemberInput {
+    pub instance_id: String,
+    pub server_name: Option<String>,
+    #[serde(default = "default_in_try")]
+    pub in_try: bool,
+}
+
+fn default_in_try() -> bool {
+    true
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    network_id: &str,
+    stage: &str,
+    instance_id: Option<&str>,
+    message: String,
+) {
+    let _ = app.emit(
+        "network-progress",
+        NetworkProgressEvent {
+            network_id: network_id.to_string(),
+            stage: stage.to_string(),
+            instance_id: instance_id.map(String::from),
+            message,
+        },
+    );
+}
+
+/// Validate backends and build members with unique server names
+async fn build_members(
+    state: &crate::state::AppState,
+    network_id: Option<&str>,
+    proxy_instance_id: &str,
+    inputs: &[NetworkMemberInput],
+) -> AppResult<Vec<NetworkMember>> {
+    let mut members = Vec::with_capacity(inputs.len());
+    let mut names: HashSet<String> = HashSet::new();
+
+    for (position, input) in inputs.iter().enumerate() {
+        if input.instance_id == proxy_instance_id {
+            return Err(AppError::Instance(
+                "The proxy cannot also be a backend server".to_string(),
+            ));
+        }
+
+        let instance = Instance::get_by_id(&state.db, &input.instance_id)
+            .await?
+            .ok_or_else(|| AppError::Instance("Backend instance not found".to_string()))?;
+
+        if !instance.is_server || ProxyKind::from_loader(instance.loader.as_deref()).is_some() {
+            return Err(AppError::Instance(format!(
+                "'{}' is not a server instance",
+                instance.name
+            )));
+        }
+
+        if let Some(existing) = db::get_network_id_for_instance(&state.db, &instance.id).await? {
+            if Some(existing.as_str()) != network_id {
+                return Err(AppError::Instance(format!(
+                    "'{}' already belongs to another network",
+                    instance.name
+                )));
+            }
+        }
+
+        let base = config::sanitize_server_name(
+            input.server_name.as_deref().unwrap_or(&instance.name),
+        );
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while names.contains(&name) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        names.insert(name.clone());
+
+        members.push(NetworkMember {
+            instance_id: instance.id,
+            server_name: name,
+            position: position as i64,
+            in_try: input.in_try,
+        });
+    }
+
+    Ok(members)
+}
+
+/// Get all proxy networks
+#[tauri::command]
+pub async fn get_networks(state: State<'_, SharedState>) -> AppResult<Vec<ServerNetwork>> {
+    let state = state.read().await;
+    Ok(db::get_networks(&state.db).await?)
+}
+
+/// Create a network from a proxy instance and backend server instances
+#[tauri::command]
+pub async fn create_network(
+    state: State<'_, SharedState>,
+    name: String,
+    proxy_instance_id: String,
+    members: Vec<NetworkMemberInput>,
+) -> AppResult<ServerNetwork> {
+    let state_guard = state.read().await;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Instance("Network name cannot be empty".to_string()));
+    }
+
+    let proxy = Instance::get_by_id(&state_guard.db, &proxy_instance_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Proxy instance not found".to_string()))?;
+    if ProxyKind::from_loader(proxy.loader.as_deref()).is_none() {
+        return Err(AppError::Instance(format!(
+            "'{}' is not a Velocity, BungeeCord or Waterfall instance",
+            proxy.name
+        )));
+    }
+    if db::get_network_id_for_instance(&state_guard.db, &proxy.id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Instance(format!(
+            "'{}' already belongs to another network",
+            proxy.name
+        )));
+    }
+
+    let members = build_members(&state_guard, None, &proxy.id, &members).await?;
+
+    let secret = config::generate_forwarding_secret();
+    let encrypted_secret = crypto::encrypt(&state_guard.encryption_key, &secret)?;
+
+    let network_id = uuid::Uuid::new_v4().to_string();
+    db::insert_network(
+        &state_guard.db,
+        &network_id,
+        name,
+        &proxy.id,
+        &encrypted_secret,
+    )
+    .await?;
+    db::set_members(&state_guard.db, &network_id, &members).await?;
+
+    tracing::info!(
+        "[NETWORK] Created network '{}' with proxy {} and {} backend(s)",
+        name,
+        proxy.name,
+        members.len()
+    );
+
+    db::get_network(&state_guard.db, &network_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Network not found".to_string()))
+}
+
+/// Rename a network and/or replace its backend list
+#[tauri::command]
+pub async fn update_network(
+    state: State<'_, SharedState>,
+    network_id: String,
+    name: Option<String>,
+    members: Vec<NetworkMemberInput>,
+) -> AppResult<ServerNetwork> {
+    let state_guard = state.read().await;
+
+    let network = db::get_network(&state_guard.db, &network_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Network not found".to_string()))?;
+
+    if let Some(name) = name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        db::rename_network(&state_guard.db, &network_id, name).await?;
+    }
+
+    let members = build_members(
+        &state_guard,
+        Some(&network_id),
+        &network.proxy_instance_id,
+        &members,
+    )
+    .await?;
+    db::set_members(&state_guard.db, &network_id, &members).await?;
+
+    db::get_network(&state_guard.db, &network_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Network not found".to_string()))
+}
+
+/// Delete a network (instances and their files are left untouched)
+#[tauri::command]
+pub async fn delete_network(state: State<'_, SharedState>, network_id: String) -> AppResult<()> {
+    let state = state.read().await;
+    db::delete_network(&state.db, &network_id).await?;
+    Ok(())
+}
+
+/// Allocate backend ports and write the proxy and backend configuration files
+#[tauri::command]
+pub async fn apply_network_config(
+    state: State<'_, SharedState>,
+    network_id: String,
+) -> AppResult<NetworkApplyReport> {
+    let used_ports = get_used_server_ports(state.clone()).await?;
+
+    let state_guard = state.read().await;
+    let network = db::get_network(&state_guard.db, &network_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Network not found".to_string()))?;
+
+    let proxy = Instance::get_by_id(&state_guard.db, &network.proxy_instance_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Proxy instance not found".to_string()))?;
+    let proxy_kind = ProxyKind::from_loader(proxy.loader.as_deref()).ok_or_else(|| {
+        AppError::Instance(format!("'{}' is not a proxy instance", proxy.name))
+    })?;
+
+    let mut warnings = Vec::new();
+    let mut backends: Vec<(NetworkMember, Instance)> = Vec::new();
+    for member in &network.members {
+        match Instance::get_by_id(&state_guard.db, &member.instance_id).await? {
+            Some(instance) => backends.push((member.clone(), instance)),
+            None => warnings.push(format!(
+                "Backend '{}' no longer exists and was skipped",
+                member.server_name
+            )),
+        }
+    }
+
+    // Ports used by anything that is not one of our backends (including the proxy)
+    let backend_ids: HashSet<&str> = backends.iter().map(|(_, i)| i.id.as_str()).collect();
+    let used: HashSet<i64> = used_ports
+        .iter()
+        .filter(|p| !backend_ids.contains(p.instance_id.as_str()))
+        .map(|p| p.port)
+        .collect();
+    let current: Vec<i64> = backends.iter().map(|(_, i)| i.server_port).collect();
+    let ports = allocate_backend_ports(&current, &used);
+
+    let secret_encrypted = db::get_forwarding_secret(&state_guard.db, &network_id)
+        .await?
+        .ok_or_else(|| AppError::Instance("Network not found".to_string()))?;
+    let secret = crypto::decrypt(&state_guard.encryption_key, &secret_encrypted)?;
+
+    let instances_dir = state_guard.data_dir.join("instances");
+    let mut assignments = Vec::with_capacity(backends.len());
+    let mut entries = Vec::with_capacity(backends.len());
+
+    for ((member, instance), port) in backends.iter().zip(ports) {
+        if port != instance.server_port {
+            Instance::update_server_port(&state_guard.db, &instance.id, port).await?;
+            tracing::info!(
+                "[NETWORK] Backend {} moved from port {} to {}",
+                instance.name,
+                instance.server_port,
+                port
+            );
+        }
+
+        let backend_dir = instances_dir.join(&instance.game_dir);
+        configure_backend(&backend_dir, instance, proxy_kind, &secret, port, &mut warnings)
+            .await?;
+
+        entries.push(BackendEntry {
+            name: member.server_name.clone(),
+            address: format!("127.0.0.1:{}", port),
+        });
+        assignments.push(BackendAssignment {
+            instance_id: instance.id.clone(),
+            server_name: member.server_name.clone(),
+            port,
+        });
+    }
+
+    let try_order: Vec<String> = backends
+        .iter()
+        .filter(|(m, _)| m.in_try)
+        .map(|(m, _)| m.server_name.clone())
+        .collect();
+    if try_order.is_empty() && !backends.is_empty() {
+        warnings.push("No backend is in the join order; players will not be able to connect".to_string());
+    }
+
+    let proxy_dir = instances_dir.join(&proxy.game_dir);
+    configure_proxy(&proxy_dir, &proxy, proxy_kind, &secret, &entries, &try_order).await?;
+
+    tracing::info!(
+        "[NETWORK] Applied configuration for network '{}' ({} backend(s), {} warning(s))",
+        network.name,
+        assignments.len(),
+        warnings.len()
+    );
+
+    Ok(NetworkApplyReport {
+        network_id,
+        proxy_kind,
+        backends: assignments,
+        warnings,
+    })
+}
+
+/// Read a config file, returning an empty string when it does not exist yet
+async fn read_or_empty(path: &Path) -> AppResult<String> {
+    if path.exists() {
+        fs::read_to_string(path)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))
+    } else {
+        Ok(String::new())
+    }
+}
+
+async fn write_config(path: &Path, content: &str) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    fs::write(path, content)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Write port, online-mode and forwarding settings for a backend server
+async fn configure_backend(
+    backend_dir: &Path,
+    instance: &Instance,
+    proxy_kind: ProxyKind,
+    secret: &str,
+    port: i64,
+    warnings: &mut Vec<String>,
+) -> AppResult<()> {
+    // The proxy authenticates players, backends must not
+    let properties_path = backend_dir.join("server.properties");
+    let properties = read_or_empty(&properties_path).await?;
+    let properties = config::set_property(&properties, "server-port", &port.to_string());
+    let properties = config::set_property(&properties, "online-mode", "false");
+    write_config(&properties_path, &properties).await?;
+
+    let loader = instance.loader.as_deref().map(str::to_lowercase);
+    let is_paper_family = matches!(
+        loader.as_deref(),
+        Some("paper") | Some("purpur") | Some("folia") | Some("pufferfish")
+    );
+
+    match proxy_kind {
+        ProxyKind::Velocity if is_paper_family => {
+            let legacy_path = backend_dir.join("paper.yml");
+            let global_path = backend_dir.join("config").join("paper-global.yml");
+            if legacy_path.exists() && !global_path.exists() {
+                let content = read_or_empty(&legacy_path).await?;
+                write_config(
+                    &legacy_path,
+                    &config::apply_legacy_paper_velocity(&content, secret),
+                )
+                .await?;
+            } else {
+                let content = read_or_empty(&global_path).await?;
+                write_config(
+                    &global_path,
+                    &config::apply_paper_global_velocity(&content, secret),
+                )
+                .await?;
+            }
+        }
+        ProxyKind::Velocity => warnings.push(format!(
+            "'{}' is not a Paper-based server; install a modern forwarding mod (e.g. FabricProxy-Lite) for Velocity to work",
+            instance.name
+        )),
+        ProxyKind::BungeeCord if is_paper_family || loader.as_deref() == Some("spigot") => {
+            let spigot_path = backend_dir.join("spigot.yml");
+            let content = read_or_empty(&spigot_path).await?;
+            write_config(
+                &spigot_path,
+                &config::set_yaml_value(&content, &["settings", "bungeecord"], "true"),
+            )
+            .await?;
+        }
+        ProxyKind::BungeeCord => warnings.push(format!(
+            "'{}' is not a Spigot-based server; BungeeCord IP forwarding will not be available",
+            instance.name
+        )),
+    }
+
+    Ok(())
+}
+
+/// Write the backend list, join order and forwarding settings of the proxy
+async fn configure_proxy(
+    proxy_dir: &Path,
+    proxy: &Instance,
+    proxy_kind: ProxyKind,
+    secret: &str,
+    entries: &[BackendEntry],
+    try_order: &[String],
+) -> AppResult<()> {
+    match proxy_kind {
+        ProxyKind::Velocity => {
+            let config_path = proxy_dir.join("velocity.toml");
+            let content = read_or_empty(&config_path).await?;
+            let mut doc = config::parse_velocity_config(&content)
+                .map_err(|e| AppError::Instance(format!("Invalid velocity.toml: {}", e)))?;
+            config::apply_velocity_bind(&mut doc, proxy.server_port);
+            config::apply_velocity_forwarding(&mut doc, FORWARDING_SECRET_FILE);
+            config::apply_velocity_servers(&mut doc, entries, try_order);
+            write_config(&config_path, &doc.to_string()).await?;
+            write_config(&proxy_dir.join(FORWARDING_SECRET_FILE), secret).await?;
+        }
+        ProxyKind::BungeeCord => {
+            let config_path = proxy_dir.join("config.yml");
+            let content = if config_path.exists() {
+                read_or_empty(&config_path).await?
+            } else {
+                config::default_bungee_config(proxy.server_port)
+            };
+            write_config(
+                &config_path,
+                &config::apply_bungee_servers(&content, entries, try_order),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn is_running(state: &State<'_, SharedState>, instance_id: &str) -> bool {
+    let state_guard = state.read().await;
+    let running = state_guard.running_instances.read().await;
+    running.contains_key(instance_id)
+}
+
+/// Wait until a backend accepts TCP connections on its port
+async fn wait_for_backend(
+    state: &State<'_, SharedState>,
+    instance_id: &str,
+    port: i64,
+) -> AppResult<()> {
+    let deadline = tokio::time::Instant::now() + BACKEND_READY_TIMEOUT;
+
+    while tokio::time::Instant::now() < deadline {
+        if !is_running(state, instance_id).await {
+            return Err(AppError::Launcher(
+                "Backend server stopped during startup".to_string(),
+            ));
+        }
+
+        let connect = tokio::net::TcpStream::connect(("127.0.0.1", port as u16));
+        if let Ok(Ok(_)) = tokio::time::timeout(Duration::from_secs(2), connect).await {
+            return Ok(());
+        }
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+
+    Err(AppError::Launcher(format!(
+        "Backend server did not open port {} in time",
+        port
+    )))
+}
+
+/// Ask an instance to stop via its console and kill it if it does not exit in time
+async fn shutdown_instance(
+    state: &State<'_, SharedState>,
+    instance_id: &str,
+    stop_command: &str,
+) -> AppResult<()> {
+    if !is_running(state, instance_id).await {
+        return Ok(());
+    }
+
+    if let Err(e) =
+        send_server_command(state.clone(), instance_id.to_string(), stop_command.to_string()).await
+    {
+        tracing::warn!("[NETWORK] Failed to send '{}': {}", stop_command, e);
+    }
+
+    let deadline = tokio::time::Instant::now() + SHUTDOWN_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        if !is_running(state, instance_id).await {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    tracing::warn!(
+        "[NETWORK] Instance {} did not stop gracefully, killing it",
+        instance_id
+    );
+    stop_instance(state.clone(), instance_id.to_string()).await
+}
+
+/// Start every backend (in order, waiting for each to accept connections) and then the proxy
+#[tauri::command]
+pub async fn start_network(
+    state: State<'_, SharedState>,
+    app: AppHandle,
+    network_id: String,
+) -> AppResult<()> {
+    let network = {
+        let state_guard = state.read().await;
+        db::get_network(&state_guard.db, &network_id)
+            .await?
+            .ok_or_else(|| AppError::Instance("Network not found".to_string()))?
+    };
+
+    // Refresh ports and configuration when the network is fully stopped
+    let mut any_running = is_running(&state, &network.proxy_instance_id).await;
+    for member in &network.members {
+        any_running |= is_running(&state, &member.instance_id).await;
+    }
+    if !any_running {
+        let report = apply_network_config(state.clone(), network_id.clone()).await?;
+        for warning in &report.warnings {
+            tracing::warn!("[NETWORK] {}", warning);
+        }
+    }
+
+    for member in &network.members {
+        let port = {
+            let state_guard = state.read().await;
+            match Instance::get_by_id(&state_guard.db, &member.instance_id).await? {
+                Some(instance) => instance.server_port,
+                None => continue,
+            }
+        };
+
+        if !is_running(&state, &member.instance_id).await {
+            emit_progress(
+                &app,
+                &network_id,
+                "starting",
+                Some(&member.instance_id),
+                format!("Starting {}", member.server_name),
+            );
+            launch_instance(
+                state.clone(),
+                app.clone(),
+                member.instance_id.clone(),
+                String::new(),
+                None,
+            )
+            .await?;
+        }
+
+        emit_progress(
+            &app,
+            &network_id,
+            "waiting",
+            Some(&member.instance_id),
+            format!("Waiting for {} on port {}", member.server_name, port),
+        );
+        wait_for_backend(&state, &member.instance_id, port).await?;
+    }
+
+    if !is_running(&state, &network.proxy_instance_id).await {
+        emit_progress(
+            &app,
+            &network_id,
+            "starting",
+            Some(&network.proxy_instance_id),
+            "Starting proxy".to_string(),
+        );
+        launch_instance(
+            state.clone(),
+            app.clone(),
+            network.proxy_instance_id.clone(),
+            String::new(),
+            None,
+        )
+        .await?;
+    }
+
+    emit_progress(&app, &network_id, "started", None, "Network started".to_string());
+    Ok(())
+}
+
+/// Stop the proxy first, then every backend in reverse start order
+#[tauri::command]
+pub async fn stop_network(
+    state: State<'_, SharedState>,
+    app: AppHandle,
+    network_id: String,
+) -> AppResult<()> {
+    let (network, proxy_kind) = {
+        let state_guard = state.read().await;
+        let network = db::get_network(&state_guard.db, &network_id)
+            .await?
+            .ok_or_else(|| AppError::Instance("Network not found".to_string()))?;
+        let proxy_kind = Instance::get_by_id(&state_guard.db, &network.proxy_instance_id)
+            .await?
+            .and_then(|p| ProxyKind::from_loader(p.loader.as_deref()))
+            .unwrap_or(ProxyKind::Velocity);
+        (network, proxy_kind)
+    };
+
+    emit_progress(
+        &app,
+        &network_id,
+        "stopping",
+        Some(&network.proxy_instance_id),
+        "Stopping proxy".to_string(),
+    );
+    shutdown_instance(&state, &network.proxy_instance_id, proxy_kind.stop_command()).await?;
+
+    for member in network.members.iter().rev() {
+        emit_progress(
+            &app,
+            &network_id,
+            "stopping",
+            Some(&member.instance_id),
+            format!("Stopping {}", member.server_name),
+        );
+        shutdown_instance(&state, &member.instance_id, "stop").await?;
+    }
+
+    emit_progress(&app, &network_id, "stopped", None, "Network stopped".to_string());
+    Ok(())
+}
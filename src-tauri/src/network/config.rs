@@ -0,0 +1,463 @@
+//! Editors for proxy and backend configuration files.
+//!
+//! `velocity.toml` is edited with `toml_edit`, the YAML and properties files
+//! at the text level. Either way comments and unrelated keys written by
+//! Velocity, BungeeCord or Paper are preserved.
+
+use rand::Rng;
+use std::collections::HashSet;
+use toml_edit::{value, Array, DocumentMut, Item, Table};
+
+/// A backend entry as seen by the proxy
+#[derive(Debug, Clone)]
+pub struct BackendEntry {
+    pub name: String,
+    pub address: String,
+}
+
+/// Generate a forwarding secret in the same shape Velocity uses (12 alphanumeric chars)
+pub fn generate_forwarding_secret() -> String {
+    rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(12)
+        .map(char::from)
+        .collect()
+}
+
+/// Keys of the servers table that are not server names
+const RESERVED_SERVER_NAMES: &[&str] = &["try"];
+
+/// Turn an instance name into a valid proxy server name (`[a-z0-9_-]`)
+pub fn sanitize_server_name(name: &str) -> String {
+    let sanitized: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let sanitized = sanitized.trim_matches('-').to_string();
+    if sanitized.is_empty() {
+        "server".to_string()
+    } else if RESERVED_SERVER_NAMES.contains(&sanitized.as_str()) {
+        format!("{}-server", sanitized)
+    } else {
+        sanitized
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Parse a `velocity.toml`, an empty file gives an empty document
+pub fn parse_velocity_config(content: &str) -> Result<DocumentMut, toml_edit::TomlError> {
+    content.parse()
+}
+
+/// Listen on all interfaces on the proxy's port
+pub fn apply_velocity_bind(doc: &mut DocumentMut, port: i64) {
+    doc["bind"] = value(format!("0.0.0.0:{}", port));
+}
+
+/// Rewrite the `[servers]` table (including `try`) of a `velocity.toml`
+///
+/// Forced hosts pointing to servers that no longer exist are dropped, since
+/// Velocity refuses to start with them. The others are kept.
+pub fn apply_velocity_servers(
+    doc: &mut DocumentMut,
+    backends: &[BackendEntry],
+    try_order: &[String],
+) {
+    let mut servers = Table::new();
+    for backend in backends {
+        servers.insert(&backend.name, value(&backend.address));
+    }
+    servers.insert("try", value(Array::from_iter(try_order)));
+    match doc.get_mut("servers").and_then(Item::as_table_mut) {
+        // Keep the comments above the table
+        Some(existing) => {
+            existing.clear();
+            existing.extend(servers);
+        }
+        None => doc["servers"] = Item::Table(servers),
+    }
+
+    let names: HashSet<&str> = backends.iter().map(|b| b.name.as_str()).collect();
+    if let Some(hosts) = doc
+        .get_mut("forced-hosts")
+        .and_then(Item::as_table_like_mut)
+    {
+        let mut unused = Vec::new();
+        for (host, item) in hosts.iter_mut() {
+            match item.as_array_mut() {
+                Some(targets) => {
+                    targets.retain(|t| t.as_str().is_some_and(|name| names.contains(name)));
+                    if targets.is_empty() {
+                        unused.push(host.get().to_string());
+                    }
+                }
+                None => unused.push(host.get().to_string()),
+            }
+        }
+        for host in unused {
+            hosts.remove(&host);
+        }
+    }
+}
+
+/// Enable modern forwarding in a `velocity.toml`
+pub fn apply_velocity_forwarding(doc: &mut DocumentMut, secret_file: &str) {
+    doc["player-info-forwarding-mode"] = value("modern");
+    doc["forwarding-secret-file"] = value(secret_file);
+}
+
+/// Set a nested key in a YAML document, creating missing parents
+///
+/// Only block-style mappings are supported, which is what Paper, Spigot and
+/// BungeeCord write.
+pub fn set_yaml_value(content: &str, path: &[&str], value: &str) -> String {
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let mut start = 0;
+    let mut end = lines.len();
+    let mut parent_indent: Option<usize> = None;
+
+    for (depth, key) in path.iter().enumerate() {
+        let is_last = depth == path.len() - 1;
+
+        // Indentation used by children of the current block
+        let child_indent = lines[start..end]
+            .iter()
+            .find(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+            .map(|l| indent_of(l))
+            .unwrap_or_else(|| parent_indent.map(|i| i + 2).unwrap_or(0));
+
+        let key_prefix = format!("{}:", key);
+        let found = (start..end).find(|&i| {
+            let line = &lines[i];
+            indent_of(line) == child_indent && line.trim_start().starts_with(&key_prefix)
+        });
+
+        match found {
+            Some(idx) if is_last => {
+                lines[idx] = format!("{}{}: {}", " ".repeat(child_indent), key, value);
+                break;
+            }
+            Some(idx) => {
+                // Narrow to the children of this key
+                let block_end = (idx + 1..end)
+                    .find(|&i| {
+                        let l = &lines[i];
+                        !l.trim().is_empty()
+                            && !l.trim_start().starts_with('#')
+                            && indent_of(l) <= child_indent
+                    })
+                    .unwrap_or(end);
+                start = idx + 1;
+                end = block_end;
+                parent_indent = Some(child_indent);
+            }
+            None => {
+                // Insert the remaining path at the end of the current block
+                let mut insert_at = end;
+                while insert_at > start && lines[insert_at - 1].trim().is_empty() {
+                    insert_at -= 1;
+                }
+                let mut new_lines = Vec::new();
+                for (offset, remaining) in path[depth..].iter().enumerate() {
+                    let indent = " ".repeat(child_indent + offset * 2);
+                    if depth + offset == path.len() - 1 {
+                        new_lines.push(format!("{}{}: {}", indent, remaining, value));
+                    } else {
+                        new_lines.push(format!("{}{}:", indent, remaining));
+                    }
+                }
+                for (i, l) in new_lines.into_iter().enumerate() {
+                    lines.insert(insert_at + i, l);
+                }
+                break;
+            }
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Configure a Paper 1.19+ `config/paper-global.yml` for Velocity modern forwarding
+pub fn apply_paper_global_velocity(content: &str, secret: &str) -> String {
+    let content = set_yaml_value(content, &["proxies", "velocity", "enabled"], "true");
+    let content = set_yaml_value(&content, &["proxies", "velocity", "online-mode"], "true");
+    set_yaml_value(
+        &content,
+        &["proxies", "velocity", "secret"],
+        &format!("'{}'", secret),
+    )
+}
+
+/// Configure a legacy Paper `paper.yml` for Velocity modern forwarding
+pub fn apply_legacy_paper_velocity(content: &str, secret: &str) -> String {
+    let content = set_yaml_value(content, &["settings", "velocity-support", "enabled"], "true");
+    let content = set_yaml_value(
+        &content,
+        &["settings", "velocity-support", "online-mode"],
+        "true",
+    );
+    set_yaml_value(
+        &content,
+        &["settings", "velocity-support", "secret"],
+        &format!("'{}'", secret),
+    )
+}
+
+/// Rewrite the top-level `servers:` block and listener `priorities` of a BungeeCord `config.yml`
+pub fn apply_bungee_servers(content: &str, backends: &[BackendEntry], priorities: &[String]) -> String {
+    let mut servers_block = vec!["servers:".to_string()];
+    for backend in backends {
+        servers_block.push(format!("  {}:", backend.name));
+        servers_block.push(format!("    motd: '{}'", backend.name));
+        servers_block.push(format!("    address: {}", backend.address));
+        servers_block.push("    restricted: false".to_string());
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    let mut skipping_servers = false;
+    let mut replaced_servers = false;
+    let mut priorities_indent: Option<usize> = None;
+
+    for line in content.lines() {
+        let is_top_level = !line.is_empty()
+            && !line.starts_with(' ')
+            && !line.starts_with('-')
+            && !line.starts_with('#');
+
+        if skipping_servers {
+            if is_top_level {
+                skipping_servers = false;
+            } else {
+                continue;
+            }
+        }
+
+        if let Some(indent) = priorities_indent {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("- ") && indent_of(line) >= indent {
+                continue;
+            }
+            priorities_indent = None;
+        }
+
+        if line.trim_end() == "servers:" && is_top_level {
+            skipping_servers = true;
+            replaced_servers = true;
+            out.extend(servers_block.iter().cloned());
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("priorities:") {
+            let indent = indent_of(line);
+            let pad = " ".repeat(indent);
+            if priorities.is_empty() {
+                out.push(format!("{}priorities: []", pad));
+            } else {
+                out.push(format!("{}priorities:", pad));
+                for name in priorities {
+                    out.push(format!("{}- {}", pad, name));
+                }
+            }
+            priorities_indent = Some(indent);
+            continue;
+        }
+
+        out.push(line.to_string());
+    }
+
+    if !replaced_servers {
+        out.extend(servers_block);
+    }
+
+    let mut result = out.join("\n");
+    result.push('\n');
+    set_yaml_value(&result, &["ip_forward"], "true")
+}
+
+/// Minimal BungeeCord `config.yml` used when the proxy has never been started
+pub fn default_bungee_config(port: i64) -> String {
+    format!(
+        "# Generated by Kaizen Launcher\nlisteners:\n- host: 0.0.0.0:{}\n  motd: '&1Kaizen Network'\n  max_players: 100\n  priorities: []\n  force_default_server: false\nonline_mode: true\n",
+        port
+    )
+}
+
+/// Set a key in a Java `.properties` file, preserving other lines
+pub fn set_property(content: &str, key: &str, value: &str) -> String {
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            if !trimmed.starts_with('#') {
+                if let Some((k, _)) = trimmed.split_once('=') {
+                    if k.trim() == key {
+                        found = true;
+                        return format!("{}={}", key, value);
+                    }
+                }
+            }
+            line.to_string()
+        })
+        .collect();
+
+    if !found {
+        lines.push(format!("{}={}", key, value));
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backends() -> Vec<BackendEntry> {
+        vec![
+            BackendEntry {
+                name: "lobby".to_string(),
+                address: "127.0.0.1:25566".to_string(),
+            },
+            BackendEntry {
+                name: "survival".to_string(),
+                address: "127.0.0.1:25567".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_sanitize_server_name() {
+        assert_eq!(sanitize_server_name("My Lobby!"), "my-lobby");
+        assert_eq!(sanitize_server_name("  "), "server");
+        assert_eq!(sanitize_server_name("survival_1"), "survival_1");
+    }
+
+    #[test]
+    fn test_velocity_servers_replaced() {
+        let input = r#"bind = "0.0.0.0:25577"
+
+# Backend servers
+[servers]
+lobby = "127.0.0.1:30066"
+factions = "127.0.0.1:30067"
+try = [
+  "lobby"
+]
+
+[forced-hosts]
+"lobby.example.com" = ["lobby"]
+"factions.example.com" = ["factions"]
+"mixed.example.com" = ["factions", "survival"]
+
+[advanced]
+compression-threshold = 256
+"#;
+        let mut doc = parse_velocity_config(input).unwrap();
+        apply_velocity_servers(
+            &mut doc,
+            &backends(),
+            &["lobby".to_string(), "survival".to_string()],
+        );
+        let out = doc.to_string();
+        assert!(out.contains("# Backend servers\n[servers]"));
+        assert!(out.contains("lobby = \"127.0.0.1:25566\""));
+        assert!(out.contains("survival = \"127.0.0.1:25567\""));
+        assert!(out.contains("try = [\"lobby\", \"survival\"]"));
+        assert!(!out.contains("30067"));
+        assert!(out.contains("compression-threshold = 256"));
+
+        let reparsed: toml::Table = toml::from_str(&out).unwrap();
+        let hosts = reparsed["forced-hosts"].as_table().unwrap();
+        assert_eq!(hosts["lobby.example.com"].as_array().unwrap().len(), 1);
+        assert!(!hosts.contains_key("factions.example.com"));
+        assert_eq!(
+            hosts["mixed.example.com"].as_array().unwrap(),
+            &vec![toml::Value::from("survival")]
+        );
+    }
+
+    #[test]
+    fn test_velocity_server_named_try() {
+        assert_eq!(sanitize_server_name("Try"), "try-server");
+
+        let mut doc = parse_velocity_config("").unwrap();
+        let backends = vec![BackendEntry {
+            name: sanitize_server_name("try"),
+            address: "127.0.0.1:25566".to_string(),
+        }];
+        apply_velocity_servers(&mut doc, &backends, &["try-server".to_string()]);
+        let reparsed: toml::Table = toml::from_str(&doc.to_string()).unwrap();
+        let servers = reparsed["servers"].as_table().unwrap();
+        assert_eq!(servers.len(), 2);
+        assert!(servers.contains_key("try-server"));
+    }
+
+    #[test]
+    fn test_velocity_forwarding_root_keys() {
+        let input = "bind = \"0.0.0.0:25577\"\nplayer-info-forwarding-mode = \"NONE\"\n\n[servers]\n";
+        let mut doc = parse_velocity_config(input).unwrap();
+        apply_velocity_bind(&mut doc, 25600);
+        apply_velocity_forwarding(&mut doc, "forwarding.secret");
+        let out = doc.to_string();
+        assert!(out.contains("bind = \"0.0.0.0:25600\""));
+        assert!(out.contains("player-info-forwarding-mode = \"modern\""));
+        let secret_pos = out.find("forwarding-secret-file").unwrap();
+        let servers_pos = out.find("[servers]").unwrap();
+        assert!(secret_pos < servers_pos);
+    }
+
+    #[test]
+    fn test_paper_global_velocity_existing_block() {
+        let input = "_version: 28\nproxies:\n  bungee-cord:\n    online-mode: true\n  velocity:\n    enabled: false\n    online-mode: false\n    secret: ''\nscoreboards:\n  x: 1\n";
+        let out = apply_paper_global_velocity(input, "abc123");
+        assert!(out.contains("    enabled: true"));
+        assert!(out.contains("    online-mode: true"));
+        assert!(out.contains("    secret: 'abc123'"));
+        assert!(out.contains("bungee-cord:\n    online-mode: true"));
+        assert!(out.contains("scoreboards:\n  x: 1"));
+    }
+
+    #[test]
+    fn test_paper_global_velocity_missing_file() {
+        let out = apply_paper_global_velocity("", "s3cret");
+        assert!(out.contains("proxies:\n  velocity:\n    enabled: true"));
+        assert!(out.contains("    secret: 's3cret'"));
+    }
+
+    #[test]
+    fn test_bungee_servers_and_priorities() {
+        let input = "listeners:\n- query_port: 25577\n  priorities:\n  - lobby\n  - old\n  host: 0.0.0.0:25577\nservers:\n  lobby:\n    motd: 'x'\n    address: localhost:25565\n    restricted: false\nip_forward: false\n";
+        let out = apply_bungee_servers(input, &backends(), &["lobby".to_string()]);
+        assert!(out.contains("  priorities:\n  - lobby\n  host: 0.0.0.0:25577"));
+        assert!(!out.contains("- old"));
+        assert!(out.contains("    address: 127.0.0.1:25567"));
+        assert!(!out.contains("localhost:25565"));
+        assert!(out.contains("ip_forward: true"));
+    }
+
+    #[test]
+    fn test_set_property() {
+        let input = "#comment\nserver-port=25565\nonline-mode=true\n";
+        let out = set_property(input, "server-port", "25570");
+        assert!(out.contains("server-port=25570"));
+        assert!(out.contains("#comment"));
+        let out = set_property(&out, "motd", "hi");
+        assert!(out.ends_with("motd=hi\n"));
+    }
+}
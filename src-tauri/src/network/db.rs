@@ -0,0 +1,201 @@
+use sqlx::SqlitePool;
+
+use super::{NetworkMember, ServerNetwork};
+
+/// Get all networks with their members
+pub async fn get_networks(db: &SqlitePool) -> sqlx::Result<Vec<ServerNetwork>> {
+    let rows = sqlx::query_as::<_, (String, String, String, String)>(
+        r#"
+        SELECT id, name, proxy_instance_id, created_at
+        FROM server_networks
+        ORDER BY created_at DESC
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut networks = Vec::with_capacity(rows.len());
+    for (id, name, proxy_instance_id, created_at) in rows {
+        let members = get_members(db, &id).await?;
+        networks.push(ServerNetwork {
+            id,
+            name,
+            proxy_instance_id,
+            members,
+            created_at,
+        });
+    }
+    Ok(networks)
+}
+
+/// Get a single network by id
+pub async fn get_network(db: &SqlitePool, network_id: &str) -> sqlx::Result<Option<ServerNetwork>> {
+    let row = sqlx::query_as::<_, (String, String, String, String)>(
+        r#"
+        SELECT id, name, proxy_instance_id, created_at
+        FROM server_networks
+        WHERE id = ?
+        "#,
+    )
+    .bind(network_id)
+    .fetch_optional(db)
+    .await?;
+
+    match row {
+        Some((id, name, proxy_instance_id, created_at)) => {
+            let members = get_members(db, &id).await?;
+            Ok(Some(ServerNetwork {
+                id,
+                name,
+                proxy_instance_id,
+                members,
+                created_at,
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Get the members of a network ordered by start position
+pub async fn get_members(db: &SqlitePool, network_id: &str) -> sqlx::Result<Vec<NetworkMember>> {
+    let rows = sqlx::query_as::<_, (String, String, i64, i32)>(
+        r#"
+        SELECT instance_id, server_name, position, in_try
+        FROM server_network_members
+        WHERE network_id = ?
+        ORDER BY position ASC
+        "#,
+    )
+    .bind(network_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(instance_id, server_name, position, in_try)| NetworkMember {
+            instance_id,
+            server_name,
+            position,
+            in_try: in_try != 0,
+        })
+        .collect())
+}
+
+/// Find the network an instance belongs to (as proxy or backend)
+pub async fn get_network_id_for_instance(
+    db: &SqlitePool,
+    instance_id: &str,
+) -> sqlx::Result<Option<String>> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT id FROM server_networks WHERE proxy_instance_id = ?
+        UNION
+        SELECT network_id FROM server_network_members WHERE instance_id = ?
+        LIMIT 1
+        "#,
+    )
+    .bind(instance_id)
+    .bind(instance_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// Insert a new network
+pub async fn insert_network(
+    db: &SqlitePool,
+    id: &str,
+    name: &str,
+    proxy_instance_id: &str,
+    forwarding_secret: &str,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO server_networks (id, name, proxy_instance_id, forwarding_secret)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(id)
+    .bind(name)
+    .bind(proxy_instance_id)
+    .bind(forwarding_secret)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Rename a network
+pub async fn rename_network(db: &SqlitePool, network_id: &str, name: &str) -> sqlx::Result<()> {
+    sqlx::query("UPDATE server_networks SET name = ? WHERE id = ?")
+        .bind(name)
+        .bind(network_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Replace all members of a network
+pub async fn set_members(
+    db: &SqlitePool,
+    network_id: &str,
+    members: &[NetworkMember],
+) -> sqlx::Result<()> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("DELETE FROM server_network_members WHERE network_id = ?")
+        .bind(network_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for member in members {
+        sqlx::query(
+            r#"
+            INSERT INTO server_network_members (network_id, instance_id, server_name, position, in_try)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(network_id)
+        .bind(&member.instance_id)
+        .bind(&member.server_name)
+        .bind(member.position)
+        .bind(member.in_try as i32)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+/// Get the stored (encrypted) forwarding secret of a network
+pub async fn get_forwarding_secret(db: &SqlitePool, network_id: &str) -> sqlx::Result<Option<String>> {
+    sqlx::query_scalar::<_, String>("SELECT forwarding_secret FROM server_networks WHERE id = ?")
+        .bind(network_id)
+        .fetch_optional(db)
+        .await
+}
+
+/// Replace the forwarding secret of a network
+pub async fn set_forwarding_secret(
+    db: &SqlitePool,
+    network_id: &str,
+    forwarding_secret: &str,
+) -> sqlx::Result<()> {
+    sqlx::query("UPDATE server_networks SET forwarding_secret = ? WHERE id = ?")
+        .bind(forwarding_secret)
+        .bind(network_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Delete a network and its members
+pub async fn delete_network(db: &SqlitePool, network_id: &str) -> sqlx::Result<()> {
+    sqlx::query("DELETE FROM server_network_members WHERE network_id = ?")
+        .bind(network_id)
+        .execute(db)
+        .await?;
+    sqlx::query("DELETE FROM server_networks WHERE id = ?")
+        .bind(network_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
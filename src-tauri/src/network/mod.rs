@@ -0,0 +1,158 @@
+// Proxy networks: a Velocity/BungeeCord/Waterfall proxy instance grouped with
+// the backend server instances it forwards players to.
+
+pub mod commands;
+pub mod config;
+pub mod db;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// First port tried when allocating backend ports
+pub const BACKEND_PORT_START: i64 = 25566;
+
+/// File name of the Velocity forwarding secret, relative to the proxy directory
+pub const FORWARDING_SECRET_FILE: &str = "forwarding.secret";
+
+/// Proxy flavour, derived from the proxy instance loader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyKind {
+    Velocity,
+    BungeeCord,
+}
+
+impl ProxyKind {
+    pub fn from_loader(loader: Option<&str>) -> Option<Self> {
+        match loader.map(|l| l.to_lowercase()).as_deref() {
+            Some("velocity") => Some(Self::Velocity),
+            Some("bungeecord") | Some("waterfall") => Some(Self::BungeeCord),
+            _ => None,
+        }
+    }
+
+    /// Console command that shuts the proxy down gracefully
+    pub fn stop_command(&self) -> &'static str {
+        match self {
+            Self::Velocity => "shutdown",
+            Self::BungeeCord => "end",
+        }
+    }
+}
+
+/// A backend server inside a network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkMember {
+    pub instance_id: String,
+    /// Name used for the server in the proxy configuration
+    pub server_name: String,
+    /// Start order (lowest first) and position in the `try` list
+    pub position: i64,
+    /// Whether players are sent to this server on join / fallback
+    pub in_try: bool,
+}
+
+/// A proxy and its backend servers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerNetwork {
+    pub id: String,
+    pub name: String,
+    pub proxy_instance_id: String,
+    pub members: Vec<NetworkMember>,
+    pub created_at: String,
+}
+
+/// Port assigned to a backend when the network configuration is applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendAssignment {
+    pub instance_id: String,
+    pub server_name: String,
+    pub port: i64,
+}
+
+/// Result of writing the proxy and backend configuration files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkApplyReport {
+    pub network_id: String,
+    pub proxy_kind: ProxyKind,
+    pub backends: Vec<BackendAssignment>,
+    pub warnings: Vec<String>,
+}
+
+/// Progress event emitted while starting or stopping a network
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkProgressEvent {
+    pub network_id: String,
+    /// "starting", "stopping", "waiting", "started", "stopped"
+    pub stage: String,
+    pub instance_id: Option<String>,
+    pub message: String,
+}
+
+/// Pick a port for each backend
+///
+/// A backend keeps its current port when no other instance uses it,
+/// otherwise the next free port from [`BACKEND_PORT_START`] is assigned.
+/// `used` holds the ports of every server/proxy instance outside the backends.
+pub fn allocate_backend_ports(current: &[i64], used: &HashSet<i64>) -> Vec<i64> {
+    let mut taken: HashSet<i64> = used.clone();
+    let mut result = vec![0; current.len()];
+    let mut needs_port = Vec::new();
+
+    for (i, &port) in current.iter().enumerate() {
+        if port > 0 && !taken.contains(&port) {
+            taken.insert(port);
+            result[i] = port;
+        } else {
+            needs_port.push(i);
+        }
+    }
+
+    let mut next = BACKEND_PORT_START;
+    for i in needs_port {
+        while taken.contains(&next) {
+            next += 1;
+        }
+        taken.insert(next);
+        result[i] = next;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_keeps_free_ports() {
+        let used: HashSet<i64> = [25577].into_iter().collect();
+        assert_eq!(
+            allocate_backend_ports(&[25570, 25571], &used),
+            vec![25570, 25571]
+        );
+    }
+
+    #[test]
+    fn test_allocate_resolves_conflicts() {
+        // Two fresh backends both on the default port, proxy on 25565
+        let used: HashSet<i64> = [25565, 25566].into_iter().collect();
+        assert_eq!(
+            allocate_backend_ports(&[25565, 25565], &used),
+            vec![25567, 25568]
+        );
+    }
+
+    #[test]
+    fn test_proxy_kind_from_loader() {
+        assert_eq!(
+            ProxyKind::from_loader(Some("Velocity")),
+            Some(ProxyKind::Velocity)
+        );
+        assert_eq!(
+            ProxyKind::from_loader(Some("waterfall")),
+            Some(ProxyKind::BungeeCord)
+        );
+        assert_eq!(ProxyKind::from_loader(Some("paper")), None);
+    }
+}
@@ -0,0 +1,653 @@
+use crate::content::{self, ContentSource};
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use crate::instance::commands::{ModMetadata, StoredDependency};
+use crate::instance::malware_scan;
+use crate::state::{AppState, SharedState};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use super::{
+    provider_for, select_latest_version, PluginDependency, PluginProvider, PluginSearchResponse,
+    PluginTarget, PluginVersion,
+};
+
+/// Plugin files already present in an instance's plugins/ folder
+struct InstalledPlugins {
+    /// (plugin filename, metadata) for plugins with a .meta.json
+    with_meta: Vec<(String, ModMetadata)>,
+    /// Lowercased names of every plugin jar, with or without metadata
+    jar_names: Vec<String>,
+}
+
+impl InstalledPlugins {
+    async fn scan(plugins_dir: &Path) -> AppResult<Self> {
+        let mut installed = Self {
+            with_meta: Vec::new(),
+            jar_names: Vec::new(),
+        };
+
+        if !plugins_dir.exists() {
+            return Ok(installed);
+        }
+
+        let mut entries = tokio::fs::read_dir(plugins_dir)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to read plugins directory: {}", e)))?;
+
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to read directory entry: {}", e)))?
+        {
+            let filename = entry.file_name().to_string_lossy().to_string();
+
+            if filename.ends_with(".jar") || filename.ends_with(".jar.disabled") {
+                installed.jar_names.push(filename.to_lowercase());
+                continue;
+            }
+
+            if let Some(base_name) = filename.strip_suffix(".meta.json") {
+                let plugin_filename = [".jar", ".jar.disabled"]
+                    .iter()
+                    .map(|ext| format!("{}{}", base_name, ext))
+                    .find(|name| plugins_dir.join(name).exists());
+
+                let Some(plugin_filename) = plugin_filename else {
+                    continue;
+                };
+
+                if let Ok(content) = tokio::fs::read_to_string(entry.path()).await {
+                    if let Ok(meta) = serde_json::from_str::<ModMetadata>(&content) {
+                        installed.with_meta.push((plugin_filename, meta));
+                    }
+                }
+            }
+        }
+
+        Ok(installed)
+    }
+
+    /// Whether a plugin matching this project id or name is installed
+    fn contains(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        if key.is_empty() {
+            return false;
+        }
+
+        self.with_meta.iter().any(|(_, meta)| {
+            meta.project_id.to_lowercase() == key || meta.name.to_lowercase() == key
+        }) || self.jar_names.iter().any(|jar| jar.starts_with(&key))
+    }
+}
+
+/// A required dependency that can't be installed automatically
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnresolvedPluginDependency {
+    pub dependency: PluginDependency,
+    pub reason: String,
+}
+
+/// What installing a plugin version would do
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInstallPlan {
+    pub source: ContentSource,
+    /// Requested version followed by the dependencies to install
+    pub to_install: Vec<PluginVersion>,
+    /// Names of required dependencies already present in plugins/
+    pub already_installed: Vec<String>,
+    /// Optional dependencies that are not installed
+    pub optional: Vec<PluginDependency>,
+    pub unresolved: Vec<UnresolvedPluginDependency>,
+}
+
+/// Result of installing a plugin and its dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInstallResult {
+    pub installed: Vec<String>,
+    pub unresolved: Vec<UnresolvedPluginDependency>,
+}
+
+/// Information about a plugin that has an update available
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginUpdateInfo {
+    pub source: ContentSource,
+    pub project_id: String,
+    pub filename: String,
+    pub current_version: String,
+    pub current_version_id: Option<String>,
+    pub latest_version: String,
+    pub latest_version_id: String,
+    pub name: String,
+    pub icon_url: Option<String>,
+}
+
+/// Load a server/proxy instance and the platform its plugins are filtered by
+async fn get_plugin_instance(
+    state: &SharedState,
+    instance_id: &str,
+) -> AppResult<(Instance, PathBuf, PluginTarget)> {
+    let state_guard = state.read().await;
+
+    let instance = Instance::get_by_id(&state_guard.db, instance_id)
+        .await
+        .map_err(AppError::from)?
+        .ok_or_else(|| AppError::Instance("Instance not found".to_string()))?;
+
+    if !instance.is_server {
+        return Err(AppError::Instance(
+            "Plugins can only be installed on server instances".to_string(),
+        ));
+    }
+
+    let target = PluginTarget::from_instance(&instance)?;
+    let plugins_dir = state_guard
+        .data_dir
+        .join("instances")
+        .join(&instance.game_dir)
+        .join("plugins");
+
+    Ok((instance, plugins_dir, target))
+}
+
+/// Find the version to install for a dependency
+async fn resolve_dependency(
+    provider: &dyn PluginProvider,
+    target: &PluginTarget,
+    dependency: &PluginDependency,
+) -> Result<PluginVersion, String> {
+    if let Some(version_id) = &dependency.version_id {
+        let version = provider
+            .get_version(
+                dependency.project_id.as_deref().unwrap_or_default(),
+                version_id,
+                target,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        return if version.file.is_some() {
+            Ok(version)
+        } else {
+            Err("This version must be downloaded manually".to_string())
+        };
+    }
+
+    let Some(project_id) = dependency.project_id.as_deref() else {
+        return Err(match &dependency.external_url {
+            Some(url) => format!("Hosted outside {}: {}", provider.source().as_str(), url),
+            None => "No project reference".to_string(),
+        });
+    };
+
+    let versions = provider
+        .get_versions(project_id, target)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    select_latest_version(&versions)
+        .cloned()
+        .ok_or_else(|| "No compatible version for this server".to_string())
+}
+
+/// Walk the required dependencies of a version, skipping plugins already installed
+async fn build_install_plan(
+    provider: &dyn PluginProvider,
+    target: &PluginTarget,
+    root: PluginVersion,
+    installed: &InstalledPlugins,
+) -> PluginInstallPlan {
+    let mut plan = PluginInstallPlan {
+        source: provider.source(),
+        to_install: Vec::new(),
+        already_installed: Vec::new(),
+        optional: Vec::new(),
+        unresolved: Vec::new(),
+    };
+
+    let mut seen: HashSet<String> = HashSet::new();
+    seen.insert(root.project_id.to_lowercase());
+    let mut queue = VecDeque::from([root]);
+
+    while let Some(version) = queue.pop_front() {
+        for dependency in &version.dependencies {
+            let key = dependency
+                .project_id
+                .as_deref()
+                .unwrap_or(&dependency.name)
+                .to_lowercase();
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+
+            if installed.contains(&key) || installed.contains(&dependency.name) {
+                if dependency.required {
+                    plan.already_installed.push(dependency.name.clone());
+                }
+                continue;
+            }
+
+            if !dependency.required {
+                plan.optional.push(dependency.clone());
+                continue;
+            }
+
+            match resolve_dependency(provider, target, dependency).await {
+                Ok(resolved) => {
+                    // Dependencies pinned by version id only are keyed by project once resolved
+                    if resolved.project_id.to_lowercase() == key
+                        || seen.insert(resolved.project_id.to_lowercase())
+                    {
+                        queue.push_back(resolved);
+                    }
+                }
+                Err(reason) => plan.unresolved.push(UnresolvedPluginDependency {
+                    dependency: dependency.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        plan.to_install.push(version);
+    }
+
+    plan
+}
+
+/// Write the .meta.json next to a plugin, in the same format used for mods
+async fn write_plugin_metadata(
+    plugins_dir: &Path,
+    filename: &str,
+    source: ContentSource,
+    name: String,
+    icon_url: Option<String>,
+    version: &PluginVersion,
+) {
+    let metadata = ModMetadata {
+        name,
+        version: version.version_number.clone(),
+        project_id: version.project_id.clone(),
+        version_id: Some(version.id.clone()),
+        icon_url,
+        server_side: Some("required".to_string()),
+        client_side: Some("unsupported".to_string()),
+        dependencies: version
+            .dependencies
+            .iter()
+            .filter_map(|d| {
+                d.project_id.as_ref().map(|pid| StoredDependency {
+                    project_id: pid.clone(),
+                    dependency_type: if d.required { "required" } else { "optional" }.to_string(),
+                })
+            })
+            .collect(),
+        source: source.meta_value(),
+    };
+
+    let meta_path = plugins_dir.join(format!("{}.meta.json", filename.trim_end_matches(".jar")));
+    if let Ok(meta_json) = serde_json::to_string_pretty(&metadata) {
+        let _ = tokio::fs::write(&meta_path, meta_json).await;
+    }
+}
+
+/// Download a plugin version into plugins/ under a temporary name and return
+/// the final filename and the temporary path
+async fn download_version(
+    http_client: &reqwest::Client,
+    plugins_dir: &Path,
+    version: &PluginVersion,
+) -> AppResult<(String, PathBuf)> {
+    let file = version.file.as_ref().ok_or_else(|| {
+        AppError::Instance(format!(
+            "{} {} must be downloaded manually{}",
+            version.project_id,
+            version.version_number,
+            version
+                .external_url
+                .as_ref()
+                .map(|url| format!(" from {}", url))
+                .unwrap_or_default()
+        ))
+    })?;
+
+    if file.filename.contains(['/', '\\']) || !file.filename.ends_with(".jar") {
+        return Err(AppError::Instance(format!(
+            "Invalid plugin file name: {}",
+            file.filename
+        )));
+    }
+
+    tokio::fs::create_dir_all(plugins_dir)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to create plugins directory: {}", e)))?;
+
+    let temp_path = plugins_dir.join(format!("{}.part", file.filename));
+    if let Err(e) = content::download_verified_file(
+        http_client,
+        &file.url,
+        &file.filename,
+        file.hashes(),
+        &temp_path,
+    )
+    .await
+    {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(e);
+    }
+
+    Ok((file.filename.clone(), temp_path))
+}
+
+/// Scan a plugin jar just added to plugins/. Fails when it was quarantined
+async fn check_added_plugin(
+    state: &AppState,
+    instance_id: &str,
+    plugins_dir: &Path,
+    path: &Path,
+) -> AppResult<()> {
+    let instance_dir = plugins_dir.parent().unwrap_or(plugins_dir);
+    malware_scan::check_added_file(state, instance_id, instance_dir, path).await
+}
+
+/// Download a version and its metadata into plugins/
+async fn install_version(
+    state: &AppState,
+    instance_id: &str,
+    provider: &dyn PluginProvider,
+    plugins_dir: &Path,
+    version: &PluginVersion,
+) -> AppResult<String> {
+    if let Some(file) = &version.file {
+        if plugins_dir.join(&file.filename).exists() {
+            return Err(AppError::Instance(format!(
+                "File {} already exists",
+                file.filename
+            )));
+        }
+    }
+
+    let project = provider.get_project(&version.project_id).await?;
+    let (filename, temp_path) = download_version(&state.http_client, plugins_dir, version).await?;
+
+    let plugin_path = plugins_dir.join(&filename);
+    tokio::fs::rename(&temp_path, &plugin_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to move {}: {}", filename, e)))?;
+    check_added_plugin(state, instance_id, plugins_dir, &plugin_path).await?;
+
+    write_plugin_metadata(
+        plugins_dir,
+        &filename,
+        provider.source(),
+        project.name,
+        project.icon_url,
+        version,
+    )
+    .await;
+
+    Ok(filename)
+}
+
+/// Search plugins for a server or proxy instance
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn search_plugins(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    source: ContentSource,
+    query: String,
+    sort_by: Option<String>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> AppResult<PluginSearchResponse> {
+    let (_, _, target) = get_plugin_instance(&state, &instance_id).await?;
+    let state_guard = state.read().await;
+    let provider = provider_for(source, &state_guard.http_client)?;
+
+    provider
+        .search(
+            &query,
+            &target,
+            sort_by.as_deref(),
+            offset.unwrap_or(0),
+            limit.unwrap_or(20),
+        )
+        .await
+}
+
+/// List the versions of a plugin that run on the instance's platform and Minecraft version
+#[tauri::command]
+pub async fn get_plugin_versions(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    source: ContentSource,
+    project_id: String,
+) -> AppResult<Vec<PluginVersion>> {
+    let (_, _, target) = get_plugin_instance(&state, &instance_id).await?;
+    let state_guard = state.read().await;
+    let provider = provider_for(source, &state_guard.http_client)?;
+
+    provider.get_versions(&project_id, &target).await
+}
+
+/// Resolve the plugins that installing a version would add
+#[tauri::command]
+pub async fn resolve_plugin_install(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    source: ContentSource,
+    project_id: String,
+    version_id: String,
+) -> AppResult<PluginInstallPlan> {
+    let (_, plugins_dir, target) = get_plugin_instance(&state, &instance_id).await?;
+    let state_guard = state.read().await;
+    let provider = provider_for(source, &state_guard.http_client)?;
+
+    let version = provider
+        .get_version(&project_id, &version_id, &target)
+        .await?;
+    let installed = InstalledPlugins::scan(&plugins_dir).await?;
+
+    Ok(build_install_plan(provider.as_ref(), &target, version, &installed).await)
+}
+
+/// Install a plugin version, and by default its required dependencies
+#[tauri::command]
+pub async fn install_plugin(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    source: ContentSource,
+    project_id: String,
+    version_id: String,
+    install_dependencies: Option<bool>,
+) -> AppResult<PluginInstallResult> {
+    let (instance, plugins_dir, target) = get_plugin_instance(&state, &instance_id).await?;
+    let state_guard = state.read().await;
+    let provider = provider_for(source, &state_guard.http_client)?;
+
+    let version = provider
+        .get_version(&project_id, &version_id, &target)
+        .await?;
+
+    let plan = if install_dependencies.unwrap_or(true) {
+        let installed = InstalledPlugins::scan(&plugins_dir).await?;
+        build_install_plan(provider.as_ref(), &target, version, &installed).await
+    } else {
+        PluginInstallPlan {
+            source,
+            to_install: vec![version],
+            already_installed: Vec::new(),
+            optional: Vec::new(),
+            unresolved: Vec::new(),
+        }
+    };
+
+    let mut result = PluginInstallResult {
+        installed: Vec::new(),
+        unresolved: plan.unresolved,
+    };
+
+    for (i, version) in plan.to_install.iter().enumerate() {
+        match install_version(
+            &state_guard,
+            &instance_id,
+            provider.as_ref(),
+            &plugins_dir,
+            version,
+        )
+        .await
+        {
+            Ok(filename) => result.installed.push(filename),
+            // The requested plugin must install, dependencies are reported instead
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => {
+                log::warn!(
+                    "Failed to install plugin dependency {}: {}",
+                    version.project_id,
+                    e
+                );
+                result.unresolved.push(UnresolvedPluginDependency {
+                    dependency: PluginDependency {
+                        project_id: Some(version.project_id.clone()),
+                        version_id: Some(version.id.clone()),
+                        name: version.project_id.clone(),
+                        required: true,
+                        external_url: version.external_url.clone(),
+                    },
+                    reason: e.to_string(),
+                });
+            }
+        }
+    }
+
+    log::info!(
+        "Installed plugins {:?} from {} to instance {}",
+        result.installed,
+        source.as_str(),
+        instance.name
+    );
+
+    Ok(result)
+}
+
+/// Check installed plugins for newer versions on their source
+#[tauri::command]
+pub async fn check_plugin_updates(
+    state: State<'_, SharedState>,
+    instance_id: String,
+) -> AppResult<Vec<PluginUpdateInfo>> {
+    let (_, plugins_dir, target) = get_plugin_instance(&state, &instance_id).await?;
+    let state_guard = state.read().await;
+
+    let installed = InstalledPlugins::scan(&plugins_dir).await?;
+    let mut updates = Vec::new();
+
+    for (filename, meta) in installed.with_meta {
+        let Some(source) = ContentSource::from_meta(meta.source.as_deref())
+            .filter(ContentSource::has_plugin_provider)
+        else {
+            continue;
+        };
+        let provider = provider_for(source, &state_guard.http_client)?;
+
+        match provider.get_versions(&meta.project_id, &target).await {
+            Ok(versions) => {
+                let Some(latest) = select_latest_version(&versions) else {
+                    continue;
+                };
+
+                let needs_update = match &meta.version_id {
+                    Some(current) => *current != latest.id,
+                    None => true,
+                };
+
+                if needs_update {
+                    updates.push(PluginUpdateInfo {
+                        source,
+                        project_id: meta.project_id.clone(),
+                        filename,
+                        current_version: meta.version.clone(),
+                        current_version_id: meta.version_id.clone(),
+                        latest_version: latest.version_number.clone(),
+                        latest_version_id: latest.id.clone(),
+                        name: meta.name.clone(),
+                        icon_url: meta.icon_url.clone(),
+                    });
+                }
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to check plugin updates for {} ({}): {}",
+                    meta.project_id,
+                    source.as_str(),
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(updates)
+}
+
+/// Replace an installed plugin with another version
+#[tauri::command]
+pub async fn update_plugin(
+    state: State<'_, SharedState>,
+    instance_id: String,
+    source: ContentSource,
+    project_id: String,
+    current_filename: String,
+    new_version_id: String,
+) -> AppResult<String> {
+    let (_, plugins_dir, target) = get_plugin_instance(&state, &instance_id).await?;
+    let state_guard = state.read().await;
+    let provider = provider_for(source, &state_guard.http_client)?;
+
+    let project = provider.get_project(&project_id).await?;
+    let version = provider
+        .get_version(&project_id, &new_version_id, &target)
+        .await?;
+
+    // Download first so a failed download leaves the current plugin in place
+    let (filename, temp_path) =
+        download_version(&state_guard.http_client, &plugins_dir, &version).await?;
+
+    let old_path = plugins_dir.join(&current_filename);
+    if old_path.exists() {
+        tokio::fs::remove_file(&old_path)
+            .await
+            .map_err(|e| AppError::Io(format!("Failed to delete old plugin: {}", e)))?;
+    }
+
+    let old_base = current_filename
+        .trim_end_matches(".disabled")
+        .trim_end_matches(".jar");
+    let _ = tokio::fs::remove_file(plugins_dir.join(format!("{}.meta.json", old_base))).await;
+
+    let plugin_path = plugins_dir.join(&filename);
+    tokio::fs::rename(&temp_path, &plugin_path)
+        .await
+        .map_err(|e| AppError::Io(format!("Failed to move {}: {}", filename, e)))?;
+    check_added_plugin(&state_guard, &instance_id, &plugins_dir, &plugin_path).await?;
+
+    write_plugin_metadata(
+        &plugins_dir,
+        &filename,
+        source,
+        project.name.clone(),
+        project.icon_url,
+        &version,
+    )
+    .await;
+
+    log::info!(
+        "Updated plugin {} from {} to {} ({})",
+        project.name,
+        current_filename,
+        filename,
+        version.version_number
+    );
+
+    Ok(filename)
+}
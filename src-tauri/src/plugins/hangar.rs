@@ -0,0 +1,427 @@
+// Hangar (PaperMC) API client
+// API Documentation: https://hangar.papermc.io/api-docs
+
+use super::{
+    PluginDependency, PluginFile, PluginProject, PluginProvider, PluginSearchResponse,
+    PluginSearchResult, PluginTarget, PluginVersion,
+};
+use crate::content::ContentSource;
+use crate::error::{AppError, AppResult};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const HANGAR_API_BASE: &str = "https://hangar.papermc.io/api/v1";
+
+/// Maximum page size accepted by the Hangar API
+const HANGAR_MAX_LIMIT: u32 = 25;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HangarPagination {
+    pub limit: u32,
+    pub offset: u32,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HangarPage<T> {
+    pub pagination: HangarPagination,
+    pub result: Vec<T>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HangarNamespace {
+    pub owner: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HangarProjectStats {
+    #[serde(default)]
+    pub downloads: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarProject {
+    pub name: String,
+    pub namespace: HangarNamespace,
+    #[serde(default)]
+    pub stats: HangarProjectStats,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarFileInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub sha256_hash: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarDownload {
+    #[serde(default)]
+    pub file_info: Option<HangarFileInfo>,
+    #[serde(default)]
+    pub external_url: Option<String>,
+    #[serde(default)]
+    pub download_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarPluginDependency {
+    pub name: String,
+    pub required: bool,
+    #[serde(default)]
+    pub external_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HangarChannel {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HangarVersion {
+    pub name: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub channel: HangarChannel,
+    #[serde(default)]
+    pub downloads: HashMap<String, HangarDownload>,
+    #[serde(default)]
+    pub plugin_dependencies: HashMap<String, Vec<HangarPluginDependency>>,
+    #[serde(default)]
+    pub platform_dependencies: HashMap<String, Vec<String>>,
+}
+
+impl HangarProject {
+    fn into_search_result(self) -> PluginSearchResult {
+        PluginSearchResult {
+            source: ContentSource::Hangar,
+            project_id: self.namespace.slug.clone(),
+            slug: self.namespace.slug,
+            name: self.name,
+            description: self.description.unwrap_or_default(),
+            author: self.namespace.owner,
+            downloads: self.stats.downloads,
+            icon_url: self.avatar_url,
+            categories: self.category.into_iter().collect(),
+        }
+    }
+}
+
+impl HangarVersion {
+    /// Convert to a provider-agnostic version for one platform
+    ///
+    /// Returns `None` when the version has no build for that platform.
+    pub fn into_plugin_version(
+        mut self,
+        project_id: &str,
+        platform: &str,
+    ) -> Option<PluginVersion> {
+        let download = self.downloads.remove(platform)?;
+
+        let file = match (download.file_info, download.download_url) {
+            (Some(info), Some(url)) => Some(PluginFile {
+                url,
+                filename: info.name,
+                size: info.size_bytes,
+                sha1: None,
+                sha256: Some(info.sha256_hash),
+                sha512: None,
+            }),
+            _ => None,
+        };
+
+        let dependencies = self
+            .plugin_dependencies
+            .remove(platform)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|d| PluginDependency {
+                // Hosted dependencies are referenced by project name, which Hangar resolves like a slug
+                project_id: d.external_url.is_none().then(|| d.name.clone()),
+                version_id: None,
+                name: d.name,
+                required: d.required,
+                external_url: d.external_url,
+            })
+            .collect();
+
+        Some(PluginVersion {
+            source: ContentSource::Hangar,
+            project_id: project_id.to_string(),
+            id: self.name.clone(),
+            version_number: self.name,
+            channel: normalize_channel(&self.channel.name),
+            game_versions: self
+                .platform_dependencies
+                .remove(platform)
+                .unwrap_or_default(),
+            date_published: self.created_at,
+            changelog: self.description,
+            file,
+            external_url: download.external_url,
+            dependencies,
+        })
+    }
+}
+
+/// Map Hangar channel names ("Release", "Snapshot", ...) to release/beta/alpha
+fn normalize_channel(channel: &str) -> String {
+    match channel.to_lowercase().as_str() {
+        "release" | "stable" => "release",
+        "alpha" => "alpha",
+        _ => "beta",
+    }
+    .to_string()
+}
+
+/// Client for the Hangar API
+pub struct HangarClient<'a> {
+    http_client: &'a reqwest::Client,
+}
+
+impl<'a> HangarClient<'a> {
+    pub fn new(http_client: &'a reqwest::Client) -> Self {
+        Self { http_client }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> AppResult<T> {
+        let response = self
+            .http_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| AppError::Network(format!("Hangar request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Network(format!(
+                "Hangar API returned status {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| AppError::Network(format!("Failed to parse Hangar response: {}", e)))
+    }
+}
+
+#[async_trait::async_trait]
+impl PluginProvider for HangarClient<'_> {
+    fn source(&self) -> ContentSource {
+        ContentSource::Hangar
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        target: &PluginTarget,
+        sort_by: Option<&str>,
+        offset: u32,
+        limit: u32,
+    ) -> AppResult<PluginSearchResponse> {
+        let mut url = format!(
+            "{}/projects?q={}&platform={}&offset={}&limit={}",
+            HANGAR_API_BASE,
+            urlencoding::encode(query),
+            target.platform.hangar_name(),
+            offset,
+            limit.min(HANGAR_MAX_LIMIT)
+        );
+
+        if let Some(version) = &target.game_version {
+            url.push_str(&format!("&version={}", urlencoding::encode(version)));
+        }
+
+        // Hangar sorts by relevance when a query is given and no sort is set
+        let sort = match sort_by {
+            Some("downloads") => Some("-downloads"),
+            Some("follows") => Some("-stars"),
+            Some("newest") => Some("-newest"),
+            Some("updated") => Some("-updated"),
+            _ => None,
+        };
+        if let Some(sort) = sort {
+            url.push_str(&format!("&sort={}", sort));
+        }
+
+        let page: HangarPage<HangarProject> = self.get_json(&url).await?;
+
+        Ok(PluginSearchResponse {
+            results: page
+                .result
+                .into_iter()
+                .map(HangarProject::into_search_result)
+                .collect(),
+            total_hits: page.pagination.count,
+            offset: page.pagination.offset,
+            limit: page.pagination.limit,
+        })
+    }
+
+    async fn get_project(&self, project_id: &str) -> AppResult<PluginProject> {
+        let url = format!(
+            "{}/projects/{}",
+            HANGAR_API_BASE,
+            urlencoding::encode(project_id)
+        );
+        let project: HangarProject = self.get_json(&url).await?;
+
+        Ok(PluginProject {
+            source: ContentSource::Hangar,
+            project_id: project.namespace.slug.clone(),
+            slug: project.namespace.slug,
+            name: project.name,
+            description: project.description.unwrap_or_default(),
+            icon_url: project.avatar_url,
+        })
+    }
+
+    async fn get_versions(
+        &self,
+        project_id: &str,
+        target: &PluginTarget,
+    ) -> AppResult<Vec<PluginVersion>> {
+        let platform = target.platform.hangar_name();
+        let mut url = format!(
+            "{}/projects/{}/versions?platform={}&limit={}",
+            HANGAR_API_BASE,
+            urlencoding::encode(project_id),
+            platform,
+            HANGAR_MAX_LIMIT
+        );
+
+        if let Some(version) = &target.game_version {
+            url.push_str(&format!(
+                "&platformVersion={}",
+                urlencoding::encode(version)
+            ));
+        }
+
+        let page: HangarPage<HangarVersion> = self.get_json(&url).await?;
+
+        let mut versions: Vec<PluginVersion> = page
+            .result
+            .into_iter()
+            .filter_map(|v| v.into_plugin_version(project_id, platform))
+            .collect();
+
+        // Pinned versions are listed first, keep newest-first like Modrinth
+        versions.sort_by(|a, b| b.date_published.cmp(&a.date_published));
+
+        Ok(versions)
+    }
+
+    async fn get_version(
+        &self,
+        project_id: &str,
+        version_id: &str,
+        target: &PluginTarget,
+    ) -> AppResult<PluginVersion> {
+        let url = format!(
+            "{}/projects/{}/versions/{}",
+            HANGAR_API_BASE,
+            urlencoding::encode(project_id),
+            urlencoding::encode(version_id)
+        );
+        let version: HangarVersion = self.get_json(&url).await?;
+
+        version
+            .into_plugin_version(project_id, target.platform.hangar_name())
+            .ok_or_else(|| {
+                AppError::Instance(format!(
+                    "{} {} is not available for {}",
+                    project_id,
+                    version_id,
+                    target.platform.hangar_name()
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hangar_version_conversion() {
+        let json = r##"{
+            "createdAt": "2024-04-20T10:00:00.000Z",
+            "id": 42,
+            "name": "2.2.3",
+            "description": "Fixes",
+            "channel": { "name": "Release", "color": "#009600" },
+            "downloads": {
+                "PAPER": {
+                    "fileInfo": { "name": "Geyser-Spigot.jar", "sizeBytes": 1024, "sha256Hash": "ABCDEF" },
+                    "externalUrl": null,
+                    "downloadUrl": "https://hangarcdn.papermc.io/plugins/GeyserMC/Geyser/versions/2.2.3/PAPER/Geyser-Spigot.jar"
+                },
+                "VELOCITY": {
+                    "fileInfo": null,
+                    "externalUrl": "https://geysermc.org/download",
+                    "downloadUrl": null
+                }
+            },
+            "pluginDependencies": {
+                "PAPER": [
+                    { "name": "floodgate", "required": false, "externalUrl": null, "platform": "PAPER" },
+                    { "name": "ViaVersion", "required": true, "externalUrl": "https://viaversion.com", "platform": "PAPER" }
+                ]
+            },
+            "platformDependencies": { "PAPER": ["1.20.4", "1.20.5"] }
+        }"##;
+
+        let version: HangarVersion = serde_json::from_str(json).unwrap();
+        let paper = version
+            .clone()
+            .into_plugin_version("Geyser", "PAPER")
+            .unwrap();
+
+        assert_eq!(paper.id, "2.2.3");
+        assert_eq!(paper.channel, "release");
+        assert_eq!(paper.game_versions, vec!["1.20.4", "1.20.5"]);
+        let file = paper.file.unwrap();
+        assert_eq!(file.filename, "Geyser-Spigot.jar");
+        assert_eq!(file.sha256.as_deref(), Some("ABCDEF"));
+        assert_eq!(paper.dependencies.len(), 2);
+        assert_eq!(
+            paper.dependencies[0].project_id.as_deref(),
+            Some("floodgate")
+        );
+        assert!(paper.dependencies[1].project_id.is_none());
+
+        let velocity = version
+            .clone()
+            .into_plugin_version("Geyser", "VELOCITY")
+            .unwrap();
+        assert!(velocity.file.is_none());
+        assert_eq!(
+            velocity.external_url.as_deref(),
+            Some("https://geysermc.org/download")
+        );
+
+        assert!(version.into_plugin_version("Geyser", "WATERFALL").is_none());
+    }
+
+    #[test]
+    fn test_normalize_channel() {
+        assert_eq!(normalize_channel("Release"), "release");
+        assert_eq!(normalize_channel("Snapshot"), "beta");
+        assert_eq!(normalize_channel("Alpha"), "alpha");
+    }
+}
@@ -0,0 +1,323 @@
+// Server plugins (Paper family, Velocity, BungeeCord/Waterfall) from Hangar and Modrinth
+// Hangar API Documentation: https://hangar.papermc.io/api-docs
+
+pub mod commands;
+pub mod hangar;
+pub mod modrinth;
+
+use crate::content::{ContentSource, FileHashes};
+use crate::db::instances::Instance;
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Server platform a plugin is built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginPlatform {
+    Paper,
+    Velocity,
+    Waterfall,
+}
+
+impl PluginPlatform {
+    pub fn from_loader(loader: &str) -> Option<Self> {
+        match loader.to_lowercase().as_str() {
+            "paper" | "purpur" | "folia" | "pufferfish" | "spigot" | "bukkit" => Some(Self::Paper),
+            "velocity" => Some(Self::Velocity),
+            "bungeecord" | "waterfall" => Some(Self::Waterfall),
+            _ => None,
+        }
+    }
+
+    /// Platform name used by the Hangar API
+    pub fn hangar_name(&self) -> &'static str {
+        match self {
+            Self::Paper => "PAPER",
+            Self::Velocity => "VELOCITY",
+            Self::Waterfall => "WATERFALL",
+        }
+    }
+}
+
+/// Platform and game version plugins are filtered by
+#[derive(Debug, Clone)]
+pub struct PluginTarget {
+    pub platform: PluginPlatform,
+    /// Lowercased instance loader (paper, purpur, velocity, ...)
+    pub loader: String,
+    /// Minecraft version, `None` for proxies
+    pub game_version: Option<String>,
+}
+
+impl PluginTarget {
+    pub fn from_instance(instance: &Instance) -> AppResult<Self> {
+        let loader = instance
+            .loader
+            .as_deref()
+            .map(|l| l.to_lowercase())
+            .unwrap_or_default();
+
+        let platform = PluginPlatform::from_loader(&loader).ok_or_else(|| {
+            AppError::Instance("This instance does not support plugins".to_string())
+        })?;
+
+        let game_version = if instance.is_proxy
+            || instance.mc_version.is_empty()
+            || instance.mc_version == "proxy"
+        {
+            None
+        } else {
+            Some(instance.mc_version.clone())
+        };
+
+        Ok(Self {
+            platform,
+            loader,
+            game_version,
+        })
+    }
+
+    /// Modrinth loader tags of plugins that run on this server
+    pub fn modrinth_loaders(&self) -> Vec<&'static str> {
+        match self.loader.as_str() {
+            // Folia breaks plugins that don't declare support for it explicitly
+            "folia" => vec!["folia"],
+            "purpur" => vec!["purpur", "paper", "spigot", "bukkit"],
+            "spigot" | "bukkit" => vec!["spigot", "bukkit"],
+            "velocity" => vec!["velocity"],
+            "bungeecord" => vec!["bungeecord"],
+            "waterfall" => vec!["waterfall", "bungeecord"],
+            _ => vec!["paper", "spigot", "bukkit"],
+        }
+    }
+}
+
+/// A plugin returned by a search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSearchResult {
+    pub source: ContentSource,
+    /// Identifier used for the other provider calls (Modrinth id, Hangar slug)
+    pub project_id: String,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub downloads: u64,
+    pub icon_url: Option<String>,
+    pub categories: Vec<String>,
+}
+
+/// Search response with pagination info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSearchResponse {
+    pub results: Vec<PluginSearchResult>,
+    pub total_hits: u32,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// Plugin project details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginProject {
+    pub source: ContentSource,
+    pub project_id: String,
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub icon_url: Option<String>,
+}
+
+/// Downloadable file of a plugin version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginFile {
+    pub url: String,
+    pub filename: String,
+    pub size: u64,
+    pub sha1: Option<String>,
+    pub sha256: Option<String>,
+    pub sha512: Option<String>,
+}
+
+impl PluginFile {
+    pub fn hashes(&self) -> FileHashes<'_> {
+        FileHashes {
+            sha512: self.sha512.as_deref(),
+            sha256: self.sha256.as_deref(),
+            sha1: self.sha1.as_deref(),
+        }
+    }
+}
+
+/// Dependency of a plugin version on another plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginDependency {
+    pub project_id: Option<String>,
+    pub version_id: Option<String>,
+    /// Display name (Hangar plugin name, or the project id on Modrinth)
+    pub name: String,
+    pub required: bool,
+    /// Download page for dependencies hosted outside the provider
+    pub external_url: Option<String>,
+}
+
+/// A version of a plugin for one platform
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginVersion {
+    pub source: ContentSource,
+    pub project_id: String,
+    /// Version identifier (Modrinth version id, Hangar version name)
+    pub id: String,
+    pub version_number: String,
+    /// "release", "beta" or "alpha"
+    pub channel: String,
+    pub game_versions: Vec<String>,
+    pub date_published: String,
+    pub changelog: Option<String>,
+    /// `None` when the version is only available from an external site
+    pub file: Option<PluginFile>,
+    /// Download page for versions without a hosted file
+    pub external_url: Option<String>,
+    pub dependencies: Vec<PluginDependency>,
+}
+
+/// A source of server plugins
+#[async_trait::async_trait]
+pub trait PluginProvider: Send + Sync {
+    fn source(&self) -> ContentSource;
+
+    /// Search plugins compatible with the target platform
+    async fn search(
+        &self,
+        query: &str,
+        target: &PluginTarget,
+        sort_by: Option<&str>,
+        offset: u32,
+        limit: u32,
+    ) -> AppResult<PluginSearchResponse>;
+
+    /// Get project details by id or slug
+    async fn get_project(&self, project_id: &str) -> AppResult<PluginProject>;
+
+    /// List versions compatible with the target, newest first
+    async fn get_versions(
+        &self,
+        project_id: &str,
+        target: &PluginTarget,
+    ) -> AppResult<Vec<PluginVersion>>;
+
+    /// Get a single version
+    async fn get_version(
+        &self,
+        project_id: &str,
+        version_id: &str,
+        target: &PluginTarget,
+    ) -> AppResult<PluginVersion>;
+}
+
+/// Create the provider for a plugin source
+pub fn provider_for(
+    source: ContentSource,
+    http_client: &reqwest::Client,
+) -> AppResult<Box<dyn PluginProvider + '_>> {
+    match source {
+        ContentSource::Modrinth => Ok(Box::new(modrinth::ModrinthPlugins::new(http_client))),
+        ContentSource::Hangar => Ok(Box::new(hangar::HangarClient::new(http_client))),
+        ContentSource::CurseForge => Err(AppError::Network(
+            "Plugins cannot be downloaded from CurseForge".to_string(),
+        )),
+    }
+}
+
+/// Pick the version to install: the newest release, or the newest version
+/// when the project has no release for this target
+pub fn select_latest_version(versions: &[PluginVersion]) -> Option<&PluginVersion> {
+    let newest = |channel_filter: Option<&str>| {
+        versions
+            .iter()
+            .filter(|v| v.file.is_some())
+            .filter(|v| channel_filter.is_none_or(|c| v.channel == c))
+            .max_by(|a, b| a.date_published.cmp(&b.date_published))
+    };
+
+    newest(Some("release")).or_else(|| newest(None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(id: &str, channel: &str, date: &str) -> PluginVersion {
+        PluginVersion {
+            source: ContentSource::Hangar,
+            project_id: "Test".to_string(),
+            id: id.to_string(),
+            version_number: id.to_string(),
+            channel: channel.to_string(),
+            game_versions: vec![],
+            date_published: date.to_string(),
+            changelog: None,
+            file: Some(PluginFile {
+                url: "https://hangarcdn.papermc.io/test.jar".to_string(),
+                filename: "test.jar".to_string(),
+                size: 1,
+                sha1: None,
+                sha256: Some("abc".to_string()),
+                sha512: None,
+            }),
+            external_url: None,
+            dependencies: vec![],
+        }
+    }
+
+    #[test]
+    fn test_platform_from_loader() {
+        assert_eq!(
+            PluginPlatform::from_loader("Purpur"),
+            Some(PluginPlatform::Paper)
+        );
+        assert_eq!(
+            PluginPlatform::from_loader("waterfall"),
+            Some(PluginPlatform::Waterfall)
+        );
+        assert_eq!(PluginPlatform::from_loader("fabric"), None);
+    }
+
+    #[test]
+    fn test_modrinth_loaders() {
+        let target = PluginTarget {
+            platform: PluginPlatform::Paper,
+            loader: "folia".to_string(),
+            game_version: Some("1.20.4".to_string()),
+        };
+        assert_eq!(target.modrinth_loaders(), vec!["folia"]);
+
+        let target = PluginTarget {
+            platform: PluginPlatform::Waterfall,
+            loader: "waterfall".to_string(),
+            game_version: None,
+        };
+        assert_eq!(target.modrinth_loaders(), vec!["waterfall", "bungeecord"]);
+    }
+
+    #[test]
+    fn test_select_latest_prefers_release() {
+        let versions = vec![
+            version("2.0.0-beta", "beta", "2024-03-01T00:00:00Z"),
+            version("1.9.0", "release", "2024-02-01T00:00:00Z"),
+            version("1.8.0", "release", "2024-01-01T00:00:00Z"),
+        ];
+        assert_eq!(select_latest_version(&versions).unwrap().id, "1.9.0");
+    }
+
+    #[test]
+    fn test_select_latest_falls_back_to_prerelease() {
+        let mut external = version("3.0.0", "release", "2024-04-01T00:00:00Z");
+        external.file = None;
+        let versions = vec![
+            external,
+            version("2.0.0-beta", "beta", "2024-03-01T00:00:00Z"),
+        ];
+        assert_eq!(select_latest_version(&versions).unwrap().id, "2.0.0-beta");
+    }
+}
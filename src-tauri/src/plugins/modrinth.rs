@@ -0,0 +1,177 @@
+// Modrinth "plugin" projects exposed through the plugin provider interface
+
+use super::{
+    PluginDependency, PluginFile, PluginProject, PluginProvider, PluginSearchResponse,
+    PluginSearchResult, PluginTarget, PluginVersion,
+};
+use crate::content::ContentSource;
+use crate::error::{AppError, AppResult};
+use crate::modrinth::{build_facets, ModrinthClient, SearchQuery, Version};
+
+/// Plugin provider backed by the Modrinth API
+pub struct ModrinthPlugins<'a> {
+    client: ModrinthClient<'a>,
+}
+
+impl<'a> ModrinthPlugins<'a> {
+    pub fn new(http_client: &'a reqwest::Client) -> Self {
+        Self {
+            client: ModrinthClient::new(http_client),
+        }
+    }
+}
+
+impl From<Version> for PluginVersion {
+    fn from(v: Version) -> Self {
+        let file = v
+            .files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| v.files.first())
+            .map(|f| PluginFile {
+                url: f.url.clone(),
+                filename: f.filename.clone(),
+                size: f.size,
+                sha1: Some(f.hashes.sha1.clone()),
+                sha256: None,
+                sha512: Some(f.hashes.sha512.clone()),
+            });
+
+        let dependencies = v
+            .dependencies
+            .into_iter()
+            .filter(|d| d.dependency_type == "required" || d.dependency_type == "optional")
+            .filter(|d| d.project_id.is_some() || d.version_id.is_some())
+            .map(|d| PluginDependency {
+                name: d
+                    .project_id
+                    .clone()
+                    .or_else(|| d.version_id.clone())
+                    .unwrap_or_default(),
+                project_id: d.project_id,
+                version_id: d.version_id,
+                required: d.dependency_type == "required",
+                external_url: None,
+            })
+            .collect();
+
+        Self {
+            source: ContentSource::Modrinth,
+            project_id: v.project_id,
+            id: v.id,
+            version_number: v.version_number,
+            channel: v.version_type,
+            game_versions: v.game_versions,
+            date_published: v.date_published,
+            changelog: v.changelog,
+            file,
+            external_url: None,
+            dependencies,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PluginProvider for ModrinthPlugins<'_> {
+    fn source(&self) -> ContentSource {
+        ContentSource::Modrinth
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        target: &PluginTarget,
+        sort_by: Option<&str>,
+        offset: u32,
+        limit: u32,
+    ) -> AppResult<PluginSearchResponse> {
+        let game_versions = target.game_version.as_deref().map(|v| vec![v]);
+        let loaders = target.modrinth_loaders();
+        let facets = build_facets(
+            Some("plugin"),
+            None,
+            game_versions.as_deref(),
+            Some(&loaders),
+        );
+
+        let search_query = SearchQuery::new(query)
+            .with_facets(&facets)
+            .with_index(sort_by.unwrap_or("relevance"))
+            .with_offset(offset)
+            .with_limit(limit);
+
+        let response = self
+            .client
+            .search(&search_query)
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(PluginSearchResponse {
+            results: response
+                .hits
+                .into_iter()
+                .map(|hit| PluginSearchResult {
+                    source: ContentSource::Modrinth,
+                    project_id: hit.project_id,
+                    slug: hit.slug,
+                    name: hit.title,
+                    description: hit.description,
+                    author: hit.author,
+                    downloads: hit.downloads,
+                    icon_url: hit.icon_url,
+                    categories: hit.categories,
+                })
+                .collect(),
+            total_hits: response.total_hits,
+            offset: response.offset,
+            limit: response.limit,
+        })
+    }
+
+    async fn get_project(&self, project_id: &str) -> AppResult<PluginProject> {
+        let project = self
+            .client
+            .get_project(project_id)
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(PluginProject {
+            source: ContentSource::Modrinth,
+            project_id: project.id,
+            slug: project.slug,
+            name: project.title,
+            description: project.description,
+            icon_url: project.icon_url,
+        })
+    }
+
+    async fn get_versions(
+        &self,
+        project_id: &str,
+        target: &PluginTarget,
+    ) -> AppResult<Vec<PluginVersion>> {
+        let game_versions = target.game_version.as_deref().map(|v| vec![v]);
+        let loaders = target.modrinth_loaders();
+
+        let versions = self
+            .client
+            .get_project_versions(project_id, Some(&loaders), game_versions.as_deref())
+            .await
+            .map_err(|e| AppError::Network(e.to_string()))?;
+
+        Ok(versions.into_iter().map(PluginVersion::from).collect())
+    }
+
+    async fn get_version(
+        &self,
+        _project_id: &str,
+        version_id: &str,
+        _target: &PluginTarget,
+    ) -> AppResult<PluginVersion> {
+        self.client
+            .get_version(version_id)
+            .await
+            .map(PluginVersion::from)
+            .map_err(|e| AppError::Network(e.to_string()))
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::db::instances::Instance;
 use crate::error::AppResult;
+use crate::instance::malware_scan;
 use crate::sharing::manifest::{ExportOptions, ExportableContent, PreparedExport, SharingManifest};
 use crate::sharing::server::{self, ActiveShare, RunningShares, SharingProvider};
 use crate::sharing::{export, import};
@@ -69,7 +70,10 @@ pub async fn import_instance(
     let instances_dir = state.get_instances_dir().await;
     let path = PathBuf::from(&package_path);
 
-    import::import_instance(&app, &state.db, &instances_dir, &path, new_name).await
+    let instance =
+        import::import_instance(&app, &state.db, &instances_dir, &path, new_name).await?;
+    malware_scan::scan_imported_instance(&state, &instance).await;
+    Ok(instance)
 }
 
 /// Get the sharing temp directory path
@@ -246,6 +250,8 @@ pub async fn download_and_import_share(
     // Cleanup temp file
     let _ = tokio::fs::remove_file(&temp_file).await;
 
+    malware_scan::scan_imported_instance(&state_guard, &instance).await;
+
     Ok(instance)
 }
 
@@ -502,6 +502,132 @@ impl AppState {
             .execute(db)
             .await;
 
+        // Migration: Proxy networks (proxy instance + backend server instances)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS server_networks (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                proxy_instance_id TEXT NOT NULL,
+                forwarding_secret TEXT NOT NULL,
+                created_at TEXT DEFAULT (datetime('now')),
+                FOREIGN KEY (proxy_instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS server_network_members (
+                network_id TEXT NOT NULL,
+                instance_id TEXT NOT NULL,
+                server_name TEXT NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                in_try INTEGER DEFAULT 1,
+                PRIMARY KEY (network_id, instance_id),
+                FOREIGN KEY (network_id) REFERENCES server_networks(id) ON DELETE CASCADE,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_network_members_instance ON server_network_members(instance_id);
+        "#,
+        )
+        .execute(db)
+        .await?;
+
+        // Migration: Minecraft EULA acceptance per server instance
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS server_eula_acceptances (
+                instance_id TEXT PRIMARY KEY,
+                accepted_at TEXT NOT NULL,
+                eula_url TEXT NOT NULL,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+        "#,
+        )
+        .execute(db)
+        .await?;
+
+        // Migration: Per-instance server console command history
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS server_command_history (
+                instance_id TEXT NOT NULL,
+                command TEXT NOT NULL,
+                use_count INTEGER NOT NULL DEFAULT 1,
+                last_used_at TEXT NOT NULL,
+                PRIMARY KEY (instance_id, command),
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_server_command_history_recent
+                ON server_command_history(instance_id, last_used_at);
+        "#,
+        )
+        .execute(db)
+        .await?;
+
+        // Migration: Malware scan results per instance jar
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS jar_scan_results (
+                instance_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                sha1 TEXT NOT NULL,
+                verdict TEXT NOT NULL,
+                findings TEXT NOT NULL DEFAULT '[]',
+                quarantined INTEGER NOT NULL DEFAULT 0,
+                scanned_at TEXT NOT NULL,
+                PRIMARY KEY (instance_id, path),
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+        "#,
+        )
+        .execute(db)
+        .await?;
+
+        // Migration: Instance files deduplicated into the shared pool
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS shared_files (
+                instance_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                sha1 TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                method TEXT NOT NULL,
+                modified_ns INTEGER NOT NULL,
+                PRIMARY KEY (instance_id, path),
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+        "#,
+        )
+        .execute(db)
+        .await?;
+
+        // Migration: New releases of the Modrinth projects installed in instances
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS release_feed (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                instance_id TEXT NOT NULL,
+                project_id TEXT NOT NULL,
+                project_name TEXT NOT NULL,
+                icon_url TEXT,
+                path TEXT NOT NULL,
+                installed_version_id TEXT NOT NULL,
+                installed_version_number TEXT NOT NULL,
+                version_id TEXT NOT NULL,
+                version_number TEXT NOT NULL,
+                version_type TEXT NOT NULL,
+                changelog TEXT,
+                date_published TEXT NOT NULL,
+                discovered_at TEXT NOT NULL,
+                read INTEGER NOT NULL DEFAULT 0,
+                UNIQUE (instance_id, version_id),
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_release_feed_published ON release_feed(date_published DESC);
+        "#,
+        )
+        .execute(db)
+        .await?;
+
         Ok(())
     }
 }
@@ -14,6 +14,7 @@ import { UpdateNotification } from "@/components/notifications/UpdateNotificatio
 import { DevMonitor } from "@/components/dev/DevMonitor"
 import { BugReportDialog } from "@/components/dev/BugReportDialog"
 import { MajorUpdateDialog } from "@/components/dialogs/MajorUpdateDialog"
+import { ServerEulaDialog } from "@/components/dialogs/ServerEulaDialog"
 import { useOnboardingStore } from "@/stores/onboardingStore"
 import { useSystemCheckStore } from "@/stores/systemCheckStore"
 import { useDevModeStore } from "@/stores/devModeStore"
@@ -312,6 +313,7 @@ function App() {
           </Route>
         </Routes>
         <MajorUpdateDialog />
+        <ServerEulaDialog />
       </BrowserRouter>
       <Toaster
         position="bottom-right"
@@ -45,6 +45,7 @@ import {
 import { Checkbox } from "@/components/ui/checkbox"
 import { useTranslation } from "@/i18n"
 import { BrowseInstance, isDatapackCompatible } from "@/pages/Browse"
+import type { InstalledContent } from "@/types/browse"
 
 // Types
 interface DatapackSearchResult {
@@ -359,7 +360,7 @@ export function DatapackBrowser({ selectedInstance }: DatapackBrowserProps) {
     setIsInstalling(true)
     try {
       console.log("[DatapackBrowser] Installing datapack:", selectedDatapack.project_id, "version:", selectedVersion)
-      const filename = await invoke<string>("install_modrinth_mod", {
+      const { filename } = await invoke<InstalledContent>("install_modrinth_mod", {
         instanceId: selectedInstance.id,
         projectId: selectedDatapack.project_id,
         versionId: selectedVersion,
@@ -45,7 +45,7 @@ import {
 import { Checkbox } from "@/components/ui/checkbox"
 import { useTranslation } from "@/i18n"
 import { useBrowseCache } from "@/hooks/useBrowseCache"
-import type { ModSearchResult, ModVersionInfo, ViewMode } from "@/types/browse"
+import type { InstalledContent, ModSearchResult, ModVersionInfo, ViewMode } from "@/types/browse"
 import { SORT_OPTIONS, ITEMS_PER_PAGE } from "@/types/browse"
 import { formatDownloads, getStoredViewMode, setStoredViewMode, getPageNumbers } from "@/lib/browse-utils"
 
@@ -287,7 +287,7 @@ export function ModBrowser({ selectedInstance }: ModBrowserProps) {
     setIsInstalling(true)
     try {
       console.log("[ModBrowser] Installing mod:", selectedMod.project_id, "version:", selectedVersion)
-      const filename = await invoke<string>("install_modrinth_mod", {
+      const { filename } = await invoke<InstalledContent>("install_modrinth_mod", {
         instanceId: selectedInstance.id,
         projectId: selectedMod.project_id,
         versionId: selectedVersion,
@@ -30,6 +30,8 @@ import {
   PopoverTrigger,
 } from "@/components/ui/popover"
 import { useTranslation, type TranslationKey } from "@/i18n"
+import { notifyShaderLoader } from "@/lib/browse-utils"
+import type { InstalledContent } from "@/types/browse"
 
 interface ModSearchResult {
   project_id: string
@@ -652,7 +654,7 @@ export function ModrinthBrowser({ instanceId, mcVersion, loader, isServer: _isSe
 
     try {
       // First install the main mod
-      await invoke<string>("install_modrinth_mod", {
+      const installed = await invoke<InstalledContent>("install_modrinth_mod", {
         instanceId,
         projectId: mod.project_id,
         versionId: versionId,
@@ -681,7 +683,7 @@ export function ModrinthBrowser({ instanceId, mcVersion, loader, isServer: _isSe
             }
 
             if (depVersionId) {
-              await invoke<string>("install_modrinth_mod", {
+              await invoke<InstalledContent>("install_modrinth_mod", {
                 instanceId,
                 projectId: dep.project_id,
                 versionId: depVersionId,
@@ -695,6 +697,9 @@ export function ModrinthBrowser({ instanceId, mcVersion, loader, isServer: _isSe
       }
 
       toast.success(t("modrinth.installedWithDeps", { title: mod.title, count: String(depsToInstall.length) }), { id: toastId })
+      if (projectType === "shader") {
+        notifyShaderLoader(instanceId, installed.shader_loader, t)
+      }
       setSelectedMod(null)
       setShowDependencies(false)
       setPendingInstall(null)
@@ -45,6 +45,7 @@ import { Checkbox } from "@/components/ui/checkbox"
 import { useTranslation } from "@/i18n"
 import { type BrowseInstance, isPluginCompatible } from "@/pages/Browse"
 import { useBrowseCache } from "@/hooks/useBrowseCache"
+import type { InstalledContent } from "@/types/browse"
 
 // Types
 interface ModSearchResult {
@@ -360,7 +361,7 @@ export function PluginBrowser({ selectedInstance }: PluginBrowserProps) {
     setIsInstalling(true)
     try {
       console.log("[PluginBrowser] Installing plugin:", selectedPlugin.project_id, "version:", selectedVersion)
-      const filename = await invoke<string>("install_modrinth_mod", {
+      const { filename } = await invoke<InstalledContent>("install_modrinth_mod", {
         instanceId: selectedInstance.id,
         projectId: selectedPlugin.project_id,
         versionId: selectedVersion,
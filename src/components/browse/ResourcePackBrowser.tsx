@@ -45,6 +45,7 @@ import {
 import { Checkbox } from "@/components/ui/checkbox"
 import { useTranslation } from "@/i18n"
 import { BrowseInstance, isResourcePackCompatible } from "@/pages/Browse"
+import type { InstalledContent } from "@/types/browse"
 
 // Types
 interface ModSearchResult {
@@ -356,7 +357,7 @@ export function ResourcePackBrowser({ selectedInstance }: ResourcePackBrowserPro
     setIsInstalling(true)
     try {
       console.log("[ResourcePackBrowser] Installing resource pack:", selectedMod.project_id, "version:", selectedVersion)
-      const filename = await invoke<string>("install_modrinth_mod", {
+      const { filename } = await invoke<InstalledContent>("install_modrinth_mod", {
         instanceId: selectedInstance.id,
         projectId: selectedMod.project_id,
         versionId: selectedVersion,
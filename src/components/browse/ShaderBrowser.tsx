@@ -45,6 +45,8 @@ import {
 import { Checkbox } from "@/components/ui/checkbox"
 import { useTranslation } from "@/i18n"
 import { BrowseInstance, isShaderCompatible } from "@/pages/Browse"
+import { notifyShaderLoader } from "@/lib/browse-utils"
+import type { InstalledContent } from "@/types/browse"
 
 // Types
 interface ModSearchResult {
@@ -355,7 +357,7 @@ export function ShaderBrowser({ selectedInstance }: ShaderBrowserProps) {
     setIsInstalling(true)
     try {
       console.log("[ShaderBrowser] Installing shader:", selectedMod.project_id, "version:", selectedVersion)
-      const filename = await invoke<string>("install_modrinth_mod", {
+      const installed = await invoke<InstalledContent>("install_modrinth_mod", {
         instanceId: selectedInstance.id,
         projectId: selectedMod.project_id,
         versionId: selectedVersion,
@@ -363,10 +365,11 @@ export function ShaderBrowser({ selectedInstance }: ShaderBrowserProps) {
       })
 
       toast.success(t("modrinth.modInstalled", { name: selectedMod.title }))
+      notifyShaderLoader(selectedInstance.id, installed.shader_loader, t)
       setInstalledModIds((prev) => new Set([...prev, selectedMod.project_id]))
       invalidateInstalledCache(selectedInstance.id, "shader")
       setInstallDialogOpen(false)
-      console.log("[ShaderBrowser] Installed:", filename)
+      console.log("[ShaderBrowser] Installed:", installed.filename)
     } catch (error) {
       console.error("[ShaderBrowser] Install failed:", error)
       toast.error(t("browse.installError"))
@@ -114,6 +114,7 @@ export function CreateInstanceDialog({
   const [mode, setMode] = useState<"client" | "server" | "proxy">("client")
   const [serverPort, setServerPort] = useState(25565)
   const [usedPorts, setUsedPorts] = useState<{ port: number; instance_name: string }[]>([])
+  const [eulaAccepted, setEulaAccepted] = useState(false)
 
   // Get current loaders based on mode
   const currentLoaders = mode === "client"
@@ -241,6 +242,7 @@ export function CreateInstanceDialog({
         isServer: mode === "server" || mode === "proxy",
         isProxy: mode === "proxy",
         serverPort: mode !== "client" ? serverPort : null,
+        eulaAccepted: mode === "server" ? eulaAccepted : null,
       })
       console.log(`[CreateInstance] Instance created successfully: ${name.trim()}`)
 
@@ -251,6 +253,7 @@ export function CreateInstanceDialog({
       setLoaderVersion("")
       setMode("client")
       setServerPort(25565)
+      setEulaAccepted(false)
       onOpenChange(false)
       onSuccess?.()
     } catch (err) {
@@ -452,6 +455,33 @@ export function CreateInstanceDialog({
             </div>
           )}
 
+          {/* Minecraft EULA (only for game servers) */}
+          {mode === "server" && (
+            <div className="grid gap-1">
+              <div className="flex items-center gap-2">
+                <Switch
+                  id="eula"
+                  checked={eulaAccepted}
+                  onCheckedChange={setEulaAccepted}
+                />
+                <Label htmlFor="eula" className="text-sm cursor-pointer">
+                  {t("createInstance.acceptEula")}
+                </Label>
+              </div>
+              <p className="text-xs text-muted-foreground">
+                {t("createInstance.eulaHint")}{" "}
+                <a
+                  href="https://aka.ms/MinecraftEULA"
+                  target="_blank"
+                  rel="noreferrer"
+                  className="underline"
+                >
+                  https://aka.ms/MinecraftEULA
+                </a>
+              </p>
+            </div>
+          )}
+
           {error && (
             <p className="text-sm text-destructive">{error}</p>
           )}
@@ -0,0 +1,76 @@
+import { useState } from "react"
+import { invoke } from "@tauri-apps/api/core"
+import { toast } from "sonner"
+import { ExternalLink, Loader2, ScrollText } from "lucide-react"
+import { useTranslation } from "@/i18n"
+import { Button } from "@/components/ui/button"
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog"
+import { useServerEulaStore } from "@/stores/serverEulaStore"
+
+const MINECRAFT_EULA_URL = "https://aka.ms/MinecraftEULA"
+
+/** Asks for the Minecraft EULA when a server could not start without it */
+export function ServerEulaDialog() {
+  const { t } = useTranslation()
+  const { request, close } = useServerEulaStore()
+  const [isAccepting, setIsAccepting] = useState(false)
+
+  const handleAccept = async () => {
+    if (!request) return
+    setIsAccepting(true)
+    try {
+      await invoke("accept_server_eula", { instanceId: request.instanceId })
+      toast.success(t("server.eulaAccepted"))
+      close()
+      request.onAccepted?.()
+    } catch (err) {
+      console.error("[ServerEula] Failed to accept EULA:", err)
+      toast.error(`${t("common.error")}: ${err}`)
+    } finally {
+      setIsAccepting(false)
+    }
+  }
+
+  return (
+    <Dialog open={request !== null} onOpenChange={(open) => !open && close()}>
+      <DialogContent className="sm:max-w-[480px]">
+        <DialogHeader>
+          <DialogTitle className="flex items-center gap-2">
+            <ScrollText className="h-5 w-5" />
+            {t("server.eulaTitle")}
+          </DialogTitle>
+          <DialogDescription>
+            {t("server.eulaDescription", { name: request?.instanceName ?? "" })}
+          </DialogDescription>
+        </DialogHeader>
+
+        <a
+          href={MINECRAFT_EULA_URL}
+          target="_blank"
+          rel="noreferrer"
+          className="flex items-center gap-2 text-sm underline"
+        >
+          <ExternalLink className="h-4 w-4" />
+          {t("server.eulaRead")}: {MINECRAFT_EULA_URL}
+        </a>
+
+        <DialogFooter>
+          <Button variant="outline" onClick={close} disabled={isAccepting}>
+            {t("common.cancel")}
+          </Button>
+          <Button onClick={handleAccept} disabled={isAccepting}>
+            {isAccepting && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+            {t("server.eulaAccept")}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  )
+}
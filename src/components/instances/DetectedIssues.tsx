@@ -49,6 +49,7 @@ type IssueType =
   | "version_mismatch"
   | "recommended_version_mismatch"
   | "mod_conflict"
+  | "discouraged_mod"
   | "minecraft_version_mismatch"
   | "loader_version_mismatch"
   | "duplicate_mod"
@@ -77,6 +78,7 @@ const ISSUE_TYPE_ICONS: Record<IssueType, React.ReactNode> = {
   version_mismatch: <ArrowUp className="h-4 w-4" />,
   recommended_version_mismatch: <ArrowUp className="h-4 w-4" />,
   mod_conflict: <XCircle className="h-4 w-4" />,
+  discouraged_mod: <AlertTriangle className="h-4 w-4" />,
   minecraft_version_mismatch: <AlertCircle className="h-4 w-4" />,
   loader_version_mismatch: <AlertCircle className="h-4 w-4" />,
   duplicate_mod: <AlertTriangle className="h-4 w-4" />,
@@ -89,6 +91,7 @@ const ISSUE_TYPE_COLORS: Record<IssueType, string> = {
   version_mismatch: "text-yellow-500",
   recommended_version_mismatch: "text-blue-500",
   mod_conflict: "text-red-500",
+  discouraged_mod: "text-yellow-500",
   minecraft_version_mismatch: "text-red-500",
   loader_version_mismatch: "text-red-500",
   duplicate_mod: "text-orange-500",
@@ -123,7 +126,18 @@ export function DetectedIssues({ instanceId, mcVersion, loader, onModInstalled,
   const analyzeIssues = useCallback(async () => {
     setIsLoading(true)
     try {
-      const result = await invoke<DetectedIssue[]>("analyze_instance_logs", { instanceId })
+      // Issues found in the last logs plus the ones declared by the installed jars
+      const [logIssues, modIssues] = await Promise.all([
+        invoke<DetectedIssue[]>("analyze_instance_logs", { instanceId }),
+        invoke<DetectedIssue[]>("analyze_instance_mods", { instanceId }),
+      ])
+      const seen = new Set<string>()
+      const result = [...modIssues, ...logIssues].filter((issue) => {
+        const key = `${issue.issue_type}:${issue.mod_id}:${issue.required_mod_id}`
+        if (seen.has(key)) return false
+        seen.add(key)
+        return true
+      })
       setIssues(result)
       setHasAnalyzed(true)
     } catch (err) {
@@ -311,14 +325,15 @@ export function DetectedIssues({ instanceId, mcVersion, loader, onModInstalled,
 
     try {
       // Get the list of mods for this instance to find the mod to delete
-      const mods = await invoke<Array<{ name: string; filename: string; project_id: string | null }>>(
+      const mods = await invoke<Array<{ name: string; filename: string; project_id: string | null; mod_id: string | null }>>(
         "get_instance_mods",
         { instanceId }
       )
 
-      // Find the mod by project_id (Modrinth project ID) or by name
+      // Find the mod by project_id (Modrinth project ID), jar mod id or by name
       let modToDelete = mods.find(
         m => m.project_id === issue.mod_id ||
+             (m.mod_id !== null && m.mod_id === issue.mod_id) ||
              m.name.toLowerCase() === issue.mod_name?.toLowerCase()
       )
 
@@ -364,6 +379,8 @@ export function DetectedIssues({ instanceId, mcVersion, loader, onModInstalled,
         return t("detectedIssues.types.recommendedVersionMismatch")
       case "mod_conflict":
         return t("detectedIssues.types.modConflict")
+      case "discouraged_mod":
+        return t("detectedIssues.types.discouragedMod")
       case "minecraft_version_mismatch":
         return t("detectedIssues.types.minecraftVersionMismatch")
       case "loader_version_mismatch":
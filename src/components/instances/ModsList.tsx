@@ -57,6 +57,12 @@ import { useTranslation } from "@/i18n"
 import { cn } from "@/lib/utils"
 
 // Types
+interface JarDependency {
+  id: string
+  version_range: string | null
+  kind: "required" | "optional" | "incompatible" | "discouraged"
+}
+
 interface ModInfo {
   name: string
   version: string
@@ -64,9 +70,17 @@ interface ModInfo {
   enabled: boolean
   icon_url: string | null
   project_id: string | null
+  mod_id: string | null
+  authors: string[]
+  description: string | null
+  dependencies: JarDependency[]
+  side: "client" | "server" | "both" | null
+  metadata_format: string | null
+  sha1: string | null
 }
 
 interface ModUpdateInfo {
+  source: "modrinth" | "curseforge"
   project_id: string
   filename: string
   current_version: string
@@ -386,6 +400,7 @@ export function ModsList({ instanceId, contentType, onOpenFolder, onModsChange }
           currentFilename: update.filename,
           newVersionId: update.latest_version_id,
           projectType,
+          source: update.source,
         })
         toast.success(t("instanceDetails.modUpdated", { name: update.name }))
         setModUpdates((prev) => prev.filter((u) => u.project_id !== update.project_id))
@@ -848,7 +863,10 @@ export function ModsList({ instanceId, contentType, onOpenFolder, onModsChange }
                           )}
                         </div>
                         <div className="flex items-center gap-2">
-                          <p className="text-xs text-muted-foreground truncate">{mod.version}</p>
+                          <p className="text-xs text-muted-foreground truncate" title={mod.description ?? undefined}>
+                            {mod.version}
+                            {mod.authors.length > 0 && ` · ${mod.authors.join(", ")}`}
+                          </p>
                           {update && (
                             <Badge variant="outline" className="text-xs text-green-500 border-green-500/50">
                               → {update.latest_version}
@@ -36,6 +36,7 @@ import {
 import { Separator } from "@/components/ui/separator";
 import { cn } from "@/lib/utils";
 import { useTranslation } from "@/i18n";
+import { useServerEulaStore } from "@/stores/serverEulaStore";
 import {
   usePlaygroundStore,
   usePlaygroundSettingsStore,
@@ -157,6 +158,14 @@ export function PlaygroundToolbar() {
       }
     } catch (err) {
       console.error("[PlaygroundToolbar] Launch error:", err);
+      if (
+        useServerEulaStore.getState().promptIfRequired(err, {
+          instanceId: instance.id,
+          instanceName: instance.name,
+        })
+      ) {
+        return;
+      }
       toast.error(String(err));
     }
   }, [instance, isInstalled, isRunning, setRunningStatus, t]);
@@ -2,7 +2,10 @@
  * Shared utilities for Modrinth browse components
  */
 
-import type { ViewMode } from "@/types/browse"
+import { invoke } from "@tauri-apps/api/core"
+import { toast } from "sonner"
+import type { TranslationKey } from "@/i18n"
+import type { ShaderLoaderStatus, ViewMode } from "@/types/browse"
 
 // ============================================================================
 // Formatters
@@ -100,3 +103,36 @@ export function getPageNumbers(
 
   return pages
 }
+
+// ============================================================================
+// Shader Loader
+// ============================================================================
+
+/**
+ * Warn after a shader pack install when the instance cannot load it,
+ * offering to install the missing shader loader
+ */
+export function notifyShaderLoader(
+  instanceId: string,
+  status: ShaderLoaderStatus | undefined,
+  t: (key: TranslationKey, params?: Record<string, string | number>) => string
+): void {
+  if (status?.state === "missing" && status.offer) {
+    const name = status.offer.shader_loader.title
+    toast.warning(t("browse.shaderLoaderMissing", { name }), {
+      duration: 15000,
+      action: {
+        label: t("browse.installShaderLoader", { name }),
+        onClick: () => {
+          invoke<string[]>("install_shader_loader", { instanceId })
+            .then(() => toast.success(t("browse.shaderLoaderInstalled", { name })))
+            .catch((err) => toast.error(`${t("common.error")}: ${err}`))
+        },
+      },
+    })
+  } else if (status?.state === "missing") {
+    toast.warning(t("browse.shaderLoaderUnavailable"))
+  } else if (status?.state === "disabled" && status.installed) {
+    toast.warning(t("browse.shaderLoaderDisabled", { name: status.installed.name }))
+  }
+}
@@ -72,6 +72,7 @@ interface CreateServerFromClientOptions {
   mods_to_include: string[]
   copy_configs: boolean
   server_port: number
+  eula_accepted: boolean
 }
 
 interface Instance {
@@ -158,6 +159,7 @@ export function CreateServerFromClient() {
   const [serverName, setServerName] = useState("")
   const [serverPort, setServerPort] = useState(25565)
   const [copyConfigs, setCopyConfigs] = useState(true)
+  const [eulaAccepted, setEulaAccepted] = useState(false)
   const [selectedUnknownMods, setSelectedUnknownMods] = useState<Set<string>>(new Set())
   const [excludedOptionalMods, setExcludedOptionalMods] = useState<Set<string>>(new Set())
 
@@ -421,7 +423,8 @@ export function CreateServerFromClient() {
         server_name: serverName.trim(),
         mods_to_include: modsToInclude,
         copy_configs: copyConfigs,
-        server_port: serverPort
+        server_port: serverPort,
+        eula_accepted: eulaAccepted
       }
 
       const newInstance = await invoke<Instance>("create_server_from_client", { options })
@@ -456,7 +459,7 @@ export function CreateServerFromClient() {
       toast.error(String(error))
       setProcessingStep("ready")
     }
-  }, [analysis, instance, serverName, requiredMods, activeOptionalMods, selectedUnknownMods, copyConfigs, serverPort, t, navigate])
+  }, [analysis, instance, serverName, requiredMods, activeOptionalMods, selectedUnknownMods, copyConfigs, serverPort, eulaAccepted, t, navigate])
 
   if (isLoading) {
     return (
@@ -577,6 +580,26 @@ export function CreateServerFromClient() {
                     <Switch checked={copyConfigs} onCheckedChange={setCopyConfigs} />
                   </div>
 
+                  <div className="space-y-1">
+                    <div className="flex items-center gap-2">
+                      <Switch id="eula" checked={eulaAccepted} onCheckedChange={setEulaAccepted} />
+                      <Label htmlFor="eula" className="text-sm cursor-pointer">
+                        {t("createInstance.acceptEula")}
+                      </Label>
+                    </div>
+                    <p className="text-xs text-muted-foreground">
+                      {t("createInstance.eulaHint")}{" "}
+                      <a
+                        href="https://aka.ms/MinecraftEULA"
+                        target="_blank"
+                        rel="noreferrer"
+                        className="underline"
+                      >
+                        https://aka.ms/MinecraftEULA
+                      </a>
+                    </p>
+                  </div>
+
                   <div className="pt-2 border-t">
                     <p className="text-sm text-muted-foreground mb-2">{t("serverFromClient.sourceInfo")}</p>
                     <div className="flex items-center gap-2 text-sm">
@@ -40,6 +40,7 @@ import {
   DropdownMenuTrigger,
 } from "@/components/ui/dropdown-menu"
 import { useEasyModeStore } from "@/stores/easyModeStore"
+import { useServerEulaStore } from "@/stores/serverEulaStore"
 
 // Lazy load QuickPlay for Easy Mode
 const QuickPlay = lazy(() => import("@/components/home/QuickPlay").then(m => ({ default: m.QuickPlay })))
@@ -324,6 +325,13 @@ export function Home() {
       console.log(`[Home] Launch command sent for: ${selectedInstance.name}`)
     } catch (err) {
       console.error("Failed to launch:", err)
+      if (useServerEulaStore.getState().promptIfRequired(err, {
+        instanceId: selectedInstance.id,
+        instanceName: selectedInstance.name,
+        onAccepted: handleLaunch,
+      })) {
+        return
+      }
       toast.error(`${t("home.launchError")}: ${err}`)
     } finally {
       setIsLaunching(false)
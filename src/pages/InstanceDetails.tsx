@@ -7,6 +7,7 @@ import { toast } from "sonner"
 import { useInstallationStore } from "@/stores/installationStore"
 import { useTourStore, TourStep } from "@/stores/tourStore"
 import { useEasyModeStore } from "@/stores/easyModeStore"
+import { useServerEulaStore } from "@/stores/serverEulaStore"
 import { ArrowLeft, Settings, Package, Loader2, FolderOpen, Search, Download, Play, AlertCircle, Square, Copy, Check, ImageIcon, Link, X, Share2, Settings2, Cpu, Archive, RefreshCw } from "lucide-react"
 import { ErrorBoundary } from "@/components/ErrorBoundary"
 import { Button } from "@/components/ui/button"
@@ -365,6 +366,14 @@ export function InstanceDetails() {
       toast.success(t("instanceDetails.started"), { id: "launch-instance" })
     } catch (err) {
       console.error("[InstanceDetails] Failed to launch instance:", err)
+      if (useServerEulaStore.getState().promptIfRequired(err, {
+        instanceId: instanceId!,
+        instanceName: instance?.name || "",
+        onAccepted: handleLaunch,
+      })) {
+        toast.dismiss("launch-instance")
+        return
+      }
       setLaunchError(`${t("errors.launchFailed")}: ${err}`)
       toast.error(`${t("errors.launchFailed")}: ${err}`, { id: "launch-instance" })
     } finally {
@@ -393,6 +402,14 @@ export function InstanceDetails() {
         toast.success(t("instanceDetails.started"), { id: "launch-after-install" })
       } catch (err) {
         console.error("Failed to install/launch instance:", err)
+        if (useServerEulaStore.getState().promptIfRequired(err, {
+          instanceId: instanceId!,
+          instanceName: instance?.name || "",
+          onAccepted: handleLaunch,
+        })) {
+          toast.dismiss("launch-after-install")
+          return
+        }
         setLaunchError(`${t("common.error")}: ${err}`)
         toast.error(`${t("common.error")}: ${err}`, { id: "install-launch" })
       } finally {
@@ -4,6 +4,7 @@ import { invoke } from "@tauri-apps/api/core"
 import { listen } from "@tauri-apps/api/event"
 import { useInstallationStore } from "@/stores/installationStore"
 import { useSharingStore } from "@/stores/sharingStore"
+import { useServerEulaStore } from "@/stores/serverEulaStore"
 import { Plus, Play, Trash2, Download, Loader2, Coffee, Monitor, Server, Network, Square, Circle, Search, Star, LayoutGrid, LayoutList, Columns, ArrowUpDown, FolderDown, Link2, ChevronDown, Palette, X } from "lucide-react"
 import { toast } from "sonner"
 import { useTranslation, TranslationKey } from "@/i18n"
@@ -890,6 +891,13 @@ export function Instances() {
       toast.success(`${t("instances.launchingInstance")} "${instance.name}"`)
     } catch (err) {
       console.error("Failed to launch instance:", err)
+      if (useServerEulaStore.getState().promptIfRequired(err, {
+        instanceId: instance.id,
+        instanceName: instance.name,
+        onAccepted: () => handleLaunch(instance),
+      })) {
+        return
+      }
       toast.error(t("instances.unableToLaunch"))
       setError(String(err))
     } finally {
@@ -0,0 +1,31 @@
+import { create } from "zustand"
+
+/** Error code launch_instance returns for servers whose EULA was not accepted */
+export const EULA_REQUIRED_ERROR = "EULA_REQUIRED"
+
+interface EulaRequest {
+  instanceId: string
+  instanceName: string
+  /** Called once the EULA is accepted, e.g. to launch again */
+  onAccepted?: () => void
+}
+
+interface ServerEulaState {
+  request: EulaRequest | null
+
+  /** Open the EULA prompt when a launch failed because of the EULA. Returns whether it did */
+  promptIfRequired: (error: unknown, request: EulaRequest) => boolean
+  close: () => void
+}
+
+export const useServerEulaStore = create<ServerEulaState>((set) => ({
+  request: null,
+
+  promptIfRequired: (error, request) => {
+    if (String(error) !== EULA_REQUIRED_ERROR) return false
+    set({ request })
+    return true
+  },
+
+  close: () => set({ request: null }),
+}))
@@ -37,6 +37,32 @@ export interface ModSearchResponse {
   limit: number
 }
 
+// ============================================================================
+// Install Results
+// ============================================================================
+
+export type ShaderLoaderState = "enabled" | "disabled" | "missing" | "no_shader_packs" | "unsupported"
+
+export interface OfferedVersion {
+  project_id: string
+  title: string
+  version_id: string
+  version_number: string
+}
+
+export interface ShaderLoaderStatus {
+  state: ShaderLoaderState
+  installed: { name: string; filename: string; enabled: boolean } | null
+  offer: { shader_loader: OfferedVersion; dependencies: OfferedVersion[] } | null
+}
+
+/** Result of install_modrinth_mod */
+export interface InstalledContent {
+  filename: string
+  /** Shader packs only: whether the instance can load them */
+  shader_loader?: ShaderLoaderStatus
+}
+
 // ============================================================================
 // View Modes
 // ============================================================================